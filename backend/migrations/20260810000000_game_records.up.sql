@@ -0,0 +1,22 @@
+-- 2 人對戰的完局紀錄：對局本身只活在記憶體（`games::common::hub`），結束即丟，
+-- 事後沒人能回頭看一盤棋。玩家吵「到底誰超時 / 那步到底有沒有走」時只能各說各話。
+--
+-- moves 存每一步 `move_made` 的 data（引擎的 move_data + 當下 turn / clock），
+-- 前端重播時直接餵給既有的 move_made 處理即可，不必另寫一套解析。
+-- winner 存座位標籤（red / black / first / ...），NULL = 和局。
+CREATE TABLE public.game_records (
+    id bigint GENERATED ALWAYS AS IDENTITY PRIMARY KEY,
+    game text NOT NULL,
+    table_name text NOT NULL,
+    winner text,
+    reason text NOT NULL,
+    moves jsonb NOT NULL DEFAULT '[]'::jsonb,
+    move_count integer NOT NULL DEFAULT 0,
+    initial_clock_ms bigint NOT NULL,
+    final_clock jsonb NOT NULL,
+    started_at timestamp with time zone NOT NULL,
+    ended_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE INDEX idx_game_records_game_ended_at ON public.game_records (game, ended_at DESC);
+CREATE INDEX idx_game_records_ended_at ON public.game_records (ended_at DESC);
@@ -0,0 +1,7 @@
+DROP TABLE IF EXISTS public.game_ratings;
+ALTER TABLE public.game_records
+    DROP COLUMN IF EXISTS first_member_id,
+    DROP COLUMN IF EXISTS second_member_id,
+    DROP COLUMN IF EXISTS winner_seat,
+    DROP COLUMN IF EXISTS first_rating_change,
+    DROP COLUMN IF EXISTS second_rating_change;
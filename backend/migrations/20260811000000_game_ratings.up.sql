@@ -0,0 +1,33 @@
+-- 2 人對戰綁定會員 + 各遊戲 Elo 積分。
+--
+-- WS 連線可選擇帶會員票（`POST /ws/member_ticket`）握手；雙方都是（不同的）會員的對局
+-- 才計分，匿名局照樣落地 game_records，只是不動積分。
+--
+-- winner_seat：0 = 先手座位、1 = 後手座位、NULL = 和局。winner 欄存的是各遊戲自己的
+-- 標籤（red / black ...），拿來算「這位會員贏了沒」得先知道他坐哪一側，所以另存座位。
+ALTER TABLE public.game_records
+    ADD COLUMN first_member_id bigint REFERENCES members(id) ON DELETE SET NULL,
+    ADD COLUMN second_member_id bigint REFERENCES members(id) ON DELETE SET NULL,
+    ADD COLUMN winner_seat smallint,
+    ADD COLUMN first_rating_change integer,
+    ADD COLUMN second_rating_change integer;
+
+CREATE INDEX idx_game_records_first_member ON public.game_records (first_member_id, ended_at DESC)
+    WHERE first_member_id IS NOT NULL;
+CREATE INDEX idx_game_records_second_member ON public.game_records (second_member_id, ended_at DESC)
+    WHERE second_member_id IS NOT NULL;
+
+-- 會員 × 遊戲 的目前積分（計分局結束時與 game_records 同一個 transaction 更新）
+CREATE TABLE public.game_ratings (
+    member_id bigint NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    game text NOT NULL,
+    rating integer NOT NULL DEFAULT 1500,
+    games integer NOT NULL DEFAULT 0,
+    wins integer NOT NULL DEFAULT 0,
+    losses integer NOT NULL DEFAULT 0,
+    draws integer NOT NULL DEFAULT 0,
+    updated_at timestamp with time zone DEFAULT now() NOT NULL,
+    PRIMARY KEY (member_id, game)
+);
+
+CREATE INDEX idx_game_ratings_game_rating ON public.game_ratings (game, rating DESC);
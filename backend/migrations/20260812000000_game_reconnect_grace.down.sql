@@ -0,0 +1 @@
+DELETE FROM public.app_settings WHERE key = 'game_reconnect_grace_secs';
@@ -0,0 +1,5 @@
+-- 對戰斷線寬限期（秒）：期間座位保留、時鐘照走，憑重連 token 可從新連線取回座位；
+-- 逾期判負（2 人桌）或中止整局（N 人房）。0 = 斷線立即判負（舊行為）。
+INSERT INTO public.app_settings (key, value, description, category) VALUES
+    ('game_reconnect_grace_secs', '60', '對戰斷線重連寬限期（秒，0–600；0 = 立即判負）', 'games')
+ON CONFLICT (key) DO NOTHING;
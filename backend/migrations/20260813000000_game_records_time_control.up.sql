@@ -0,0 +1,4 @@
+-- 每桌計時制（Fischer / 讀秒 / 不計時）上線：紀錄要帶計時制，否則重播時看不出
+-- 「剩 0 秒還沒輸」是讀秒中、還是不計時局。舊紀錄一律是遊戲預設的 Fischer，留 NULL。
+-- 不計時局的 final_clock 為 JSON null。
+ALTER TABLE public.game_records ADD COLUMN time_control jsonb;
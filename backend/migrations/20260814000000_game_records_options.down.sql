@@ -0,0 +1 @@
+ALTER TABLE public.game_records DROP COLUMN options;
@@ -0,0 +1,3 @@
+-- 每桌規則選項（圍棋盤面大小 / 讓子 / 計分法…）上線：重播與記譜匯出要從同一個開局重走，
+-- 紀錄得帶選項。舊紀錄與沒有選項的遊戲為 JSON null（= 遊戲預設）。
+ALTER TABLE public.game_records ADD COLUMN options jsonb NOT NULL DEFAULT 'null'::jsonb;
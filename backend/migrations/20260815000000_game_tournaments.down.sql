@@ -0,0 +1,6 @@
+DELETE FROM role_permissions rp USING permissions p
+WHERE rp.permission_id = p.id AND p.resource = 'game' AND p.action = 'manage';
+DELETE FROM permissions WHERE resource = 'game' AND action = 'manage';
+DROP TABLE IF EXISTS public.game_tournament_pairings;
+DROP TABLE IF EXISTS public.game_tournament_entries;
+DROP TABLE IF EXISTS public.game_tournaments;
@@ -0,0 +1,58 @@
+-- 2 人對戰錦標賽：後台建賽 → 會員報名 → 開賽後每輪自動配對開桌，完局結果回寫、整輪下完自動開下一輪。
+--
+-- 進行中的對局只在記憶體（hub），這裡只存賽程與結果；重啟後卡住的對陣由後台手動登錄結果。
+-- 分數存 1 / 0.5 / 0（同 game_ratings 的 score）；first_score 為 NULL = 尚未完成。
+CREATE TABLE public.game_tournaments (
+    id bigserial PRIMARY KEY,
+    game text NOT NULL,
+    name text NOT NULL,
+    format text NOT NULL CHECK (format IN ('round_robin', 'swiss')),
+    -- 瑞士制由主辦指定；循環賽開賽時依人數算出
+    rounds integer NOT NULL DEFAULT 0,
+    time_control jsonb NOT NULL,
+    options jsonb NOT NULL DEFAULT 'null',
+    status text NOT NULL DEFAULT 'registering' CHECK (status IN ('registering', 'running', 'finished')),
+    current_round integer NOT NULL DEFAULT 0,
+    created_at timestamp with time zone DEFAULT now() NOT NULL,
+    started_at timestamp with time zone,
+    finished_at timestamp with time zone
+);
+
+CREATE INDEX idx_game_tournaments_created ON public.game_tournaments (created_at DESC);
+
+-- 報名；final_rank 與三項分數於完賽時寫入（最終名次）
+CREATE TABLE public.game_tournament_entries (
+    tournament_id bigint NOT NULL REFERENCES game_tournaments(id) ON DELETE CASCADE,
+    member_id bigint NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    registered_at timestamp with time zone DEFAULT now() NOT NULL,
+    final_rank integer,
+    points double precision,
+    buchholz double precision,
+    sonneborn_berger double precision,
+    PRIMARY KEY (tournament_id, member_id)
+);
+
+-- 每輪對陣；second_member_id 為 NULL = 輪空（建立時即記 first_score = 1）
+CREATE TABLE public.game_tournament_pairings (
+    id bigserial PRIMARY KEY,
+    tournament_id bigint NOT NULL REFERENCES game_tournaments(id) ON DELETE CASCADE,
+    round integer NOT NULL,
+    first_member_id bigint NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    second_member_id bigint REFERENCES members(id) ON DELETE CASCADE,
+    first_score double precision,
+    second_score double precision,
+    -- 不戰（開桌時缺席）或後台手動登錄
+    forfeit boolean NOT NULL DEFAULT false,
+    finished_at timestamp with time zone
+);
+
+CREATE INDEX idx_game_tournament_pairings_round ON public.game_tournament_pairings (tournament_id, round);
+
+-- 後台建賽 / 開賽 / 登錄結果權限（super_admin 自動取全部，這裡另授予 admin 角色）
+INSERT INTO permissions (resource, action, description)
+VALUES ('game', 'manage', '建立與執行對戰錦標賽');
+
+INSERT INTO role_permissions (role_id, permission_id)
+SELECT r.id, p.id
+FROM roles r, permissions p
+WHERE r.name = 'admin' AND p.resource = 'game' AND p.action = 'manage';
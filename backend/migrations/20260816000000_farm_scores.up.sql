@@ -0,0 +1,17 @@
+-- 農場經營完局分數：每位登入會員一列（匿名座位不落地），供個人最佳與高分榜。
+--
+-- breakdown 為逐項計分明細（`engine::ScoreBreakdown`）；solo = 單人模式、cards = 卡牌版，各自分開排行。
+CREATE TABLE public.farm_scores (
+    id bigserial PRIMARY KEY,
+    member_id bigint NOT NULL REFERENCES members(id) ON DELETE CASCADE,
+    score integer NOT NULL,
+    breakdown jsonb NOT NULL,
+    solo boolean NOT NULL,
+    players integer NOT NULL,
+    -- 卡牌版
+    cards boolean NOT NULL DEFAULT false,
+    created_at timestamp with time zone DEFAULT now() NOT NULL
+);
+
+CREATE INDEX idx_farm_scores_board ON public.farm_scores (solo, cards, score DESC);
+CREATE INDEX idx_farm_scores_member ON public.farm_scores (member_id, solo, cards, score DESC);
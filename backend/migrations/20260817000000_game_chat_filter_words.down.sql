@@ -0,0 +1 @@
+DELETE FROM public.app_settings WHERE key = 'game_chat_filter_words';
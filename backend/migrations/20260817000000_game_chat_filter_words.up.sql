@@ -0,0 +1,5 @@
+-- 對戰聊天過濾詞：命中者（不分大小寫）逐字換成 *，原文只留給後台檢視
+INSERT INTO public.app_settings (key, value, description, category)
+VALUES
+    ('game_chat_filter_words', '[]', '對戰聊天過濾詞（JSON 字串陣列，每項 1–32 字、最多 200 項；不分大小寫）', 'games')
+ON CONFLICT (key) DO NOTHING;
@@ -1,17 +1,22 @@
-//! 阿瓦隆狀態機 — 純函式，零 WS 依賴。組隊 → 投票 → 任務 → （刺客）→ 結算。
+//! 阿瓦隆狀態機 — 純函式，零 WS 依賴。組隊 → 投票 → 任務 →（湖中女神）→ … →（刺客）→ 結算。
+//! 每個公開 / 終局才揭露的事件都記進 `timeline`，終局整份公開並落地供事後檢討。
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 
 use super::roles::{self, Alignment, Options, Role};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Phase {
     TeamBuilding, // 隊長提名
     TeamVote,     // 全員公開投票
     Quest,        // 上場者暗投成功/失敗
+    LadyOfTheLake, // 湖中女神持有者查驗一人
     Assassinate,  // 好人完成 3 任務 → 刺客猜梅林
     GameOver,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AvalonState {
     pub roles: Vec<Role>,
     pub n: usize,
@@ -25,7 +30,36 @@ pub struct AvalonState {
     pub votes: Vec<Option<bool>>, // 組隊投票（每座位）
     pub cards: Vec<Option<bool>>, // 任務牌（僅上場者）
     pub winner: Option<Alignment>,
+    /// 終局理由；重啟保存不帶（只保存進行中的局）。
+    #[serde(skip)]
     pub reason: &'static str,
+    /// 湖中女神目前持有者；未開此選項為 None。
+    #[serde(default)]
+    pub lady: Option<usize>,
+    /// 持有過湖中女神的座位（依序，含目前持有者）；這些人不能再被查驗。
+    #[serde(default)]
+    pub lady_holders: Vec<usize>,
+    /// 事件時間軸（依發生順序）。含私密資訊（查驗結果），終局前不對外。
+    #[serde(default)]
+    pub timeline: Vec<Event>,
+    #[serde(default = "Utc::now")]
+    pub started_at: DateTime<Utc>,
+}
+
+/// 時間軸的一筆。`round` 為任務序（0..5）。
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Event {
+    /// 隊長提名。
+    Proposal { round: usize, leader: usize, team: Vec<usize> },
+    /// 組隊投票（`votes[seat]` = 是否贊成）。
+    Vote { round: usize, votes: Vec<bool>, approved: bool },
+    /// 任務結算：成功 / 失敗牌數（不記誰出哪張）。
+    Quest { round: usize, team: Vec<usize>, successes: usize, fails: usize, success: bool },
+    /// 湖中女神查驗。
+    Lady { holder: usize, target: usize, alignment: Alignment },
+    /// 刺客猜梅林。
+    Assassination { assassin: usize, target: usize, correct: bool },
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -41,6 +75,13 @@ pub struct QuestTally {
     pub success: bool,
 }
 
+#[derive(Debug, PartialEq, Eq)]
+pub struct LadyResult {
+    pub holder: usize,
+    pub target: usize,
+    pub alignment: Alignment,
+}
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct AssassinResult {
     pub target: usize,
@@ -65,9 +106,20 @@ pub fn setup_with_roles(roles: Vec<Role>, leader: usize) -> AvalonState {
         cards: vec![None; n],
         winner: None,
         reason: "",
+        lady: None,
+        lady_holders: Vec::new(),
+        timeline: Vec::new(),
+        started_at: Utc::now(),
     }
 }
 
+/// 開啟湖中女神：交給首任隊長的上一位（輪到他當隊長前最後一個）。
+pub fn enable_lady(s: &mut AvalonState) {
+    let holder = (s.leader + s.n - 1) % s.n;
+    s.lady = Some(holder);
+    s.lady_holders = vec![holder];
+}
+
 /// 正式建局：組角色、洗牌、隨機隊長。
 pub fn setup(n: usize, opt: Options) -> Result<AvalonState, &'static str> {
     use rand::seq::SliceRandom;
@@ -75,7 +127,11 @@ pub fn setup(n: usize, opt: Options) -> Result<AvalonState, &'static str> {
     let mut rng = rand::rng();
     roles.shuffle(&mut rng);
     let leader = (rand::random::<u64>() % n as u64) as usize;
-    Ok(setup_with_roles(roles, leader))
+    let mut s = setup_with_roles(roles, leader);
+    if opt.lady_of_lake {
+        enable_lady(&mut s);
+    }
+    Ok(s)
 }
 
 pub fn current_quest_size(s: &AvalonState) -> usize {
@@ -106,6 +162,7 @@ pub fn propose_team(s: &mut AvalonState, leader: usize, team: &[usize]) -> Resul
     s.team = team.to_vec();
     s.votes = vec![None; s.n];
     s.phase = Phase::TeamVote;
+    s.timeline.push(Event::Proposal { round: s.round, leader, team: s.team.clone() });
     Ok(())
 }
 
@@ -124,6 +181,7 @@ pub fn team_vote(s: &mut AvalonState, seat: usize, approve: bool) -> Result<Opti
     let votes: Vec<(usize, bool)> = s.votes.iter().map(|v| v.unwrap()).enumerate().collect();
     let approvals = votes.iter().filter(|(_, a)| *a).count();
     let approved = approvals * 2 > s.n; // 嚴格多數（平手＝否決）
+    s.timeline.push(Event::Vote { round: s.round, votes: votes.iter().map(|&(_, a)| a).collect(), approved });
 
     if approved {
         s.rejects = 0;
@@ -163,6 +221,13 @@ pub fn quest_card(s: &mut AvalonState, seat: usize, success: bool) -> Result<Opt
     let req = roles::fails_required(s.n, s.round);
     let success_quest = fails < req;
     s.results.push(success_quest);
+    s.timeline.push(Event::Quest {
+        round: s.round,
+        team: s.team.clone(),
+        successes: s.team.len() - fails,
+        fails,
+        success: success_quest,
+    });
 
     let tally = QuestTally { round: s.round, fails, success: success_quest };
 
@@ -175,14 +240,36 @@ pub fn quest_card(s: &mut AvalonState, seat: usize, success: bool) -> Result<Opt
         s.reason = "evil_three_fails";
         s.phase = Phase::GameOver;
     } else {
+        // 第 2–4 個任務後輪到湖中女神
+        let lady_turn = s.lady.is_some() && (1..=3).contains(&s.round);
         s.leader = (s.leader + 1) % s.n;
         s.round += 1;
         s.team.clear();
-        s.phase = Phase::TeamBuilding;
+        s.phase = if lady_turn { Phase::LadyOfTheLake } else { Phase::TeamBuilding };
     }
     Ok(Some(tally))
 }
 
+/// 湖中女神查驗：持有者得知 `target` 的陣營（只回給本人），女神交給 `target`。
+/// 不能查自己或曾持有過女神的人。
+pub fn lady_of_the_lake(s: &mut AvalonState, seat: usize, target: usize) -> Result<LadyResult, &'static str> {
+    if s.phase != Phase::LadyOfTheLake {
+        return Err("wrong_phase");
+    }
+    if s.lady != Some(seat) {
+        return Err("not_lady_holder");
+    }
+    if !in_range(s, target) || s.lady_holders.contains(&target) {
+        return Err("bad_target");
+    }
+    let alignment = s.roles[target].alignment();
+    s.timeline.push(Event::Lady { holder: seat, target, alignment });
+    s.lady = Some(target);
+    s.lady_holders.push(target);
+    s.phase = Phase::TeamBuilding;
+    Ok(LadyResult { holder: seat, target, alignment })
+}
+
 /// 刺客猜梅林。
 pub fn assassinate(s: &mut AvalonState, seat: usize, target: usize) -> Result<AssassinResult, &'static str> {
     if s.phase != Phase::Assassinate {
@@ -195,6 +282,7 @@ pub fn assassinate(s: &mut AvalonState, seat: usize, target: usize) -> Result<As
         return Err("bad_target");
     }
     let correct = s.roles[target] == Role::Merlin;
+    s.timeline.push(Event::Assassination { assassin: seat, target, correct });
     s.winner = Some(if correct { Alignment::Evil } else { Alignment::Good });
     s.reason = if correct { "evil_assassinate" } else { "good_assassin_miss" };
     s.phase = Phase::GameOver;
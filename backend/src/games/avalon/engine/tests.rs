@@ -20,7 +20,7 @@ fn five() -> AvalonState {
 fn role_counts_per_size() {
     assert_eq!(good_evil(5), Some((3, 2)));
     assert_eq!(good_evil(10), Some((6, 4)));
-    let r = build_roles(7, Options { mordred: true, oberon: false }).unwrap();
+    let r = build_roles(7, Options { mordred: true, oberon: false, lady_of_lake: false }).unwrap();
     assert_eq!(r.len(), 7);
     assert_eq!(r.iter().filter(|x| x.alignment() == Alignment::Evil).count(), 3);
 }
@@ -28,7 +28,7 @@ fn role_counts_per_size() {
 #[test]
 fn too_many_special_evil_rejected() {
     // 5 人只有 2 壞，要 Assassin+Morgana+Mordred+Oberon = 4 → 爆
-    let e = build_roles(5, Options { mordred: true, oberon: true });
+    let e = build_roles(5, Options { mordred: true, oberon: true, lady_of_lake: false });
     assert_eq!(e, Err("too_many_special_evil"));
 }
 
@@ -168,3 +168,68 @@ fn fails_required_two_on_fourth_quest_big_table() {
     assert_eq!(roles::fails_required(5, 3), 1);
     assert_eq!(roles::fails_required(7, 0), 1);
 }
+
+/// 目前隊長提名 `team`、全員贊成、上場者全出成功牌。
+fn pass_quest(s: &mut AvalonState, team: &[usize]) {
+    let l = s.leader;
+    propose_team(s, l, team).unwrap();
+    for seat in 0..s.n {
+        team_vote(s, seat, true).unwrap();
+    }
+    for &t in team {
+        quest_card(s, t, true).unwrap();
+    }
+}
+
+#[test]
+fn lady_starts_right_of_leader_and_acts_after_second_quest() {
+    let mut s = five();
+    enable_lady(&mut s);
+    assert_eq!(s.lady, Some(4)); // 首任隊長 0 的上一位
+    pass_quest(&mut s, &[0, 1]);
+    assert_eq!(s.phase, Phase::TeamBuilding, "第 1 個任務後不用女神");
+    pass_quest(&mut s, &[0, 1, 2]);
+    assert_eq!(s.phase, Phase::LadyOfTheLake);
+    let l = s.leader;
+    assert_eq!(propose_team(&mut s, l, &[0, 1]), Err("wrong_phase"));
+}
+
+#[test]
+fn lady_reveals_alignment_and_passes_token() {
+    let mut s = five();
+    enable_lady(&mut s);
+    s.phase = Phase::LadyOfTheLake;
+    assert_eq!(lady_of_the_lake(&mut s, 0, 3), Err("not_lady_holder"));
+    assert_eq!(lady_of_the_lake(&mut s, 4, 4), Err("bad_target")); // 不能查自己
+    let r = lady_of_the_lake(&mut s, 4, 3).unwrap();
+    assert_eq!(r.alignment, Alignment::Evil);
+    assert_eq!(s.lady, Some(3));
+    assert_eq!(s.phase, Phase::TeamBuilding);
+
+    // 曾持有者不能再被查
+    s.phase = Phase::LadyOfTheLake;
+    assert_eq!(lady_of_the_lake(&mut s, 3, 4), Err("bad_target"));
+    assert_eq!(lady_of_the_lake(&mut s, 3, 0).unwrap().alignment, Alignment::Good);
+    assert_eq!(s.lady_holders, vec![4, 3, 0]);
+}
+
+#[test]
+fn timeline_records_every_vote_and_quest() {
+    let mut s = five();
+    propose_team(&mut s, 0, &[0, 3]).unwrap();
+    for seat in 0..s.n {
+        team_vote(&mut s, seat, seat != 2).unwrap();
+    }
+    quest_card(&mut s, 0, true).unwrap();
+    quest_card(&mut s, 3, false).unwrap();
+    assert_eq!(
+        s.timeline,
+        vec![
+            Event::Proposal { round: 0, leader: 0, team: vec![0, 3] },
+            Event::Vote { round: 0, votes: vec![true, true, false, true, true], approved: true },
+            Event::Quest { round: 0, team: vec![0, 3], successes: 1, fails: 1, success: false },
+        ]
+    );
+    let v = serde_json::to_value(&s.timeline[2]).unwrap();
+    assert_eq!(v["type"], "quest");
+}
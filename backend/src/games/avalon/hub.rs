@@ -1,9 +1,10 @@
-//! 阿瓦隆房型參數。大廳 / 房間狀態走 `common::room` 泛型框架。N 人房，重啟即丟失。
+//! 阿瓦隆房型參數。大廳 / 房間狀態走 `common::room` 泛型框架。N 人房，對局中的房重啟後還原（見 `common::persist`）。
 
 use serde_json::{json, Map, Value};
 
 use super::engine::AvalonState;
 use super::roles::Options;
+use super::service;
 use crate::games::common::room::{RoomHub, RoomHubInner, RoomKind};
 
 pub const MIN_PLAYERS: usize = 5;
@@ -28,11 +29,27 @@ impl RoomKind for AvalonRoom {
         Options {
             mordred: data.and_then(|d| d.pointer("/options/mordred")).and_then(|v| v.as_bool()).unwrap_or(false),
             oberon: data.and_then(|d| d.pointer("/options/oberon")).and_then(|v| v.as_bool()).unwrap_or(false),
+            lady_of_lake: data
+                .and_then(|d| d.pointer("/options/lady_of_lake"))
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false),
         }
     }
 
     fn extend_room_snapshot(options: &Options, obj: &mut Map<String, Value>) {
-        obj.insert("options".into(), json!({ "mordred": options.mordred, "oberon": options.oberon }));
+        obj.insert(
+            "options".into(),
+            json!({ "mordred": options.mordred, "oberon": options.oberon, "lady_of_lake": options.lady_of_lake }),
+        );
+    }
+
+    /// 重連補送：自己的私有角色 + 自己做過的湖中女神查驗 + 目前階段。
+    fn resync(room: &Room, seat: usize) -> Vec<(&'static str, Value)> {
+        let RoomState::Playing(st) = &room.state else { return Vec::new() };
+        let mut out = vec![("role_assigned", service::role_payload(&room.names, st, seat))];
+        out.extend(service::lady_results(st, seat).into_iter().map(|v| ("lady_result", v)));
+        out.push(("phase_changed", service::phase_payload(st)));
+        out
     }
 }
 
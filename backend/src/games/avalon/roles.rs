@@ -1,6 +1,8 @@
 //! 阿瓦隆角色、陣營、人數配置表、私有可見資訊。純資料/純函式。
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Role {
     Merlin,       // 好：看得見壞人（除莫德雷）
     Percival,     // 好：看得見梅林與莫甘娜（無法分辨）
@@ -12,7 +14,8 @@ pub enum Role {
     Minion,       // 壞：無特殊
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
 pub enum Alignment {
     Good,
     Evil,
@@ -40,10 +43,22 @@ impl Role {
     }
 }
 
-#[derive(Debug, Clone, Copy, Default)]
+impl Alignment {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Alignment::Good => "good",
+            Alignment::Evil => "evil",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Options {
     pub mordred: bool,
     pub oberon: bool,
+    /// 湖中女神：第 2–4 個任務後，持有者私下查驗一人陣營並把女神交給對方。
+    #[serde(default)]
+    pub lady_of_lake: bool,
 }
 
 /// 各人數的 (好人, 壞人) 數。
@@ -1,6 +1,7 @@
-//! 阿瓦隆 WS 串接：私有角色推送 / 階段機 / 投票 / 任務 / 刺客 / chat / 斷線。
+//! 阿瓦隆 WS 串接：私有角色推送 / 階段機 / 投票 / 任務 / 湖中女神 / 刺客 / chat / 斷線。
 //!
-//! 信封 `{ game:"avalon", type, data }`。角色資訊**逐座位私有推送**（各人看到不同 `known`）。
+//! 信封 `{ game:"avalon", type, data }`。角色資訊**逐座位私有推送**（各人看到不同 `known`）；
+//! 湖中女神的查驗結果同樣只推給持有者。終局 `game_over` 帶完整時間軸並寫進 `game_records`。
 //! 大廳 / 房間共通指令（join_lobby / list_rooms / create_room / join_room / leave_room）
 //! 走 `common::room` 泛型框架。
 
@@ -8,11 +9,13 @@ use std::net::SocketAddr;
 
 use serde_json::{json, Value};
 
-use super::engine::{self, AvalonState, Phase};
+use super::engine::{self, AvalonState, Event, Phase};
 use super::hub::{AvalonHub, AvalonHubInner, AvalonRoom, Room, RoomState};
-use super::roles::{self, Alignment};
+use super::roles;
 use crate::games::common::room;
+use crate::services::game_records;
 use crate::state::AppState;
+use crate::structs::game_records::NewGameRecord;
 
 pub async fn handle(hub: &AvalonHub, state: &AppState, who: SocketAddr, value: &Value) -> bool {
     let data = value.get("data");
@@ -26,6 +29,7 @@ pub async fn handle(hub: &AvalonHub, state: &AppState, who: SocketAddr, value: &
         "propose_team" => propose_team(hub, state, who, data).await,
         "team_vote" => team_vote(hub, state, who, data).await,
         "quest_card" => quest_card(hub, state, who, data).await,
+        "lady_of_the_lake" => lady_of_the_lake(hub, state, who, data).await,
         "assassinate" => assassinate(hub, state, who, data).await,
         _ => return false,
     }
@@ -62,23 +66,10 @@ async fn start_game(hub: &AvalonHub, state: &AppState, who: SocketAddr) {
         };
         // 私有角色推送：每座位收到自己的角色 + known
         for seat in 0..n {
-            let role = st.roles[seat];
-            let known = roles::known_seats(&st.roles, seat);
-            let players: Vec<Value> = room.names.iter().enumerate()
-                .map(|(i, name)| json!({ "seat": i, "name": name })).collect();
-            outbox.push((
-                room.players[seat],
-                msg("role_assigned", json!({
-                    "your_seat": seat,
-                    "your_role": role.as_str(),
-                    "known": known,
-                    "n": n,
-                    "sizes": st.sizes,
-                    "players": players,
-                })),
-            ));
+            outbox.push((room.players[seat], msg("role_assigned", role_payload(&room.names, &st, seat))));
         }
         room.state = RoomState::Playing(st);
+        room::issue_reconnect_tokens(room, &mut outbox);
         // 公開階段
         let room = h.rooms.get(&room_id).unwrap();
         broadcast_phase(room, &mut outbox);
@@ -141,7 +132,7 @@ async fn team_vote(hub: &AvalonHub, state: &AppState, who: SocketAddr, data: Opt
                 let votes: Vec<Value> = tally.votes.iter().map(|(s, a)| json!({ "seat": s, "approve": a })).collect();
                 let m = msg("vote_result", json!({ "votes": votes, "approved": tally.approved }));
                 for &p in &room.players { outbox.push((p, m.clone())); }
-                push_transition(&mut h, room_id, &mut outbox);
+                push_transition(state, &mut h, room_id, &mut outbox);
             }
         }
     }
@@ -166,13 +157,36 @@ async fn quest_card(hub: &AvalonHub, state: &AppState, who: SocketAddr, data: Op
                     "round": tally.round, "fails": tally.fails, "success": tally.success,
                 }));
                 for &p in &room.players { outbox.push((p, m.clone())); }
-                push_transition(&mut h, room_id, &mut outbox);
+                push_transition(state, &mut h, room_id, &mut outbox);
             }
         }
     }
     room::flush(state, outbox);
 }
 
+/// 湖中女神查驗：持有者私下收 `lady_result`（對方陣營），全房收 `lady_used`（誰查了誰）。
+async fn lady_of_the_lake(hub: &AvalonHub, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let Some(target) = data.and_then(|d| d.get("target")).and_then(|v| v.as_u64()).map(|u| u as usize) else {
+        err1(state, who, "bad_target"); return;
+    };
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((room_id, seat)) = room::playing_seat(&h, who) else { err1(state, who, "not_in_game"); return; };
+        let room = h.rooms.get_mut(&room_id).unwrap();
+        let RoomState::Playing(st) = &mut room.state else { return; };
+        let res = match engine::lady_of_the_lake(st, seat, target) {
+            Ok(res) => res,
+            Err(e) => { err1(state, who, e); return; }
+        };
+        outbox.push((who, msg("lady_result", json!({ "target": res.target, "alignment": res.alignment.as_str() }))));
+        let m = msg("lady_used", json!({ "holder": res.holder, "target": res.target }));
+        for &p in &room.players { outbox.push((p, m.clone())); }
+        push_transition(state, &mut h, room_id, &mut outbox);
+    }
+    room::flush(state, outbox);
+}
+
 async fn assassinate(hub: &AvalonHub, state: &AppState, who: SocketAddr, data: Option<&Value>) {
     let Some(target) = data.and_then(|d| d.get("target")).and_then(|v| v.as_u64()).map(|u| u as usize) else {
         err1(state, who, "bad_target"); return;
@@ -184,24 +198,39 @@ async fn assassinate(hub: &AvalonHub, state: &AppState, who: SocketAddr, data: O
         let room = h.rooms.get_mut(&room_id).unwrap();
         let RoomState::Playing(st) = &mut room.state else { return; };
         if let Err(e) = engine::assassinate(st, seat, target) { err1(state, who, e); return; }
-        push_transition(&mut h, room_id, &mut outbox); // phase 已 GameOver → 推 game_over + 解散
+        push_transition(state, &mut h, room_id, &mut outbox); // phase 已 GameOver → 推 game_over + 解散
     }
     room::flush(state, outbox);
 }
 
 // ---- 階段推送 ----
 
+/// 單一座位的私有角色資訊（開局推送與重連補送共用）。
+pub(super) fn role_payload(names: &[String], st: &AvalonState, seat: usize) -> Value {
+    let players: Vec<Value> = names.iter().enumerate()
+        .map(|(i, name)| json!({ "seat": i, "name": name })).collect();
+    json!({
+        "your_seat": seat,
+        "your_role": st.roles[seat].as_str(),
+        "known": roles::known_seats(&st.roles, seat),
+        "n": st.roles.len(),
+        "sizes": st.sizes,
+        "players": players,
+    })
+}
+
 fn phase_str(p: Phase) -> &'static str {
     match p {
         Phase::TeamBuilding => "team_building",
         Phase::TeamVote => "team_vote",
         Phase::Quest => "quest",
+        Phase::LadyOfTheLake => "lady_of_the_lake",
         Phase::Assassinate => "assassinate",
         Phase::GameOver => "game_over",
     }
 }
 
-fn phase_payload(st: &AvalonState) -> Value {
+pub(super) fn phase_payload(st: &AvalonState) -> Value {
     json!({
         "phase": phase_str(st.phase),
         "leader": st.leader,
@@ -210,9 +239,50 @@ fn phase_payload(st: &AvalonState) -> Value {
         "results": st.results,
         "rejects": st.rejects,
         "team": st.team,
+        "lady": st.lady,
+        "lady_holders": st.lady_holders,
     })
 }
 
+/// `seat` 做過的湖中女神查驗（重連補送；查驗結果只有本人知道）。
+pub(super) fn lady_results(st: &AvalonState, seat: usize) -> Vec<Value> {
+    st.timeline
+        .iter()
+        .filter_map(|e| match e {
+            Event::Lady { holder, target, alignment } if *holder == seat => {
+                Some(json!({ "target": target, "alignment": alignment.as_str() }))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// 終局公開的完整時間軸：首筆 `setup`（座位 / 角色、首任湖中女神），其後依序為
+/// `engine::Event`（每次提名、逐人投票、任務牌數、查驗、刺殺）。
+///
+/// `names` 只在即時的 `game_over` 帶（當場的玩家對得上座位）；寫進公開完局紀錄的那份傳 None，
+/// 只留座位與角色 —— 匿名房的暱稱不該跟著紀錄永久公開。
+fn timeline(names: Option<&[String]>, st: &AvalonState) -> Vec<Value> {
+    let players: Vec<Value> = st.roles.iter().enumerate()
+        .map(|(i, r)| {
+            let mut p = json!({ "seat": i, "role": r.as_str() });
+            if let Some(name) = names.and_then(|n| n.get(i)) {
+                p["name"] = json!(name);
+            }
+            p
+        })
+        .collect();
+    let setup = json!({
+        "type": "setup",
+        "players": players,
+        "sizes": st.sizes,
+        "lady": st.lady_holders.first(),
+    });
+    std::iter::once(setup)
+        .chain(st.timeline.iter().map(|e| serde_json::to_value(e).unwrap_or_default()))
+        .collect()
+}
+
 fn broadcast_phase(room: &Room, outbox: &mut Vec<(SocketAddr, String)>) {
     if let RoomState::Playing(st) = &room.state {
         let m = msg("phase_changed", phase_payload(st));
@@ -222,22 +292,61 @@ fn broadcast_phase(room: &Room, outbox: &mut Vec<(SocketAddr, String)>) {
     }
 }
 
-/// 動作後依 phase 推 phase_changed，或 GameOver → 推 game_over（含全角色揭露）並解散房。
-fn push_transition(h: &mut AvalonHubInner, room_id: u64, outbox: &mut Vec<(SocketAddr, String)>) {
+/// 動作後依 phase 推 phase_changed，或 GameOver → 推 game_over（含全角色揭露與時間軸）、
+/// 寫完局紀錄並解散房。
+fn push_transition(state: &AppState, h: &mut AvalonHubInner, room_id: u64, outbox: &mut Vec<(SocketAddr, String)>) {
     let room = h.rooms.get(&room_id).unwrap();
     let RoomState::Playing(st) = &room.state else { return; };
     if st.phase == Phase::GameOver {
         let roles_reveal: Vec<Value> = st.roles.iter().enumerate()
             .map(|(i, r)| json!({ "seat": i, "role": r.as_str() })).collect();
-        let winner = match st.winner {
-            Some(Alignment::Good) => json!("good"),
-            Some(Alignment::Evil) => json!("evil"),
-            None => Value::Null,
-        };
-        let m = msg("game_over", json!({ "winner": winner, "reason": st.reason, "roles": roles_reveal }));
+        let winner = st.winner.map(|a| a.as_str());
+        let m = msg("game_over", json!({
+            "winner": winner, "reason": st.reason, "roles": roles_reveal,
+            "timeline": timeline(Some(&room.names), st),
+        }));
         for &p in &room.players { outbox.push((p, m.clone())); }
+        // 匿名房：不綁會員、不計分。`moves` 存時間軸，重看時逐筆套用即可
+        game_records::spawn_save(
+            state.get_pool().clone(),
+            NewGameRecord {
+                game: super::NAME,
+                table_name: room.name.clone(),
+                winner,
+                winner_seat: None,
+                members: [None, None],
+                rated: false,
+                reason: st.reason.to_string(),
+                moves: timeline(None, st),
+                initial_clock_ms: 0,
+                time_control: Value::Null,
+                options: serde_json::to_value(room.options).unwrap_or_default(),
+                final_clock: Value::Null,
+                started_at: st.started_at,
+            },
+        );
         room::dissolve_room(h, room_id, outbox);
     } else {
         broadcast_phase(room, outbox);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::avalon::roles::Role;
+
+    #[test]
+    fn saved_timeline_keeps_roles_but_not_names() {
+        let roles = vec![Role::Merlin, Role::Percival, Role::LoyalServant, Role::Assassin, Role::Morgana];
+        let st = engine::setup_with_roles(roles, 0);
+        let names: Vec<String> = (1..=5).map(|i| format!("玩家{i}")).collect();
+
+        let live = timeline(Some(&names), &st);
+        assert_eq!(live[0]["players"][3], json!({ "seat": 3, "name": "玩家4", "role": "assassin" }));
+
+        let saved = timeline(None, &st);
+        assert_eq!(saved[0]["players"][3], json!({ "seat": 3, "role": "assassin" }));
+        assert!(!saved.iter().any(|e| e.to_string().contains("玩家")));
+    }
+}
@@ -5,13 +5,42 @@
 //! - 一般子吃子：相鄰一步，吃階 ≤ 己之敵子。例外：卒/兵可吃將/帥；將/帥不可吃卒/兵。同階可互吃。
 //! - 炮：不吃子時相鄰一步走空格；吃子時同線隔「恰一個棋架」（任意距離），可吃任意階「已翻開」敵子。
 //! - 勝負：某方棋子全被吃 / 輪到方無步可走 → 該方負。連續 N 手無吃子 → 和。
-//! - 本實作房規：一般子與炮皆**不可吃面朝下**的子（僅吃已翻開敵子）。
+//! - 預設房規：一般子與炮皆**不可吃面朝下**的子（僅吃已翻開敵子）。其餘台灣常見房規見 `Rules`，每桌自選。
 
 use rand::seq::SliceRandom;
 
 pub const COLS: i8 = 8;
 pub const ROWS: i8 = 4;
-const QUIET_DRAW: u32 = 60; // 連續無吃子半步數上限 → 和
+/// 和局門檻（連續無吃子半步數）的預設與可選範圍。
+pub const QUIET_DRAW: u32 = 60;
+pub const QUIET_DRAW_RANGE: std::ops::RangeInclusive<u32> = 20..=200;
+
+/// 每桌房規。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// 連吃：吃子後同一子還能再吃就可以接著吃（也可以停手），直到停手或無子可吃才換手。
+    pub chain_captures: bool,
+    /// 車直行：車可沿直線走任意格（不能越子），吃子仍照位階。
+    pub rook_slides: bool,
+    /// 炮打暗子：炮隔架可吃面朝下的子，翻開即吃掉（翻到自己的子也一樣吃掉）。
+    pub cannon_hits_hidden: bool,
+    /// 卒吃將：關掉則卒/兵不能吃將/帥，將/帥照位階可吃卒/兵。
+    pub pawn_takes_king: bool,
+    /// 連續無吃子半步數達此值 → 和。
+    pub quiet_draw: u32,
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            chain_captures: false,
+            rook_slides: false,
+            cannon_hits_hidden: false,
+            pawn_takes_king: true,
+            quiet_draw: QUIET_DRAW,
+        }
+    }
+}
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Color {
@@ -27,6 +56,14 @@ impl Color {
         }
     }
 
+    pub fn parse(s: &str) -> Option<Color> {
+        match s {
+            "red" => Some(Color::Red),
+            "black" => Some(Color::Black),
+            _ => None,
+        }
+    }
+
     pub fn as_str(self) -> &'static str {
         match self {
             Color::Red => "red",
@@ -59,6 +96,12 @@ impl Kind {
         }
     }
 
+    pub fn parse(s: &str) -> Option<Kind> {
+        [Kind::King, Kind::Guard, Kind::Elephant, Kind::Rook, Kind::Horse, Kind::Cannon, Kind::Pawn]
+            .into_iter()
+            .find(|k| k.as_str() == s)
+    }
+
     pub fn as_str(self) -> &'static str {
         match self {
             Kind::King => "king",
@@ -108,19 +151,26 @@ pub struct BanqiState {
     /// First 座位所執顏色，首次翻子後確定。
     pub first_color: Option<Color>,
     pub quiet: u32,
+    pub rules: Rules,
+    /// 連吃中的子（行棋方只能用它再吃，或停手）；None = 一般回合。
+    pub chain: Option<(i8, i8)>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Action {
     Flip { col: i8, row: i8 },
     Move { from: (i8, i8), to: (i8, i8) },
+    /// 連吃中停手、換對方。
+    EndChain,
 }
 
 /// 一步套用後的效果，供外層產生事件。
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Effect {
     Flipped { col: i8, row: i8, piece: Piece },
-    Moved { from: (i8, i8), to: (i8, i8), captured: Option<Piece> },
+    /// `chain` = 連吃中、同一方繼續。
+    Moved { from: (i8, i8), to: (i8, i8), captured: Option<Piece>, chain: bool },
+    ChainEnded,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -145,13 +195,43 @@ fn full_deck() -> Vec<Piece> {
 }
 
 /// 隨機洗牌的初始局面（全部面朝下，First 先行）。
-pub fn initial_state() -> BanqiState {
+pub fn initial_state(rules: Rules) -> BanqiState {
     let mut deck = full_deck();
     deck.shuffle(&mut rand::rng());
-    from_deck(deck)
+    BanqiState { rules, ..from_deck(deck) }
+}
+
+/// 開局盤面的蓋牌順序（與 `from_deck` 同序）；已有子翻開或被吃回 None。重啟保存用。
+pub fn hidden_deck(s: &BanqiState) -> Option<Vec<Piece>> {
+    s.board
+        .iter()
+        .flatten()
+        .map(|cell| match cell {
+            Cell::Hidden(p) => Some(*p),
+            _ => None,
+        })
+        .collect()
+}
+
+/// 盤上還蓋著的子數。
+pub fn hidden_count(s: &BanqiState) -> usize {
+    s.board.iter().flatten().filter(|c| matches!(c, Cell::Hidden(_))).count()
 }
 
-/// 由給定 32 子順序鋪盤（測試用，確定性）。
+/// 是否恰為一副完整的 32 子（還原保存的開局前驗證）。
+pub fn is_full_deck(deck: &[Piece]) -> bool {
+    let mut rest = full_deck();
+    deck.len() == rest.len()
+        && deck.iter().all(|p| match rest.iter().position(|q| q == p) {
+            Some(i) => {
+                rest.swap_remove(i);
+                true
+            }
+            None => false,
+        })
+}
+
+/// 由給定 32 子順序鋪盤（測試 / 還原保存的開局用，確定性；預設房規）。
 pub fn from_deck(deck: Vec<Piece>) -> BanqiState {
     assert_eq!(deck.len(), 32);
     let mut board = [[Cell::Empty; 8]; 4];
@@ -166,6 +246,8 @@ pub fn from_deck(deck: Vec<Piece>) -> BanqiState {
         turn: Side::First,
         first_color: None,
         quiet: 0,
+        rules: Rules::default(),
+        chain: None,
     }
 }
 
@@ -188,11 +270,11 @@ pub fn color_of(state: &BanqiState, side: Side) -> Option<Color> {
     })
 }
 
-/// 吃子位階規則（炮另計）。
-fn can_capture(att: Kind, tgt: Kind) -> bool {
+/// 吃子位階規則（炮另計）。`pawn_takes_king` 關掉時卒不吃將、將照位階吃卒。
+fn can_capture(rules: &Rules, att: Kind, tgt: Kind) -> bool {
     match (att, tgt) {
-        (Kind::Pawn, Kind::King) => true,
-        (Kind::King, Kind::Pawn) => false,
+        (Kind::Pawn, Kind::King) => rules.pawn_takes_king,
+        (Kind::King, Kind::Pawn) => !rules.pawn_takes_king,
         _ => att.rank() >= tgt.rank(),
     }
 }
@@ -253,12 +335,17 @@ fn validate_move(
         return match cell(state, to.0, to.1) {
             Cell::Up(t) if t.color != piece.color => Ok(Some(t)),
             Cell::Up(_) => Err("occupied_friendly"),
+            Cell::Hidden(t) if state.rules.cannon_hits_hidden => Ok(Some(t)),
             _ => Err("target_hidden"), // 空或面朝下：非合法炮吃
         };
     }
 
-    // 一般子：相鄰一步
-    if dc + dr != 1 {
+    // 一般子：相鄰一步（車直行房規：同線、中間無子即可）
+    let slides = piece.kind == Kind::Rook
+        && state.rules.rook_slides
+        && dc + dr >= 1
+        && pieces_between(state, from, to) == Some(0);
+    if dc + dr != 1 && !slides {
         return Err("not_adjacent");
     }
     match cell(state, to.0, to.1) {
@@ -267,7 +354,7 @@ fn validate_move(
         Cell::Up(t) => {
             if t.color == piece.color {
                 Err("occupied_friendly")
-            } else if can_capture(piece.kind, t.kind) {
+            } else if can_capture(&state.rules, piece.kind, t.kind) {
                 Ok(Some(t))
             } else {
                 Err("cannot_capture")
@@ -282,7 +369,19 @@ pub fn apply_action(
     mover: Side,
     action: Action,
 ) -> Result<Effect, &'static str> {
+    if let Some(at) = state.chain {
+        match action {
+            Action::EndChain => {
+                state.chain = None;
+                state.turn = state.turn.opponent();
+                return Ok(Effect::ChainEnded);
+            }
+            Action::Move { from, .. } if from == at => {}
+            _ => return Err("chain_in_progress"),
+        }
+    }
     match action {
+        Action::EndChain => Err("no_chain"),
         Action::Flip { col, row } => {
             let piece = match cell(state, col, row) {
                 Cell::Hidden(p) => p,
@@ -315,6 +414,9 @@ pub fn apply_action(
                 return Err("not_your_piece");
             }
             let captured = validate_move(state, piece, from, to)?;
+            if state.chain.is_some() && captured.is_none() {
+                return Err("chain_must_capture");
+            }
             // 套用
             state.board[from.1 as usize][from.0 as usize] = Cell::Empty;
             state.board[to.1 as usize][to.0 as usize] = Cell::Up(piece);
@@ -323,8 +425,13 @@ pub fn apply_action(
             } else {
                 state.quiet = state.quiet.saturating_add(1);
             }
-            state.turn = state.turn.opponent();
-            Ok(Effect::Moved { from, to, captured })
+            // 連吃：還能再吃就留在同一方
+            let chain = state.rules.chain_captures && captured.is_some() && can_capture_from(state, piece, to);
+            state.chain = chain.then_some(to);
+            if !chain {
+                state.turn = state.turn.opponent();
+            }
+            Ok(Effect::Moved { from, to, captured, chain })
         }
     }
 }
@@ -351,9 +458,22 @@ fn any_hidden(state: &BanqiState) -> bool {
         .any(|c| matches!(c, Cell::Hidden(_)))
 }
 
-/// `side` 是否還有任何合法動作（翻子或走子）。
+/// 從 `from` 出發的所有合法走法（`(to, captured)`）。炮隔架與車直行都只在同行同列，掃整行整列即可。
+fn moves_from(state: &BanqiState, piece: Piece, from: (i8, i8)) -> impl Iterator<Item = ((i8, i8), Option<Piece>)> + '_ {
+    let (col, row) = from;
+    let line = (0..ROWS).map(move |r| (col, r)).chain((0..COLS).map(move |c| (c, row)));
+    line.filter(move |&to| to != from)
+        .filter_map(move |to| validate_move(state, piece, from, to).ok().map(|cap| (to, cap)))
+}
+
+/// 位於 `at` 的 `piece` 是否還有子可吃（連吃判定）。
+fn can_capture_from(state: &BanqiState, piece: Piece, at: (i8, i8)) -> bool {
+    moves_from(state, piece, at).any(|(_, cap)| cap.is_some())
+}
+
+/// `side` 是否還有任何合法動作（翻子或走子；連吃中永遠可以停手）。
 fn has_action(state: &BanqiState, side: Side) -> bool {
-    if any_hidden(state) {
+    if any_hidden(state) || state.chain.is_some() {
         return true; // 永遠可翻
     }
     let Some(color) = color_of(state, side) else {
@@ -364,33 +484,8 @@ fn has_action(state: &BanqiState, side: Side) -> bool {
             let Cell::Up(p) = cell(state, col, row) else {
                 continue;
             };
-            if p.color != color {
-                continue;
-            }
-            if p.kind == Kind::Cannon {
-                // 相鄰空格
-                for (dc, dr) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
-                    if matches!(cell(state, col + dc, row + dr), Cell::Empty) {
-                        return true;
-                    }
-                }
-                // 隔架吃：掃同行同列
-                for r in 0..ROWS {
-                    if r != row && validate_move(state, p, (col, row), (col, r)).is_ok() {
-                        return true;
-                    }
-                }
-                for c in 0..COLS {
-                    if c != col && validate_move(state, p, (col, row), (c, row)).is_ok() {
-                        return true;
-                    }
-                }
-            } else {
-                for (dc, dr) in [(1, 0), (-1, 0), (0, 1), (0, -1)] {
-                    if validate_move(state, p, (col, row), (col + dc, row + dr)).is_ok() {
-                        return true;
-                    }
-                }
+            if p.color == color && moves_from(state, p, (col, row)).next().is_some() {
+                return true;
             }
         }
     }
@@ -414,7 +509,7 @@ pub fn status(state: &BanqiState) -> Outcome {
     if !has_action(state, state.turn) {
         return Outcome::Win { winner: state.turn.opponent(), reason: "no_moves" };
     }
-    if state.quiet >= QUIET_DRAW {
+    if state.quiet >= state.rules.quiet_draw {
         return Outcome::Draw;
     }
     Outcome::Continue
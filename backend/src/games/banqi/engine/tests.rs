@@ -6,6 +6,8 @@ fn empty(first_color: Color, turn: Side) -> BanqiState {
         turn,
         first_color: Some(first_color),
         quiet: 0,
+        rules: Rules::default(),
+        chain: None,
     }
 }
 
@@ -57,7 +59,7 @@ fn pawn_captures_king_but_not_reverse() {
     let eff = apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (1, 0) }).unwrap();
     assert_eq!(
         eff,
-        Effect::Moved { from: (0, 0), to: (1, 0), captured: Some(Piece { color: Color::Black, kind: Kind::King }) }
+        Effect::Moved { from: (0, 0), to: (1, 0), captured: Some(Piece { color: Color::Black, kind: Kind::King }), chain: false }
     );
 
     let mut s2 = empty(Color::Red, Side::First);
@@ -116,7 +118,7 @@ fn cannon_jumps_one_screen_to_capture() {
     let eff = apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (3, 0) }).unwrap();
     assert_eq!(
         eff,
-        Effect::Moved { from: (0, 0), to: (3, 0), captured: Some(Piece { color: Color::Black, kind: Kind::King }) }
+        Effect::Moved { from: (0, 0), to: (3, 0), captured: Some(Piece { color: Color::Black, kind: Kind::King }), chain: false }
     );
 }
 
@@ -151,3 +153,134 @@ fn ongoing_before_first_flip() {
     let s = from_deck(deck);
     assert_eq!(status(&s), Outcome::Continue);
 }
+
+#[test]
+fn pawn_takes_king_toggle() {
+    let mut s = empty(Color::Red, Side::First);
+    s.rules.pawn_takes_king = false;
+    up(&mut s, 0, 0, Color::Red, Kind::Pawn);
+    up(&mut s, 1, 0, Color::Black, Kind::King);
+    up(&mut s, 0, 1, Color::Red, Kind::King);
+    up(&mut s, 1, 1, Color::Black, Kind::Pawn);
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (1, 0) }),
+        Err("cannot_capture")
+    );
+    // 反過來將照位階吃卒
+    assert!(apply_action(&mut s, Side::First, Action::Move { from: (0, 1), to: (1, 1) }).is_ok());
+}
+
+#[test]
+fn rook_slides_when_enabled() {
+    let mut s = empty(Color::Red, Side::First);
+    up(&mut s, 0, 0, Color::Red, Kind::Rook);
+    up(&mut s, 5, 0, Color::Black, Kind::Horse);
+    up(&mut s, 0, 3, Color::Black, Kind::Guard);
+    s.rules.rook_slides = true;
+    // 被擋不能越子
+    up(&mut s, 3, 0, Color::Black, Kind::Pawn);
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (5, 0) }),
+        Err("not_adjacent")
+    );
+    // 位階仍然算：車吃不了士
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (0, 3) }),
+        Err("cannot_capture")
+    );
+    let eff = apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (2, 0) }).unwrap();
+    assert_eq!(eff, Effect::Moved { from: (0, 0), to: (2, 0), captured: None, chain: false });
+}
+
+#[test]
+fn cannon_hits_hidden_when_enabled() {
+    let mut s = empty(Color::Red, Side::First);
+    up(&mut s, 0, 0, Color::Red, Kind::Cannon);
+    up(&mut s, 1, 0, Color::Black, Kind::Pawn);
+    hidden(&mut s, 2, 0, Color::Red, Kind::Guard);
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (2, 0) }),
+        Err("target_hidden")
+    );
+    s.rules.cannon_hits_hidden = true;
+    // 翻到自己的子一樣吃掉
+    let eff = apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (2, 0) }).unwrap();
+    assert_eq!(
+        eff,
+        Effect::Moved { from: (0, 0), to: (2, 0), captured: Some(Piece { color: Color::Red, kind: Kind::Guard }), chain: false }
+    );
+}
+
+#[test]
+fn chain_captures_keep_the_turn() {
+    let mut s = empty(Color::Red, Side::First);
+    s.rules.chain_captures = true;
+    up(&mut s, 0, 0, Color::Red, Kind::Rook);
+    up(&mut s, 1, 0, Color::Black, Kind::Pawn);
+    up(&mut s, 2, 0, Color::Black, Kind::Horse);
+    up(&mut s, 7, 3, Color::Black, Kind::Pawn);
+    up(&mut s, 6, 3, Color::Red, Kind::Pawn);
+    let eff = apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (1, 0) }).unwrap();
+    assert!(matches!(eff, Effect::Moved { chain: true, .. }));
+    assert_eq!(s.turn, Side::First);
+    // 連吃中只能動同一子、且必須吃
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (6, 3), to: (7, 3) }),
+        Err("chain_in_progress")
+    );
+    assert_eq!(
+        apply_action(&mut s, Side::First, Action::Move { from: (1, 0), to: (1, 1) }),
+        Err("chain_must_capture")
+    );
+    // 吃完沒得再吃 → 自動換手
+    let eff = apply_action(&mut s, Side::First, Action::Move { from: (1, 0), to: (2, 0) }).unwrap();
+    assert!(matches!(eff, Effect::Moved { chain: false, .. }));
+    assert_eq!(s.turn, Side::Second);
+    assert_eq!(s.chain, None);
+}
+
+#[test]
+fn chain_can_be_ended_early() {
+    let mut s = empty(Color::Red, Side::First);
+    s.rules.chain_captures = true;
+    up(&mut s, 0, 0, Color::Red, Kind::Rook);
+    up(&mut s, 1, 0, Color::Black, Kind::Pawn);
+    up(&mut s, 2, 0, Color::Black, Kind::Horse);
+    assert_eq!(apply_action(&mut s, Side::First, Action::EndChain), Err("no_chain"));
+    apply_action(&mut s, Side::First, Action::Move { from: (0, 0), to: (1, 0) }).unwrap();
+    assert_eq!(apply_action(&mut s, Side::First, Action::EndChain), Ok(Effect::ChainEnded));
+    assert_eq!(s.turn, Side::Second);
+}
+
+#[test]
+fn quiet_draw_threshold_is_per_table() {
+    let mut s = empty(Color::Red, Side::First);
+    up(&mut s, 0, 0, Color::Red, Kind::King);
+    up(&mut s, 7, 3, Color::Black, Kind::King);
+    s.rules.quiet_draw = 20;
+    s.quiet = 20;
+    assert_eq!(status(&s), Outcome::Draw);
+    s.rules.quiet_draw = 60;
+    assert_eq!(status(&s), Outcome::Continue);
+}
+
+#[test]
+fn hidden_deck_only_before_first_flip() {
+    let s = initial_state(Rules::default());
+    let deck = hidden_deck(&s).expect("開局全蓋");
+    assert!(is_full_deck(&deck));
+    let mut again = from_deck(deck);
+    assert!(again.board == s.board, "同一副排列應鋪出同一盤面");
+    apply_action(&mut again, Side::First, Action::Flip { col: 0, row: 0 }).unwrap();
+    assert!(hidden_deck(&again).is_none());
+}
+
+#[test]
+fn is_full_deck_rejects_wrong_counts() {
+    let mut deck = full_deck();
+    assert!(is_full_deck(&deck));
+    deck[1] = deck[0]; // 兩個帥、少一個仕
+    assert!(!is_full_deck(&deck));
+    deck.pop();
+    assert!(!is_full_deck(&deck));
+}
@@ -4,9 +4,11 @@
 
 use serde_json::{json, Value};
 
-use super::engine::{self, Action, BanqiState, Effect, Outcome, Piece, Side as BSide};
+use super::engine::{self, Action, BanqiState, Cell, Color, Effect, Kind, Outcome, Piece, Rules, Side as BSide};
+use crate::games::common::bot::NoBot;
 use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
 
+#[derive(Clone)]
 pub struct BanqiGame(BanqiState);
 
 fn to_common(s: BSide) -> Side {
@@ -35,11 +37,62 @@ fn piece_json(p: Piece) -> Value {
     json!({ "color": p.color.as_str(), "kind": p.kind.as_str() })
 }
 
+fn parse_piece(v: &Value) -> Option<Piece> {
+    Some(Piece { color: Color::parse(v["color"].as_str()?)?, kind: Kind::parse(v["kind"].as_str()?)? })
+}
+
+/// 正規化過的房規選項 → `Rules`（缺的欄位取預設）。
+fn rules_of(options: &Value) -> Rules {
+    let d = Rules::default();
+    let flag = |key: &str, default: bool| options[key].as_bool().unwrap_or(default);
+    Rules {
+        chain_captures: flag("chain_captures", d.chain_captures),
+        rook_slides: flag("rook_slides", d.rook_slides),
+        cannon_hits_hidden: flag("cannon_hits_hidden", d.cannon_hits_hidden),
+        pawn_takes_king: flag("pawn_takes_king", d.pawn_takes_king),
+        quiet_draw: options["quiet_draw"].as_u64().map_or(d.quiet_draw, |n| n as u32),
+    }
+}
+
 impl GameEngine for BanqiGame {
     const NAME: &'static str = "banqi";
+    type Bot = NoBot;
+
+    /// 房規：`{"chain_captures", "rook_slides", "cannon_hits_hidden", "pawn_takes_king": bool, "quiet_draw": 20..=200}`，
+    /// 缺的欄位取預設。正規化後整份進大廳清單，讓入座前就看得到這桌玩哪種規則。
+    fn parse_options(v: &Value) -> Option<Value> {
+        let empty = serde_json::Map::new();
+        let o = if v.is_null() { &empty } else { v.as_object()? };
+        let d = Rules::default();
+        let flag = |key: &str, default: bool| o.get(key).map_or(Some(default), Value::as_bool);
+        let quiet_draw = o.get("quiet_draw").map_or(Some(d.quiet_draw as u64), Value::as_u64)?;
+        if !engine::QUIET_DRAW_RANGE.contains(&u32::try_from(quiet_draw).ok()?) {
+            return None;
+        }
+        Some(json!({
+            "chain_captures": flag("chain_captures", d.chain_captures)?,
+            "rook_slides": flag("rook_slides", d.rook_slides)?,
+            "cannon_hits_hidden": flag("cannon_hits_hidden", d.cannon_hits_hidden)?,
+            "pawn_takes_king": flag("pawn_takes_king", d.pawn_takes_king)?,
+            "quiet_draw": quiet_draw,
+        }))
+    }
+
+    fn initial(options: &Value) -> Self {
+        BanqiGame(engine::initial_state(rules_of(options)))
+    }
 
-    fn initial() -> Self {
-        BanqiGame(engine::initial_state())
+    /// 開局的蓋牌排列（32 子，鋪盤順序）。
+    fn initial_seed(&self) -> Value {
+        engine::hidden_deck(&self.0).map_or(Value::Null, |deck| deck.into_iter().map(piece_json).collect())
+    }
+
+    fn initial_from_seed(options: &Value, seed: &Value) -> Option<Self> {
+        let deck = seed.as_array()?.iter().map(parse_piece).collect::<Option<Vec<_>>>()?;
+        if !engine::is_full_deck(&deck) {
+            return None;
+        }
+        Some(BanqiGame(BanqiState { rules: rules_of(options), ..engine::from_deck(deck) }))
     }
 
     fn turn(&self) -> Side {
@@ -70,6 +123,7 @@ impl GameEngine for BanqiGame {
                 };
                 Action::Move { from, to }
             }
+            Some("end_chain") => Action::EndChain,
             _ => return Err("bad_action".into()),
         };
 
@@ -82,15 +136,17 @@ impl GameEngine for BanqiGame {
                 "at": [col, row],
                 "piece": piece_json(piece),
             }),
-            Effect::Moved { from, to, captured } => json!({
+            Effect::Moved { from, to, captured, chain } => json!({
                 "action": "move",
                 "from": [from.0, from.1],
                 "to": [to.0, to.1],
                 "captured": captured.map(piece_json),
+                "chain": chain,
             }),
+            Effect::ChainEnded => json!({ "action": "end_chain" }),
         };
 
-        Ok(Applied { move_data, extra: Vec::new() })
+        Ok(Applied { move_data, extra: Vec::new(), swap_seats: false })
     }
 
     fn status(&self) -> GameStatus {
@@ -103,4 +159,30 @@ impl GameEngine for BanqiGame {
             Outcome::Draw => GameStatus::Draw { reason: "draw_quiet" },
         }
     }
+
+    /// 未翻子只給 `hidden`，不帶棋種 —— 快照送給任何中途加入者，不能變成偷看底牌的管道。
+    fn snapshot(&self) -> Value {
+        let mut cells = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                match cell {
+                    Cell::Empty => {}
+                    Cell::Hidden(_) => cells.push(json!({ "at": [col, row], "hidden": true })),
+                    Cell::Up(p) => cells.push(json!({ "at": [col, row], "piece": piece_json(*p) })),
+                }
+            }
+        }
+        json!({
+            "cells": cells,
+            "first_color": self.0.first_color.map(|c| c.as_str()),
+            "chain": self.0.chain.map(|(c, r)| json!([c, r])),
+            "quiet": self.0.quiet,
+            "quiet_draw": self.0.rules.quiet_draw,
+        })
+    }
+
+    /// 翻子、炮打暗子都會讓蓋著的子變少。
+    fn reveals_hidden(&self, before: &Self) -> bool {
+        engine::hidden_count(&self.0) < engine::hidden_count(&before.0)
+    }
 }
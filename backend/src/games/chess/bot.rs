@@ -0,0 +1,157 @@
+//! 象棋電腦：alpha-beta + 子力 / 位置評估，吃子依 MVV-LVA 排序並做靜態搜尋。
+
+use std::time::Duration;
+
+use super::engine;
+use super::types::{GameState, Move, PieceKind, Side, Square, Status};
+use crate::games::common::bot::{self as search, BotLevel, SearchLimits, Searchable, MATE};
+
+fn value(k: PieceKind) -> i32 {
+    match k {
+        PieceKind::Rook => 900,
+        PieceKind::Cannon => 450,
+        PieceKind::Horse => 400,
+        PieceKind::Elephant | PieceKind::Advisor => 200,
+        PieceKind::Soldier => 100,
+        PieceKind::General => 0,
+    }
+}
+
+/// 位置分。`row` 已轉成己方視角（0 = 底線，5 起過河）。
+fn placement(k: PieceKind, col: i8, row: i8) -> i32 {
+    let central = 4 - (col - 4).abs() as i32;
+    match k {
+        // 過河兵價值翻倍，越靠近九宮越好（但底線兵沒用）
+        PieceKind::Soldier if row >= 5 => 100 + if row < 9 { central * 10 + (row as i32 - 5) * 15 } else { 0 },
+        PieceKind::Horse => central * 8 + if (3..=7).contains(&row) { 20 } else { 0 },
+        PieceKind::Cannon if col == 4 => 20,
+        PieceKind::Rook if row >= 5 => 20,
+        _ => 0,
+    }
+}
+
+#[derive(Clone)]
+struct Pos(GameState);
+
+impl Pos {
+    fn at(&self, sq: Square) -> Option<PieceKind> {
+        self.0.board[sq.row as usize][sq.col as usize].map(|p| p.kind)
+    }
+
+    fn sorted(&self, mut moves: Vec<Move>) -> Vec<Move> {
+        moves.sort_by_cached_key(|mv| {
+            let gain = self.at(mv.to).map_or(0, |v| value(v) * 10 - self.at(mv.from).map_or(0, value) / 10);
+            std::cmp::Reverse(gain)
+        });
+        moves
+    }
+}
+
+impl Searchable for Pos {
+    type Move = Move;
+
+    fn moves(&self) -> Vec<Move> {
+        self.sorted(engine::all_legal_moves(&self.0))
+    }
+
+    fn play(&mut self, mv: Move) {
+        engine::apply(&mut self.0, mv);
+    }
+
+    fn evaluate(&self) -> i32 {
+        let mut score = 0;
+        for (r, line) in self.0.board.iter().enumerate() {
+            for (c, cell) in line.iter().enumerate() {
+                let Some(p) = cell else { continue };
+                let row = match p.side {
+                    Side::Red => r as i8,
+                    Side::Black => 9 - r as i8,
+                };
+                let v = value(p.kind) + placement(p.kind, c as i8, row);
+                score += if p.side == self.0.turn { v } else { -v };
+            }
+        }
+        score
+    }
+
+    fn terminal(&self) -> Option<i32> {
+        matches!(engine::game_status(&self.0), Status::Draw | Status::RepetitionDraw).then_some(0)
+    }
+
+    /// 將死與困斃在象棋都是行棋方負。
+    fn no_moves_score(&self) -> i32 {
+        -MATE
+    }
+
+    fn noisy_moves(&self) -> Vec<Move> {
+        let captures = engine::all_legal_moves(&self.0)
+            .into_iter()
+            .filter(|mv| self.at(mv.to).is_some())
+            .collect();
+        self.sorted(captures)
+    }
+}
+
+/// 為行棋方選一步。
+pub fn choose(state: &GameState, level: BotLevel) -> Option<Move> {
+    let limits = match level {
+        BotLevel::Easy => SearchLimits { max_depth: 1, budget: Duration::from_millis(300), noise: 120 },
+        BotLevel::Normal => SearchLimits { max_depth: 3, budget: Duration::from_secs(1), noise: 15 },
+        BotLevel::Hard => SearchLimits { max_depth: 6, budget: Duration::from_millis(2500), noise: 0 },
+    };
+    search::best_move(&Pos(state.clone()), limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::chess::types::Piece;
+
+    fn empty(turn: Side) -> GameState {
+        let mut s = engine::initial_state();
+        s.board = Default::default();
+        s.turn = turn;
+        s
+    }
+
+    fn put(s: &mut GameState, (c, r): (i8, i8), kind: PieceKind, side: Side) {
+        s.board[r as usize][c as usize] = Some(Piece::new(kind, side));
+    }
+
+    #[test]
+    fn wins_in_one() {
+        // 黑將 (3,9)：(4,9) 被帥對臉封住、(3,8) 被俥控住，一步即可殺或困斃
+        let mut s = empty(Side::Red);
+        put(&mut s, (4, 0), PieceKind::General, Side::Red);
+        put(&mut s, (0, 8), PieceKind::Rook, Side::Red);
+        put(&mut s, (8, 8), PieceKind::Rook, Side::Red);
+        put(&mut s, (3, 9), PieceKind::General, Side::Black);
+        let mv = choose(&s, BotLevel::Normal).unwrap();
+        engine::apply(&mut s, mv);
+        // 困斃在象棋同樣是被困方負
+        assert!(matches!(
+            engine::game_status(&s),
+            Status::Checkmate { winner: Side::Red } | Status::Stalemate { loser: Side::Black }
+        ));
+    }
+
+    #[test]
+    fn takes_hanging_rook() {
+        let mut s = empty(Side::Black);
+        put(&mut s, (3, 0), PieceKind::General, Side::Red);
+        put(&mut s, (4, 5), PieceKind::Rook, Side::Red);
+        put(&mut s, (5, 9), PieceKind::General, Side::Black);
+        put(&mut s, (3, 7), PieceKind::Horse, Side::Black);
+        for level in [BotLevel::Normal, BotLevel::Hard] {
+            let mv = choose(&s, level).unwrap();
+            assert_eq!(mv.to, Square::new(4, 5), "{level:?}");
+        }
+    }
+
+    #[test]
+    fn opening_move_is_legal() {
+        let s = engine::initial_state();
+        let mv = choose(&s, BotLevel::Easy).unwrap();
+        assert!(engine::is_legal(&s, mv).is_ok());
+    }
+}
@@ -1,8 +1,16 @@
-//! 象棋規則引擎 — 純函式，可單測。標準走法 + 將軍/將死 + 飛將 + 困斃 + 60 回合和。
+//! 象棋規則引擎 — 純函式，可單測。標準走法 + 將軍/將死 + 飛將 + 困斃 + 60 回合和
+//! + 重複局面裁決（亞洲規則簡化版：長將、長捉判負，雙方同等則和）。
 
-use super::types::{Board, GameState, IllegalReason, Move, Piece, PieceKind, Side, Square, Status};
+use super::types::{Board, GameState, IllegalReason, Move, Piece, PieceKind, PlyMark, Side, Square, Status};
+use crate::games::common::zobrist;
 
 const N_NO_CAPTURE_DRAW: u8 = 120; // 60 回合 = 120 半步
+/// 同一局面出現幾次開始裁決。
+const N_REPETITION: usize = 3;
+
+// Zobrist 鍵配置：14 種棋子 × 90 格、黑方行棋。
+const Z_BLACK: usize = 14 * 90;
+const ZOBRIST: [u64; Z_BLACK + 1] = zobrist::table();
 
 /// 標準開局局面（紅先行）。
 pub fn initial_state() -> GameState {
@@ -60,6 +68,7 @@ pub fn initial_state() -> GameState {
         board,
         turn: Side::Red,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     }
 }
 
@@ -276,6 +285,146 @@ pub fn apply(state: &mut GameState, mv: Move) {
     state.turn = state.turn.opponent();
 }
 
+/// 對局中實際走一步：先記下重複判定資訊（`PlyMark`）再 `apply`。吃子後不可能回到之前的局面，紀錄清空。
+/// 搜尋 / 合法性模擬用 `apply` 即可（不記紀錄，省下逐步的捉子判定）。
+pub fn play(state: &mut GameState, mv: Move) {
+    let captured = piece_at(&state.board, mv.to).is_some();
+    let mark = mark_move(state, mv);
+    apply(state, mv);
+    if captured {
+        state.history.clear();
+    } else {
+        state.history.push(mark);
+    }
+}
+
+/// 局面 hash（棋子 + 行棋方）。
+pub fn position_hash(state: &GameState) -> u64 {
+    let mut h = 0;
+    for (r, line) in state.board.iter().enumerate() {
+        for (c, cell) in line.iter().enumerate() {
+            if let Some(p) = cell {
+                let piece = p.side as usize * 7 + p.kind as usize;
+                h ^= ZOBRIST[piece * 90 + r * 9 + c];
+            }
+        }
+    }
+    if state.turn == Side::Black {
+        h ^= ZOBRIST[Z_BLACK];
+    }
+    h
+}
+
+fn mark_move(state: &GameState, mv: Move) -> PlyMark {
+    let me = state.turn;
+    let mut next = state.clone();
+    apply(&mut next, mv);
+    // 走之前就有的威脅（走動的子換到新位置比對），走之後多出來的才算捉
+    let before: Vec<(Square, Square)> = chase_threats(&state.board, me)
+        .into_iter()
+        .map(|(a, t)| (if a == mv.from { mv.to } else { a }, t))
+        .collect();
+    let chase = chase_threats(&next.board, me).iter().any(|t| !before.contains(t));
+    PlyMark {
+        hash: position_hash(state),
+        check: is_in_check(&next, me.opponent()),
+        chase,
+    }
+}
+
+/// `side` 此刻構成「捉」的威脅 (攻擊者, 目標)：能合法吃掉對方的子，且該子無根（吃了對方吃不回），
+/// 或是馬 / 炮捉車（車有根也算）。將、兵捉子不算；未過河的兵不算被捉。
+fn chase_threats(board: &Board, side: Side) -> Vec<(Square, Square)> {
+    let probe = GameState { board: *board, turn: side, halfmove_no_capture: 0, history: Vec::new() };
+    let mut out = Vec::new();
+    for (r, line) in board.iter().enumerate() {
+        for (c, cell) in line.iter().enumerate() {
+            let Some(attacker) = cell else { continue };
+            if attacker.side != side || matches!(attacker.kind, PieceKind::General | PieceKind::Soldier) {
+                continue;
+            }
+            let from = Square::new(c as i8, r as i8);
+            for (tr, tline) in board.iter().enumerate() {
+                for (tc, tcell) in tline.iter().enumerate() {
+                    let Some(target) = tcell else { continue };
+                    if target.side == side
+                        || target.kind == PieceKind::General
+                        || (target.kind == PieceKind::Soldier && !crossed_river(target.side, tr as i8))
+                    {
+                        continue;
+                    }
+                    let to = Square::new(tc as i8, tr as i8);
+                    let mv = Move { from, to };
+                    if is_legal(&probe, mv).is_err() {
+                        continue;
+                    }
+                    let rook_hunt = target.kind == PieceKind::Rook
+                        && matches!(attacker.kind, PieceKind::Horse | PieceKind::Cannon);
+                    let mut after = probe.clone();
+                    apply(&mut after, mv);
+                    if rook_hunt || !is_attacked(&after.board, to, target.side) {
+                        out.push((from, to));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+/// 違例輕重：閒著 < 長捉 < 長將。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Violation {
+    Idle,
+    Chase,
+    Check,
+}
+
+fn violation<'a>(marks: impl Iterator<Item = &'a PlyMark>) -> Violation {
+    let mut all_check = true;
+    let mut all_forcing = true;
+    for m in marks {
+        all_check &= m.check;
+        all_forcing &= m.check || m.chase;
+    }
+    if all_check {
+        Violation::Check
+    } else if all_forcing {
+        Violation::Chase
+    } else {
+        Violation::Idle
+    }
+}
+
+/// 目前局面（含本次）在上次吃子後出現過幾次。
+pub fn repetitions(state: &GameState) -> usize {
+    let now = position_hash(state);
+    1 + state.history.iter().filter(|m| m.hash == now).count()
+}
+
+/// 目前局面第 3 次出現時的裁決；未達次數回 None。
+/// 看從第一次出現到現在的所有步：一方每步皆將為長將、每步皆將或捉為長捉，違例較重的一方判負，同等則和。
+fn repetition_ruling(state: &GameState) -> Option<Status> {
+    let now = position_hash(state);
+    let first = state.history.iter().position(|m| m.hash == now)?;
+    let cycle = &state.history[first..];
+    if 1 + cycle.iter().filter(|m| m.hash == now).count() < N_REPETITION {
+        return None;
+    }
+    // 同一局面行棋方相同 → cycle[0] 是目前行棋方走的，之後雙方交替
+    let mine = violation(cycle.iter().step_by(2));
+    let theirs = violation(cycle.iter().skip(1).step_by(2));
+    let (loser, worst) = match mine.cmp(&theirs) {
+        std::cmp::Ordering::Equal => return Some(Status::RepetitionDraw),
+        std::cmp::Ordering::Greater => (state.turn, mine),
+        std::cmp::Ordering::Less => (state.turn.opponent(), theirs),
+    };
+    Some(match worst {
+        Violation::Check => Status::PerpetualCheck { loser },
+        _ => Status::PerpetualChase { loser },
+    })
+}
+
 /// 完整合法性檢查（含送將/飛將）。
 pub fn is_legal(state: &GameState, mv: Move) -> Result<(), IllegalReason> {
     if !mv.from.in_bounds() || !mv.to.in_bounds() {
@@ -337,6 +486,49 @@ pub fn legal_moves(state: &GameState, sq: Square) -> Vec<Square> {
     out
 }
 
+/// 行棋方的全部合法步。只對各子形狀上可能的落點跑 `is_legal`，比逐格掃描快得多（給電腦搜尋用）。
+pub fn all_legal_moves(state: &GameState) -> Vec<Move> {
+    const HORSE: [(i8, i8); 8] = [(1, 2), (2, 1), (2, -1), (1, -2), (-1, -2), (-2, -1), (-2, 1), (-1, 2)];
+    const ELEPHANT: [(i8, i8); 4] = [(2, 2), (2, -2), (-2, 2), (-2, -2)];
+    const ADVISOR: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+    const ORTHO: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+    let mut out = Vec::new();
+    for (r, line) in state.board.iter().enumerate() {
+        for (c, cell) in line.iter().enumerate() {
+            let Some(p) = cell else { continue };
+            if p.side != state.turn {
+                continue;
+            }
+            let from = Square::new(c as i8, r as i8);
+            let mut push = |to: Square| {
+                let mv = Move { from, to };
+                if is_legal(state, mv).is_ok() {
+                    out.push(mv);
+                }
+            };
+            match p.kind {
+                PieceKind::Rook | PieceKind::Cannon => {
+                    (0..9).filter(|&col| col != from.col).for_each(|col| push(Square::new(col, from.row)));
+                    (0..10).filter(|&row| row != from.row).for_each(|row| push(Square::new(from.col, row)));
+                }
+                kind => {
+                    let offsets: &[(i8, i8)] = match kind {
+                        PieceKind::Horse => &HORSE,
+                        PieceKind::Elephant => &ELEPHANT,
+                        PieceKind::Advisor => &ADVISOR,
+                        _ => &ORTHO,
+                    };
+                    for (dc, dr) in offsets {
+                        push(Square::new(from.col + dc, from.row + dr));
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
 /// 行棋方是否還有任何合法步。
 fn has_any_legal_move(state: &GameState) -> bool {
     for r in 0..10 {
@@ -365,6 +557,9 @@ pub fn game_status(state: &GameState) -> Status {
         // 困斃：行棋方判負
         return Status::Stalemate { loser: state.turn };
     }
+    if let Some(ruling) = repetition_ruling(state) {
+        return ruling;
+    }
     if state.halfmove_no_capture >= N_NO_CAPTURE_DRAW {
         return Status::Draw;
     }
@@ -29,6 +29,7 @@ fn bare_kings(turn: Side) -> GameState {
         board,
         turn,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     }
 }
 
@@ -135,6 +136,7 @@ fn flying_general_move_is_illegal() {
         board,
         turn: Side::Red,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     };
     // 兵橫走不行（未過河），改用過河兵測：放過河紅兵在 (4,5)
     let mut board2 = empty_board();
@@ -145,6 +147,7 @@ fn flying_general_move_is_illegal() {
         board: board2,
         turn: Side::Red,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     };
     // 兵從 (4,5) 橫走到 (3,5) → 中間清空 → 兩將對臉 → FlyingGeneral
     assert_eq!(
@@ -166,6 +169,7 @@ fn cannot_leave_own_general_in_check() {
         board,
         turn: Side::Red,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     };
     // 現在沒被將（士擋著）
     assert!(!is_in_check(&s, Side::Red));
@@ -189,6 +193,7 @@ fn checkmate_detected() {
         board,
         turn: Side::Black,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     };
     assert!(is_in_check(&s, Side::Black));
     assert_eq!(game_status(&s), Status::Checkmate { winner: Side::Red });
@@ -208,6 +213,7 @@ fn stalemate_is_a_loss() {
         board,
         turn: Side::Black,
         halfmove_no_capture: 0,
+        history: Vec::new(),
     };
     assert!(!is_in_check(&s, Side::Black));
     assert_eq!(game_status(&s), Status::Stalemate { loser: Side::Black });
@@ -247,3 +253,93 @@ fn legal_moves_filters_self_check() {
     // 非行棋方（黑子）回空
     assert!(legal_moves(&s, sq(0, 9)).is_empty());
 }
+
+#[test]
+fn all_legal_moves_matches_per_square_scan() {
+    let mut s = initial_state();
+    apply(&mut s, mv(1, 2, 4, 2)); // 炮二平五
+    apply(&mut s, mv(7, 9, 6, 7)); // 馬8進7
+    for state in [initial_state(), s] {
+        let mut expected = Vec::new();
+        for r in 0..10 {
+            for c in 0..9 {
+                for to in legal_moves(&state, sq(c, r)) {
+                    expected.push(Move { from: sq(c, r), to });
+                }
+            }
+        }
+        let all = all_legal_moves(&state);
+        assert_eq!(all.len(), expected.len());
+        assert!(expected.iter().all(|m| all.contains(m)));
+    }
+    assert_eq!(all_legal_moves(&initial_state()).len(), 44);
+}
+
+// ---- 重複局面裁決 ----
+
+/// 依序 `play` 一串走步（會記錄重複判定資訊）。
+fn play_all(s: &mut GameState, moves: &[Move]) {
+    for &m in moves {
+        assert!(is_legal(s, m).is_ok(), "{m:?}");
+        play(s, m);
+    }
+}
+
+#[test]
+fn perpetual_check_loses() {
+    // 俥在 8、9 線來回將軍，黑將上下躲
+    let mut s = bare_kings(Side::Red);
+    put(&mut s.board, 0, 8, PieceKind::Rook, Side::Red);
+    let cycle = [mv(0, 8, 0, 9), mv(3, 9, 3, 8), mv(0, 9, 0, 8), mv(3, 8, 3, 9)];
+    play_all(&mut s, &cycle);
+    assert_eq!(game_status(&s), Status::Ongoing, "第 2 次出現還不裁決");
+    play_all(&mut s, &cycle);
+    assert_eq!(game_status(&s), Status::PerpetualCheck { loser: Side::Red });
+}
+
+#[test]
+fn perpetual_chase_of_unprotected_piece_loses() {
+    // 俥沿 8 路追無根的黑砲，砲上下躲
+    let mut s = bare_kings(Side::Red);
+    put(&mut s.board, 8, 7, PieceKind::Rook, Side::Red);
+    put(&mut s.board, 6, 6, PieceKind::Cannon, Side::Black);
+    let cycle = [mv(8, 7, 8, 6), mv(6, 6, 6, 7), mv(8, 6, 8, 7), mv(6, 7, 6, 6)];
+    play_all(&mut s, &cycle);
+    play_all(&mut s, &cycle);
+    assert_eq!(game_status(&s), Status::PerpetualChase { loser: Side::Red });
+}
+
+#[test]
+fn chasing_a_protected_piece_is_a_draw() {
+    // 同上，但黑車在 (6,9) 保護砲：俥吃了會被吃回，不算捉
+    let mut s = bare_kings(Side::Red);
+    put(&mut s.board, 8, 7, PieceKind::Rook, Side::Red);
+    put(&mut s.board, 6, 6, PieceKind::Cannon, Side::Black);
+    put(&mut s.board, 6, 9, PieceKind::Rook, Side::Black);
+    let cycle = [mv(8, 7, 8, 6), mv(6, 6, 6, 7), mv(8, 6, 8, 7), mv(6, 7, 6, 6)];
+    play_all(&mut s, &cycle);
+    play_all(&mut s, &cycle);
+    assert_eq!(game_status(&s), Status::RepetitionDraw);
+}
+
+#[test]
+fn mutual_idle_repetition_is_a_draw() {
+    // 開局雙方馬跳出跳回
+    let mut s = initial_state();
+    let cycle = [mv(1, 0, 2, 2), mv(1, 9, 2, 7), mv(2, 2, 1, 0), mv(2, 7, 1, 9)];
+    play_all(&mut s, &cycle);
+    play_all(&mut s, &cycle);
+    assert_eq!(game_status(&s), Status::RepetitionDraw);
+}
+
+#[test]
+fn capture_clears_repetition_history() {
+    let mut s = bare_kings(Side::Red);
+    put(&mut s.board, 0, 0, PieceKind::Rook, Side::Red);
+    put(&mut s.board, 0, 5, PieceKind::Soldier, Side::Black);
+    play(&mut s, mv(4, 0, 4, 1));
+    assert_eq!(s.history.len(), 1);
+    play(&mut s, mv(3, 9, 3, 8));
+    play(&mut s, mv(0, 0, 0, 5)); // 吃卒
+    assert!(s.history.is_empty());
+}
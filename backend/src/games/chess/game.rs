@@ -2,12 +2,26 @@
 
 use serde_json::{json, Value};
 
+use super::bot;
 use super::engine;
-use super::types::{GameState, Move, Side as ChSide, Square, Status};
+use super::notation::{self, Format};
+use super::types::{GameState, Move, PieceKind, Side as ChSide, Square, Status};
+use crate::games::common::bot::{BotLevel, GameBot};
 use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+use crate::games::common::notation::{ExportMeta, Exported};
 
+#[derive(Clone)]
 pub struct ChessGame(GameState);
 
+pub struct ChessBot;
+
+impl GameBot<ChessGame> for ChessBot {
+    fn choose(engine: &ChessGame, level: BotLevel) -> Option<Value> {
+        let mv = bot::choose(&engine.0, level)?;
+        Some(json!({ "from": sq_json(mv.from), "to": sq_json(mv.to) }))
+    }
+}
+
 fn to_common(s: ChSide) -> Side {
     match s {
         ChSide::Red => Side::First,
@@ -31,10 +45,24 @@ fn sq_json(sq: Square) -> Value {
     json!([sq.col, sq.row])
 }
 
+/// 棋種字串，與前端 `chess-logic.ts` 的 `PieceType` 一致。
+fn kind_str(k: PieceKind) -> &'static str {
+    match k {
+        PieceKind::Rook => "chariot",
+        PieceKind::Horse => "horse",
+        PieceKind::Cannon => "cannon",
+        PieceKind::Elephant => "elephant",
+        PieceKind::Advisor => "advisor",
+        PieceKind::General => "general",
+        PieceKind::Soldier => "soldier",
+    }
+}
+
 impl GameEngine for ChessGame {
     const NAME: &'static str = "chess";
+    type Bot = ChessBot;
 
-    fn initial() -> Self {
+    fn initial(_options: &Value) -> Self {
         ChessGame(engine::initial_state())
     }
 
@@ -58,7 +86,7 @@ impl GameEngine for ChessGame {
         };
         let mv = Move { from, to };
         engine::is_legal(&self.0, mv).map_err(|r| r.code().to_string())?;
-        engine::apply(&mut self.0, mv);
+        engine::play(&mut self.0, mv);
 
         let mut extra = Vec::new();
         if matches!(engine::game_status(&self.0), Status::Ongoing)
@@ -73,6 +101,7 @@ impl GameEngine for ChessGame {
         Ok(Applied {
             move_data: json!({ "from": sq_json(from), "to": sq_json(to) }),
             extra,
+            swap_seats: false,
         })
     }
 
@@ -88,6 +117,50 @@ impl GameEngine for ChessGame {
                 reason: "stalemate",
             },
             Status::Draw => GameStatus::Draw { reason: "draw_60" },
+            Status::RepetitionDraw => GameStatus::Draw { reason: "repetition" },
+            Status::PerpetualCheck { loser } => GameStatus::Win {
+                winner: to_common(loser.opponent()),
+                reason: "perpetual_check",
+            },
+            Status::PerpetualChase { loser } => GameStatus::Win {
+                winner: to_common(loser.opponent()),
+                reason: "perpetual_chase",
+            },
+        }
+    }
+
+    fn snapshot(&self) -> Value {
+        let mut pieces = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(p) = cell {
+                    pieces.push(json!({
+                        "at": [col, row],
+                        "side": Self::side_label(to_common(p.side)),
+                        "type": kind_str(p.kind),
+                    }));
+                }
+            }
         }
+        json!({ "pieces": pieces })
+    }
+
+    /// 預設 WXF；`format: "iccs"` 改用 ICCS 座標記法。
+    fn export(plies: &[(&Self, &Value)], _last: &Self, meta: &ExportMeta) -> Option<Exported> {
+        let format = meta.format.as_deref().and_then(Format::parse).unwrap_or(Format::Wxf);
+        let moves = plies
+            .iter()
+            .map(|(pos, data)| {
+                let from = parse_square(data.get("from"))?;
+                let to = parse_square(data.get("to"))?;
+                Some((&pos.0, Move { from, to }))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Exported {
+            format: format.as_str(),
+            extension: "pgn",
+            mime: "text/plain; charset=utf-8",
+            text: notation::pgn(&moves, format, meta),
+        })
     }
 }
@@ -0,0 +1,292 @@
+//! 象棋記譜：WXF（`C2.5`、`H8+7`）與 ICCS（`H2-E2`），包成象棋 PGN 匯出；FEN 讀入。
+//!
+//! 座標：col 0..=8 由紅方左至右、row 0..=9 由紅方底線往上。
+//! WXF 路數從各自右手邊起算：紅方 `9 - col`、黑方 `col + 1`；「進」為朝對方底線。
+
+use super::engine;
+use super::types::{Board, GameState, Move, Piece, PieceKind, Side};
+use crate::games::common::notation::{pgn_movetext, pgn_result, pgn_tag, ExportMeta};
+
+/// 支援的記法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Wxf,
+    Iccs,
+}
+
+impl Format {
+    /// `wxf` / `iccs`；其他值回 None（呼叫端用預設 WXF）。
+    pub fn parse(s: &str) -> Option<Format> {
+        match s {
+            "wxf" => Some(Format::Wxf),
+            "iccs" => Some(Format::Iccs),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Format::Wxf => "wxf",
+            Format::Iccs => "iccs",
+        }
+    }
+}
+
+fn letter(k: PieceKind) -> char {
+    match k {
+        PieceKind::Rook => 'R',
+        PieceKind::Horse => 'H',
+        PieceKind::Elephant => 'E',
+        PieceKind::Advisor => 'A',
+        PieceKind::General => 'K',
+        PieceKind::Cannon => 'C',
+        PieceKind::Soldier => 'P',
+    }
+}
+
+fn file_no(side: Side, col: i8) -> i8 {
+    match side {
+        Side::Red => 9 - col,
+        Side::Black => col + 1,
+    }
+}
+
+/// ICCS：`H2-E2`（起點 - 終點，file `A`–`I`、rank `0`–`9`）。
+pub fn iccs(mv: Move) -> String {
+    let sq = |c: i8, r: i8| format!("{}{}", (b'A' + c as u8) as char, r);
+    format!("{}-{}", sq(mv.from.col, mv.from.row), sq(mv.to.col, mv.to.row))
+}
+
+/// 在 `state` 的合法步中找 ICCS 對應的那一步（不分大小寫，`-` 可省：`h2e2` 亦可）。
+pub fn parse_iccs(state: &GameState, s: &str) -> Option<Move> {
+    let mut s = s.trim().to_ascii_uppercase();
+    if s.len() == 4 && s.is_ascii() {
+        s.insert(2, '-');
+    }
+    engine::all_legal_moves(state).into_iter().find(|&mv| iccs(mv) == s)
+}
+
+/// 由 FEN 建局面（首列為黑方底線 row 9；紅方大寫，`n`/`h` 馬、`b`/`e` 象皆可）。
+/// 行棋方 `w` / `r` 為紅；第 5 欄為無吃子半步數，其餘欄位不讀。讀入的局面沒有歷史，重複局面從這裡起算。
+/// 雙方須恰有一將且在九宮內、非行棋方不能正被將（含飛將）。
+pub fn from_fen(fen: &str) -> Result<GameState, &'static str> {
+    use PieceKind::*;
+    let mut fields = fen.split_whitespace();
+    let ranks: Vec<&str> = fields.next().ok_or("bad_fen_board")?.split('/').collect();
+    if ranks.len() != 10 {
+        return Err("bad_fen_board");
+    }
+    let mut board: Board = Default::default();
+    for (i, rank) in ranks.iter().enumerate() {
+        let r = 9 - i;
+        let mut c = 0;
+        for ch in rank.chars() {
+            if let Some(n @ 1..=9) = ch.to_digit(10) {
+                c += n as usize;
+                continue;
+            }
+            let kind = match ch.to_ascii_lowercase() {
+                'r' => Rook,
+                'n' | 'h' => Horse,
+                'b' | 'e' => Elephant,
+                'a' => Advisor,
+                'k' => General,
+                'c' => Cannon,
+                'p' => Soldier,
+                _ => return Err("bad_fen_board"),
+            };
+            if c >= 9 {
+                return Err("bad_fen_board");
+            }
+            let side = if ch.is_ascii_uppercase() { Side::Red } else { Side::Black };
+            board[r][c] = Some(Piece::new(kind, side));
+            c += 1;
+        }
+        if c != 9 {
+            return Err("bad_fen_board");
+        }
+    }
+
+    let turn = match fields.next() {
+        None | Some("w" | "r") => Side::Red,
+        Some("b") => Side::Black,
+        _ => return Err("bad_fen_turn"),
+    };
+    let halfmove_no_capture = match fields.nth(2) {
+        None => 0,
+        Some(n) => n.parse().map_err(|_| "bad_fen_clock")?,
+    };
+
+    for side in [Side::Red, Side::Black] {
+        let palace = if side == Side::Red { 0..=2 } else { 7..=9 };
+        let generals: Vec<(usize, usize)> = (0..10)
+            .flat_map(|r| (0..9).map(move |c| (r, c)))
+            .filter(|&(r, c)| board[r][c] == Some(Piece::new(General, side)))
+            .collect();
+        if !matches!(generals[..], [(r, c)] if palace.contains(&r) && (3..=5).contains(&c)) {
+            return Err("bad_fen_generals");
+        }
+    }
+
+    let state = GameState { board, turn, halfmove_no_capture, history: Vec::new() };
+    if engine::is_in_check(&state, turn.opponent()) {
+        return Err("bad_fen_check");
+    }
+    Ok(state)
+}
+
+/// `mv` 在 `state`（走之前）的 WXF。假設 `mv` 合法。
+pub fn wxf(state: &GameState, mv: Move) -> String {
+    let piece = state.board[mv.from.row as usize][mv.from.col as usize].expect("合法步的起點必有子");
+    let side = piece.side;
+    // 己方視角的「前進」量
+    let forward = match side {
+        Side::Red => mv.to.row - mv.from.row,
+        Side::Black => mv.from.row - mv.to.row,
+    };
+
+    // 同一路有兩個同種子：路數改用 `+`（前）/ `-`（後）
+    let mut same_file: Vec<i8> = (0..10)
+        .filter(|&r| state.board[r as usize][mv.from.col as usize] == Some(piece))
+        .collect();
+    same_file.sort_by_key(|&r| match side {
+        Side::Red => -r,
+        Side::Black => r,
+    });
+    let origin = if same_file.len() >= 2 && same_file[0] == mv.from.row {
+        '+'
+    } else if same_file.len() >= 2 && same_file[same_file.len() - 1] == mv.from.row {
+        '-'
+    } else {
+        (b'0' + file_no(side, mv.from.col) as u8) as char
+    };
+
+    let op = match forward {
+        0 => '.',
+        f if f > 0 => '+',
+        _ => '-',
+    };
+    let straight = matches!(
+        piece.kind,
+        PieceKind::Rook | PieceKind::Cannon | PieceKind::Soldier | PieceKind::General
+    );
+    // 直行子縱走記步數，其餘記到達的路數
+    let target = if straight && forward != 0 {
+        forward.abs()
+    } else {
+        file_no(side, mv.to.col)
+    };
+    format!("{}{origin}{op}{target}", letter(piece.kind))
+}
+
+/// 象棋 PGN（`Game` / `Format` tag 標明記法）。`plies` 為每步之前的局面與該步。
+pub fn pgn(plies: &[(&GameState, Move)], format: Format, meta: &ExportMeta) -> String {
+    let result = pgn_result(meta.result.as_ref());
+    let mut out = String::new();
+    pgn_tag(&mut out, "Game", "Chinese Chess");
+    pgn_tag(&mut out, "Event", &meta.event);
+    pgn_tag(&mut out, "Site", "?");
+    pgn_tag(&mut out, "Date", &meta.date.format("%Y.%m.%d").to_string());
+    pgn_tag(&mut out, "Round", "-");
+    pgn_tag(&mut out, "Red", &meta.players[0]);
+    pgn_tag(&mut out, "Black", &meta.players[1]);
+    pgn_tag(&mut out, "Result", result);
+    pgn_tag(&mut out, "Format", &format.as_str().to_uppercase());
+    out.push('\n');
+    let moves: Vec<String> = plies
+        .iter()
+        .map(|(state, mv)| match format {
+            Format::Wxf => wxf(state, *mv),
+            Format::Iccs => iccs(*mv),
+        })
+        .collect();
+    out.push_str(&pgn_movetext(&moves, result));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::chess::engine;
+    use crate::games::chess::types::{Piece, Square};
+
+    fn mv(from: (i8, i8), to: (i8, i8)) -> Move {
+        Move { from: Square::new(from.0, from.1), to: Square::new(to.0, to.1) }
+    }
+
+    #[test]
+    fn opening_moves() {
+        let mut s = engine::initial_state();
+        // 炮二平五
+        let m = mv((7, 2), (4, 2));
+        assert_eq!(wxf(&s, m), "C2.5");
+        assert_eq!(iccs(m), "H2-E2");
+        engine::apply(&mut s, m);
+        // 馬８進７
+        let m = mv((7, 9), (6, 7));
+        assert_eq!(wxf(&s, m), "H8+7");
+        engine::apply(&mut s, m);
+        // 俥一進一
+        assert_eq!(wxf(&s, mv((8, 0), (8, 1))), "R1+1");
+    }
+
+    #[test]
+    fn tandem_pieces_use_front_and_rear() {
+        let mut s = engine::initial_state();
+        s.board = Default::default();
+        s.board[0][4] = Some(Piece::new(PieceKind::General, Side::Red));
+        s.board[9][3] = Some(Piece::new(PieceKind::General, Side::Black));
+        s.board[2][5] = Some(Piece::new(PieceKind::Rook, Side::Red));
+        s.board[5][5] = Some(Piece::new(PieceKind::Rook, Side::Red));
+        // 前俥平六、後俥退一
+        assert_eq!(wxf(&s, mv((5, 5), (3, 5))), "R+.6");
+        assert_eq!(wxf(&s, mv((5, 2), (5, 1))), "R--1");
+    }
+
+    #[test]
+    fn pgn_tags_and_movetext() {
+        let s = engine::initial_state();
+        let meta = ExportMeta {
+            event: "桌".into(),
+            date: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            players: ["?".into(), "?".into()],
+            result: None,
+            format: None,
+        };
+        let text = pgn(&[(&s, mv((7, 2), (4, 2)))], Format::Iccs, &meta);
+        assert!(text.contains("[Format \"ICCS\"]\n"));
+        assert!(text.ends_with("\n1. H2-E2 *\n"), "{text}");
+    }
+
+    #[test]
+    fn fen_start_position_matches_initial_state() {
+        let s = from_fen("rnbakabnr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RNBAKABNR w - - 0 1").unwrap();
+        assert_eq!(s.board, engine::initial_state().board);
+        assert_eq!(s.turn, Side::Red);
+        // h / e 別名、省略後續欄位
+        let alias = from_fen("rheakaehr/9/1c5c1/p1p1p1p1p/9/9/P1P1P1P1P/1C5C1/9/RHEAKAEHR b").unwrap();
+        assert_eq!(alias.board, s.board);
+        assert_eq!(alias.turn, Side::Black);
+    }
+
+    #[test]
+    fn fen_rejects_bad_positions() {
+        assert_eq!(from_fen("4k4/9/9/9/9/9/9/9/9/9 w").unwrap_err(), "bad_fen_generals");
+        // 將出九宮
+        assert_eq!(from_fen("k8/9/9/9/9/9/9/9/9/4K4 w").unwrap_err(), "bad_fen_generals");
+        assert_eq!(from_fen("4k4/9/9/9/9/9/9/9/9/3K5 x").unwrap_err(), "bad_fen_turn");
+        assert_eq!(from_fen("4k4/9/9/9/9/9/9/9/4K4").unwrap_err(), "bad_fen_board");
+        // 輪紅走但兩將對臉（黑將等同正被將）
+        assert_eq!(from_fen("4k4/9/9/9/9/9/9/9/9/4K4 w").unwrap_err(), "bad_fen_check");
+    }
+
+    #[test]
+    fn iccs_parses_with_or_without_dash() {
+        let s = engine::initial_state();
+        let m = parse_iccs(&s, "h2e2").unwrap();
+        assert_eq!(m, mv((7, 2), (4, 2)));
+        assert_eq!(parse_iccs(&s, "H2-E2"), Some(m));
+        assert!(parse_iccs(&s, "h2h8").is_none());
+        assert!(parse_iccs(&s, "zz").is_none());
+    }
+}
@@ -75,6 +75,19 @@ pub struct GameState {
     pub turn: Side,
     /// 連續無吃子半步數（兩半步＝一回合，120 半步＝60 回合判和）。
     pub halfmove_no_capture: u8,
+    /// 上次吃子後每一步的紀錄（`engine::play` 寫入），判長將 / 長捉用。
+    pub history: Vec<PlyMark>,
+}
+
+/// 一步的重複判定資訊。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlyMark {
+    /// 走這步之前的局面 hash（含行棋方）。
+    pub hash: u64,
+    /// 這步將軍。
+    pub check: bool,
+    /// 這步捉子（新威脅吃對方無根子，或馬 / 炮捉車）。
+    pub chase: bool,
 }
 
 /// 對局結果。
@@ -84,7 +97,14 @@ pub enum Status {
     Checkmate { winner: Side },
     /// 困斃：被困方判負。
     Stalemate { loser: Side },
+    /// 60 回合無吃子。
     Draw,
+    /// 同一局面第 3 次出現且雙方違例相當（都沒有、都長將、都長捉）：和。
+    RepetitionDraw,
+    /// 同一局面第 3 次出現，一方每步皆將軍：長將方判負。
+    PerpetualCheck { loser: Side },
+    /// 同一局面第 3 次出現，一方每步皆將或捉（對方沒有長將）：長捉方判負。
+    PerpetualChase { loser: Side },
 }
 
 /// 非法走步原因；`code()` 為傳給前端的字串。
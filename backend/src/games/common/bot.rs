@@ -0,0 +1,279 @@
+//! 電腦對手：`GameBot` trait + 共用的 alpha-beta 搜尋。
+//!
+//! 每款遊戲在 `GameEngine::Bot` 指定自己的 bot（沒有就填 `NoBot`）；純搜尋放各遊戲的
+//! `bot.rs`（只碰引擎型別），`game.rs` 把選中的步轉成 `try_move` 吃的 data。
+//! bot 在 blocking thread 上跑（見 `service::schedule_bot`），用時間預算收斂，不阻塞 runtime。
+
+use std::time::{Duration, Instant};
+
+use serde_json::Value;
+
+/// 電腦強度。各遊戲自行對應成搜尋深度 / 時間預算 / 隨機擾動。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotLevel {
+    Easy,
+    Normal,
+    Hard,
+}
+
+impl BotLevel {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "easy" => Some(BotLevel::Easy),
+            "normal" => Some(BotLevel::Normal),
+            "hard" => Some(BotLevel::Hard),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BotLevel::Easy => "easy",
+            BotLevel::Normal => "normal",
+            BotLevel::Hard => "hard",
+        }
+    }
+}
+
+/// 為任一局面選一步的電腦對手。`E` 為對應的 `GameEngine`。
+pub trait GameBot<E>: 'static {
+    /// 是否真的有電腦對手（`NoBot` 為 false，`play_vs_computer` 直接回 `bot_unavailable`）。
+    const AVAILABLE: bool = true;
+
+    /// 為目前行棋方選一步，回傳 `GameEngine::try_move` 吃的 data；無步可走回 None。
+    /// 跑在 blocking thread 上，可吃滿 CPU 直到 `level` 的時間預算。
+    fn choose(engine: &E, level: BotLevel) -> Option<Value>;
+}
+
+/// 沒有電腦對手的遊戲（如暗棋：翻子是機率事件，alpha-beta 不適用）。
+pub struct NoBot;
+
+impl<E> GameBot<E> for NoBot {
+    const AVAILABLE: bool = false;
+
+    fn choose(_engine: &E, _level: BotLevel) -> Option<Value> {
+        None
+    }
+}
+
+// ---- alpha-beta ----
+
+/// 必勝 / 必敗分（以行棋方視角）。搜尋中依步數遞減，越快的殺棋分越高。
+pub const MATE: i32 = 1_000_000;
+
+/// 可做 alpha-beta 的局面。分數一律以**行棋方**視角（negamax）。
+pub trait Searchable: Clone {
+    type Move: Copy + PartialEq;
+
+    /// 行棋方的候選步，已排序（好的在前，剪枝效率靠這個）。
+    fn moves(&self) -> Vec<Self::Move>;
+
+    fn play(&mut self, mv: Self::Move);
+
+    /// 靜態評估。
+    fn evaluate(&self) -> i32;
+
+    /// 走步前即可判定的終局（例如上一手已連五）；`-MATE` = 行棋方已輸、0 = 和。
+    fn terminal(&self) -> Option<i32> {
+        None
+    }
+
+    /// `moves()` 為空時的分數（將死 / 困斃 / 和）。
+    fn no_moves_score(&self) -> i32;
+
+    /// 靜態搜尋用的「吵」步（吃子），已排序。預設空 = 葉節點直接評估。
+    /// 棋類不看這個會在搜尋邊界送子（水平線效應）。
+    fn noisy_moves(&self) -> Vec<Self::Move> {
+        Vec::new()
+    }
+}
+
+/// 靜態搜尋最多再往下幾層吃子。
+const QUIESCENCE_PLIES: u32 = 4;
+
+/// 搜尋參數：最大深度、時間預算、根節點隨機擾動（弱化用，0 = 不擾動）。
+#[derive(Debug, Clone, Copy)]
+pub struct SearchLimits {
+    pub max_depth: u32,
+    pub budget: Duration,
+    pub noise: i32,
+}
+
+/// 迭代加深 alpha-beta。時間到即放棄進行中的那一層，回傳上一層的最佳步。
+/// 至少會完成深度 1（否則連送子都看不出來）。
+pub fn best_move<P: Searchable>(pos: &P, limits: SearchLimits) -> Option<P::Move> {
+    let mut root_moves = pos.moves();
+    if root_moves.is_empty() {
+        return None;
+    }
+    let deadline = Instant::now() + limits.budget;
+    let mut best = root_moves[0];
+    for depth in 1..=limits.max_depth.max(1) {
+        // 深度 1 不設期限，保證有結果
+        let dl = if depth == 1 { None } else { Some(deadline) };
+        let Some((mv, _)) = search_root(pos, &root_moves, depth, dl, limits.noise) else {
+            break;
+        };
+        best = mv;
+        // 上一層最佳步排到最前，下一層先搜它，剪枝較多
+        if let Some(i) = root_moves.iter().position(|&m| m == mv) {
+            root_moves[..=i].rotate_right(1);
+        }
+        if Instant::now() >= deadline {
+            break;
+        }
+    }
+    Some(best)
+}
+
+fn search_root<P: Searchable>(
+    pos: &P,
+    moves: &[P::Move],
+    depth: u32,
+    deadline: Option<Instant>,
+    noise: i32,
+) -> Option<(P::Move, i32)> {
+    let mut alpha = -MATE - 1;
+    let mut best: Option<(P::Move, i32)> = None;
+    for &mv in moves {
+        let mut child = pos.clone();
+        child.play(mv);
+        let mut score = -negamax(&child, depth - 1, -MATE - 1, -alpha + noise, 1, deadline)?;
+        if noise > 0 {
+            score += rand::random_range(0..noise);
+        }
+        if best.is_none_or(|(_, b)| score > b) {
+            best = Some((mv, score));
+            alpha = alpha.max(score - noise);
+        }
+    }
+    best
+}
+
+/// None = 時間到（整層作廢）。
+fn negamax<P: Searchable>(
+    pos: &P,
+    depth: u32,
+    mut alpha: i32,
+    beta: i32,
+    ply: i32,
+    deadline: Option<Instant>,
+) -> Option<i32> {
+    if let Some(s) = pos.terminal() {
+        return Some(mate_adjust(s, ply));
+    }
+    if depth == 0 {
+        return Some(quiesce(pos, alpha, beta, QUIESCENCE_PLIES));
+    }
+    if deadline.is_some_and(|d| Instant::now() >= d) {
+        return None;
+    }
+    let moves = pos.moves();
+    if moves.is_empty() {
+        return Some(mate_adjust(pos.no_moves_score(), ply));
+    }
+    let mut best = -MATE - 1;
+    for mv in moves {
+        let mut child = pos.clone();
+        child.play(mv);
+        let score = -negamax(&child, depth - 1, -beta, -alpha, ply + 1, deadline)?;
+        best = best.max(score);
+        alpha = alpha.max(score);
+        if alpha >= beta {
+            break;
+        }
+    }
+    Some(best)
+}
+
+/// 只搜吃子、可隨時「不吃」（stand pat）的靜態搜尋。
+fn quiesce<P: Searchable>(pos: &P, mut alpha: i32, beta: i32, plies: u32) -> i32 {
+    let stand = pos.evaluate();
+    if plies == 0 || stand >= beta {
+        return stand;
+    }
+    alpha = alpha.max(stand);
+    for mv in pos.noisy_moves() {
+        let mut child = pos.clone();
+        child.play(mv);
+        let score = -quiesce(&child, -beta, -alpha, plies - 1);
+        if score >= beta {
+            return score;
+        }
+        alpha = alpha.max(score);
+    }
+    alpha
+}
+
+/// 殺棋分依距根步數遞減（早死比晚死糟、早殺比晚殺好）。
+fn mate_adjust(score: i32, ply: i32) -> i32 {
+    if score <= -MATE {
+        -MATE + ply
+    } else if score >= MATE {
+        MATE - ply
+    } else {
+        score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 取子遊戲：盤上 n 顆，每次拿 1–3 顆，拿到最後一顆者勝。n % 4 == 0 為必敗局。
+    #[derive(Clone)]
+    struct Nim(u32);
+
+    impl Searchable for Nim {
+        type Move = u32;
+
+        fn moves(&self) -> Vec<u32> {
+            (1..=3.min(self.0)).collect()
+        }
+
+        fn play(&mut self, mv: u32) {
+            self.0 -= mv;
+        }
+
+        fn evaluate(&self) -> i32 {
+            0
+        }
+
+        fn terminal(&self) -> Option<i32> {
+            // 對手拿走了最後一顆
+            (self.0 == 0).then_some(-MATE)
+        }
+
+        fn no_moves_score(&self) -> i32 {
+            -MATE
+        }
+    }
+
+    const LIMITS: SearchLimits = SearchLimits { max_depth: 12, budget: Duration::from_secs(5), noise: 0 };
+
+    #[test]
+    fn finds_winning_move_in_nim() {
+        for n in [5, 6, 7, 9, 10, 11] {
+            let mv = best_move(&Nim(n), LIMITS).unwrap();
+            assert_eq!((n - mv) % 4, 0, "n={n} 應留給對手 4 的倍數，實際拿 {mv}");
+        }
+    }
+
+    #[test]
+    fn takes_immediate_win() {
+        assert_eq!(best_move(&Nim(3), LIMITS), Some(3));
+    }
+
+    #[test]
+    fn no_moves_returns_none() {
+        assert_eq!(best_move(&Nim(0), LIMITS), None);
+    }
+
+    #[test]
+    fn level_round_trips() {
+        for l in [BotLevel::Easy, BotLevel::Normal, BotLevel::Hard] {
+            assert_eq!(BotLevel::parse(l.as_str()), Some(l));
+        }
+        assert_eq!(BotLevel::parse("godlike"), None);
+    }
+}
@@ -0,0 +1,394 @@
+//! 2 人框架的聊天：桌內（雙方 + 觀戰）與大廳。
+//!
+//! - 每則 ≤ `MAX_CHARS` 字；每條連線 `RATE_WINDOW` 內最多 `RATE_LIMIT` 則（桌內 / 大廳合計）。
+//! - 伺服器端過濾詞：app_settings `game_chat_filter_words`（JSON 字串陣列），命中處換成 `*`，熱更新。
+//! - 靜音：各人自己的名單，被靜音者的訊息不送給他（對方不知情）。登入者以會員 id 記（雙方皆然），
+//!   重新整理 / 換分頁重連後照樣有效，直到伺服器重啟；匿名連線才退而以位址記，斷線即清空。
+//! - 桌內 / 大廳各留最近 `HISTORY` 則（記憶體，不落地），供進大廳補送與後台檢視。
+//!
+//! 信封 `chat` = `{ scope: "table"|"lobby", from, label, text, at }`，`from` 為連線位址（同 `challenge` 的對象識別）。
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::SocketAddr;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::time::{Duration, Instant};
+
+use super::engine::GameEngine;
+use super::hub::{GameHub, HubInner, TableState};
+use crate::state::AppState;
+
+pub const MAX_CHARS: usize = 200;
+pub const RATE_LIMIT: usize = 5;
+pub const RATE_WINDOW: Duration = Duration::from_secs(10);
+pub const HISTORY: usize = 50;
+/// 過濾詞設定的 app_settings key。
+pub const FILTER_SETTING: &str = "game_chat_filter_words";
+
+/// 靜音名單上的身分：登入者是會員，匿名者只能是連線位址。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChatId {
+    Member(i64),
+    Conn(SocketAddr),
+}
+
+impl ChatId {
+    pub fn of(who: SocketAddr, member: Option<i64>) -> Self {
+        member.map_or(ChatId::Conn(who), ChatId::Member)
+    }
+}
+
+/// 一則聊天（`text` 為過濾後實際送出的內容）。
+#[derive(Debug, Clone, Serialize)]
+pub struct ChatLine {
+    pub from: String,
+    /// 發言者身分（補送紀錄時過濾靜音用；發言者可能已換了連線）。
+    #[serde(skip)]
+    pub speaker: ChatId,
+    /// 桌內為 side label / `spectator` / `host`，大廳為 None。
+    pub label: Option<String>,
+    pub text: String,
+    /// 被過濾前的原文（有命中過濾詞才有；只給後台看）。
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original: Option<String>,
+    pub at: DateTime<Utc>,
+}
+
+impl ChatLine {
+    /// 送給玩家的版本（不含原文）。
+    fn public(&self, scope: &str) -> Value {
+        json!({ "scope": scope, "from": self.from, "label": self.label, "text": self.text, "at": self.at })
+    }
+}
+
+/// hub 層的聊天狀態（大廳紀錄、發言頻率、靜音名單）。桌內紀錄在 `Table::chat`。
+#[derive(Default)]
+pub struct ChatState {
+    pub lobby: VecDeque<ChatLine>,
+    sent: HashMap<SocketAddr, VecDeque<Instant>>,
+    mutes: HashMap<ChatId, HashSet<ChatId>>,
+}
+
+impl ChatState {
+    /// 記一次發言；超過頻率回 false（不記）。
+    pub fn allow(&mut self, who: SocketAddr, now: Instant) -> bool {
+        let sent = self.sent.entry(who).or_default();
+        while sent.front().is_some_and(|&t| now.duration_since(t) >= RATE_WINDOW) {
+            sent.pop_front();
+        }
+        if sent.len() >= RATE_LIMIT {
+            return false;
+        }
+        sent.push_back(now);
+        true
+    }
+
+    pub fn set_muted(&mut self, listener: ChatId, speaker: ChatId, muted: bool) {
+        let set = self.mutes.entry(listener).or_default();
+        if muted {
+            set.insert(speaker);
+        } else {
+            set.remove(&speaker);
+        }
+    }
+
+    pub fn muted_by(&self, listener: ChatId) -> impl Iterator<Item = ChatId> + '_ {
+        self.mutes.get(&listener).into_iter().flatten().copied()
+    }
+
+    fn is_muted(&self, listener: ChatId, speaker: ChatId) -> bool {
+        self.mutes.get(&listener).is_some_and(|s| s.contains(&speaker))
+    }
+
+    /// 連線離開本遊戲：頻率與以位址記的靜音都丟掉（位址之後可能被別條連線重用）；以會員記的留著。
+    pub fn forget(&mut self, who: SocketAddr) {
+        let conn = ChatId::Conn(who);
+        self.sent.remove(&who);
+        self.mutes.remove(&conn);
+        for set in self.mutes.values_mut() {
+            set.remove(&conn);
+        }
+    }
+}
+
+/// 最近紀錄：超過 `HISTORY` 丟最舊的。
+pub fn remember(buf: &mut VecDeque<ChatLine>, line: ChatLine) {
+    if buf.len() >= HISTORY {
+        buf.pop_front();
+    }
+    buf.push_back(line);
+}
+
+/// 驗證並正規化：trim、不可空、不可超長。
+pub fn clean(text: Option<&str>) -> Result<&str, &'static str> {
+    let text = text.map(str::trim).unwrap_or("");
+    if text.is_empty() {
+        return Err("empty_chat");
+    }
+    if text.chars().count() > MAX_CHARS {
+        return Err("chat_too_long");
+    }
+    Ok(text)
+}
+
+/// 把命中的過濾詞（不分大小寫）逐字換成 `*`。
+pub fn censor(text: &str, words: &[String]) -> String {
+    let lower = |c: char| c.to_lowercase().next().unwrap_or(c);
+    let mut chars: Vec<char> = text.chars().collect();
+    let folded: Vec<char> = chars.iter().map(|&c| lower(c)).collect();
+    for word in words {
+        let w: Vec<char> = word.chars().map(lower).collect();
+        if w.is_empty() || w.len() > folded.len() {
+            continue;
+        }
+        for i in 0..=folded.len() - w.len() {
+            if folded[i..i + w.len()] == w[..] {
+                chars[i..i + w.len()].fill('*');
+            }
+        }
+    }
+    chars.into_iter().collect()
+}
+
+/// 解析過濾詞設定（JSON 字串陣列；未設定 / 壞值視為無過濾詞）。
+pub fn parse_filter_words(raw: Option<&str>) -> Vec<String> {
+    raw.and_then(|v| serde_json::from_str::<Vec<String>>(v).ok())
+        .unwrap_or_default()
+        .into_iter()
+        .map(|w| w.trim().to_string())
+        .filter(|w| !w.is_empty())
+        .collect()
+}
+
+/// 連線目前的靜音身分（會員綁定見 `HubInner::bind_member`）。
+pub fn chat_id<E>(h: &HubInner<E>, who: SocketAddr) -> ChatId {
+    ChatId::of(who, h.members.get(&who).copied())
+}
+
+/// `listener` 的靜音名單，換成目前在本遊戲的連線位址（會員可能有多條、也可能都不在）。
+fn mute_list<E>(h: &HubInner<E>, listener: ChatId) -> Vec<String> {
+    let mut v: Vec<String> = h
+        .chat
+        .muted_by(listener)
+        .flat_map(|id| match id {
+            ChatId::Conn(a) => vec![a],
+            ChatId::Member(m) => h.members.iter().filter(|&(_, &mm)| mm == m).map(|(&a, _)| a).collect(),
+        })
+        .map(|a| a.to_string())
+        .collect();
+    v.sort();
+    v.dedup();
+    v
+}
+
+fn line(state: &AppState, who: SocketAddr, speaker: ChatId, label: Option<String>, text: &str) -> ChatLine {
+    let words = parse_filter_words(state.get_settings().get(FILTER_SETTING).as_deref());
+    let shown = censor(text, &words);
+    ChatLine {
+        from: who.to_string(),
+        speaker,
+        label,
+        original: (shown != text).then(|| text.to_string()),
+        text: shown,
+        at: Utc::now(),
+    }
+}
+
+fn msg<E: GameEngine>(typ: &str, data: Value) -> String {
+    crate::structs::ws::game_envelope(E::NAME, typ, data)
+}
+
+fn err<E: GameEngine>(state: &AppState, who: SocketAddr, reason: &str) {
+    state.send_to(who, msg::<E>("error", json!({ "reason": reason })));
+}
+
+/// 驗證文字與頻率，通過回 (text)。
+fn admit<E: GameEngine>(h: &mut HubInner<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) -> Option<String> {
+    let text = match clean(data.and_then(|d| d.get("text")).and_then(|v| v.as_str())) {
+        Ok(t) => t.to_string(),
+        Err(reason) => {
+            err::<E>(state, who, reason);
+            return None;
+        }
+    };
+    if !h.chat.allow(who, Instant::now()) {
+        err::<E>(state, who, "chat_rate_limited");
+        return None;
+    }
+    Some(text)
+}
+
+/// 桌內聊天：入座者（等待中的房主、對戰雙方）與觀戰者都可發，送給同桌全體（略過靜音發言者的人）。
+pub async fn table_chat<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        h.bind_member(who, member);
+        let Some(table_id) = h.conn_table.get(&who).or_else(|| h.spectating.get(&who)).copied() else {
+            err::<E>(state, who, "not_at_table");
+            return;
+        };
+        let Some(text) = admit(&mut h, state, who, data) else { return };
+        let h = &mut *h;
+        let Some(table) = h.tables.get_mut(&table_id) else { return };
+        let (label, audience): (String, Vec<SocketAddr>) = match &table.state {
+            TableState::Waiting { host } => ("host".into(), vec![*host]),
+            TableState::Playing(g) => {
+                let label = g.side_of(who).map_or("spectator", E::side_label).to_string();
+                (label, g.audience().collect())
+            }
+        };
+        let speaker = ChatId::of(who, member);
+        let line = line(state, who, speaker, Some(label), &text);
+        let m = msg::<E>("chat", line.public("table"));
+        for to in audience.into_iter().filter(|&to| !h.chat.is_muted(ChatId::of(to, h.members.get(&to).copied()), speaker)) {
+            outbox.push((to, m.clone()));
+        }
+        remember(&mut table.chat, line);
+    }
+    state.send_outbox(outbox);
+}
+
+/// 大廳聊天：訂閱大廳者才可發，送給大廳全體（略過靜音發言者的人）。
+pub async fn lobby_chat<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        h.bind_member(who, member);
+        if !h.lobby.contains(&who) {
+            err::<E>(state, who, "not_in_lobby");
+            return;
+        }
+        let Some(text) = admit(&mut h, state, who, data) else { return };
+        let speaker = ChatId::of(who, member);
+        let line = line(state, who, speaker, None, &text);
+        let m = msg::<E>("chat", line.public("lobby"));
+        for &to in h.lobby.iter().filter(|&&to| !h.chat.is_muted(chat_id(&h, to), speaker)) {
+            outbox.push((to, m.clone()));
+        }
+        remember(&mut h.chat.lobby, line);
+    }
+    state.send_outbox(outbox);
+}
+
+/// 進大廳補送最近的大廳聊天。
+pub fn lobby_history<E: GameEngine>(h: &HubInner<E>, who: SocketAddr) -> String {
+    let listener = chat_id(h, who);
+    let lines: Vec<Value> = h
+        .chat
+        .lobby
+        .iter()
+        .filter(|l| !h.chat.is_muted(listener, l.speaker))
+        .map(|l| l.public("lobby"))
+        .collect();
+    msg::<E>("chat_history", json!({ "scope": "lobby", "lines": lines }))
+}
+
+/// 靜音 / 解除靜音 `data.addr` 那條連線的人（登入者記會員 id，見模組說明），
+/// 回 `mute_list`（靜音對象目前在本遊戲的全部位址）。
+pub async fn mute<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>, muted: bool) {
+    let Some(target) = data
+        .and_then(|d| d.get("addr"))
+        .and_then(|v| v.as_str())
+        .and_then(|s| s.parse::<SocketAddr>().ok())
+        .filter(|&a| a != who)
+    else {
+        err::<E>(state, who, "bad_target");
+        return;
+    };
+    let (member, target_member) = (state.member_of(who).await, state.member_of(target).await);
+    let mut h = hub.lock().await;
+    h.bind_member(who, member);
+    let listener = ChatId::of(who, member);
+    h.chat.set_muted(listener, ChatId::of(target, target_member), muted);
+    let list = msg::<E>("mute_list", json!({ "muted": mute_list(&h, listener) }));
+    drop(h);
+    state.send_to(who, list);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn censor_is_case_insensitive_and_keeps_length() {
+        let words = vec!["bad".to_string(), "壞話".to_string()];
+        assert_eq!(censor("BaD move, bad!", &words), "*** move, ***!");
+        assert_eq!(censor("說壞話不好", &words), "說**不好");
+        assert_eq!(censor("good", &words), "good");
+    }
+
+    #[test]
+    fn clean_rejects_empty_and_long() {
+        assert_eq!(clean(Some("  hi ")), Ok("hi"));
+        assert_eq!(clean(Some("   ")), Err("empty_chat"));
+        assert_eq!(clean(None), Err("empty_chat"));
+        let long = "字".repeat(MAX_CHARS + 1);
+        assert_eq!(clean(Some(&long)), Err("chat_too_long"));
+        assert!(clean(Some(&"字".repeat(MAX_CHARS))).is_ok());
+    }
+
+    #[test]
+    fn rate_limit_slides_with_window() {
+        let mut c = ChatState::default();
+        let who = SocketAddr::from(([127, 0, 0, 1], 5000));
+        let t0 = Instant::now();
+        for _ in 0..RATE_LIMIT {
+            assert!(c.allow(who, t0));
+        }
+        assert!(!c.allow(who, t0 + Duration::from_secs(1)));
+        assert!(c.allow(who, t0 + RATE_WINDOW));
+    }
+
+    #[test]
+    fn mute_is_per_listener_and_forgotten_on_leave() {
+        let mut c = ChatState::default();
+        let a = ChatId::Conn(SocketAddr::from(([127, 0, 0, 1], 5000)));
+        let b = SocketAddr::from(([127, 0, 0, 1], 5001));
+        c.set_muted(a, ChatId::Conn(b), true);
+        assert!(c.is_muted(a, ChatId::Conn(b)));
+        assert!(!c.is_muted(ChatId::Conn(b), a));
+        assert_eq!(c.muted_by(a).collect::<Vec<_>>(), vec![ChatId::Conn(b)]);
+        c.forget(b);
+        assert!(!c.is_muted(a, ChatId::Conn(b)));
+    }
+
+    #[test]
+    fn member_mute_survives_reconnect() {
+        let mut h: HubInner<crate::games::western_chess::game::WesternChessGame> = HubInner::default();
+        let (me, troll, troll_again) = (
+            SocketAddr::from(([127, 0, 0, 1], 5000)),
+            SocketAddr::from(([127, 0, 0, 1], 5001)),
+            SocketAddr::from(([127, 0, 0, 1], 5002)),
+        );
+        h.bind_member(me, Some(1));
+        h.bind_member(troll, Some(2));
+        h.chat.set_muted(chat_id(&h, me), chat_id(&h, troll), true);
+        assert_eq!(mute_list(&h, chat_id(&h, me)), vec![troll.to_string()]);
+
+        // 對方重新整理：舊連線離開、換新位址 → 仍在靜音名單上
+        h.members.remove(&troll);
+        h.chat.forget(troll);
+        h.bind_member(troll_again, Some(2));
+        assert!(h.chat.is_muted(chat_id(&h, me), chat_id(&h, troll_again)));
+        assert_eq!(mute_list(&h, chat_id(&h, me)), vec![troll_again.to_string()]);
+
+        // 自己重新整理也一樣
+        h.members.remove(&me);
+        h.chat.forget(me);
+        let me_again = SocketAddr::from(([127, 0, 0, 1], 5003));
+        h.bind_member(me_again, Some(1));
+        assert!(h.chat.is_muted(chat_id(&h, me_again), ChatId::Member(2)));
+    }
+
+    #[test]
+    fn filter_words_setting_parses_json_array() {
+        assert_eq!(parse_filter_words(Some(r#"["a", " b ", ""]"#)), vec!["a", "b"]);
+        assert!(parse_filter_words(Some("not json")).is_empty());
+        assert!(parse_filter_words(None).is_empty());
+    }
+}
@@ -0,0 +1,311 @@
+//! 每桌計時制（純資料 + 純計算，無 WS / 無時鐘來源依賴，呼叫端傳入已用毫秒數）。
+//!
+//! - Fischer：主時間 + 每步增量（各遊戲預設 `GameEngine::INITIAL_CLOCK_MS` / `INCREMENT_MS`）
+//! - 日式讀秒：主時間用完後進讀秒，每步在一次讀秒內下完則該次重置；超過則耗掉一次，耗盡判負
+//! - 不計時：休閒局，永不超時（也不計積分，見 `Game::is_rated`）
+
+use serde_json::{json, Value};
+
+use super::engine::Side;
+
+/// 主時間上限 3 小時
+const MAX_BASE_MS: i64 = 3 * 60 * 60 * 1000;
+/// Fischer 增量上限 3 分鐘
+const MAX_INCREMENT_MS: i64 = 3 * 60 * 1000;
+/// 讀秒單次 5 秒–5 分鐘
+const MIN_PERIOD_MS: i64 = 5_000;
+const MAX_PERIOD_MS: i64 = 5 * 60 * 1000;
+const MAX_PERIODS: u8 = 10;
+/// Fischer 主時間下限 10 秒（太短等於開局即超時）
+const MIN_FISCHER_BASE_MS: i64 = 10_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeControl {
+    Untimed,
+    Fischer { base_ms: i64, increment_ms: i64 },
+    ByoYomi { base_ms: i64, period_ms: i64, periods: u8 },
+}
+
+impl TimeControl {
+    /// 各遊戲預設：Fischer `INITIAL_CLOCK_MS` + `INCREMENT_MS`。
+    pub fn fischer(base_ms: i64, increment_ms: i64) -> Self {
+        TimeControl::Fischer { base_ms, increment_ms }
+    }
+
+    /// 解析 `create_table` 的 `time_control`（形狀同 `to_json`）。欄位缺漏或超出範圍回 None。
+    pub fn parse(v: &Value) -> Option<Self> {
+        let ms = |k: &str| v.get(k).and_then(Value::as_i64);
+        let tc = match v.get("type")?.as_str()? {
+            "untimed" => TimeControl::Untimed,
+            "fischer" => TimeControl::Fischer {
+                base_ms: ms("base_ms")?,
+                increment_ms: ms("increment_ms").unwrap_or(0),
+            },
+            "byoyomi" => TimeControl::ByoYomi {
+                base_ms: ms("base_ms").unwrap_or(0),
+                period_ms: ms("period_ms")?,
+                periods: u8::try_from(ms("periods")?).ok()?,
+            },
+            _ => return None,
+        };
+        tc.is_valid().then_some(tc)
+    }
+
+    fn is_valid(self) -> bool {
+        match self {
+            TimeControl::Untimed => true,
+            TimeControl::Fischer { base_ms, increment_ms } => {
+                (MIN_FISCHER_BASE_MS..=MAX_BASE_MS).contains(&base_ms)
+                    && (0..=MAX_INCREMENT_MS).contains(&increment_ms)
+            }
+            TimeControl::ByoYomi { base_ms, period_ms, periods } => {
+                (0..=MAX_BASE_MS).contains(&base_ms)
+                    && (MIN_PERIOD_MS..=MAX_PERIOD_MS).contains(&period_ms)
+                    && (1..=MAX_PERIODS).contains(&periods)
+            }
+        }
+    }
+
+    pub fn is_timed(self) -> bool {
+        !matches!(self, TimeControl::Untimed)
+    }
+
+    /// 主時間（不計時為 0）。
+    pub fn base_ms(self) -> i64 {
+        match self {
+            TimeControl::Untimed => 0,
+            TimeControl::Fischer { base_ms, .. } | TimeControl::ByoYomi { base_ms, .. } => base_ms,
+        }
+    }
+
+    /// 大廳 / match_found / 紀錄用的描述。
+    pub fn to_json(self) -> Value {
+        match self {
+            TimeControl::Untimed => json!({ "type": "untimed" }),
+            TimeControl::Fischer { base_ms, increment_ms } => {
+                json!({ "type": "fischer", "base_ms": base_ms, "increment_ms": increment_ms })
+            }
+            TimeControl::ByoYomi { base_ms, period_ms, periods } => json!({
+                "type": "byoyomi", "base_ms": base_ms, "period_ms": period_ms, "periods": periods,
+            }),
+        }
+    }
+}
+
+/// 結算本回合後的一方時間：主時間剩餘 + 讀秒剩餘次數。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settled {
+    pub main_ms: i64,
+    pub periods: u8,
+}
+
+/// 雙方時鐘。`main_ms` / `periods` 都是**回合起點**的值；進行中的回合由呼叫端傳入已用時間計算。
+#[derive(Debug, Clone)]
+pub struct Clock {
+    pub control: TimeControl,
+    main_ms: [i64; 2],
+    periods: [u8; 2],
+}
+
+impl Clock {
+    pub fn new(control: TimeControl) -> Self {
+        let periods = match control {
+            TimeControl::ByoYomi { periods, .. } => periods,
+            _ => 0,
+        };
+        let base = control.base_ms();
+        Clock { control, main_ms: [base, base], periods: [periods, periods] }
+    }
+
+    /// `side` 本回合已用 `elapsed_ms` 時的結算；None = 已超時。不改狀態。
+    pub fn settle(&self, side: Side, elapsed_ms: i64) -> Option<Settled> {
+        let main = self.main_ms[side.index()];
+        let periods = self.periods[side.index()];
+        match self.control {
+            TimeControl::Untimed => Some(Settled { main_ms: 0, periods: 0 }),
+            TimeControl::Fischer { .. } => {
+                let left = main - elapsed_ms;
+                (left > 0).then_some(Settled { main_ms: left, periods: 0 })
+            }
+            TimeControl::ByoYomi { period_ms, .. } => {
+                if elapsed_ms < main {
+                    return Some(Settled { main_ms: main - elapsed_ms, periods });
+                }
+                // 主時間用完：每滿一次讀秒耗掉一次，剩下的那次在本步下完即重置
+                let lost = (elapsed_ms - main) / period_ms;
+                (lost < i64::from(periods)).then(|| Settled { main_ms: 0, periods: periods - lost as u8 })
+            }
+        }
+    }
+
+    pub fn is_flagged(&self, side: Side, elapsed_ms: i64) -> bool {
+        self.settle(side, elapsed_ms).is_none()
+    }
+
+    /// 落子：寫回 `settle` 的結果並加 Fischer 增量。
+    pub fn commit(&mut self, side: Side, settled: Settled) {
+        self.charge(side, settled);
+        if let TimeControl::Fischer { increment_ms, .. } = self.control {
+            self.main_ms[side.index()] += increment_ms;
+        }
+    }
+
+    /// 只寫回 `settle` 的結果、不加增量（沒落子卻要結算已用時間時，例如悔棋換手）。
+    pub fn charge(&mut self, side: Side, settled: Settled) {
+        self.main_ms[side.index()] = settled.main_ms;
+        self.periods[side.index()] = settled.periods;
+    }
+
+    /// 保存用（見 `common::persist`）：`{ main_ms: [先手, 後手], periods: [先手, 後手] }`。
+    pub fn to_json(&self) -> Value {
+        json!({ "main_ms": self.main_ms, "periods": self.periods })
+    }
+
+    /// `to_json` 的反向；欄位缺漏 / 型別不符回 None。
+    pub fn from_json(control: TimeControl, v: &Value) -> Option<Self> {
+        let main_ms = serde_json::from_value(v.get("main_ms")?.clone()).ok()?;
+        let periods = serde_json::from_value(v.get("periods")?.clone()).ok()?;
+        Some(Clock { control, main_ms, periods })
+    }
+
+    /// 兩座位對調時連同剩餘時間一起換（時間跟著人走）。
+    pub fn swap_sides(&mut self) {
+        self.main_ms.swap(0, 1);
+        self.periods.swap(0, 1);
+    }
+
+    /// 超時判負時把該方歸零（終局時鐘顯示用）。
+    pub fn flag(&mut self, side: Side) {
+        self.main_ms[side.index()] = 0;
+        self.periods[side.index()] = 0;
+    }
+
+    /// 悔棋：收回該方上一步拿到的 Fischer 增量（至少留 1ms，不因悔棋直接超時）。
+    pub fn revoke_increment(&mut self, side: Side) {
+        if let TimeControl::Fischer { increment_ms, .. } = self.control {
+            let ms = &mut self.main_ms[side.index()];
+            *ms = (*ms - increment_ms).max(1);
+        }
+    }
+
+    /// 顯示值：主時間未用完顯示主時間，已進讀秒顯示本次讀秒剩餘。
+    /// `elapsed_ms` 為該方本回合已用時間（非行棋方傳 0）。
+    pub fn display_ms(&self, side: Side, elapsed_ms: i64) -> i64 {
+        match self.control {
+            TimeControl::Untimed => 0,
+            TimeControl::Fischer { .. } => (self.main_ms[side.index()] - elapsed_ms).max(0),
+            TimeControl::ByoYomi { period_ms, .. } => {
+                let main = self.main_ms[side.index()];
+                if elapsed_ms < main {
+                    main - elapsed_ms
+                } else if self.is_flagged(side, elapsed_ms) {
+                    0
+                } else {
+                    period_ms - (elapsed_ms - main) % period_ms
+                }
+            }
+        }
+    }
+
+    /// 顯示用的讀秒剩餘次數（非讀秒制為 None）。
+    pub fn display_periods(&self, side: Side, elapsed_ms: i64) -> Option<u8> {
+        match self.control {
+            TimeControl::ByoYomi { .. } => {
+                Some(self.settle(side, elapsed_ms).map_or(0, |s| s.periods))
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const BYO: TimeControl = TimeControl::ByoYomi { base_ms: 60_000, period_ms: 30_000, periods: 3 };
+
+    #[test]
+    fn fischer_deducts_and_adds_increment() {
+        let mut c = Clock::new(TimeControl::fischer(300_000, 30_000));
+        let s = c.settle(Side::First, 10_000).unwrap();
+        c.commit(Side::First, s);
+        assert_eq!(c.display_ms(Side::First, 0), 320_000);
+        assert!(c.is_flagged(Side::First, 320_000));
+        assert!(!c.is_flagged(Side::First, 319_999));
+        // 另一方不受影響
+        assert_eq!(c.display_ms(Side::Second, 0), 300_000);
+    }
+
+    #[test]
+    fn byoyomi_resets_period_when_moved_in_time() {
+        let mut c = Clock::new(BYO);
+        // 主時間用完又進讀秒 20 秒：不耗次數，主時間歸零
+        let s = c.settle(Side::First, 80_000).unwrap();
+        assert_eq!(s, Settled { main_ms: 0, periods: 3 });
+        c.commit(Side::First, s);
+        assert_eq!(c.display_ms(Side::First, 0), 30_000);
+        // 下一步在 29.9 秒內下完仍是 3 次
+        assert_eq!(c.settle(Side::First, 29_900).unwrap().periods, 3);
+    }
+
+    #[test]
+    fn byoyomi_consumes_periods_then_flags() {
+        let mut c = Clock::new(BYO);
+        c.commit(Side::First, c.settle(Side::First, 60_000).unwrap());
+        // 超過一次讀秒 → 剩 2 次
+        let s = c.settle(Side::First, 45_000).unwrap();
+        assert_eq!(s.periods, 2);
+        assert_eq!(c.display_ms(Side::First, 45_000), 15_000);
+        assert_eq!(c.display_periods(Side::First, 45_000), Some(2));
+        // 三次全耗盡 → 超時
+        assert!(c.is_flagged(Side::First, 90_000));
+        assert!(!c.is_flagged(Side::First, 89_999));
+    }
+
+    #[test]
+    fn untimed_never_flags() {
+        let c = Clock::new(TimeControl::Untimed);
+        assert!(!c.is_flagged(Side::First, i64::MAX / 2));
+        assert_eq!(c.display_periods(Side::First, 0), None);
+    }
+
+    #[test]
+    fn revoke_increment_keeps_clock_positive() {
+        let mut c = Clock::new(TimeControl::fischer(20_000, 30_000));
+        c.commit(Side::First, c.settle(Side::First, 19_000).unwrap());
+        assert_eq!(c.display_ms(Side::First, 0), 31_000);
+        c.revoke_increment(Side::First);
+        assert_eq!(c.display_ms(Side::First, 0), 1_000);
+        c.revoke_increment(Side::First);
+        assert_eq!(c.display_ms(Side::First, 0), 1);
+    }
+
+    #[test]
+    fn clock_json_round_trips() {
+        let mut c = Clock::new(BYO);
+        c.commit(Side::First, c.settle(Side::First, 75_000).unwrap());
+        let back = Clock::from_json(BYO, &c.to_json()).unwrap();
+        assert_eq!(back.display_ms(Side::First, 0), c.display_ms(Side::First, 0));
+        assert_eq!(back.display_periods(Side::First, 0), Some(3));
+        assert_eq!(back.display_ms(Side::Second, 0), 60_000);
+        assert!(Clock::from_json(BYO, &json!({ "main_ms": [1, 2] })).is_none());
+    }
+
+    #[test]
+    fn parse_round_trips_and_rejects_out_of_range() {
+        for tc in [TimeControl::Untimed, TimeControl::fischer(600_000, 5_000), BYO] {
+            assert_eq!(TimeControl::parse(&tc.to_json()), Some(tc));
+        }
+        assert_eq!(
+            TimeControl::parse(&json!({ "type": "fischer", "base_ms": 60_000 })),
+            Some(TimeControl::fischer(60_000, 0))
+        );
+        assert!(TimeControl::parse(&json!({ "type": "fischer", "base_ms": 1_000 })).is_none());
+        assert!(TimeControl::parse(&json!({ "type": "fischer", "base_ms": 60_000, "increment_ms": -1 })).is_none());
+        assert!(TimeControl::parse(&json!({ "type": "byoyomi", "period_ms": 30_000, "periods": 0 })).is_none());
+        assert!(TimeControl::parse(&json!({ "type": "byoyomi", "period_ms": 30_000, "periods": 300 })).is_none());
+        assert!(TimeControl::parse(&json!({ "type": "blitz" })).is_none());
+        assert!(TimeControl::parse(&json!("fischer")).is_none());
+    }
+}
@@ -3,6 +3,9 @@
 
 use serde_json::Value;
 
+use super::bot::GameBot;
+use super::notation::{ExportMeta, Exported};
+
 /// 兩個座位（隨機分派給兩條連線）。`First` 先手。
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Side {
@@ -39,16 +42,44 @@ pub struct Applied {
     pub move_data: Value,
     /// move_made 之後額外推給雙方的事件（type, data），例如象棋 `("check", {...})`。
     pub extra: Vec<(&'static str, Value)>,
+    /// 兩座位對調（五子棋 Swap2 選了另一色）。框架連同時鐘 / 會員 / 重連 token 一起換，並私下通知雙方新顏色。
+    pub swap_seats: bool,
 }
 
 /// 各遊戲實作此 trait 即接上共用對戰框架。
-pub trait GameEngine: Send + Sync + Sized + 'static {
+///
+/// `Clone`：框架每步前留一份局面快照，悔棋（`accept_takeback`）直接換回上一份，
+/// 引擎不必自己實作 undo。
+pub trait GameEngine: Clone + Send + Sync + Sized + 'static {
     /// 遊戲識別字串，對應 WS 信封 `game` 欄、outbound 也帶回。
     const NAME: &'static str;
+    /// 預設計時制（建桌未指定 / 快速配對）。各桌可另選，見 `common::clock::TimeControl`。
     const INITIAL_CLOCK_MS: i64 = 300_000; // 5:00
     const INCREMENT_MS: i64 = 30_000; // Fischer +30s
 
-    fn initial() -> Self;
+    /// 電腦對手（「對電腦」選項）；沒有就填 `common::bot::NoBot`。
+    type Bot: GameBot<Self>;
+
+    /// 解析建桌選項 `data.options`（每桌規則，例如圍棋盤面大小 / 讓子），回傳補齊預設值的正規化 JSON：
+    /// 大廳顯示、開局、紀錄落地、再戰沿用都用這份。省略時收到 null；None = 不合法。
+    /// 預設：此遊戲沒有選項，只接受省略。
+    fn parse_options(v: &Value) -> Option<Value> {
+        v.is_null().then_some(Value::Null)
+    }
+
+    /// 依 `parse_options` 正規化過的選項開局。
+    fn initial(options: &Value) -> Self;
+
+    /// 開局的隨機部分（暗棋的蓋牌排列），重啟保存用：還原時以 `initial_from_seed` 重建同一個開局，
+    /// 再重走 `Game::moves`。在開局局面上呼叫。預設 Null = 開局完全由選項決定。
+    fn initial_seed(&self) -> Value {
+        Value::Null
+    }
+
+    /// 依選項 + `initial_seed` 重建開局。None = seed 不合法。預設忽略 seed、同 `initial`。
+    fn initial_from_seed(options: &Value, _seed: &Value) -> Option<Self> {
+        Some(Self::initial(options))
+    }
 
     /// 當前輪到哪個座位。
     fn turn(&self) -> Side;
@@ -62,4 +93,26 @@ pub trait GameEngine: Send + Sync + Sized + 'static {
     fn try_move(&mut self, mover: Side, data: Option<&Value>) -> Result<Applied, String>;
 
     fn status(&self) -> GameStatus;
+
+    /// 當前完整盤面（JSON 物件），給中途加入的連線（觀戰）一次同步。
+    /// 行棋方與時鐘由框架補，這裡只放引擎自己的狀態；不可洩漏隱藏資訊（暗棋未翻子）。
+    fn snapshot(&self) -> Value;
+
+    /// 目前局面可由任一方提出的和棋理由（`claim_draw`，例如西洋棋三次重複 `repetition`）。
+    /// 成立即以此理由和棋，不需對手同意。預設 None = 此遊戲沒有可提出的和棋。
+    fn claimable_draw(&self) -> Option<&'static str> {
+        None
+    }
+
+    /// 從 `before` 走到目前局面的這一步是否揭露了隱藏資訊（暗棋翻子、炮打暗子）。
+    /// 是則不准悔棋 —— 否則翻完看過再悔，等於免費偷看底牌。預設 false = 此遊戲沒有隱藏資訊。
+    fn reveals_hidden(&self, _before: &Self) -> bool {
+        false
+    }
+
+    /// 匯出標準記譜（見 `common::notation`）。`plies` 為每步之前的局面與該步 `move_made` data，
+    /// `last` 為目前局面。預設 None = 此遊戲沒有通行的記譜格式。
+    fn export(_plies: &[(&Self, &Value)], _last: &Self, _meta: &ExportMeta) -> Option<Exported> {
+        None
+    }
 }
@@ -1,15 +1,24 @@
-//! 泛型大廳 / 桌位 / 對局記憶體狀態（純資料，無 WS 依賴）。重啟即丟失。
+//! 泛型大廳 / 桌位 / 對局記憶體狀態（純資料，無 WS 依賴）。進行中的對局定期快照進 Redis、重啟後還原（見 `persist`）。
 
 use std::collections::{HashMap, HashSet, VecDeque};
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
 use std::sync::Arc;
 use tokio::sync::Mutex;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 use tokio::time::Instant;
 
+use super::bot::BotLevel;
+use super::chat::{ChatLine, ChatState};
+use super::clock::{Clock, TimeControl};
 use super::engine::{GameEngine, Side};
+use crate::games::tournament::TournamentSeat;
 
 pub type GameHub<E> = Arc<Mutex<HubInner<E>>>;
 
+/// 電腦對手佔的座位位址（`0.0.0.0:0`，不會是真連線）。不進 `conn_table`、不收訊息（`audience` 已濾掉）。
+pub const BOT_SEAT: SocketAddr = SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, 0));
+
 pub struct HubInner<E> {
     /// 快速配對佇列。
     pub queue: VecDeque<SocketAddr>,
@@ -19,6 +28,16 @@ pub struct HubInner<E> {
     pub conn_table: HashMap<SocketAddr, u64>,
     /// 訂閱大廳更新的連線。
     pub lobby: HashSet<SocketAddr>,
+    /// 觀戰連線 → 所看的桌。觀戰不算承諾（不在 `conn_table`），進桌 / 排隊時自動退出觀戰。
+    pub spectating: HashMap<SocketAddr, u64>,
+    /// 連線 → 綁定的會員（握手帶會員票者）。進大廳 / 進桌 / 觀戰 / 排隊 / 聊天時登記，斷線才移除。
+    pub members: HashMap<SocketAddr, i64>,
+    /// 完局雙方 → 上一盤資訊，供 `rematch` 換邊再戰。下一盤完局覆寫、斷線移除。
+    pub rematch: HashMap<SocketAddr, RematchSlot>,
+    /// 發起方 → 待回應的直接挑戰。一條連線同時只發一個，再發即取代。
+    pub challenges: HashMap<SocketAddr, Challenge>,
+    /// 大廳聊天紀錄、發言頻率與靜音名單。
+    pub chat: ChatState,
     pub next_id: u64,
 }
 
@@ -30,6 +49,11 @@ impl<E> Default for HubInner<E> {
             tables: HashMap::new(),
             conn_table: HashMap::new(),
             lobby: HashSet::new(),
+            spectating: HashMap::new(),
+            members: HashMap::new(),
+            rematch: HashMap::new(),
+            challenges: HashMap::new(),
+            chat: ChatState::default(),
             next_id: 0,
         }
     }
@@ -40,14 +64,75 @@ impl<E> HubInner<E> {
     pub fn is_committed(&self, who: SocketAddr) -> bool {
         self.conn_table.contains_key(&who) || self.queue.contains(&who)
     }
+
+    /// 登記連線的會員身分（匿名連線不登記）。
+    pub fn bind_member(&mut self, who: SocketAddr, member: Option<i64>) {
+        if let Some(m) = member {
+            self.members.insert(who, m);
+        }
+    }
+
+    /// 產生一組目前沒有桌在用的邀請碼。
+    pub fn new_invite_code(&self) -> String {
+        loop {
+            let code: String = (0..INVITE_CODE_LEN)
+                .map(|_| INVITE_ALPHABET[rand::random_range(0..INVITE_ALPHABET.len())] as char)
+                .collect();
+            if self.table_by_invite(&code).is_none() {
+                return code;
+            }
+        }
+    }
+
+    /// 憑邀請碼找私人桌（不分大小寫）。
+    pub fn table_by_invite(&self, code: &str) -> Option<u64> {
+        self.tables
+            .values()
+            .find(|t| t.invite.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(code.trim())))
+            .map(|t| t.id)
+    }
+}
+
+/// 邀請碼長度與字元集（去掉易混淆的 0/O、1/I/L）。
+const INVITE_CODE_LEN: usize = 6;
+const INVITE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+
+/// 對大廳裡某條連線發出的直接挑戰。對方 `accept_challenge` 才開桌（隨機分座）。
+pub struct Challenge {
+    pub to: SocketAddr,
+    pub time_control: TimeControl,
+    pub options: Value,
+}
+
+/// 上一盤的對手與座位。雙方都送出 `rematch` 才開新局。
+pub struct RematchSlot {
+    pub opponent: SocketAddr,
+    pub side: Side,
+    pub table_name: String,
+    /// 再戰沿用同一計時制與規則選項
+    pub time_control: TimeControl,
+    pub options: Value,
+    /// 上一盤是私人桌則沿用同一邀請碼（觀戰者憑碼續看）；那時已被別桌用掉就另發新碼。
+    pub invite: Option<String>,
+    pub wants: bool,
 }
 
 pub struct Table<E> {
     pub id: u64,
     pub name: String,
+    /// 建桌時選定的計時制（快速配對用遊戲預設）。
+    pub time_control: TimeControl,
+    /// 規則選項（`GameEngine::parse_options` 正規化過；快速配對用預設）。
+    pub options: Value,
+    /// 私人桌的邀請碼：不列在大廳清單，只能憑碼入座 / 觀戰。公開桌為 None。
+    pub invite: Option<String>,
     pub state: TableState<E>,
+    /// 桌內最近聊天（不隨快照保存）。
+    pub chat: VecDeque<ChatLine>,
 }
 
+// 桌數少、Waiting 短命，Playing 不 Box 也無記憶體壓力（同 `room::RoomState`）
+#[allow(clippy::large_enum_variant)]
 pub enum TableState<E> {
     Waiting { host: SocketAddr },
     Playing(Game<E>),
@@ -57,20 +142,99 @@ pub enum TableState<E> {
 pub struct Game<E> {
     pub seats: [SocketAddr; 2],
     pub engine: E,
-    pub clock_ms: [i64; 2],
+    /// 開局的隨機部分（`GameEngine::initial_seed`），重啟保存用。
+    pub seed: Value,
+    /// 開局的規則選項（同 `Table::options`），紀錄落地 / 重播用。
+    pub options: Value,
+    pub clock: Clock,
     /// 當前行棋方本回合開始時刻（server 單調時鐘）。
     pub turn_started_at: Instant,
     pub ended: bool,
+    /// 觀戰者：收 move_made / 額外事件 / game_over，不能行棋。
+    pub spectators: HashSet<SocketAddr>,
+    /// 每一步 `move_made` 的 data（依序），終局時寫進 `game_records` 供重播。
+    pub moves: Vec<Value>,
+    /// 兩座位綁定的會員（匿名為 None）。雙方皆為不同會員才計積分。
+    pub members: [Option<i64>; 2],
+    /// 開局牆鐘時間（紀錄用；計時一律走上面的單調時鐘）。
+    pub started_at: DateTime<Utc>,
+    /// 各座位的重連 token（`match_found` 私下發給本人）。憑 token 從新連線取回座位。
+    pub tokens: [String; 2],
+    /// 每一步之前的局面（與 `moves` 一一對應），悔棋時換回最後一份。
+    pub history: Vec<Ply<E>>,
+    /// `history` 的前這麼多步不可悔：Swap2 換座位（含選色那一步）之前的快照行棋方是舊座位。
+    pub undo_floor: usize,
+    /// 待回應的和棋提議（提議方）。任一方行棋即失效。
+    pub draw_offer: Option<Side>,
+    /// 待回應的悔棋請求（請求方）。任一方行棋即失效。
+    pub takeback_request: Option<Side>,
+    /// 斷線座位的重連期限（斷線當下 + 寬限期）；逾期由 `timeout_watcher` 判負。期間時鐘照走。
+    pub reconnect_deadline: [Option<Instant>; 2],
+    /// 對電腦局的電腦強度（電腦坐 `seats[1]` = `BOT_SEAT`，Swap2 選色後可能換到 `seats[0]`）；人對人為 None。
+    pub bot: Option<BotLevel>,
+    /// 錦標賽對局的賽事 / 對陣（完局時回報結果）；一般對局為 None。
+    pub tournament: Option<TournamentSeat>,
+}
+
+/// 一步之前的快照：行棋方與當時的引擎狀態。
+pub struct Ply<E> {
+    pub mover: Side,
+    pub engine: E,
 }
 
 impl<E: GameEngine> Game<E> {
-    pub fn new(first: SocketAddr, second: SocketAddr) -> Self {
+    pub fn new(
+        first: SocketAddr,
+        second: SocketAddr,
+        members: [Option<i64>; 2],
+        time_control: TimeControl,
+        options: Value,
+    ) -> Self {
+        let engine = E::initial(&options);
         Game {
             seats: [first, second],
-            engine: E::initial(),
-            clock_ms: [E::INITIAL_CLOCK_MS, E::INITIAL_CLOCK_MS],
+            members,
+            seed: engine.initial_seed(),
+            engine,
+            options,
+            clock: Clock::new(time_control),
             turn_started_at: Instant::now(),
             ended: false,
+            spectators: HashSet::new(),
+            moves: Vec::new(),
+            started_at: Utc::now(),
+            tokens: [uuid::Uuid::new_v4().to_string(), uuid::Uuid::new_v4().to_string()],
+            reconnect_deadline: [None, None],
+            history: Vec::new(),
+            undo_floor: 0,
+            draw_offer: None,
+            takeback_request: None,
+            bot: None,
+            tournament: None,
+        }
+    }
+
+    /// 是否為計分局：計時局、雙方都綁了會員、且不是同一人（開兩個分頁自己打自己不算）。
+    /// 不計時局是休閒局、對電腦局是練習局，都不動積分。
+    pub fn is_rated(&self) -> bool {
+        self.bot.is_none()
+            && self.clock.control.is_timed()
+            && matches!(self.members, [Some(a), Some(b)] if a != b)
+    }
+
+    /// 行棋方本回合已用時間（ms）。
+    pub fn turn_elapsed_ms(&self) -> i64 {
+        self.turn_started_at.elapsed().as_millis() as i64
+    }
+
+    /// 憑重連 token 找座位。
+    pub fn side_of_token(&self, token: &str) -> Option<Side> {
+        if token == self.tokens[0] {
+            Some(Side::First)
+        } else if token == self.tokens[1] {
+            Some(Side::Second)
+        } else {
+            None
         }
     }
 
@@ -84,11 +248,45 @@ impl<E: GameEngine> Game<E> {
         }
     }
 
-    pub fn clock_of(&self, side: Side) -> i64 {
-        self.clock_ms[side.index()]
+    /// 兩座位對調：人、會員、重連 token、斷線期限與剩餘時間都跟著換。
+    /// 悔棋快照的行棋方是舊座位：快照留著（與 `moves` 一一對應，記譜要用），只把 `undo_floor`
+    /// 推到目前步數，不能悔到選色之前。
+    pub fn swap_seats(&mut self) {
+        self.seats.swap(0, 1);
+        self.members.swap(0, 1);
+        self.tokens.swap(0, 1);
+        self.reconnect_deadline.swap(0, 1);
+        self.clock.swap_sides();
+        self.undo_floor = self.history.len();
+    }
+
+    /// 每一步之前的局面與該步 `move_made` data（`GameEngine::export` 的 `plies`）。
+    pub fn plies(&self) -> Vec<(&E, &Value)> {
+        self.history.iter().zip(&self.moves).map(|(ply, mv)| (&ply.engine, mv)).collect()
+    }
+
+    /// 對局事件的收件人：雙方座位 + 觀戰者（不含電腦座位）。
+    pub fn audience(&self) -> impl Iterator<Item = SocketAddr> + '_ {
+        self.seats
+            .iter()
+            .copied()
+            .filter(|&a| a != BOT_SEAT)
+            .chain(self.spectators.iter().copied())
     }
+}
 
-    pub fn set_clock(&mut self, side: Side, ms: i64) {
-        self.clock_ms[side.index()] = ms;
+#[cfg(test)]
+impl<E: GameEngine> Game<E> {
+    /// 測試用：照 `service::play_move` 記一步（留下走之前的局面、存 `move_made` data、Swap2 換座位），
+    /// 不碰時鐘、不推訊息。步不合法即 panic。
+    pub fn play(&mut self, data: Value) {
+        let mover = self.engine.turn();
+        let before = self.engine.clone();
+        let applied = self.engine.try_move(mover, Some(&data)).expect("測試步應合法");
+        self.history.push(Ply { mover, engine: before });
+        self.moves.push(applied.move_data);
+        if applied.swap_seats {
+            self.swap_seats();
+        }
     }
 }
@@ -1,9 +1,16 @@
 //! 通用對戰框架。
-//! 回合制 2 人：`GameEngine` trait + 泛型大廳/桌位/配對/計時/斷線（`engine`/`hub`/`service`），
+//! 回合制 2 人：`GameEngine` trait + 泛型大廳/桌位/配對/計時/斷線（`engine`/`hub`/`service`，聊天在 `chat`，計時制在 `clock`，電腦對手在 `bot`，記譜匯出在 `notation`，重複局面 hash 在 `zobrist`），
 //! 各遊戲只需 impl `GameEngine`（見 `games::chess::game` 等）。
 //! N 人房（avalon/farm）：`RoomKind` trait + 泛型大廳/房間/斷線（`room`）。
+//! 兩者進行中的對局都定期快照進 Redis、重啟後還原（`persist`）。
 
+pub mod bot;
+pub mod chat;
+pub mod clock;
 pub mod engine;
 pub mod hub;
+pub mod notation;
+pub mod persist;
 pub mod room;
 pub mod service;
+pub mod zobrist;
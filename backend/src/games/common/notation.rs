@@ -0,0 +1,146 @@
+//! 記譜匯出：把一局的走步轉成外部工具讀得懂的標準格式（PGN / SGF / 象棋 PGN …）。
+//!
+//! 各遊戲在 `GameEngine::export` 實作自己的格式，輸入是「每步之前的局面 + 該步 move data」。
+//! 進行中的局直接拿 `Game::history` / `Game::moves`；完局紀錄只存 move data，用 `replay`
+//! 從開局重走一遍還原局面（暗棋翻子是隨機的，重走不出來，故不支援）。
+
+use chrono::{DateTime, Utc};
+use serde_json::{json, Value};
+
+use super::engine::{GameEngine, Side};
+use crate::structs::game_records::GameRecord;
+
+/// 與遊戲無關的檔頭資訊。
+pub struct ExportMeta {
+    /// 桌名（PGN `Event` / SGF `GN`）。
+    pub event: String,
+    pub date: DateTime<Utc>,
+    /// 兩座位顯示名（`[0]` 先手）；匿名對戰填 `?`。
+    pub players: [String; 2],
+    /// 終局結果；進行中為 None。
+    pub result: Option<ExportResult>,
+    /// 呼叫端指定的格式（有多種記法的遊戲用，例如象棋 `wxf` / `iccs`）；None / 不認得 = 該遊戲預設。
+    pub format: Option<String>,
+}
+
+pub struct ExportResult {
+    /// 勝方座位；None = 和局。
+    pub winner: Option<Side>,
+    /// 結束原因（`game_over.reason`：checkmate / resign / timeout / score …）。
+    pub reason: String,
+}
+
+/// 匯出結果：格式代號、副檔名、MIME 與全文。
+pub struct Exported {
+    pub format: &'static str,
+    pub extension: &'static str,
+    pub mime: &'static str,
+    pub text: String,
+}
+
+impl Exported {
+    /// WS `notation` 回應的 data。
+    pub fn to_json(&self) -> Value {
+        json!({ "format": self.format, "extension": self.extension, "text": self.text })
+    }
+}
+
+/// 依開局選項從頭重走 `moves`（`move_made` data），回傳每步之前的局面與終局局面。任一步走不通回 None。
+pub fn replay<E: GameEngine>(options: &Value, moves: &[Value]) -> Option<(Vec<E>, E)> {
+    let mut positions = Vec::with_capacity(moves.len());
+    let mut engine = E::initial(options);
+    for mv in moves {
+        positions.push(engine.clone());
+        let mover = engine.turn();
+        engine.try_move(mover, Some(mv)).ok()?;
+    }
+    Some((positions, engine))
+}
+
+/// 重播完局紀錄並匯出。
+fn export_moves<E: GameEngine>(options: &Value, moves: &[Value], meta: &ExportMeta) -> Option<Exported> {
+    let (positions, last) = replay::<E>(options, moves)?;
+    let plies: Vec<(&E, &Value)> = positions.iter().zip(moves).collect();
+    E::export(&plies, &last, meta)
+}
+
+/// 完局紀錄匯出：勝方標籤換回座位、玩家一律匿名（公開紀錄不含身份）。
+pub fn export_record<E: GameEngine>(rec: &GameRecord, format: Option<String>) -> Option<Exported> {
+    let winner = match rec.winner.as_deref() {
+        Some(label) => Some([Side::First, Side::Second].into_iter().find(|&s| E::side_label(s) == label)?),
+        None => None,
+    };
+    let meta = ExportMeta {
+        event: rec.table_name.clone(),
+        date: rec.started_at,
+        players: ["?".into(), "?".into()],
+        result: Some(ExportResult { winner, reason: rec.reason.clone() }),
+        format,
+    };
+    // 舊紀錄 / 無選項遊戲為 null，正規化後即遊戲預設
+    let options = E::parse_options(&rec.options)?;
+    export_moves::<E>(&options, rec.moves.as_array()?, &meta)
+}
+
+/// PGN 風格的 tag 行 `[Key "value"]`（值內的 `\` 與 `"` 跳脫）。PGN 與象棋 PGN 共用。
+pub fn pgn_tag(out: &mut String, key: &str, value: &str) {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"");
+    out.push_str(&format!("[{key} \"{escaped}\"]\n"));
+}
+
+/// PGN 結果字串（先手勝 `1-0`、後手勝 `0-1`、和 `1/2-1/2`、進行中 `*`）。
+pub fn pgn_result(result: Option<&ExportResult>) -> &'static str {
+    match result {
+        None => "*",
+        Some(ExportResult { winner: Some(Side::First), .. }) => "1-0",
+        Some(ExportResult { winner: Some(Side::Second), .. }) => "0-1",
+        Some(ExportResult { winner: None, .. }) => "1/2-1/2",
+    }
+}
+
+/// 把「回合號 + 雙方走步」排成每行至多 80 字的 movetext，結尾接結果字串。
+pub fn pgn_movetext(sans: &[String], result: &str) -> String {
+    let mut tokens = Vec::with_capacity(sans.len() * 3 / 2 + 1);
+    for (i, san) in sans.iter().enumerate() {
+        if i % 2 == 0 {
+            tokens.push(format!("{}.", i / 2 + 1));
+        }
+        tokens.push(san.clone());
+    }
+    tokens.push(result.to_string());
+    let mut out = String::new();
+    let mut line_len = 0;
+    for t in tokens {
+        if line_len > 0 && line_len + 1 + t.len() > 80 {
+            out.push('\n');
+            line_len = 0;
+        } else if line_len > 0 {
+            out.push(' ');
+            line_len += 1;
+        }
+        line_len += t.len();
+        out.push_str(&t);
+    }
+    out.push('\n');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tag_escapes_quotes() {
+        let mut s = String::new();
+        pgn_tag(&mut s, "Event", r#"桌 "A"\B"#);
+        assert_eq!(s, "[Event \"桌 \\\"A\\\"\\\\B\"]\n");
+    }
+
+    #[test]
+    fn movetext_numbers_and_wraps() {
+        let sans: Vec<String> = ["e4", "e5", "Nf3"].iter().map(|s| s.to_string()).collect();
+        assert_eq!(pgn_movetext(&sans, "*"), "1. e4 e5 2. Nf3 *\n");
+        let long: Vec<String> = (0..60).map(|_| "Nf3".to_string()).collect();
+        assert!(pgn_movetext(&long, "*").lines().all(|l| l.len() <= 80));
+    }
+}
@@ -0,0 +1,402 @@
+//! 重啟保存：進行中的對局定期（及優雅關機時）快照進 Redis，啟動時在 `routes::app` 還原。
+//!
+//! - 2 人桌：不序列化引擎，只存開局種子（`GameEngine::initial_seed`）+ `Game::moves`，
+//!   還原時從開局重走一遍（悔棋快照一併重建）。時鐘存回合起點的值 + 行棋方本回合已用時間，
+//!   停機期間不扣時。
+//! - N 人房：`RoomKind::Playing` / `Options` 整份序列化。
+//! - 只存對局中的桌 / 房。等待中的桌、佇列、觀戰、挑戰、再戰都綁著連線，重啟後連線全斷，存了也沒用。
+//!
+//! 還原後座位先放佔位位址（`vacant_seat`），每個座位都在斷線寬限期內：玩家憑開局拿到的
+//! reconnect token 從新連線 `reconnect` 取回座位，逾期照一般斷線處理（2 人判負、N 人房中止）。
+
+use std::collections::VecDeque;
+use std::net::{Ipv4Addr, SocketAddr, SocketAddrV4};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::{Duration, Instant};
+
+use super::bot::BotLevel;
+use super::clock::{Clock, TimeControl};
+use super::engine::{GameEngine, GameStatus};
+use super::hub::{Game, GameHub, HubInner, Ply, Table, TableState, BOT_SEAT};
+use super::room::{Room, RoomHub, RoomHubInner, RoomKind, RoomState};
+use super::service::{reconnect_grace, schedule_bot};
+use crate::games::tournament::TournamentSeat;
+use crate::repositories::redis;
+use crate::state::AppState;
+
+/// 定期快照間隔：重啟（含非優雅的 OOM / 當機）最多丟這麼久的走步。
+const SNAPSHOT_INTERVAL: Duration = Duration::from_secs(15);
+/// 快照效期。停機超過這麼久才起來，對局早就沒人等了，不再還原。
+const SNAPSHOT_TTL_SECS: u64 = 30 * 60;
+/// 還原後的重連寬限期下限：重啟期間所有人同時斷線，`game_reconnect_grace_secs` 設得很短（或 0）
+/// 時也要留時間讓前端重連回來。
+const MIN_RESTORE_GRACE: Duration = Duration::from_secs(120);
+
+fn snapshot_key(game: &str) -> String {
+    format!("games:snapshot:{game}")
+}
+
+/// 還原後尚未有人取回的座位位址（`0.0.0.0:{座位+1}`）。同 `BOT_SEAT` 不會是真連線，
+/// 送給它的訊息在 `AppState::send_to` 靜默丟棄；座位號編進 port，同桌 / 同房內不重複。
+pub fn vacant_seat(seat: usize) -> SocketAddr {
+    SocketAddr::V4(SocketAddrV4::new(Ipv4Addr::UNSPECIFIED, seat as u16 + 1))
+}
+
+/// 一個 hub 的快照：`next_id` 跟著存，還原後新開的桌 / 房不撞號。
+#[derive(Serialize, Deserialize)]
+struct Saved<T> {
+    next_id: u64,
+    items: Vec<T>,
+}
+
+// ---- 2 人桌 ----
+
+#[derive(Serialize, Deserialize)]
+struct SavedTable {
+    id: u64,
+    name: String,
+    /// `TimeControl::to_json`
+    time_control: Value,
+    options: Value,
+    invite: Option<String>,
+    seed: Value,
+    moves: Vec<Value>,
+    /// `Clock::to_json`（回合起點的值）
+    clock: Value,
+    turn_elapsed_ms: i64,
+    members: [Option<i64>; 2],
+    tokens: [String; 2],
+    /// 對電腦局：電腦強度與電腦所坐的座位 index。
+    bot: Option<(String, usize)>,
+    tournament: Option<TournamentSeat>,
+    started_at: DateTime<Utc>,
+}
+
+/// 2 人 hub 的快照（呼叫端持有 hub 鎖）。
+pub fn save_tables<E: GameEngine>(h: &HubInner<E>) -> Value {
+    let items: Vec<SavedTable> = h
+        .tables
+        .values()
+        .filter_map(|t| match &t.state {
+            TableState::Playing(g) if !g.ended => Some(save_table(t, g)),
+            _ => None,
+        })
+        .collect();
+    serde_json::to_value(Saved { next_id: h.next_id, items }).unwrap_or_default()
+}
+
+fn save_table<E: GameEngine>(t: &Table<E>, g: &Game<E>) -> SavedTable {
+    SavedTable {
+        id: t.id,
+        name: t.name.clone(),
+        time_control: g.clock.control.to_json(),
+        options: g.options.clone(),
+        invite: t.invite.clone(),
+        seed: g.seed.clone(),
+        moves: g.moves.clone(),
+        clock: g.clock.to_json(),
+        turn_elapsed_ms: g.turn_elapsed_ms(),
+        members: g.members,
+        tokens: g.tokens.clone(),
+        bot: g.bot.and_then(|level| {
+            let seat = g.seats.iter().position(|&s| s == BOT_SEAT)?;
+            Some((level.as_str().to_string(), seat))
+        }),
+        tournament: g.tournament,
+        started_at: g.started_at,
+    }
+}
+
+/// 由快照重建一局：依種子開局、重走所有走步。走不通（引擎改版後舊步不合法）或已終局回 None。
+fn rebuild_game<E: GameEngine>(t: &SavedTable, grace: Duration) -> Option<Game<E>> {
+    let control = TimeControl::parse(&t.time_control)?;
+    let clock = Clock::from_json(control, &t.clock)?;
+    let initial = E::initial_from_seed(&t.options, &t.seed)?;
+    let mut engine = initial.clone();
+    let mut history = Vec::with_capacity(t.moves.len());
+    let mut undo_floor = 0;
+    for mv in &t.moves {
+        let mover = engine.turn();
+        let before = engine.clone();
+        let applied = engine.try_move(mover, Some(mv)).ok()?;
+        history.push(Ply { mover, engine: before });
+        // 同 `Game::swap_seats`：不能悔到換座位之前
+        if applied.swap_seats {
+            undo_floor = history.len();
+        }
+    }
+    if !matches!(engine.status(), GameStatus::Ongoing) {
+        return None;
+    }
+
+    let bot = match &t.bot {
+        Some((level, seat)) if *seat < 2 => Some((BotLevel::parse(level)?, *seat)),
+        Some(_) => return None,
+        None => None,
+    };
+    let mut seats = [vacant_seat(0), vacant_seat(1)];
+    let deadline = Instant::now() + grace;
+    let mut reconnect_deadline = [Some(deadline), Some(deadline)];
+    if let Some((_, seat)) = bot {
+        seats[seat] = BOT_SEAT;
+        reconnect_deadline[seat] = None;
+    }
+    let now = Instant::now();
+    let elapsed = Duration::from_millis(t.turn_elapsed_ms.max(0) as u64);
+    Some(Game {
+        seats,
+        engine,
+        seed: initial.initial_seed(),
+        options: t.options.clone(),
+        clock,
+        turn_started_at: now.checked_sub(elapsed).unwrap_or(now),
+        ended: false,
+        spectators: Default::default(),
+        moves: t.moves.clone(),
+        members: t.members,
+        started_at: t.started_at,
+        tokens: t.tokens.clone(),
+        history,
+        undo_floor,
+        draw_offer: None,
+        takeback_request: None,
+        reconnect_deadline,
+        bot: bot.map(|(level, _)| level),
+        tournament: t.tournament,
+    })
+}
+
+/// 還原 2 人 hub 的快照，回傳還原的局數。輪到電腦的對電腦局接著排電腦想棋。
+pub async fn restore_tables<E: GameEngine>(hub: &GameHub<E>, state: &AppState, saved: Value) -> usize {
+    let Ok(saved) = serde_json::from_value::<Saved<SavedTable>>(saved) else {
+        tracing::warn!("{} snapshot: unreadable, skipped", E::NAME);
+        return 0;
+    };
+    let grace = reconnect_grace(state).max(MIN_RESTORE_GRACE);
+    let mut h = hub.lock().await;
+    h.next_id = h.next_id.max(saved.next_id);
+    let mut restored = Vec::new();
+    for t in &saved.items {
+        let Some(game) = rebuild_game::<E>(t, grace) else {
+            tracing::warn!("{} snapshot: table #{} cannot be replayed, dropped", E::NAME, t.id);
+            continue;
+        };
+        h.tables.insert(
+            t.id,
+            Table {
+                id: t.id,
+                name: t.name.clone(),
+                time_control: game.clock.control,
+                options: t.options.clone(),
+                invite: t.invite.clone(),
+                state: TableState::Playing(game),
+                chat: VecDeque::new(),
+            },
+        );
+        restored.push(t.id);
+    }
+    for &table_id in &restored {
+        schedule_bot(hub, state, &h, table_id);
+    }
+    restored.len()
+}
+
+// ---- N 人房 ----
+
+#[derive(Serialize, Deserialize)]
+struct SavedRoom {
+    id: u64,
+    name: String,
+    names: Vec<String>,
+    host_seat: usize,
+    options: Value,
+    state: Value,
+    tokens: Vec<String>,
+}
+
+/// N 人 hub 的快照（呼叫端持有 hub 鎖）。
+pub fn save_rooms<K: RoomKind>(h: &RoomHubInner<K>) -> Value {
+    let items: Vec<SavedRoom> = h
+        .rooms
+        .values()
+        .filter_map(|r| {
+            let RoomState::Playing(st) = &r.state else { return None };
+            Some(SavedRoom {
+                id: r.id,
+                name: r.name.clone(),
+                names: r.names.clone(),
+                host_seat: r.seat_of(r.host).unwrap_or(0),
+                options: serde_json::to_value(&r.options).ok()?,
+                state: serde_json::to_value(st).ok()?,
+                tokens: r.tokens.clone(),
+            })
+        })
+        .collect();
+    serde_json::to_value(Saved { next_id: h.next_id, items }).unwrap_or_default()
+}
+
+fn rebuild_room<K: RoomKind>(r: SavedRoom, grace: Duration) -> Option<Room<K>> {
+    let n = r.names.len();
+    if r.tokens.len() != n || r.host_seat >= n {
+        return None;
+    }
+    let players: Vec<SocketAddr> = (0..n).map(vacant_seat).collect();
+    Some(Room {
+        id: r.id,
+        name: r.name,
+        host: players[r.host_seat],
+        players,
+        names: r.names,
+        options: serde_json::from_value(r.options).ok()?,
+        state: RoomState::Playing(serde_json::from_value(r.state).ok()?),
+        tokens: r.tokens,
+        reconnect_deadline: vec![Some(Instant::now() + grace); n],
+    })
+}
+
+/// 還原 N 人 hub 的快照，回傳還原的房數。
+pub async fn restore_rooms<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, saved: Value) -> usize {
+    let Ok(saved) = serde_json::from_value::<Saved<SavedRoom>>(saved) else {
+        tracing::warn!("{} snapshot: unreadable, skipped", K::NAME);
+        return 0;
+    };
+    let grace = reconnect_grace(state).max(MIN_RESTORE_GRACE);
+    let mut h = hub.lock().await;
+    h.next_id = h.next_id.max(saved.next_id);
+    let mut restored = 0;
+    for r in saved.items {
+        let id = r.id;
+        match rebuild_room::<K>(r, grace) {
+            Some(room) => {
+                h.rooms.insert(id, room);
+                restored += 1;
+            }
+            None => tracing::warn!("{} snapshot: room #{id} unreadable, dropped", K::NAME),
+        }
+    }
+    restored
+}
+
+// ---- Redis ----
+
+/// 所有遊戲的快照寫進 Redis。定期跑、優雅關機（`main` 的 serve 結束後）再跑一次。
+pub async fn save_all(state: &AppState) {
+    for (name, hub) in state.games().entries() {
+        let snapshot = hub.save().await.to_string();
+        if let Err(e) = redis::cache_set(state.get_redis_pool(), &snapshot_key(name), &snapshot, SNAPSHOT_TTL_SECS).await {
+            tracing::warn!("{name} snapshot: save failed: {e}");
+        }
+    }
+}
+
+/// 啟動時從 Redis 還原所有遊戲（在 watcher 啟動前呼叫）。讀不到 / 壞掉的快照略過，不擋開機。
+pub async fn restore_all(state: &AppState) {
+    for (name, hub) in state.games().entries() {
+        let saved = match redis::cache_get(state.get_redis_pool(), &snapshot_key(name)).await {
+            Ok(Some(s)) => s,
+            Ok(None) => continue,
+            Err(e) => {
+                tracing::warn!("{name} snapshot: load failed: {e}");
+                continue;
+            }
+        };
+        let Ok(saved) = serde_json::from_str::<Value>(&saved) else {
+            tracing::warn!("{name} snapshot: unreadable, skipped");
+            continue;
+        };
+        let restored = hub.restore(state, saved).await;
+        if restored > 0 {
+            tracing::info!("{name}: restored {restored} game(s) from snapshot");
+        }
+    }
+}
+
+/// 啟動時 spawn；每 `SNAPSHOT_INTERVAL` 存一次全部遊戲。
+pub async fn snapshot_loop(state: AppState) {
+    let mut interval = tokio::time::interval(SNAPSHOT_INTERVAL);
+    interval.tick().await; // 第一個 tick 立即返回；剛還原完不必馬上存
+    loop {
+        interval.tick().await;
+        save_all(&state).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::banqi::game::BanqiGame;
+    use crate::games::western_chess::game::WesternChessGame;
+    use serde_json::json;
+
+    fn new_game<E: GameEngine>(options: Value) -> Game<E> {
+        let addr = |p: u16| SocketAddr::from(([127, 0, 0, 1], p));
+        Game::new(addr(1000), addr(1001), [Some(1), Some(2)], TimeControl::fischer(300_000, 5_000), options)
+    }
+
+    fn table<E: GameEngine>(game: Game<E>) -> Table<E> {
+        Table {
+            id: 7,
+            name: "桌 #7".into(),
+            time_control: game.clock.control,
+            options: game.options.clone(),
+            invite: None,
+            state: TableState::Playing(game),
+            chat: VecDeque::new(),
+        }
+    }
+
+    fn round_trip<E: GameEngine>(t: &Table<E>) -> Game<E> {
+        let TableState::Playing(g) = &t.state else { unreachable!() };
+        let saved = serde_json::to_value(save_table(t, g)).unwrap();
+        rebuild_game(&serde_json::from_value(saved).unwrap(), Duration::from_secs(60)).expect("應可重建")
+    }
+
+    #[test]
+    fn chess_game_replays_to_same_position() {
+        let mut game = new_game::<WesternChessGame>(Value::Null);
+        // e2-e4 e7-e5
+        game.play(json!({ "from": [4, 1], "to": [4, 3] }));
+        game.play(json!({ "from": [4, 6], "to": [4, 4] }));
+        let t = table(game);
+        let restored = round_trip(&t);
+        let TableState::Playing(orig) = &t.state else { unreachable!() };
+        assert_eq!(restored.engine.snapshot(), orig.engine.snapshot());
+        assert_eq!(restored.history.len(), 2, "悔棋快照應一併重建");
+        assert_eq!(restored.tokens, orig.tokens);
+        assert_eq!(restored.members, [Some(1), Some(2)]);
+        // 座位是佔位，兩邊都在重連寬限期內
+        assert_eq!(restored.seats, [vacant_seat(0), vacant_seat(1)]);
+        assert!(restored.reconnect_deadline.iter().all(Option::is_some));
+    }
+
+    #[test]
+    fn banqi_restores_the_same_hidden_deck() {
+        let mut game = new_game::<BanqiGame>(json!({}));
+        game.play(json!({ "action": "flip", "at": [0, 0] }));
+        let t = table(game);
+        let mut restored = round_trip(&t);
+        let TableState::Playing(orig) = &t.state else { unreachable!() };
+        assert_eq!(restored.seed, orig.seed);
+        // 接下來翻的子也要一樣：未翻子的排列跟著還原
+        let mut orig = orig.engine.clone();
+        let flip = json!({ "action": "flip", "at": [7, 3] });
+        let side = orig.turn();
+        orig.try_move(side, Some(&flip)).unwrap();
+        restored.engine.try_move(side, Some(&flip)).unwrap();
+        assert_eq!(restored.engine.snapshot(), orig.snapshot());
+    }
+
+    #[test]
+    fn bot_seat_stays_with_the_computer() {
+        let mut game = new_game::<WesternChessGame>(Value::Null);
+        game.seats[1] = BOT_SEAT;
+        game.bot = Some(BotLevel::Easy);
+        let restored = round_trip(&table(game));
+        assert_eq!(restored.seats, [vacant_seat(0), BOT_SEAT]);
+        assert_eq!(restored.reconnect_deadline[1], None);
+        assert_eq!(restored.bot, Some(BotLevel::Easy));
+    }
+}
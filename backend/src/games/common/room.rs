@@ -3,22 +3,29 @@
 //! 2 人對戰走 `common::{hub,service}`（桌位 + 配對 + 計時）；N 人子系統（avalon / farm）
 //! 走這裡：各遊戲以 marker type impl `RoomKind` 提供靜態參數，遊戲專屬的開局 / 對局
 //! 動作 / 廣播留在各自 `service.rs`，共通指令由 `handle_common` 統一分派。
+//!
+//! 對局中斷線保留座位一段寬限期（同 2 人桌的 `service::reconnect_grace`）：開局時每座位
+//! 私下收到 `reconnect_token`，憑它從新連線 `reconnect` 取回座位；逾期由 `grace_watcher`
+//! 中止整局。主動 `leave_room` 仍立即中止。
 
 use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::{json, Map, Value};
 use tokio::sync::Mutex;
+use tokio::time::{Duration, Instant};
 
+use super::service::reconnect_grace;
 use crate::state::AppState;
 
 /// N 人房遊戲的靜態參數。各遊戲在 `hub.rs` 以 marker type 實作。
 pub trait RoomKind: Send + Sized + 'static {
-    /// 對局中的完整狀態（engine state）。
-    type Playing: Send;
+    /// 對局中的完整狀態（engine state）。可序列化：重啟保存整份存進 Redis（見 `common::persist`）。
+    type Playing: Send + Serialize + DeserializeOwned;
     /// 建房選項；無選項用 `()`。
-    type Options: Default + Send;
+    type Options: Default + Send + Serialize + DeserializeOwned;
 
     /// 遊戲代號（registry key / WS 信封 `game` 欄）。
     const NAME: &'static str;
@@ -37,6 +44,11 @@ pub trait RoomKind: Send + Sized + 'static {
 
     /// `room_snapshot` 的附加欄位（如阿瓦隆的 `options`）。
     fn extend_room_snapshot(_options: &Self::Options, _obj: &mut Map<String, Value>) {}
+
+    /// 重連後補送給該座位的遊戲專屬訊息 `(type, data)`，依序送出（如阿瓦隆的私有角色 + 目前階段）。
+    fn resync(_room: &Room<Self>, _seat: usize) -> Vec<(&'static str, Value)> {
+        Vec::new()
+    }
 }
 
 pub type RoomHub<K> = Arc<Mutex<RoomHubInner<K>>>;
@@ -70,6 +82,10 @@ pub struct Room<K: RoomKind> {
     pub host: SocketAddr,
     pub options: K::Options,
     pub state: RoomState<K::Playing>,
+    /// 各座位的重連 token（開局時由 `issue_reconnect_tokens` 發放；等待中為空）。
+    pub tokens: Vec<String>,
+    /// 斷線座位的重連期限；逾期由 `grace_watcher` 中止整局。等待中為空。
+    pub reconnect_deadline: Vec<Option<Instant>>,
 }
 
 // 房間數少、Waiting 短命，Playing 大 payload 不 Box 也無記憶體壓力
@@ -199,6 +215,7 @@ pub async fn handle_common<K: RoomKind>(
         "create_room" => create_room(hub, state, who, data).await,
         "join_room" => join_room(hub, state, who, data).await,
         "leave_room" => leave_room(hub, state, who).await,
+        "reconnect" => reconnect(hub, state, who, data).await,
         _ => return false,
     }
     true
@@ -240,7 +257,17 @@ pub async fn create_room<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, who: S
         let pname = nickname(data).unwrap_or_else(|| "玩家1".to_string()); // host = seat 0 → 顯示玩家1
         h.rooms.insert(
             id,
-            Room { id, name, players: vec![who], names: vec![pname], host: who, options, state: RoomState::Waiting },
+            Room {
+                id,
+                name,
+                players: vec![who],
+                names: vec![pname],
+                host: who,
+                options,
+                state: RoomState::Waiting,
+                tokens: Vec::new(),
+                reconnect_deadline: Vec::new(),
+            },
         );
         h.conn_room.insert(who, id);
         outbox.push((who, msg::<K>("room_created", json!({ "room_id": id }))));
@@ -289,12 +316,89 @@ pub async fn leave_room<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, who: So
     flush(state, outbox);
 }
 
+/// 斷線：對局中保留座位一段寬限期（寬限期 0 則同 `leave_room` 立即中止）；其餘同 `leave_room`。
 pub async fn handle_disconnect<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, who: SocketAddr) {
+    let grace = reconnect_grace(state);
     let mut outbox = Vec::new();
     {
         let mut h = hub.lock().await;
         h.lobby.remove(&who);
-        remove_from_room(&mut h, who, &mut outbox);
+        if grace.is_zero() || !hold_seat(&mut h, who, grace, &mut outbox) {
+            remove_from_room(&mut h, who, &mut outbox);
+        }
+    }
+    flush(state, outbox);
+}
+
+/// 對局中斷線：記下重連期限、解除連線對應、通知其餘玩家。不在對局中回 false。
+fn hold_seat<K: RoomKind>(
+    h: &mut RoomHubInner<K>,
+    who: SocketAddr,
+    grace: Duration,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> bool {
+    let Some((room_id, seat)) = playing_seat(h, who) else { return false };
+    h.conn_room.remove(&who);
+    let room = h.rooms.get_mut(&room_id).unwrap();
+    room.reconnect_deadline[seat] = Some(Instant::now() + grace);
+    let m = msg::<K>("player_disconnected", json!({ "seat": seat, "grace_ms": grace.as_millis() as u64 }));
+    for &p in &room.players {
+        if p != who {
+            outbox.push((p, m.clone()));
+        }
+    }
+    true
+}
+
+/// 憑 `reconnect_token` 取回對局中的座位（新連線；仍活著的舊連線一併被頂替）。
+/// 回 `reconnected` + `room_update` + `RoomKind::resync` 補送的訊息，其餘玩家收 `player_reconnected`。
+pub async fn reconnect<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        if h.is_committed(who) {
+            err1::<K>(state, who, "already_committed");
+            return;
+        }
+        let Some(token) = data.and_then(|d| d.get("token")).and_then(|v| v.as_str()) else {
+            err1::<K>(state, who, "bad_token");
+            return;
+        };
+        let found = h.rooms.values().find_map(|r| {
+            let seat = r.tokens.iter().position(|t| t == token)?;
+            matches!(r.state, RoomState::Playing(_)).then_some((r.id, seat))
+        });
+        let Some((room_id, seat)) = found else {
+            err1::<K>(state, who, "bad_token");
+            return;
+        };
+        h.lobby.remove(&who);
+        let room = h.rooms.get_mut(&room_id).unwrap();
+        let old = std::mem::replace(&mut room.players[seat], who);
+        if room.host == old {
+            room.host = who;
+        }
+        room.reconnect_deadline[seat] = None;
+        if h.conn_room.remove(&old).is_some() {
+            outbox.push((old, msg::<K>("error", json!({ "reason": "seat_reclaimed" }))));
+        }
+        h.conn_room.insert(who, room_id);
+
+        let room = h.rooms.get(&room_id).unwrap();
+        outbox.push((
+            who,
+            msg::<K>("reconnected", json!({ "room_id": room_id, "seat": seat, "token": room.tokens[seat] })),
+        ));
+        push_room_update(room, &mut outbox);
+        for (typ, d) in K::resync(room, seat) {
+            outbox.push((who, msg::<K>(typ, d)));
+        }
+        let m = msg::<K>("player_reconnected", json!({ "seat": seat }));
+        for &p in &room.players {
+            if p != who {
+                outbox.push((p, m.clone()));
+            }
+        }
     }
     flush(state, outbox);
 }
@@ -325,6 +429,15 @@ pub fn start_check<K: RoomKind>(h: &RoomHubInner<K>, who: SocketAddr) -> Result<
     Ok(room_id)
 }
 
+/// 開局時呼叫：為每座位發新的重連 token，並私下推 `reconnect_token` 給本人。
+pub fn issue_reconnect_tokens<K: RoomKind>(room: &mut Room<K>, outbox: &mut Vec<(SocketAddr, String)>) {
+    room.tokens = room.players.iter().map(|_| uuid::Uuid::new_v4().to_string()).collect();
+    room.reconnect_deadline = vec![None; room.players.len()];
+    for (seat, &p) in room.players.iter().enumerate() {
+        outbox.push((p, msg::<K>("reconnect_token", json!({ "seat": seat, "token": room.tokens[seat] }))));
+    }
+}
+
 /// 對局結束解散房：移除房與所有連線對應，推大廳更新。
 pub fn dissolve_room<K: RoomKind>(h: &mut RoomHubInner<K>, room_id: u64, outbox: &mut Vec<(SocketAddr, String)>) {
     if let Some(room) = h.rooms.remove(&room_id) {
@@ -370,3 +483,69 @@ pub fn remove_from_room<K: RoomKind>(h: &mut RoomHubInner<K>, who: SocketAddr, o
         push_lobby_update(h, outbox);
     }
 }
+
+// ---- 後台管理 ----
+
+/// 後台關房：所有玩家收 `room_closed`（reason `closed_by_admin`）後解散；對局中即中止、不計結果。
+pub async fn admin_close<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, room_id: u64) -> Result<(), &'static str> {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some(room) = h.rooms.get(&room_id) else { return Err("no_such_room") };
+        let m = msg::<K>("room_closed", json!({ "reason": "closed_by_admin" }));
+        for &p in &room.players {
+            outbox.push((p, m.clone()));
+        }
+        dissolve_room(&mut h, room_id, &mut outbox);
+    }
+    flush(state, outbox);
+    Ok(())
+}
+
+/// 後台踢出連線：退出大廳並比照 `leave_room` 離房（對局中＝中止整局），本人收 `kicked`。
+pub async fn kick<K: RoomKind>(hub: &RoomHub<K>, state: &AppState, who: SocketAddr) -> Result<(), &'static str> {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        if !h.lobby.remove(&who) && !h.conn_room.contains_key(&who) {
+            return Err("not_in_game");
+        }
+        remove_from_room(&mut h, who, &mut outbox);
+        outbox.push((who, msg::<K>("kicked", json!({}))));
+    }
+    flush(state, outbox);
+    Ok(())
+}
+
+// ---- 斷線寬限掃描 ----
+
+/// 啟動時 spawn；每秒掃對局中的房，有座位過了重連期限即中止整局
+/// （`room_closed` reason `disconnect`，帶逾期座位）並解散房。
+pub async fn grace_watcher<K: RoomKind>(hub: RoomHub<K>, state: AppState) {
+    let mut interval = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        interval.tick().await;
+        let mut outbox = Vec::new();
+        {
+            let mut h = hub.lock().await;
+            let now = Instant::now();
+            let expired: Vec<(u64, usize)> = h
+                .rooms
+                .values()
+                .filter_map(|r| {
+                    let seat = r.reconnect_deadline.iter().position(|d| d.is_some_and(|d| d <= now))?;
+                    Some((r.id, seat))
+                })
+                .collect();
+            for (room_id, seat) in expired {
+                let room = h.rooms.get(&room_id).unwrap();
+                let m = msg::<K>("room_closed", json!({ "reason": "disconnect", "seat": seat }));
+                for &p in &room.players {
+                    outbox.push((p, m.clone()));
+                }
+                dissolve_room(&mut h, room_id, &mut outbox);
+            }
+        }
+        flush(&state, outbox);
+    }
+}
@@ -1,16 +1,31 @@
-//! 泛型 WS 串接：大廳 / 桌位 / 配對 / 行棋 / 計時 / 斷線。任何 `E: GameEngine` 共用。
+//! 泛型 WS 串接：大廳 / 桌位 / 私人桌 / 挑戰 / 配對 / 錦標賽開桌 / 對電腦 / 行棋 / 計時 / 斷線重連 / 和棋 / 悔棋 / 再戰 / 記譜。任何 `E: GameEngine` 共用。
 //!
-//! 信封 `{ game, type, data }`；`game` = `E::NAME`。事件點對點送相關連線，
+//! 信封 `{ game, type, data }`；`game` = `E::NAME`。事件點對點送相關連線（雙方 + 觀戰者），
 //! 大廳更新只送 `lobby` 訂閱集。
 
+use std::collections::{HashSet, VecDeque};
 use std::net::SocketAddr;
+use std::sync::LazyLock;
 
 use serde_json::{json, Map, Value};
+use tokio::sync::Semaphore;
 use tokio::time::{Duration, Instant};
 
+use super::bot::{BotLevel, GameBot};
+use super::chat;
+use super::clock::TimeControl;
 use super::engine::{GameEngine, GameStatus, Side};
-use super::hub::{Game, GameHub, HubInner, Table, TableState};
+use super::hub::{Challenge, Game, GameHub, HubInner, Ply, RematchSlot, Table, TableState, BOT_SEAT};
+use super::notation::ExportMeta;
+use crate::games::tournament::{RoundPlan, TournamentSeat};
 use crate::state::AppState;
+use crate::structs::game_records::NewGameRecord;
+
+/// `game_reconnect_grace_secs` 未設定 / 壞值時的預設寬限期。
+const DEFAULT_RECONNECT_GRACE_SECS: u64 = 60;
+
+/// 電腦搜尋同時只跑一個（全遊戲共用）：1 核 VPS 上多桌同時想棋時排隊，不把 blocking pool 塞滿。
+static BOT_SLOTS: LazyLock<Semaphore> = LazyLock::new(|| Semaphore::new(1));
 
 /// 收到的 WS 文字訊息分派。回傳 true 表示已處理（呼叫端不再 echo）。
 pub async fn handle<E: GameEngine>(
@@ -26,39 +41,107 @@ pub async fn handle<E: GameEngine>(
         Some("create_table") => create_table(hub, state, who, data).await,
         Some("join_table") => join_table(hub, state, who, data).await,
         Some("leave_table") => leave_table(hub, state, who).await,
+        Some("challenge") => challenge(hub, state, who, data).await,
+        Some("accept_challenge") => accept_challenge(hub, state, who, data).await,
+        Some("decline_challenge") => decline_challenge(hub, state, who, data).await,
+        Some("cancel_challenge") => cancel_challenge(hub, state, who).await,
         Some("join_queue") => join_queue(hub, state, who).await,
+        Some("play_vs_computer") => play_vs_computer(hub, state, who, data).await,
         Some("leave_queue") => leave_queue(hub, who).await,
+        Some("spectate") => spectate(hub, state, who, data).await,
+        Some("unspectate") => unspectate(hub, state, who).await,
+        Some("reconnect") => reconnect(hub, state, who, data).await,
         Some("resign") => resign(hub, state, who).await,
+        Some("offer_draw") => offer_draw(hub, state, who).await,
+        Some("accept_draw") => accept_draw(hub, state, who).await,
+        Some("decline_draw") => decline_draw(hub, state, who).await,
+        Some("claim_draw") => claim_draw(hub, state, who).await,
+        Some("request_takeback") => request_takeback(hub, state, who).await,
+        Some("accept_takeback") => accept_takeback(hub, state, who).await,
+        Some("decline_takeback") => decline_takeback(hub, state, who).await,
+        Some("rematch") => rematch(hub, state, who).await,
         Some("move") => handle_move(hub, state, who, data).await,
+        Some("export") => export(hub, state, who, data).await,
+        Some("chat") => chat::table_chat(hub, state, who, data).await,
+        Some("lobby_chat") => chat::lobby_chat(hub, state, who, data).await,
+        Some("mute") => chat::mute(hub, state, who, data, true).await,
+        Some("unmute") => chat::mute(hub, state, who, data, false).await,
         _ => return false,
     }
     true
 }
 
+/// 斷線寬限期（app_settings `game_reconnect_grace_secs`，預設 60 秒；0 = 斷線立即判負）。
+/// 2 人桌與 N 人房共用。
+pub fn reconnect_grace(state: &AppState) -> Duration {
+    let secs = state
+        .get_settings()
+        .get("game_reconnect_grace_secs")
+        .and_then(|v| v.trim().parse().ok())
+        .unwrap_or(DEFAULT_RECONNECT_GRACE_SECS);
+    Duration::from_secs(secs)
+}
+
 fn msg<E: GameEngine>(typ: &str, data: Value) -> String {
     crate::structs::ws::game_envelope(E::NAME, typ, data)
 }
 
-fn clock_json<E: GameEngine>(game: &Game<E>) -> Value {
-    let mut m = Map::new();
-    m.insert(E::side_label(Side::First).to_string(), json!(game.clock_of(Side::First)));
-    m.insert(E::side_label(Side::Second).to_string(), json!(game.clock_of(Side::Second)));
-    Value::Object(m)
+/// 時鐘欄位併入 `obj`：`clock` = `{label: ms}`（主時間；已進讀秒則為本次讀秒剩餘；不計時為 null），
+/// 讀秒制另帶 `periods` = `{label: 剩餘次數}`。`elapsed_ms` 為行棋方本回合已用時間
+/// （傳 0 即回合起點的值；中途同步傳 `turn_elapsed_ms`）。
+fn with_clock<E: GameEngine>(mut obj: Value, game: &Game<E>, elapsed_ms: i64) -> Value {
+    let Value::Object(map) = &mut obj else {
+        return obj;
+    };
+    if !game.clock.control.is_timed() {
+        map.insert("clock".into(), Value::Null);
+        return obj;
+    }
+    let turn = game.engine.turn();
+    let mut clock = Map::new();
+    let mut periods = Map::new();
+    for side in [Side::First, Side::Second] {
+        let elapsed = if !game.ended && side == turn { elapsed_ms } else { 0 };
+        let label = E::side_label(side).to_string();
+        clock.insert(label.clone(), json!(game.clock.display_ms(side, elapsed)));
+        if let Some(p) = game.clock.display_periods(side, elapsed) {
+            periods.insert(label, json!(p));
+        }
+    }
+    map.insert("clock".into(), Value::Object(clock));
+    if !periods.is_empty() {
+        map.insert("periods".into(), Value::Object(periods));
+    }
+    obj
+}
+
+/// 中途同步（觀戰 / 重連）用：行棋方扣掉本回合已經過的時間。
+fn with_live_clock<E: GameEngine>(obj: Value, game: &Game<E>) -> Value {
+    with_clock(obj, game, game.turn_elapsed_ms())
 }
 
 // ---- 大廳 ----
 
+/// 大廳清單：只列公開桌（私人桌憑邀請碼進出）。
 fn lobby_snapshot<E: GameEngine>(hub: &HubInner<E>) -> Value {
-    let mut tables: Vec<&Table<E>> = hub.tables.values().collect();
+    let mut tables: Vec<&Table<E>> = hub.tables.values().filter(|t| t.invite.is_none()).collect();
     tables.sort_by_key(|t| t.id);
     let list: Vec<Value> = tables
         .iter()
         .map(|t| {
-            let status = match &t.state {
-                TableState::Waiting { .. } => "waiting",
-                TableState::Playing(_) => "playing",
+            let (status, spectators, bot) = match &t.state {
+                TableState::Waiting { .. } => ("waiting", 0, None),
+                TableState::Playing(g) => ("playing", g.spectators.len(), g.bot.map(BotLevel::as_str)),
             };
-            json!({ "id": t.id, "name": t.name, "status": status })
+            json!({
+                "id": t.id,
+                "name": t.name,
+                "status": status,
+                "spectators": spectators,
+                "time_control": t.time_control.to_json(),
+                "options": t.options,
+                "bot": bot,
+            })
         })
         .collect();
     json!({ "tables": list })
@@ -71,12 +154,16 @@ fn push_lobby_update<E: GameEngine>(hub: &HubInner<E>, outbox: &mut Vec<(SocketA
     }
 }
 
+/// 訂閱大廳：回桌列表，再補最近的大廳聊天。
 async fn join_lobby<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let member = state.member_of(who).await;
     let mut h = hub.lock().await;
+    h.bind_member(who, member);
     h.lobby.insert(who);
     let snapshot = msg::<E>("table_list", lobby_snapshot(&h));
+    let history = chat::lobby_history(&h, who);
     drop(h);
-    state.send_to(who, snapshot);
+    flush(state, vec![(who, snapshot), (who, history)]);
 }
 
 async fn list_tables<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
@@ -88,42 +175,100 @@ async fn list_tables<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: Soc
 
 // ---- 桌位 ----
 
+/// 建桌沒指定計時制 / 快速配對時用遊戲預設（Fischer）。
+fn default_time_control<E: GameEngine>() -> TimeControl {
+    TimeControl::fischer(E::INITIAL_CLOCK_MS, E::INCREMENT_MS)
+}
+
+/// 建桌 / 對電腦的 `data.options`（省略 = 遊戲預設）。None = 不合法。
+fn parse_options<E: GameEngine>(data: Option<&Value>) -> Option<Value> {
+    E::parse_options(data.and_then(|d| d.get("options")).unwrap_or(&Value::Null))
+}
+
+/// 建桌 / 挑戰的 `data.time_control` + `data.options`（省略 = 遊戲預設）。Err 為 error reason。
+pub fn parse_setup<E: GameEngine>(data: Option<&Value>) -> Result<(TimeControl, Value), &'static str> {
+    let time_control = match data.and_then(|d| d.get("time_control")) {
+        None => default_time_control::<E>(),
+        Some(v) => TimeControl::parse(v).ok_or("bad_time_control")?,
+    };
+    let options = parse_options::<E>(data).ok_or("bad_options")?;
+    Ok((time_control, options))
+}
+
+/// 入座 / 觀戰的目標桌：`data.invite_code`（私人桌）或 `data.table_id`（公開桌）。
+/// 私人桌不接受只帶 id，回 `table_not_found`（不洩漏私人桌存在）。
+fn resolve_table<E: GameEngine>(hub: &HubInner<E>, data: Option<&Value>) -> Result<u64, &'static str> {
+    if let Some(code) = data.and_then(|d| d.get("invite_code")) {
+        let code = code.as_str().ok_or("bad_invite_code")?;
+        return hub.table_by_invite(code).ok_or("table_not_found");
+    }
+    let table_id = data.and_then(|d| d.get("table_id")).and_then(|v| v.as_u64()).ok_or("bad_table_id")?;
+    match hub.tables.get(&table_id) {
+        Some(t) if t.invite.is_none() => Ok(table_id),
+        _ => Err("table_not_found"),
+    }
+}
+
+/// 建桌。`data.private` 為 true 開私人桌：不進大廳清單，`table_created` 回 `invite_code`
+/// 給 host 分享（前端組成邀請連結），對方帶碼 `join_table` 入座。
 async fn create_table<E: GameEngine>(
     hub: &GameHub<E>,
     state: &AppState,
     who: SocketAddr,
     data: Option<&Value>,
 ) {
+    let member = state.member_of(who).await;
     let mut outbox = Vec::new();
-    {
-        let mut h = hub.lock().await;
-        if h.is_committed(who) {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_committed" })))]);
-            return;
-        }
-        let id = h.next_id;
-        h.next_id += 1;
-        let name = data
-            .and_then(|d| d.get("name"))
-            .and_then(|v| v.as_str())
-            .map(|s| s.trim())
-            .filter(|s| !s.is_empty())
-            .map(|s| s.chars().take(40).collect::<String>())
-            .unwrap_or_else(|| format!("桌 #{id}"));
+    if let Err(reason) = open_table(&mut *hub.lock().await, who, member, data, &mut outbox) {
+        outbox.push((who, msg::<E>("error", json!({ "reason": reason }))));
+    }
+    flush(state, outbox);
+}
 
-        h.tables.insert(
+/// `create_table` 的本體（不碰 `AppState`，可直接對 hub 單測；入座 / 挑戰同）。
+fn open_table<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    member: Option<i64>,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), &'static str> {
+    if h.is_committed(who) {
+        return Err("already_committed");
+    }
+    let (time_control, options) = parse_setup::<E>(data)?;
+    h.bind_member(who, member);
+    stop_spectating(h, who);
+    let id = h.next_id;
+    h.next_id += 1;
+    let name = data
+        .and_then(|d| d.get("name"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.chars().take(40).collect::<String>())
+        .unwrap_or_else(|| format!("桌 #{id}"));
+    let private = data.and_then(|d| d.get("private")).and_then(|v| v.as_bool()).unwrap_or(false);
+    let invite = private.then(|| h.new_invite_code());
+
+    h.tables.insert(
+        id,
+        Table {
             id,
-            Table {
-                id,
-                name,
-                state: TableState::Waiting { host: who },
-            },
-        );
-        h.conn_table.insert(who, id);
-        outbox.push((who, msg::<E>("table_created", json!({ "table_id": id }))));
-        push_lobby_update(&h, &mut outbox);
+            name,
+            time_control,
+            options,
+            invite: invite.clone(),
+            state: TableState::Waiting { host: who },
+            chat: VecDeque::new(),
+        },
+    );
+    h.conn_table.insert(who, id);
+    outbox.push((who, msg::<E>("table_created", json!({ "table_id": id, "invite_code": invite }))));
+    if !private {
+        push_lobby_update(h, outbox);
     }
-    flush(state, outbox);
+    Ok(())
 }
 
 async fn join_table<E: GameEngine>(
@@ -132,39 +277,37 @@ async fn join_table<E: GameEngine>(
     who: SocketAddr,
     data: Option<&Value>,
 ) {
+    let member = state.member_of(who).await;
     let mut outbox = Vec::new();
-    {
-        let mut h = hub.lock().await;
-        if h.is_committed(who) {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_committed" })))]);
-            return;
-        }
-        let Some(table_id) = data.and_then(|d| d.get("table_id")).and_then(|v| v.as_u64()) else {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_table_id" })))]);
-            return;
-        };
-        let host = match h.tables.get(&table_id) {
-            Some(t) => match &t.state {
-                TableState::Waiting { host } => *host,
-                TableState::Playing(_) => {
-                    flush(state, vec![(who, msg::<E>("error", json!({ "reason": "table_full" })))]);
-                    return;
-                }
-            },
-            None => {
-                flush(state, vec![(who, msg::<E>("error", json!({ "reason": "table_not_found" })))]);
-                return;
-            }
-        };
-        if host == who {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "cannot_join_self" })))]);
-            return;
-        }
-        open_game(&mut h, table_id, host, who, &mut outbox);
+    if let Err(reason) = take_seat(&mut *hub.lock().await, who, member, data, &mut outbox) {
+        outbox.push((who, msg::<E>("error", json!({ "reason": reason }))));
     }
     flush(state, outbox);
 }
 
+fn take_seat<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    member: Option<i64>,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), &'static str> {
+    if h.is_committed(who) {
+        return Err("already_committed");
+    }
+    let table_id = resolve_table(h, data)?;
+    let TableState::Waiting { host } = h.tables[&table_id].state else {
+        return Err("table_full");
+    };
+    if host == who {
+        return Err("cannot_join_self");
+    }
+    h.bind_member(who, member);
+    stop_spectating(h, who);
+    open_game(h, table_id, host, who, outbox);
+    Ok(())
+}
+
 /// host 在等待中離開 → 銷毀桌。對戰中請用 `resign`。
 async fn leave_table<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
     let mut outbox = Vec::new();
@@ -185,15 +328,153 @@ async fn leave_table<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: Soc
     flush(state, outbox);
 }
 
+// ---- 挑戰 ----
+
+/// 解析 `data.<key>` 的連線位址（大廳裡其他連線的 `addr`，見 `/ws/connections`）。
+fn parse_addr(data: Option<&Value>, key: &str) -> Option<SocketAddr> {
+    data?.get(key)?.as_str()?.parse().ok()
+}
+
+/// 撤回 `who` 發出的挑戰（沒有則無事），通知被挑戰方 `challenge_cancelled`。
+fn withdraw_challenge<E: GameEngine>(hub: &mut HubInner<E>, who: SocketAddr, outbox: &mut Vec<(SocketAddr, String)>) {
+    if let Some(c) = hub.challenges.remove(&who) {
+        outbox.push((c.to, msg::<E>("challenge_cancelled", json!({ "from": who.to_string() }))));
+    }
+}
+
+/// 向同一大廳裡的另一條連線發出直接挑戰（`data.to` = 對方 addr，可帶 `time_control` / `options`，同建桌）。
+/// 對方收 `challenge_received`，回 `accept_challenge` / `decline_challenge`；再發一次即取代前一個挑戰。
+async fn challenge<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    if let Err(reason) = issue_challenge(&mut *hub.lock().await, who, member, data, &mut outbox) {
+        outbox.push((who, msg::<E>("error", json!({ "reason": reason }))));
+    }
+    flush(state, outbox);
+}
+
+fn issue_challenge<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    member: Option<i64>,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), &'static str> {
+    if h.is_committed(who) {
+        return Err("already_committed");
+    }
+    let to = parse_addr(data, "to").ok_or("bad_target")?;
+    if to == who || !h.lobby.contains(&to) || h.is_committed(to) {
+        return Err("player_unavailable");
+    }
+    let (time_control, options) = parse_setup::<E>(data)?;
+    h.bind_member(who, member);
+    withdraw_challenge(h, who, outbox);
+    let offer = json!({
+        "from": who.to_string(),
+        "time_control": time_control.to_json(),
+        "options": options,
+    });
+    h.challenges.insert(who, Challenge { to, time_control, options });
+    outbox.push((to, msg::<E>("challenge_received", offer)));
+    outbox.push((who, msg::<E>("challenge_sent", json!({ "to": to.to_string() }))));
+    Ok(())
+}
+
+/// 接受 `data.from` 發來的挑戰：以挑戰的計時制 / 選項開一桌、隨機分座。
+/// 發起方已撤回 / 已進別桌 / 已排隊則回 `challenge_unavailable`。
+async fn accept_challenge<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    if let Err(reason) = take_challenge(&mut *hub.lock().await, who, member, data, &mut outbox) {
+        outbox.push((who, msg::<E>("error", json!({ "reason": reason }))));
+    }
+    flush(state, outbox);
+}
+
+fn take_challenge<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    member: Option<i64>,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), &'static str> {
+    if h.is_committed(who) {
+        return Err("already_committed");
+    }
+    let from = parse_addr(data, "from").ok_or("bad_target")?;
+    if h.challenges.get(&from).is_none_or(|c| c.to != who) {
+        return Err("challenge_unavailable");
+    }
+    let c = h.challenges.remove(&from).unwrap();
+    if h.is_committed(from) {
+        return Err("challenge_unavailable");
+    }
+    h.bind_member(who, member);
+    // 開局後雙方各自發出的其他挑戰作廢
+    withdraw_challenge(h, who, outbox);
+    stop_spectating(h, who);
+    stop_spectating(h, from);
+    let id = h.next_id;
+    h.next_id += 1;
+    h.tables.insert(
+        id,
+        Table {
+            id,
+            name: format!("挑戰 #{id}"),
+            time_control: c.time_control,
+            options: c.options,
+            invite: None,
+            state: TableState::Waiting { host: from },
+            chat: VecDeque::new(),
+        },
+    );
+    open_game(h, id, from, who, outbox);
+    Ok(())
+}
+
+/// 婉拒 `data.from` 發來的挑戰，通知發起方 `challenge_declined`。
+async fn decline_challenge<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let mut outbox = Vec::new();
+    refuse_challenge(&mut *hub.lock().await, who, data, &mut outbox);
+    flush(state, outbox);
+}
+
+fn refuse_challenge<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    let Some(from) = parse_addr(data, "from") else {
+        return;
+    };
+    if h.challenges.get(&from).is_some_and(|c| c.to == who) {
+        h.challenges.remove(&from);
+        outbox.push((from, msg::<E>("challenge_declined", json!({ "by": who.to_string() }))));
+    }
+}
+
+async fn cancel_challenge<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    withdraw_challenge(&mut *hub.lock().await, who, &mut outbox);
+    flush(state, outbox);
+}
+
 // ---- 快速配對 ----
 
 async fn join_queue<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let member = state.member_of(who).await;
     let mut outbox = Vec::new();
     {
         let mut h = hub.lock().await;
         if h.is_committed(who) {
             return;
         }
+        h.bind_member(who, member);
+        if stop_spectating(&mut h, who) {
+            push_lobby_update(&h, &mut outbox);
+        }
         h.queue.push_back(who);
         if h.queue.len() >= 2 {
             let a = h.queue.pop_front().unwrap();
@@ -205,7 +486,11 @@ async fn join_queue<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: Sock
                 Table {
                     id,
                     name: format!("快速對局 #{id}"),
+                    time_control: default_time_control::<E>(),
+                    options: parse_options::<E>(None).unwrap_or_default(),
+                    invite: None,
                     state: TableState::Waiting { host: a },
+                    chat: VecDeque::new(),
                 },
             );
             open_game(&mut h, id, a, b, &mut outbox);
@@ -230,29 +515,56 @@ fn open_game<E: GameEngine>(
     outbox: &mut Vec<(SocketAddr, String)>,
 ) {
     let (first, second) = if rand::random::<bool>() { (a, b) } else { (b, a) };
-    let game = Game::<E>::new(first, second);
+    start_game(hub, table_id, first, second, None, outbox);
+}
 
-    match hub.tables.get_mut(&table_id) {
-        Some(table) => table.state = TableState::Playing(game),
-        None => return,
-    }
+/// 以指定座位開局（`open_game` 隨機分座；`rematch` 換邊；`play_vs_computer` 電腦坐後手、`bot` 為其強度）。
+fn start_game<E: GameEngine>(
+    hub: &mut HubInner<E>,
+    table_id: u64,
+    first: SocketAddr,
+    second: SocketAddr,
+    bot: Option<BotLevel>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    let members = [hub.members.get(&first).copied(), hub.members.get(&second).copied()];
+    let Some(table) = hub.tables.get_mut(&table_id) else {
+        return;
+    };
+    let time_control = table.time_control;
+    let options = table.options.clone();
+    let invite = table.invite.clone();
+    let mut game = Game::<E>::new(first, second, members, time_control, options.clone());
+    game.bot = bot;
+    let rated = game.is_rated();
+    let tokens = game.tokens.clone();
+    table.state = TableState::Playing(game);
 
-    hub.conn_table.insert(first, table_id);
-    hub.conn_table.insert(second, table_id);
-    hub.queue.retain(|&c| c != a && c != b);
-    hub.lobby.remove(&a);
-    hub.lobby.remove(&b);
+    for conn in [first, second].into_iter().filter(|&c| c != BOT_SEAT) {
+        hub.conn_table.insert(conn, table_id);
+        hub.queue.retain(|&c| c != conn);
+        hub.lobby.remove(&conn);
+    }
 
     for side in [Side::First, Side::Second] {
         let conn = if side == Side::First { first } else { second };
+        if conn == BOT_SEAT {
+            continue;
+        }
         outbox.push((
             conn,
             msg::<E>(
                 "match_found",
                 json!({
                     "color": E::side_label(side),
-                    "clock_ms": E::INITIAL_CLOCK_MS,
+                    "clock_ms": time_control.base_ms(),
+                    "time_control": time_control.to_json(),
+                    "options": options,
                     "table_id": table_id,
+                    "invite_code": invite,
+                    "rated": rated,
+                    "bot": bot.map(BotLevel::as_str),
+                    "reconnect_token": tokens[side.index()],
                 }),
             ),
         ));
@@ -260,174 +572,1104 @@ fn open_game<E: GameEngine>(
     push_lobby_update(hub, outbox);
 }
 
-// ---- 行棋 ----
+// ---- 錦標賽 ----
 
-async fn handle_move<E: GameEngine>(
+/// 會員在本遊戲可開局的一條連線：訂閱了本遊戲大廳、沒在下棋（等待中的 host 也算，開局前撤桌）。
+/// 只挑大廳裡的 —— 開著別的遊戲 / 別的頁面的連線收不到這一局，硬拉進來只會讓對手乾等到判不戰。
+fn idle_conn<E: GameEngine>(hub: &HubInner<E>, conns: &[(SocketAddr, i64)], member: i64) -> Option<SocketAddr> {
+    conns
+        .iter()
+        .filter(|&&(addr, m)| {
+            m == member
+                && hub.lobby.contains(&addr)
+                && hub.conn_table.get(&addr).is_none_or(|id| {
+                    matches!(hub.tables.get(id).map(|t| &t.state), Some(TableState::Waiting { .. }))
+                })
+        })
+        .map(|&(addr, _)| addr)
+        .next()
+}
+
+/// 錦標賽事件的收件人：大廳 + 所有參賽者的連線。
+fn tournament_audience<E: GameEngine>(
+    hub: &HubInner<E>,
+    entrants: &[i64],
+    conns: &[(SocketAddr, i64)],
+) -> HashSet<SocketAddr> {
+    let mut to: HashSet<SocketAddr> = hub.lobby.clone();
+    to.extend(conns.iter().filter(|(_, m)| entrants.contains(m)).map(|&(addr, _)| addr));
+    to
+}
+
+/// 錦標賽一輪開桌（`services::game_tournaments` 排好對陣後呼叫）。
+///
+/// 每組對陣替雙方各找一條閒置連線，依對陣表的先後手開局（`match_found` 照常推），
+/// 另私下推 `tournament_pairing`；整輪對陣以 `tournament_round` 推給大廳與全體參賽者。
+/// 開桌當下不在本遊戲大廳（或正在本遊戲別桌對局）者判不戰：回傳 `(pairing_id, [先手到場, 後手到場])`
+/// 交回 service 登錄。輪空不開桌。
+pub async fn seat_tournament_round<E: GameEngine>(
     hub: &GameHub<E>,
     state: &AppState,
-    who: SocketAddr,
-    data: Option<&Value>,
-) {
+    plan: &RoundPlan,
+    conns: &[(SocketAddr, i64)],
+) -> Vec<(i64, [bool; 2])> {
+    let mut absent = Vec::new();
+    let mut listing = Vec::new();
     let mut outbox = Vec::new();
     {
         let mut h = hub.lock().await;
-
-        let Some(&table_id) = h.conn_table.get(&who) else {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
-            return;
-        };
-        let Some(game) = playing_game_mut(&mut h, table_id) else {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
-            return;
-        };
-        if game.ended {
-            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "game_ended" })))]);
-            return;
-        }
-
-        let side = game.side_of(who).unwrap();
-        if game.engine.turn() != side {
-            flush(state, vec![(who, msg::<E>("illegal_move", json!({ "reason": "NotYourTurn" })))]);
-            return;
-        }
-
-        // 計時：先看行棋方是否已超時
-        let now = Instant::now();
-        let elapsed = now.duration_since(game.turn_started_at).as_millis() as i64;
-        let remaining = game.clock_of(side) - elapsed;
-        if remaining <= 0 {
-            game.set_clock(side, 0);
-            end_game(&mut h, table_id, Some(side.opponent()), "timeout", &mut outbox);
-            flush(state, outbox);
-            return;
-        }
-
-        // 合法性 + 套用
-        let applied = match game.engine.try_move(side, data) {
-            Err(reason) => {
-                flush(state, vec![(who, msg::<E>("illegal_move", json!({ "reason": reason })))]);
-                return;
+        for g in &plan.games {
+            let Some(second) = &g.second else {
+                listing.push(json!({ "first": g.first.1, "second": null, "table_id": null, "status": "bye" }));
+                continue;
+            };
+            let seats = [idle_conn(&h, conns, g.first.0), idle_conn(&h, conns, second.0)];
+            let [Some(a), Some(b)] = seats else {
+                absent.push((g.id, seats.map(|s| s.is_some())));
+                listing.push(json!({ "first": g.first.1, "second": second.1, "table_id": null, "status": "forfeit" }));
+                continue;
+            };
+            for (conn, member) in [(a, g.first.0), (b, second.0)] {
+                if let Some(table_id) = h.conn_table.remove(&conn) {
+                    h.tables.remove(&table_id);
+                }
+                h.bind_member(conn, Some(member));
+                stop_spectating(&mut h, conn);
+                withdraw_challenge(&mut h, conn, &mut outbox);
             }
-            Ok(a) => a,
-        };
-
-        // 落子：扣時 + Fischer 增量
-        game.set_clock(side, remaining + E::INCREMENT_MS);
-        game.turn_started_at = now;
-
-        let seats = game.seats;
-        let mut move_data = applied.move_data;
-        if let Value::Object(map) = &mut move_data {
-            map.insert("turn".into(), json!(E::side_label(game.engine.turn())));
-            map.insert("clock".into(), clock_json(game));
-        }
-        let move_msg = msg::<E>("move_made", move_data);
-        outbox.push((seats[0], move_msg.clone()));
-        outbox.push((seats[1], move_msg));
-
-        for (typ, d) in applied.extra {
-            let em = msg::<E>(typ, d);
-            outbox.push((seats[0], em.clone()));
-            outbox.push((seats[1], em));
-        }
-
-        match game.engine.status() {
-            GameStatus::Ongoing => {}
-            GameStatus::Win { winner, reason } => {
-                end_game(&mut h, table_id, Some(winner), reason, &mut outbox);
+            let id = h.next_id;
+            h.next_id += 1;
+            h.tables.insert(
+                id,
+                Table {
+                    id,
+                    name: format!("{} 第 {} 輪", plan.name, plan.round),
+                    time_control: plan.time_control,
+                    options: plan.options.clone(),
+                    invite: None,
+                    state: TableState::Waiting { host: a },
+                    chat: VecDeque::new(),
+                },
+            );
+            start_game(&mut h, id, a, b, None, &mut outbox);
+            if let Some(game) = playing_game_mut(&mut h, id) {
+                game.tournament = Some(TournamentSeat { tournament_id: plan.tournament_id, pairing_id: g.id });
             }
-            GameStatus::Draw { reason } => {
-                end_game(&mut h, table_id, None, reason, &mut outbox);
+            for (conn, side, opponent) in [(a, Side::First, &second.1), (b, Side::Second, &g.first.1)] {
+                let data = json!({
+                    "tournament_id": plan.tournament_id,
+                    "round": plan.round,
+                    "table_id": id,
+                    "color": E::side_label(side),
+                    "opponent": opponent,
+                });
+                outbox.push((conn, msg::<E>("tournament_pairing", data)));
             }
+            listing.push(json!({ "first": g.first.1, "second": second.1, "table_id": id, "status": "playing" }));
+        }
+        let m = msg::<E>(
+            "tournament_round",
+            json!({
+                "tournament_id": plan.tournament_id,
+                "name": plan.name,
+                "round": plan.round,
+                "pairings": listing,
+            }),
+        );
+        for addr in tournament_audience(&h, &plan.entrants, conns) {
+            outbox.push((addr, m.clone()));
         }
     }
     flush(state, outbox);
+    absent
 }
 
-async fn resign<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+/// 推錦標賽事件（`tournament_finished`）給大廳與全體參賽者。
+pub async fn announce_tournament<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    entrants: &[i64],
+    conns: &[(SocketAddr, i64)],
+    typ: &str,
+    data: Value,
+) {
+    let m = msg::<E>(typ, data);
+    let outbox = {
+        let h = hub.lock().await;
+        tournament_audience(&h, entrants, conns).into_iter().map(|addr| (addr, m.clone())).collect()
+    };
+    flush(state, outbox);
+}
+
+// ---- 觀戰 ----
+
+/// 觀戰進行中的桌：回一份完整快照（盤面 / 行棋方 / 即時時鐘 / 至今的走步），
+/// 之後的 move_made、額外事件與 game_over 都會轉送。一條連線同時只看一桌。
+async fn spectate<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    who: SocketAddr,
+    data: Option<&Value>,
+) {
+    let member = state.member_of(who).await;
     let mut outbox = Vec::new();
     {
         let mut h = hub.lock().await;
-        let Some(&table_id) = h.conn_table.get(&who) else {
-            return;
-        };
-        let Some(game) = playing_game_mut(&mut h, table_id) else {
+        if h.is_committed(who) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_committed" })))]);
             return;
+        }
+        h.bind_member(who, member);
+        let table_id = match resolve_table(&h, data) {
+            Ok(id) => id,
+            Err(reason) => {
+                flush(state, vec![(who, msg::<E>("error", json!({ "reason": reason })))]);
+                return;
+            }
         };
-        if game.ended {
+        if !matches!(&h.tables[&table_id].state, TableState::Playing(g) if !g.ended) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "table_not_playing" })))]);
             return;
         }
-        let Some(side) = game.side_of(who) else {
-            return;
+        stop_spectating(&mut h, who);
+        h.spectating.insert(who, table_id);
+        let table = h.tables.get_mut(&table_id).unwrap();
+        let TableState::Playing(game) = &mut table.state else {
+            unreachable!("上面已確認為對戰中");
         };
-        end_game(&mut h, table_id, Some(side.opponent()), "resign", &mut outbox);
+        game.spectators.insert(who);
+        let snapshot = with_live_clock(
+            json!({
+                "table_id": table_id,
+                "name": table.name,
+                "turn": E::side_label(game.engine.turn()),
+                "time_control": game.clock.control.to_json(),
+                "options": game.options,
+                "board": game.engine.snapshot(),
+                "moves": game.moves,
+            }),
+            game,
+        );
+        outbox.push((who, msg::<E>("spectate_snapshot", snapshot)));
+        push_lobby_update(&h, &mut outbox);
     }
     flush(state, outbox);
 }
 
-/// 連線斷開：清佇列/大廳；等待中 host 斷線銷毀桌；對戰中斷線判對手勝。
-pub async fn handle_disconnect<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+async fn unspectate<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
     let mut outbox = Vec::new();
     {
         let mut h = hub.lock().await;
-        h.queue.retain(|&c| c != who);
-        h.lobby.remove(&who);
-
-        if let Some(&table_id) = h.conn_table.get(&who) {
-            let info: Option<(bool, Option<Side>)> = h.tables.get(&table_id).map(|t| match &t.state {
-                TableState::Waiting { .. } => (true, None),
-                TableState::Playing(g) => {
-                    let winner = if g.ended { None } else { g.side_of(who).map(|s| s.opponent()) };
-                    (false, winner)
-                }
-            });
-            match info {
-                Some((true, _)) => {
-                    h.tables.remove(&table_id);
-                    h.conn_table.remove(&who);
-                    push_lobby_update(&h, &mut outbox);
-                }
-                Some((false, Some(winner))) => {
-                    end_game(&mut h, table_id, Some(winner), "disconnect", &mut outbox);
-                }
-                _ => {}
-            }
+        if stop_spectating(&mut h, who) {
+            push_lobby_update(&h, &mut outbox);
         }
     }
     flush(state, outbox);
 }
 
-// ---- 共用 ----
-
-fn playing_game_mut<E: GameEngine>(hub: &mut HubInner<E>, table_id: u64) -> Option<&mut Game<E>> {
-    match hub.tables.get_mut(&table_id)?.state {
-        TableState::Playing(ref mut g) => Some(g),
-        TableState::Waiting { .. } => None,
+/// 讓連線退出觀戰（沒在觀戰則無事）。回傳是否真的退出了 —— 呼叫端據此決定要不要推大廳更新。
+fn stop_spectating<E: GameEngine>(hub: &mut HubInner<E>, who: SocketAddr) -> bool {
+    let Some(table_id) = hub.spectating.remove(&who) else {
+        return false;
+    };
+    if let Some(game) = playing_game_mut(hub, table_id) {
+        game.spectators.remove(&who);
     }
+    true
 }
 
-/// 推 game_over 給雙方並移除桌。`winner` 為 None 表和棋。
-fn end_game<E: GameEngine>(
-    hub: &mut HubInner<E>,
-    table_id: u64,
-    winner: Option<Side>,
-    reason: &str,
-    outbox: &mut Vec<(SocketAddr, String)>,
+// ---- 對電腦 ----
+
+/// 開一桌對電腦：本人坐先手、電腦坐後手（`BOT_SEAT`）。`data` 可帶 `level`（easy/normal/hard，
+/// 預設 normal）與 `time_control`（預設不計時）。練習局：不計分、不能提和 / 悔棋、不登記再戰。
+async fn play_vs_computer<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    who: SocketAddr,
+    data: Option<&Value>,
 ) {
-    if let Some(game) = playing_game_mut(hub, table_id) {
-        game.ended = true;
-        let seats = game.seats;
-        let winner_val = match winner {
-            Some(s) => json!(E::side_label(s)),
-            None => Value::Null,
-        };
-        let m = msg::<E>("game_over", json!({ "winner": winner_val, "reason": reason }));
-        outbox.push((seats[0], m.clone()));
-        outbox.push((seats[1], m));
-        hub.conn_table.remove(&seats[0]);
-        hub.conn_table.remove(&seats[1]);
+    if !<E::Bot as GameBot<E>>::AVAILABLE {
+        flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bot_unavailable" })))]);
+        return;
     }
-    hub.tables.remove(&table_id);
+    let level = match data.and_then(|d| d.get("level")) {
+        None => BotLevel::Normal,
+        Some(v) => match v.as_str().and_then(BotLevel::parse) {
+            Some(l) => l,
+            None => {
+                flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_level" })))]);
+                return;
+            }
+        },
+    };
+    let time_control = match data.and_then(|d| d.get("time_control")) {
+        None => TimeControl::Untimed,
+        Some(v) => match TimeControl::parse(v) {
+            Some(tc) => tc,
+            None => {
+                flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_time_control" })))]);
+                return;
+            }
+        },
+    };
+    let Some(options) = parse_options::<E>(data) else {
+        flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_options" })))]);
+        return;
+    };
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        if h.is_committed(who) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_committed" })))]);
+            return;
+        }
+        h.bind_member(who, member);
+        stop_spectating(&mut h, who);
+        let id = h.next_id;
+        h.next_id += 1;
+        h.tables.insert(
+            id,
+            Table {
+                id,
+                name: format!("對電腦 #{id}"),
+                time_control,
+                options,
+                invite: None,
+                state: TableState::Waiting { host: who },
+                chat: VecDeque::new(),
+            },
+        );
+        start_game(&mut h, id, who, BOT_SEAT, Some(level), &mut outbox);
+        schedule_bot(hub, state, &h, id);
+    }
+    flush(state, outbox);
+}
+
+/// 輪到電腦就在背景想一步：搜尋跑在 `spawn_blocking`（受 `BOT_SLOTS` 限流），想完重新上鎖，
+/// 確認對局還在、局面沒變（期間人認輸 / 超時 / 斷線判負都會讓這步作廢）才落子。
+/// 呼叫端持有 hub 鎖；這裡只 spawn，不等。
+pub(super) fn schedule_bot<E: GameEngine>(hub: &GameHub<E>, state: &AppState, h: &HubInner<E>, table_id: u64) {
+    let Some(TableState::Playing(game)) = h.tables.get(&table_id).map(|t| &t.state) else {
+        return;
+    };
+    let Some(level) = game.bot else {
+        return;
+    };
+    let side = game.engine.turn();
+    if game.ended || game.seats[side.index()] != BOT_SEAT {
+        return;
+    }
+    let engine = game.engine.clone();
+    let ply = game.history.len();
+    let (hub, state) = (hub.clone(), state.clone());
+    tokio::spawn(async move {
+        let chosen = {
+            let _permit = BOT_SLOTS.acquire().await;
+            // 排隊等別桌電腦的時間不算電腦的：拿到名額才起算本回合（排隊期間 watcher 也不判電腦超時）
+            {
+                let mut h = hub.lock().await;
+                match playing_game_mut(&mut h, table_id) {
+                    Some(game) if !game.ended && game.history.len() == ply => game.turn_started_at = Instant::now(),
+                    _ => return,
+                }
+            }
+            tokio::task::spawn_blocking(move || E::Bot::choose(&engine, level)).await
+        };
+        let chosen = chosen.unwrap_or_else(|e| {
+            tracing::error!("{} bot: search task failed: {e}", E::NAME);
+            None
+        });
+        let mut outbox = Vec::new();
+        {
+            let mut h = hub.lock().await;
+            let Some(game) = playing_game_mut(&mut h, table_id) else {
+                return;
+            };
+            if game.ended || game.history.len() != ply {
+                return;
+            }
+            let played = match &chosen {
+                Some(data) => play_move(&state, &mut h, table_id, side, Some(data), &mut outbox),
+                None => Err("no_move".to_string()),
+            };
+            // 電腦無步可走或選了非法步（引擎 bug）：認輸收局，不讓人卡在等電腦
+            if let Err(reason) = played {
+                tracing::error!("{} bot: cannot move ({reason}), resigning", E::NAME);
+                end_game(&state, &mut h, table_id, Some(side.opponent()), "resign", &mut outbox);
+            }
+            schedule_bot(&hub, &state, &h, table_id);
+        }
+        flush(&state, outbox);
+    });
+}
+
+// ---- 行棋 ----
+
+async fn handle_move<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    who: SocketAddr,
+    data: Option<&Value>,
+) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+
+        let Some(&table_id) = h.conn_table.get(&who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let Some(game) = playing_game_mut(&mut h, table_id) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        if game.ended {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "game_ended" })))]);
+            return;
+        }
+
+        let side = game.side_of(who).unwrap();
+        if game.engine.turn() != side {
+            flush(state, vec![(who, msg::<E>("illegal_move", json!({ "reason": "NotYourTurn" })))]);
+            return;
+        }
+
+        if let Err(reason) = play_move(state, &mut h, table_id, side, data, &mut outbox) {
+            flush(state, vec![(who, msg::<E>("illegal_move", json!({ "reason": reason })))]);
+            return;
+        }
+        schedule_bot(hub, state, &h, table_id);
+    }
+    flush(state, outbox);
+}
+
+/// 替 `side` 走一步（呼叫端已確認對局進行中且輪到 `side`）：計時、合法性、推 move_made /
+/// 額外事件、判終局。行棋方已超時則直接判負（回 Ok）。非法步回 `Err(reason)`，狀態不變。
+fn play_move<E: GameEngine>(
+    state: &AppState,
+    h: &mut HubInner<E>,
+    table_id: u64,
+    side: Side,
+    data: Option<&Value>,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), String> {
+    let game = playing_game_mut(h, table_id).expect("呼叫端已確認對局進行中");
+
+    // 計時：先看行棋方是否已超時
+    let now = Instant::now();
+    let elapsed = now.duration_since(game.turn_started_at).as_millis() as i64;
+    let Some(settled) = game.clock.settle(side, elapsed) else {
+        game.clock.flag(side);
+        end_game(state, h, table_id, Some(side.opponent()), "timeout", outbox);
+        return Ok(());
+    };
+
+    // 合法性 + 套用（先留快照供悔棋；非法步不會改動狀態，快照直接丟掉）
+    let before = game.engine.clone();
+    let applied = game.engine.try_move(side, data)?;
+    game.history.push(Ply { mover: side, engine: before });
+    game.draw_offer = None;
+    game.takeback_request = None;
+
+    // 落子：扣時（含讀秒）+ Fischer 增量
+    game.clock.commit(side, settled);
+    game.turn_started_at = now;
+
+    let audience: Vec<SocketAddr> = game.audience().collect();
+    let mut move_data = applied.move_data;
+    if let Value::Object(map) = &mut move_data {
+        map.insert("turn".into(), json!(E::side_label(game.engine.turn())));
+    }
+    let move_data = with_clock(move_data, game, 0);
+    game.moves.push(move_data.clone());
+    let move_msg = msg::<E>("move_made", move_data);
+    for &addr in &audience {
+        outbox.push((addr, move_msg.clone()));
+    }
+
+    for (typ, d) in applied.extra {
+        let em = msg::<E>(typ, d);
+        for &addr in &audience {
+            outbox.push((addr, em.clone()));
+        }
+    }
+
+    // 換座位：各自私下收新顏色與新 token（token 跟著人走）
+    if applied.swap_seats {
+        game.swap_seats();
+        for side in [Side::First, Side::Second] {
+            let conn = game.seats[side.index()];
+            if conn == BOT_SEAT {
+                continue;
+            }
+            let m = msg::<E>(
+                "seats_swapped",
+                json!({ "color": E::side_label(side), "reconnect_token": game.tokens[side.index()] }),
+            );
+            outbox.push((conn, m));
+        }
+    }
+
+    match game.engine.status() {
+        GameStatus::Ongoing => {}
+        GameStatus::Win { winner, reason } => {
+            end_game(state, h, table_id, Some(winner), reason, outbox);
+        }
+        GameStatus::Draw { reason } => {
+            end_game(state, h, table_id, None, reason, outbox);
+        }
+    }
+    Ok(())
+}
+
+async fn resign<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            return;
+        };
+        end_game(state, &mut h, table_id, Some(side.opponent()), "resign", &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+// ---- 和棋 / 悔棋 / 再戰 ----
+
+/// 進行中對局裡的座位 `(table_id, side)`；不在對局中或已結束回 None。
+fn seat_in_game<E: GameEngine>(hub: &HubInner<E>, who: SocketAddr) -> Option<(u64, Side)> {
+    let &table_id = hub.conn_table.get(&who)?;
+    match &hub.tables.get(&table_id)?.state {
+        TableState::Playing(g) if !g.ended => Some((table_id, g.side_of(who)?)),
+        _ => None,
+    }
+}
+
+/// 推給對局其餘人（對手 + 觀戰者），不含 `who`。
+fn push_to_others<E: GameEngine>(
+    game: &Game<E>,
+    who: SocketAddr,
+    m: String,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    for addr in game.audience().filter(|&a| a != who) {
+        outbox.push((addr, m.clone()));
+    }
+}
+
+/// 提和。對手已先提和時視同接受。提議在任一方行棋後失效。
+async fn offer_draw<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        if game.bot.is_some() {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "vs_bot" })))]);
+            return;
+        }
+        match game.draw_offer {
+            Some(s) if s == side => {
+                flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_offered" })))]);
+                return;
+            }
+            Some(_) => end_game(state, &mut h, table_id, None, "agreement", &mut outbox),
+            None => {
+                game.draw_offer = Some(side);
+                let m = msg::<E>("draw_offered", json!({ "color": E::side_label(side) }));
+                push_to_others(game, who, m, &mut outbox);
+            }
+        }
+    }
+    flush(state, outbox);
+}
+
+async fn accept_draw<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        if game.draw_offer != Some(side.opponent()) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "no_draw_offer" })))]);
+            return;
+        }
+        end_game(state, &mut h, table_id, None, "agreement", &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+/// 依規則提和（西洋棋三次重複 / 50 步）：不需對手同意，局面成立即和；對電腦局也可用。
+async fn claim_draw<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, _)) = seat_in_game(&h, who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        let Some(reason) = game.engine.claimable_draw() else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "no_claimable_draw" })))]);
+            return;
+        };
+        end_game(state, &mut h, table_id, None, reason, &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+async fn decline_draw<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        if game.draw_offer != Some(side.opponent()) {
+            return;
+        }
+        game.draw_offer = None;
+        let m = msg::<E>("draw_declined", json!({ "color": E::side_label(side) }));
+        push_to_others(game, who, m, &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+/// `side` 此刻不能請求悔棋的理由；None = 可以。最後一步揭露了隱藏資訊（見 `GameEngine::reveals_hidden`）也不行。
+fn takeback_refusal<E: GameEngine>(game: &Game<E>, side: Side) -> Option<&'static str> {
+    if game.bot.is_some() {
+        return Some("vs_bot");
+    }
+    let undoable = game.history.get(game.undo_floor..).unwrap_or_default();
+    let Some(last) = undoable.last().filter(|p| p.mover == side) else {
+        return Some("nothing_to_take_back");
+    };
+    if game.engine.reveals_hidden(&last.engine) {
+        return Some("hidden_revealed");
+    }
+    if game.takeback_request.is_some() {
+        return Some("already_requested");
+    }
+    None
+}
+
+/// 請求悔一步：只有最後一步的行棋方能請求，對手同意才還原。請求在任一方行棋後失效。
+async fn request_takeback<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        if let Some(reason) = takeback_refusal(game, side) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": reason })))]);
+            return;
+        }
+        game.takeback_request = Some(side);
+        let m = msg::<E>("takeback_requested", json!({ "color": E::side_label(side) }));
+        push_to_others(game, who, m, &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+/// 同意悔棋：換回最後一步之前的局面，輪回請求方。
+///
+/// 時鐘不退還：同意方（目前行棋方）照扣本回合已用時間，請求方只扣回那一步拿到的增量。
+/// 同意方若其實已超時（watcher 每秒才掃一次）則直接判超時，不悔棋。
+async fn accept_takeback<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "not_in_game" })))]);
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        let requester = side.opponent();
+        if game.takeback_request != Some(requester) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "no_takeback_request" })))]);
+            return;
+        }
+        game.takeback_request = None;
+
+        let now = Instant::now();
+        let to_move = game.engine.turn();
+        let elapsed = now.duration_since(game.turn_started_at).as_millis() as i64;
+        let Some(settled) = game.clock.settle(to_move, elapsed) else {
+            game.clock.flag(to_move);
+            end_game(state, &mut h, table_id, Some(to_move.opponent()), "timeout", &mut outbox);
+            flush(state, outbox);
+            return;
+        };
+        game.clock.charge(to_move, settled);
+        let ply = game.history.pop().expect("request_takeback 已確認有上一步");
+        game.engine = ply.engine;
+        game.moves.pop();
+        game.clock.revoke_increment(requester);
+        game.turn_started_at = now;
+        game.draw_offer = None;
+
+        let m = msg::<E>(
+            "takeback",
+            with_clock(
+                json!({
+                    "color": E::side_label(requester),
+                    "turn": E::side_label(game.engine.turn()),
+                    "board": game.engine.snapshot(),
+                    "move_count": game.moves.len(),
+                }),
+                game,
+                0,
+            ),
+        );
+        for addr in game.audience() {
+            outbox.push((addr, m.clone()));
+        }
+    }
+    flush(state, outbox);
+}
+
+async fn decline_takeback<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((table_id, side)) = seat_in_game(&h, who) else {
+            return;
+        };
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        if game.takeback_request != Some(side.opponent()) {
+            return;
+        }
+        game.takeback_request = None;
+        let m = msg::<E>("takeback_declined", json!({ "color": E::side_label(side) }));
+        push_to_others(game, who, m, &mut outbox);
+    }
+    flush(state, outbox);
+}
+
+/// 完局後與上一盤對手再戰一盤（換邊）。先送者通知對手 `rematch_offered`，
+/// 雙方都送出才開局；對手已離開 / 已進別桌 / 已排隊則回 `opponent_unavailable`。
+async fn rematch<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    if let Err(reason) = agree_rematch(&mut *hub.lock().await, who, &mut outbox) {
+        outbox.push((who, msg::<E>("error", json!({ "reason": reason }))));
+    }
+    flush(state, outbox);
+}
+
+fn agree_rematch<E: GameEngine>(
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) -> Result<(), &'static str> {
+    if h.is_committed(who) {
+        return Err("already_committed");
+    }
+    let opponent = h.rematch.get(&who).map(|r| r.opponent).ok_or("no_rematch")?;
+    let available = !h.is_committed(opponent)
+        && h.rematch.get(&opponent).is_some_and(|r| r.opponent == who);
+    if !available {
+        h.rematch.remove(&who);
+        return Err("opponent_unavailable");
+    }
+    h.rematch.get_mut(&who).unwrap().wants = true;
+    if !h.rematch[&opponent].wants {
+        outbox.push((opponent, msg::<E>("rematch_offered", json!({}))));
+        return Ok(());
+    }
+
+    let mine = h.rematch.remove(&who).unwrap();
+    h.rematch.remove(&opponent);
+    // 換邊：上一盤的後手這盤先手
+    let (first, second) = match mine.side {
+        Side::First => (opponent, who),
+        Side::Second => (who, opponent),
+    };
+    stop_spectating(h, who);
+    stop_spectating(h, opponent);
+    // 私人桌沿用原碼（觀戰者憑碼續看）；原碼在這期間已被別桌拿走就換一組新的，由 `match_found` 帶給雙方
+    let invite = mine.invite.map(|code| match h.table_by_invite(&code) {
+        Some(_) => h.new_invite_code(),
+        None => code,
+    });
+    let id = h.next_id;
+    h.next_id += 1;
+    h.tables.insert(
+        id,
+        Table {
+            id,
+            name: mine.table_name,
+            time_control: mine.time_control,
+            options: mine.options,
+            invite,
+            state: TableState::Waiting { host: who },
+            chat: VecDeque::new(),
+        },
+    );
+    start_game(h, id, first, second, None, outbox);
+    Ok(())
+}
+
+/// 連線斷開：清佇列/大廳/挑戰；等待中 host 斷線銷毀桌。
+///
+/// 對戰中斷線不立即判負：座位保留一段寬限期（`reconnect_grace`），期間時鐘照走，
+/// 本人可憑 `match_found` 拿到的 token 從新連線 `reconnect` 取回座位；逾期由
+/// `timeout_watcher` 判對手勝（reason `disconnect`）。寬限期設 0 則照舊立即判負。
+pub async fn handle_disconnect<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) {
+    let mut outbox = Vec::new();
+    release(state, &mut *hub.lock().await, who, reconnect_grace(state), "disconnect", &mut outbox);
+    flush(state, outbox);
+}
+
+/// 連線離開本遊戲的清理（斷線 / 後台踢出共用）。對戰中的座位：`grace` 為 0 即以 `reason` 判負，
+/// 否則保留座位等重連。
+fn release<E: GameEngine>(
+    state: &AppState,
+    h: &mut HubInner<E>,
+    who: SocketAddr,
+    grace: Duration,
+    reason: &str,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    h.queue.retain(|&c| c != who);
+    h.lobby.remove(&who);
+    h.members.remove(&who);
+    h.chat.forget(who);
+    // 發出的挑戰撤回；收到的挑戰當作婉拒
+    withdraw_challenge(h, who, outbox);
+    let incoming: Vec<SocketAddr> = h.challenges.iter().filter(|(_, c)| c.to == who).map(|(&from, _)| from).collect();
+    for from in incoming {
+        h.challenges.remove(&from);
+        outbox.push((from, msg::<E>("challenge_declined", json!({ "by": who.to_string() }))));
+    }
+    if stop_spectating(h, who) {
+        push_lobby_update(h, outbox);
+    }
+
+    if let Some(&table_id) = h.conn_table.get(&who) {
+        let info: Option<(bool, Option<Side>)> = h.tables.get(&table_id).map(|t| match &t.state {
+            TableState::Waiting { .. } => (true, None),
+            TableState::Playing(g) => (false, if g.ended { None } else { g.side_of(who) }),
+        });
+        match info {
+            Some((true, _)) => {
+                h.tables.remove(&table_id);
+                h.conn_table.remove(&who);
+                push_lobby_update(h, outbox);
+            }
+            Some((false, Some(side))) if grace.is_zero() => {
+                end_game(state, h, table_id, Some(side.opponent()), reason, outbox);
+            }
+            Some((false, Some(side))) => {
+                h.conn_table.remove(&who);
+                let game = playing_game_mut(h, table_id).unwrap();
+                game.reconnect_deadline[side.index()] = Some(Instant::now() + grace);
+                let m = msg::<E>(
+                    "opponent_disconnected",
+                    json!({ "color": E::side_label(side), "grace_ms": grace.as_millis() as u64 }),
+                );
+                for addr in game.audience().filter(|&a| a != who) {
+                    outbox.push((addr, m.clone()));
+                }
+            }
+            _ => {}
+        }
+    }
+    // 放最後：寬限期 0 時上面的 end_game 會替這條連線登記再戰
+    h.rematch.remove(&who);
+}
+
+/// 憑 `reconnect_token` 取回對戰中的座位（新連線）。舊連線若還活著（例如換網路但舊 socket
+/// 尚未被偵測斷開）一併被頂替。成功後回 `resync` 完整狀態，其餘人收 `opponent_reconnected`。
+async fn reconnect<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    who: SocketAddr,
+    data: Option<&Value>,
+) {
+    let member = state.member_of(who).await;
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        if h.is_committed(who) {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "already_committed" })))]);
+            return;
+        }
+        let Some(token) = data.and_then(|d| d.get("token")).and_then(|v| v.as_str()) else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_token" })))]);
+            return;
+        };
+        let found = h.tables.values().find_map(|t| match &t.state {
+            TableState::Playing(g) if !g.ended => g.side_of_token(token).map(|side| (t.id, side)),
+            _ => None,
+        });
+        let Some((table_id, side)) = found else {
+            flush(state, vec![(who, msg::<E>("error", json!({ "reason": "bad_token" })))]);
+            return;
+        };
+        h.bind_member(who, member);
+        stop_spectating(&mut h, who);
+        h.lobby.remove(&who);
+
+        let game = playing_game_mut(&mut h, table_id).unwrap();
+        let old = std::mem::replace(&mut game.seats[side.index()], who);
+        game.reconnect_deadline[side.index()] = None;
+        let resync = with_live_clock(
+            json!({
+                "table_id": table_id,
+                "color": E::side_label(side),
+                "turn": E::side_label(game.engine.turn()),
+                "time_control": game.clock.control.to_json(),
+                "options": game.options,
+                "board": game.engine.snapshot(),
+                "moves": game.moves,
+                "rated": game.is_rated(),
+                "bot": game.bot.map(BotLevel::as_str),
+                "reconnect_token": game.tokens[side.index()],
+            }),
+            game,
+        );
+        outbox.push((who, msg::<E>("resync", resync)));
+        let m = msg::<E>("opponent_reconnected", json!({ "color": E::side_label(side) }));
+        for addr in game.audience().filter(|&a| a != who) {
+            outbox.push((addr, m.clone()));
+        }
+        if h.conn_table.remove(&old).is_some() {
+            outbox.push((old, msg::<E>("error", json!({ "reason": "seat_reclaimed" }))));
+        }
+        h.conn_table.insert(who, table_id);
+    }
+    flush(state, outbox);
+}
+
+// ---- 記譜 ----
+
+/// 進行中對局的記譜（對局雙方或觀戰者皆可）。`data.format` 選記法（象棋 `wxf` / `iccs`），
+/// 結果一律為進行中；完局請走 `GET /games/records/{id}/export`。
+async fn export<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let reply = {
+        let h = hub.lock().await;
+        let table_id = h.conn_table.get(&who).or_else(|| h.spectating.get(&who));
+        let table = table_id.and_then(|id| h.tables.get(id));
+        match table.map(|t| (t, &t.state)) {
+            Some((t, TableState::Playing(game))) => {
+                let player = |side: Side| match game.seats[side.index()] {
+                    BOT_SEAT => "Computer".to_string(),
+                    _ => "?".to_string(),
+                };
+                let meta = ExportMeta {
+                    event: t.name.clone(),
+                    date: game.started_at,
+                    players: [player(Side::First), player(Side::Second)],
+                    result: None,
+                    format: data.and_then(|d| d.get("format")).and_then(|v| v.as_str()).map(str::to_string),
+                };
+                match E::export(&game.plies(), &game.engine, &meta) {
+                    Some(exported) => msg::<E>("notation", exported.to_json()),
+                    None => msg::<E>("error", json!({ "reason": "export_unavailable" })),
+                }
+            }
+            _ => msg::<E>("error", json!({ "reason": "not_in_game" })),
+        }
+    };
+    flush(state, vec![(who, reply)]);
+}
+
+// ---- 後台管理（`/admin/games`，稽核由 admin 路由統一記） ----
+
+/// 後台強制結束對局：`winner` 為 side label（None＝和棋），照常寫局紀錄（reason `admin`）
+/// 與回報賽事結果。
+pub async fn admin_end<E: GameEngine>(
+    hub: &GameHub<E>,
+    state: &AppState,
+    table_id: u64,
+    winner: Option<&str>,
+) -> Result<(), &'static str> {
+    let winner = match winner {
+        None => None,
+        Some(label) => Some(
+            [Side::First, Side::Second]
+                .into_iter()
+                .find(|&s| E::side_label(s) == label)
+                .ok_or("bad_winner")?,
+        ),
+    };
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        match h.tables.get(&table_id).map(|t| &t.state) {
+            None => return Err("no_such_table"),
+            Some(TableState::Playing(g)) if !g.ended => {}
+            Some(_) => return Err("not_playing"),
+        }
+        end_game(state, &mut h, table_id, winner, "admin", &mut outbox);
+    }
+    flush(state, outbox);
+    Ok(())
+}
+
+/// 後台關桌：等待中的桌直接移除；對局中的桌中止、不寫紀錄。在座與觀戰者收 `table_closed`。
+/// 賽事桌不可關（該盤會卡住），改用 `admin_end` 判定結果。
+pub async fn admin_close<E: GameEngine>(hub: &GameHub<E>, state: &AppState, table_id: u64) -> Result<(), &'static str> {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        match h.tables.get(&table_id).map(|t| &t.state) {
+            None => return Err("no_such_table"),
+            Some(TableState::Playing(g)) if g.tournament.is_some() && !g.ended => return Err("tournament_table"),
+            Some(_) => {}
+        }
+        discard_table(&mut h, table_id, "closed_by_admin", &mut outbox);
+    }
+    flush(state, outbox);
+    Ok(())
+}
+
+/// 後台踢出連線：等同寬限期 0 的斷線（對戰中判對手勝，reason `kicked`），本人收 `kicked`。
+/// WS 連線本身不斷，只是離開本遊戲的大廳 / 桌 / 佇列 / 觀戰。
+pub async fn kick<E: GameEngine>(hub: &GameHub<E>, state: &AppState, who: SocketAddr) -> Result<(), &'static str> {
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let present = h.lobby.contains(&who)
+            || h.is_committed(who)
+            || h.spectating.contains_key(&who)
+            || h.rematch.contains_key(&who);
+        if !present {
+            return Err("not_in_game");
+        }
+        release(state, &mut h, who, Duration::ZERO, "kicked", &mut outbox);
+        outbox.push((who, msg::<E>("kicked", json!({}))));
+    }
+    flush(state, outbox);
+    Ok(())
+}
+
+// ---- 共用 ----
+
+fn playing_game_mut<E: GameEngine>(hub: &mut HubInner<E>, table_id: u64) -> Option<&mut Game<E>> {
+    match hub.tables.get_mut(&table_id)?.state {
+        TableState::Playing(ref mut g) => Some(g),
+        TableState::Waiting { .. } => None,
+    }
+}
+
+/// 移除一桌、不寫紀錄、不計分：在座與觀戰者收 `table_closed`（`reason`）。
+/// 後台關桌與雙方都沒回來的中止共用；賽事對局由呼叫端處理（後台關桌拒絕、中止記雙方不戰）。
+fn discard_table<E: GameEngine>(
+    hub: &mut HubInner<E>,
+    table_id: u64,
+    reason: &str,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    let Some(table) = hub.tables.remove(&table_id) else {
+        return;
+    };
+    let m = msg::<E>("table_closed", json!({ "reason": reason }));
+    match table.state {
+        TableState::Waiting { host } => {
+            hub.conn_table.remove(&host);
+            outbox.push((host, m));
+        }
+        TableState::Playing(game) => {
+            for addr in game.audience() {
+                outbox.push((addr, m.clone()));
+            }
+            for addr in game.seats {
+                hub.conn_table.remove(&addr);
+            }
+            for addr in &game.spectators {
+                hub.spectating.remove(addr);
+            }
+        }
+    }
+    push_lobby_update(hub, outbox);
+}
+
+/// 推 game_over 給雙方與觀戰者、寫完局紀錄、登記再戰並移除桌。`winner` 為 None 表和棋。
+fn end_game<E: GameEngine>(
+    state: &AppState,
+    hub: &mut HubInner<E>,
+    table_id: u64,
+    winner: Option<Side>,
+    reason: &str,
+    outbox: &mut Vec<(SocketAddr, String)>,
+) {
+    let table_name = hub.tables.get(&table_id).map(|t| t.name.clone()).unwrap_or_default();
+    let invite = hub.tables.get(&table_id).and_then(|t| t.invite.clone());
+    if let Some(game) = playing_game_mut(hub, table_id) {
+        game.ended = true;
+        if let Some(seat) = game.tournament {
+            crate::services::game_tournaments::spawn_result(
+                state.clone(),
+                seat,
+                winner.and_then(|s| game.members[s.index()]),
+            );
+        }
+        crate::services::game_records::spawn_save(
+            state.get_pool().clone(),
+            NewGameRecord {
+                game: E::NAME,
+                table_name: table_name.clone(),
+                winner: winner.map(E::side_label),
+                winner_seat: winner.map(|s| s.index() as i16),
+                members: game.members,
+                rated: game.is_rated(),
+                reason: reason.to_string(),
+                moves: std::mem::take(&mut game.moves),
+                initial_clock_ms: game.clock.control.base_ms(),
+                time_control: game.clock.control.to_json(),
+                options: game.options.clone(),
+                final_clock: with_clock(json!({}), game, 0)["clock"].take(),
+                started_at: game.started_at,
+            },
+        );
+        let seats = game.seats;
+        let time_control = game.clock.control;
+        let options = game.options.clone();
+        let winner_val = match winner {
+            Some(s) => json!(E::side_label(s)),
+            None => Value::Null,
+        };
+        let m = msg::<E>("game_over", json!({ "winner": winner_val, "reason": reason }));
+        for addr in game.audience() {
+            outbox.push((addr, m.clone()));
+        }
+        let spectators = std::mem::take(&mut game.spectators);
+        // 還在線的雙方登記再戰（斷線等重連中的座位不登記：該 addr 已死）
+        // 對電腦局不登記（再開一盤用 `play_vs_computer`）
+        let both_online = game.bot.is_none() && game.reconnect_deadline.iter().all(Option::is_none);
+        hub.conn_table.remove(&seats[0]);
+        hub.conn_table.remove(&seats[1]);
+        if both_online {
+            for side in [Side::First, Side::Second] {
+                let slot = RematchSlot {
+                    opponent: seats[side.opponent().index()],
+                    side,
+                    table_name: table_name.clone(),
+                    time_control,
+                    options: options.clone(),
+                    invite: invite.clone(),
+                    wants: false,
+                };
+                hub.rematch.insert(seats[side.index()], slot);
+            }
+        }
+        for addr in spectators {
+            hub.spectating.remove(&addr);
+        }
+    }
+    hub.tables.remove(&table_id);
     push_lobby_update(hub, outbox);
 }
 
@@ -439,7 +1681,41 @@ fn flush(state: &AppState, outbox: Vec<(SocketAddr, String)>) {
 
 // ---- 計時掃描 ----
 
-/// 啟動時 spawn；每秒掃對戰中桌，當前行棋方超時即主動判負。
+/// 計時掃描對一局的判定。
+#[derive(Debug, PartialEq, Eq)]
+enum Expiry {
+    /// 行棋方超時，判負。
+    Flag(Side),
+    /// 只有這一方沒在重連期限內回來，判負。
+    Forfeit(Side),
+    /// 雙方（不含電腦）都沒回來：中止，不寫紀錄、不計分；賽事對局記雙方不戰。
+    Abandon,
+}
+
+/// 一局此刻該不該結束。斷線座位過了期限時，另一座位若也斷線且還在寬限期內就先等 ——
+/// 它回來了才判沒回來的一方負；也沒回來（例如重啟還原後雙方期限相同）就整局中止，不替任何一方記敗。
+///
+/// 電腦那一方不在這裡判超時：它可能還在排 `BOT_SLOTS`，本回合從拿到名額才起算，落子時照常結算（`play_move`）。
+fn expiry<E: GameEngine>(g: &Game<E>, now: Instant) -> Option<Expiry> {
+    let side = g.engine.turn();
+    let elapsed = now.duration_since(g.turn_started_at).as_millis() as i64;
+    if g.seats[side.index()] != BOT_SEAT && g.clock.is_flagged(side, elapsed) {
+        return Some(Expiry::Flag(side));
+    }
+    let expired: Vec<Side> = [Side::First, Side::Second]
+        .into_iter()
+        .filter(|s| g.reconnect_deadline[s.index()].is_some_and(|d| d <= now))
+        .collect();
+    let pending = g.reconnect_deadline.iter().flatten().any(|&d| d > now);
+    match expired[..] {
+        _ if pending => None,
+        [] => None,
+        [side] => Some(Expiry::Forfeit(side)),
+        _ => Some(Expiry::Abandon),
+    }
+}
+
+/// 啟動時 spawn；每秒掃對戰中桌：當前行棋方超時、或斷線座位過了重連期限，即主動結束（見 `expiry`）。
 pub async fn timeout_watcher<E: GameEngine>(hub: GameHub<E>, state: AppState) {
     let mut interval = tokio::time::interval(Duration::from_secs(1));
     loop {
@@ -448,29 +1724,349 @@ pub async fn timeout_watcher<E: GameEngine>(hub: GameHub<E>, state: AppState) {
         {
             let mut h = hub.lock().await;
             let now = Instant::now();
-            let timed_out: Vec<(u64, Side)> = h
+            let due: Vec<(u64, Expiry)> = h
                 .tables
                 .values()
                 .filter_map(|t| match &t.state {
-                    TableState::Playing(g) if !g.ended => {
-                        let side = g.engine.turn();
-                        let elapsed = now.duration_since(g.turn_started_at).as_millis() as i64;
-                        if g.clock_of(side) - elapsed <= 0 {
-                            Some((t.id, side))
-                        } else {
-                            None
-                        }
-                    }
+                    TableState::Playing(g) if !g.ended => expiry(g, now).map(|e| (t.id, e)),
                     _ => None,
                 })
                 .collect();
-            for (table_id, side) in timed_out {
-                if let Some(game) = playing_game_mut(&mut h, table_id) {
-                    game.set_clock(side, 0);
+            for (table_id, e) in due {
+                match e {
+                    Expiry::Flag(side) => {
+                        if let Some(game) = playing_game_mut(&mut h, table_id) {
+                            game.clock.flag(side);
+                        }
+                        end_game(&state, &mut h, table_id, Some(side.opponent()), "timeout", &mut outbox);
+                    }
+                    Expiry::Forfeit(side) => {
+                        end_game(&state, &mut h, table_id, Some(side.opponent()), "disconnect", &mut outbox);
+                    }
+                    Expiry::Abandon => {
+                        // 賽事桌不能就這樣消失（該輪會卡住）：記雙方不戰
+                        if let Some(seat) = playing_game_mut(&mut h, table_id).and_then(|g| g.tournament) {
+                            tracing::warn!(
+                                "錦標賽 #{} 對陣 #{}：雙方都沒重連，記雙方不戰",
+                                seat.tournament_id,
+                                seat.pairing_id
+                            );
+                            crate::services::game_tournaments::spawn_abandoned(state.clone(), seat);
+                        }
+                        discard_table(&mut h, table_id, "abandoned", &mut outbox);
+                    }
                 }
-                end_game(&mut h, table_id, Some(side.opponent()), "timeout", &mut outbox);
             }
         }
         flush(&state, outbox);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::banqi::game::BanqiGame;
+    use crate::games::western_chess::game::WesternChessGame;
+
+    fn addr(port: u16) -> SocketAddr {
+        SocketAddr::from(([127, 0, 0, 1], port))
+    }
+
+    fn game(first: SocketAddr, second: SocketAddr) -> Game<WesternChessGame> {
+        Game::new(first, second, [Some(1), Some(2)], TimeControl::fischer(300_000, 0), Value::Null)
+    }
+
+    fn playing_table(hub: &mut HubInner<WesternChessGame>, id: u64, game: Game<WesternChessGame>) {
+        for addr in game.seats {
+            hub.conn_table.insert(addr, id);
+        }
+        hub.tables.insert(
+            id,
+            Table {
+                id,
+                name: format!("桌 #{id}"),
+                time_control: game.clock.control,
+                options: Value::Null,
+                invite: None,
+                state: TableState::Playing(game),
+                chat: VecDeque::new(),
+            },
+        );
+    }
+
+    #[test]
+    fn both_seats_empty_after_deadline_abandons_without_result() {
+        let now = Instant::now();
+        let watcher = addr(3000);
+        // 還原後的情形：雙方都是空位、期限相同
+        let mut g = game(addr(1), addr(2));
+        g.reconnect_deadline = [Some(now), Some(now)];
+        g.spectators.insert(watcher);
+        assert_eq!(expiry(&g, now), Some(Expiry::Abandon));
+
+        let mut hub = HubInner::default();
+        hub.spectating.insert(watcher, 5);
+        hub.lobby.insert(addr(3001));
+        playing_table(&mut hub, 5, g);
+        let mut outbox = Vec::new();
+        discard_table(&mut hub, 5, "abandoned", &mut outbox);
+        assert!(hub.tables.is_empty() && hub.spectating.is_empty() && hub.conn_table.is_empty());
+        assert!(hub.rematch.is_empty(), "中止不登記再戰");
+        assert!(outbox.iter().any(|(a, m)| *a == watcher && m.contains("table_closed") && m.contains("abandoned")));
+        assert!(outbox.iter().any(|(a, m)| *a == addr(3001) && m.contains("lobby_update")));
+    }
+
+    #[test]
+    fn bot_waiting_for_a_search_slot_is_not_flagged() {
+        let now = Instant::now();
+        let mut g = game(addr(1), BOT_SEAT);
+        g.bot = Some(BotLevel::Easy);
+        g.turn_started_at = now;
+        let late = now + Duration::from_secs(301);
+        // 人類超時照判
+        assert_eq!(expiry(&g, late), Some(Expiry::Flag(Side::First)));
+        g.swap_seats();
+        assert_eq!(expiry(&g, late), None);
+    }
+
+    #[test]
+    fn only_the_side_that_stayed_away_forfeits() {
+        let now = Instant::now();
+        let later = now + Duration::from_secs(30);
+        let mut g = game(addr(1), addr(2));
+        // 先手過期、後手在線 → 先手判負
+        g.reconnect_deadline = [Some(now), None];
+        assert_eq!(expiry(&g, now), Some(Expiry::Forfeit(Side::First)));
+        // 後手也斷線、還在寬限期內 → 先等
+        g.reconnect_deadline = [Some(now), Some(later)];
+        assert_eq!(expiry(&g, now), None);
+        // 後手也沒回來 → 中止
+        assert_eq!(expiry(&g, later), Some(Expiry::Abandon));
+        // 後手回來了 → 只判先手
+        g.reconnect_deadline = [Some(now), None];
+        assert_eq!(expiry(&g, later), Some(Expiry::Forfeit(Side::First)));
+        g.reconnect_deadline = [None, None];
+        assert_eq!(expiry(&g, later), None);
+    }
+
+    #[test]
+    fn swap2_keeps_every_move_for_export_but_blocks_undo_past_the_choice() {
+        use crate::games::gomoku::engine::Rule;
+        use crate::games::gomoku::game::GomokuGame;
+        use crate::games::gomoku::notation;
+
+        let options = json!({ "rule": "freestyle", "swap2": true });
+        let mut g: Game<GomokuGame> =
+            Game::new(addr(1), addr(2), [Some(1), Some(2)], TimeControl::fischer(300_000, 0), options);
+        for at in [[7, 7], [8, 7], [7, 8]] {
+            g.play(json!({ "at": at }));
+        }
+        // 後手選黑 → 換座位
+        g.play(json!({ "choose": "black" }));
+        assert_eq!(g.seats, [addr(2), addr(1)]);
+        for side in [Side::First, Side::Second] {
+            assert_eq!(takeback_refusal(&g, side), Some("nothing_to_take_back"));
+        }
+        g.play(json!({ "at": [9, 9] }));
+        assert_eq!(takeback_refusal(&g, Side::Second), None, "換座位之後的步照常可悔");
+
+        assert_eq!(g.history.len(), g.moves.len());
+        let meta = ExportMeta {
+            event: "桌 #1".into(),
+            date: g.started_at,
+            players: ["?".into(), "?".into()],
+            result: None,
+            format: None,
+        };
+        let exported = GomokuGame::export(&g.plies(), &g.engine, &meta).unwrap();
+        assert_eq!(exported.text, notation::record(&[(7, 7), (8, 7), (7, 8), (9, 9)], Rule::Freestyle, &meta));
+    }
+
+    #[test]
+    fn flip_cannot_be_taken_back() {
+        let mut g: Game<BanqiGame> =
+            Game::new(addr(1), addr(2), [Some(1), Some(2)], TimeControl::fischer(300_000, 0), Value::Null);
+        g.play(json!({ "action": "flip", "at": [0, 0] }));
+        assert_eq!(takeback_refusal(&g, Side::First), Some("hidden_revealed"));
+        assert_eq!(takeback_refusal(&g, Side::Second), Some("nothing_to_take_back"));
+    }
+
+    #[test]
+    fn ordinary_move_can_be_taken_back() {
+        let mut g = game(addr(1), addr(2));
+        g.play(json!({ "from": [4, 1], "to": [4, 3] }));
+        assert_eq!(takeback_refusal(&g, Side::First), None);
+        g.takeback_request = Some(Side::First);
+        assert_eq!(takeback_refusal(&g, Side::First), Some("already_requested"));
+    }
+
+    #[test]
+    fn tournament_seats_only_come_from_this_lobby() {
+        let mut hub: HubInner<WesternChessGame> = HubInner::default();
+        let (elsewhere, lobby, playing) = (addr(1), addr(2), addr(3));
+        hub.lobby.insert(lobby);
+        hub.lobby.insert(playing);
+        playing_table(&mut hub, 9, game(playing, addr(4)));
+        let conns = [(elsewhere, 7), (playing, 7), (lobby, 7)];
+        assert_eq!(idle_conn(&hub, &conns, 7), Some(lobby));
+        assert_eq!(idle_conn(&hub, &conns[..2], 7), None, "別處的連線、對局中的連線都不拉");
+        assert_eq!(idle_conn(&hub, &conns, 8), None);
+    }
+
+    type Hub = HubInner<WesternChessGame>;
+    type Outbox = Vec<(SocketAddr, String)>;
+
+    /// outbox 裡送給 `to` 的第一則 `typ`，回其 data。
+    fn sent(outbox: &Outbox, to: SocketAddr, typ: &str) -> Option<Value> {
+        outbox.iter().filter(|(a, _)| *a == to).find_map(|(_, m)| {
+            let v: Value = serde_json::from_str(m).unwrap();
+            (v["type"] == typ).then(|| v["data"].clone())
+        })
+    }
+
+    fn listed(hub: &Hub) -> Vec<u64> {
+        lobby_snapshot(hub)["tables"].as_array().unwrap().iter().map(|t| t["id"].as_u64().unwrap()).collect()
+    }
+
+    #[test]
+    fn private_table_is_joined_by_code_and_never_listed() {
+        let mut hub = Hub::default();
+        let (host, guest, browser) = (addr(1), addr(2), addr(3));
+        hub.lobby.extend([guest, browser]);
+        let mut outbox = Outbox::new();
+        open_table(&mut hub, host, None, Some(&json!({ "private": true })), &mut outbox).unwrap();
+        let code = sent(&outbox, host, "table_created").unwrap()["invite_code"].as_str().unwrap().to_string();
+        let id = hub.table_by_invite(&code).unwrap();
+        assert!(listed(&hub).is_empty());
+        assert!(sent(&outbox, browser, "lobby_update").is_none(), "私人桌不觸發大廳更新");
+
+        // 知道桌號也不能繞過邀請碼
+        let mut outbox = Outbox::new();
+        assert_eq!(take_seat(&mut hub, guest, None, Some(&json!({ "table_id": id })), &mut outbox), Err("table_not_found"));
+        assert_eq!(take_seat(&mut hub, guest, None, Some(&json!({ "invite_code": "NOPE" })), &mut outbox), Err("table_not_found"));
+        assert_eq!(take_seat(&mut hub, host, None, Some(&json!({ "invite_code": code })), &mut outbox), Err("already_committed"));
+
+        take_seat(&mut hub, guest, None, Some(&json!({ "invite_code": code.to_lowercase() })), &mut outbox).unwrap();
+        assert!(matches!(hub.tables[&id].state, TableState::Playing(_)));
+        assert_eq!(sent(&outbox, guest, "match_found").unwrap()["invite_code"], json!(code));
+        assert!(listed(&hub).is_empty(), "開局後仍不列出");
+        assert!(!hub.lobby.contains(&guest));
+    }
+
+    #[test]
+    fn public_table_is_listed_and_joined_by_id() {
+        let mut hub = Hub::default();
+        let (host, guest) = (addr(1), addr(2));
+        hub.lobby.insert(guest);
+        let mut outbox = Outbox::new();
+        open_table(&mut hub, host, None, Some(&json!({ "name": "來下棋" })), &mut outbox).unwrap();
+        assert_eq!(sent(&outbox, host, "table_created").unwrap()["invite_code"], Value::Null);
+        assert!(sent(&outbox, guest, "lobby_update").is_some());
+        let id = listed(&hub)[0];
+        take_seat(&mut hub, guest, None, Some(&json!({ "table_id": id })), &mut outbox).unwrap();
+        assert!(sent(&outbox, host, "match_found").is_some());
+    }
+
+    #[test]
+    fn accepted_challenge_starts_a_game() {
+        let mut hub = Hub::default();
+        let (a, b) = (addr(1), addr(2));
+        hub.lobby.extend([a, b]);
+        let mut outbox = Outbox::new();
+        assert_eq!(
+            issue_challenge(&mut hub, a, None, Some(&json!({ "to": addr(9).to_string() })), &mut outbox),
+            Err("player_unavailable")
+        );
+        issue_challenge(&mut hub, a, Some(1), Some(&json!({ "to": b.to_string() })), &mut outbox).unwrap();
+        assert_eq!(sent(&outbox, b, "challenge_received").unwrap()["from"], json!(a.to_string()));
+
+        let mut outbox = Outbox::new();
+        take_challenge(&mut hub, b, Some(2), Some(&json!({ "from": a.to_string() })), &mut outbox).unwrap();
+        assert!(hub.challenges.is_empty());
+        let id = hub.conn_table[&a];
+        assert_eq!(hub.conn_table[&b], id);
+        let TableState::Playing(game) = &hub.tables[&id].state else { panic!("挑戰應直接開局") };
+        assert!(game.members.contains(&Some(1)) && game.members.contains(&Some(2)));
+        assert!(sent(&outbox, a, "match_found").is_some() && sent(&outbox, b, "match_found").is_some());
+    }
+
+    #[test]
+    fn declined_challenge_notifies_challenger_and_cannot_be_accepted() {
+        let mut hub = Hub::default();
+        let (a, b, c) = (addr(1), addr(2), addr(3));
+        hub.lobby.extend([a, b, c]);
+        let mut outbox = Outbox::new();
+        issue_challenge(&mut hub, a, None, Some(&json!({ "to": b.to_string() })), &mut outbox).unwrap();
+        let from_a = json!({ "from": a.to_string() });
+
+        // 旁人不能替被挑戰方婉拒
+        let mut outbox = Outbox::new();
+        refuse_challenge(&mut hub, c, Some(&from_a), &mut outbox);
+        assert!(outbox.is_empty() && hub.challenges.contains_key(&a));
+
+        refuse_challenge(&mut hub, b, Some(&from_a), &mut outbox);
+        assert_eq!(sent(&outbox, a, "challenge_declined").unwrap()["by"], json!(b.to_string()));
+        assert_eq!(take_challenge(&mut hub, b, None, Some(&from_a), &mut outbox), Err("challenge_unavailable"));
+        assert!(hub.tables.is_empty());
+    }
+
+    #[test]
+    fn withdrawn_or_replaced_challenge_cannot_be_accepted() {
+        let mut hub = Hub::default();
+        let (a, b, c) = (addr(1), addr(2), addr(3));
+        hub.lobby.extend([a, b, c]);
+        let from_a = json!({ "from": a.to_string() });
+        let mut outbox = Outbox::new();
+        issue_challenge(&mut hub, a, None, Some(&json!({ "to": b.to_string() })), &mut outbox).unwrap();
+        withdraw_challenge(&mut hub, a, &mut outbox);
+        assert_eq!(sent(&outbox, b, "challenge_cancelled").unwrap()["from"], json!(a.to_string()));
+        assert_eq!(take_challenge(&mut hub, b, None, Some(&from_a), &mut outbox), Err("challenge_unavailable"));
+
+        // 改挑戰別人：前一個自動撤回
+        let mut outbox = Outbox::new();
+        issue_challenge(&mut hub, a, None, Some(&json!({ "to": b.to_string() })), &mut outbox).unwrap();
+        issue_challenge(&mut hub, a, None, Some(&json!({ "to": c.to_string() })), &mut outbox).unwrap();
+        assert!(sent(&outbox, b, "challenge_cancelled").is_some());
+        assert_eq!(take_challenge(&mut hub, b, None, Some(&from_a), &mut outbox), Err("challenge_unavailable"));
+        take_challenge(&mut hub, c, None, Some(&from_a), &mut outbox).unwrap();
+    }
+
+    #[test]
+    fn rematch_gets_a_fresh_code_when_the_old_one_was_reissued() {
+        let mut hub = Hub::default();
+        let (a, b, other) = (addr(1), addr(2), addr(3));
+        let slot = |opponent, side| RematchSlot {
+            opponent,
+            side,
+            table_name: "私人桌".into(),
+            time_control: TimeControl::fischer(300_000, 0),
+            options: Value::Null,
+            invite: Some("ABCDEF".into()),
+            wants: false,
+        };
+        hub.rematch.insert(a, slot(b, Side::First));
+        hub.rematch.insert(b, slot(a, Side::Second));
+        // 上一盤結束後，另一張私人桌拿到了同一組碼
+        hub.tables.insert(
+            7,
+            Table {
+                id: 7,
+                name: "別桌".into(),
+                time_control: TimeControl::fischer(300_000, 0),
+                options: Value::Null,
+                invite: Some("ABCDEF".into()),
+                state: TableState::Waiting { host: other },
+                chat: VecDeque::new(),
+            },
+        );
+        hub.next_id = 8;
+        let mut outbox = Outbox::new();
+        agree_rematch(&mut hub, a, &mut outbox).unwrap();
+        assert!(sent(&outbox, b, "rematch_offered").is_some());
+        agree_rematch(&mut hub, b, &mut outbox).unwrap();
+        let code = hub.tables[&8].invite.clone().unwrap();
+        assert_ne!(code, "ABCDEF");
+        assert_eq!(hub.table_by_invite("ABCDEF"), Some(7));
+        assert_eq!(sent(&outbox, a, "match_found").unwrap()["invite_code"], json!(code));
+    }
+}
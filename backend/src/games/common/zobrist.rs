@@ -0,0 +1,19 @@
+//! Zobrist hash 的亂數表：編譯期用 splitmix64 產生，重啟後不變（hash 只在單局內比對重複局面，不落地）。
+
+const fn splitmix64(seed: u64) -> u64 {
+    let mut z = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+/// `N` 個互不相同的 64-bit 鍵。各遊戲在自己的引擎裡 `const` 一份，索引配置自訂。
+pub const fn table<const N: usize>() -> [u64; N] {
+    let mut t = [0; N];
+    let mut i = 0;
+    while i < N {
+        t[i] = splitmix64(i as u64 + 1);
+        i += 1;
+    }
+    t
+}
@@ -0,0 +1,283 @@
+//! 農場經營 phase-2 卡牌：職業 / 小改良 / 大改良的資料表與查詢。純資料 + 純函式。
+//!
+//! 卡名與效果皆原創。每張卡是一列 `CardDef`：費用、前置條件、觸發效果、終局分數、煮食率。
+//! 加卡只需在表裡加一列；引擎（`engine::play_card` / `fire`）不必改。
+//! - **職業**：`Lessons` 行動打出（第一張免費，之後每張 1 糧）
+//! - **小改良**：手牌，`Improvements` 行動付費打出
+//! - **大改良**：公共池（每張全場一份），`Improvements` 行動付費建造
+
+use serde::{Deserialize, Serialize};
+
+use super::engine::{Action, Farm, House};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CardKind {
+    Occupation,
+    Minor,
+    Major,
+}
+
+/// 一組資源 / 牲畜（費用與效果共用）。`..Goods::NONE` 只寫有用到的欄位。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct Goods {
+    pub wood: u32,
+    pub clay: u32,
+    pub reed: u32,
+    pub stone: u32,
+    pub grain: u32,
+    pub veg: u32,
+    pub food: u32,
+    pub sheep: u32,
+    pub boar: u32,
+    pub cattle: u32,
+}
+
+impl Goods {
+    pub const NONE: Goods = Goods {
+        wood: 0, clay: 0, reed: 0, stone: 0, grain: 0, veg: 0, food: 0, sheep: 0, boar: 0, cattle: 0,
+    };
+}
+
+/// 打出前置條件。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Prereq {
+    None,
+    /// 已打出至少 N 張職業。
+    Occupations(u8),
+    /// 至少 N 塊田。
+    Fields(u8),
+    /// 至少 N 間房。
+    Rooms(u8),
+    /// 至少 N 塊牧場。
+    Pastures(u8),
+    /// 房屋材質至少為土屋。
+    ClayHouse,
+}
+
+/// 效果觸發時機。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trigger {
+    /// 每次收穫（田收成之後、餵食之前）。
+    Harvest,
+    /// 擁有者在該行動格放工人後。
+    Action(Action),
+    /// 打出 / 建造當下一次。
+    Build,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Effect {
+    pub trigger: Trigger,
+    pub gain: Goods,
+}
+
+/// 煮食率：每單位換幾糧（0 = 不能換）。農場取基礎率（`Farm::cooking`）與所有已打出卡的逐項最大值。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cooking {
+    pub grain: u32,
+    pub veg: u32,
+    pub sheep: u32,
+    pub boar: u32,
+    pub cattle: u32,
+}
+
+impl Cooking {
+    /// 家庭版（無卡）的固定轉換率：不必蓋改良也能把穀菜牲畜煮掉，避免必乞討。
+    pub const BASIC: Cooking = Cooking { grain: 1, veg: 1, sheep: 2, boar: 2, cattle: 3 };
+    /// 卡牌版的基礎率：穀菜可生吃，牲畜要有煮食改良才換得了糧。
+    pub const RAW: Cooking = Cooking { grain: 1, veg: 1, sheep: 0, boar: 0, cattle: 0 };
+
+    /// 逐項取大。
+    pub fn max(self, o: Cooking) -> Cooking {
+        Cooking {
+            grain: self.grain.max(o.grain),
+            veg: self.veg.max(o.veg),
+            sheep: self.sheep.max(o.sheep),
+            boar: self.boar.max(o.boar),
+            cattle: self.cattle.max(o.cattle),
+        }
+    }
+}
+
+impl Default for Cooking {
+    fn default() -> Self {
+        Cooking::BASIC
+    }
+}
+
+#[derive(Debug)]
+pub struct CardDef {
+    pub id: &'static str,
+    pub name: &'static str,
+    pub kind: CardKind,
+    pub cost: Goods,
+    pub prereq: Prereq,
+    pub effects: &'static [Effect],
+    /// 終局分數。
+    pub points: i32,
+    pub cooking: Option<Cooking>,
+}
+
+const fn on(trigger: Trigger, gain: Goods) -> Effect {
+    Effect { trigger, gain }
+}
+
+const fn cook(grain: u32, veg: u32, sheep: u32, boar: u32, cattle: u32) -> Option<Cooking> {
+    Some(Cooking { grain, veg, sheep, boar, cattle })
+}
+
+use CardKind::{Major, Minor, Occupation};
+use Trigger::{Build, Harvest};
+
+const fn act(a: Action) -> Trigger {
+    Trigger::Action(a)
+}
+
+/// 全卡表。id 一經上線不可改（手牌 / 已打出以 id 存，重啟保存也靠它）。
+pub const CARDS: &[CardDef] = &[
+    // ---- 職業 ----
+    CardDef { id: "woodcutter", name: "伐木工", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::Forest), Goods { wood: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "clay_digger", name: "挖土工", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::ClayPit), Goods { clay: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "reed_cutter", name: "割葦人", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::Reed), Goods { reed: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "mason", name: "石匠", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::Quarry), Goods { stone: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "fisher", name: "漁夫", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::River), Goods { food: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "seed_trader", name: "種子商", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::GrainSeeds), Goods { grain: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "greengrocer", name: "菜販", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::VegSeeds), Goods { veg: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "foreman", name: "工頭", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::DayLabor), Goods { grain: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "shepherd", name: "牧羊人", kind: Occupation, cost: Goods::NONE, prereq: Prereq::Pastures(1),
+        effects: &[on(act(Action::SheepPen), Goods { sheep: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "swineherd", name: "養豬人", kind: Occupation, cost: Goods::NONE, prereq: Prereq::Pastures(1),
+        effects: &[on(act(Action::BoarPen), Goods { boar: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "cowherd", name: "牧牛人", kind: Occupation, cost: Goods::NONE, prereq: Prereq::Occupations(2),
+        effects: &[on(act(Action::CattlePen), Goods { cattle: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "granary_keeper", name: "倉管", kind: Occupation, cost: Goods::NONE, prereq: Prereq::Fields(1),
+        effects: &[on(Harvest, Goods { grain: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "homesteader", name: "墾荒者", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(Build, Goods { wood: 2, clay: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "ploughwright", name: "犁匠", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::Plow), Goods { food: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "thatcher", name: "茅草匠", kind: Occupation, cost: Goods::NONE, prereq: Prereq::None,
+        effects: &[on(act(Action::BuildRooms), Goods { reed: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "storyteller", name: "說書人", kind: Occupation, cost: Goods::NONE, prereq: Prereq::Occupations(2),
+        effects: &[], points: 3, cooking: None },
+    // ---- 小改良 ----
+    CardDef { id: "hand_mill", name: "手搖磨", kind: Minor, cost: Goods { wood: 1, stone: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[], points: 1, cooking: cook(2, 0, 0, 0, 0) },
+    CardDef { id: "smoking_rack", name: "燻肉架", kind: Minor, cost: Goods { wood: 2, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[], points: 0, cooking: cook(0, 0, 1, 1, 2) },
+    CardDef { id: "clay_pot", name: "陶鍋", kind: Minor, cost: Goods { clay: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[], points: 0, cooking: cook(0, 2, 0, 0, 0) },
+    CardDef { id: "bread_basket", name: "麵包籃", kind: Minor, cost: Goods { reed: 1, ..Goods::NONE },
+        prereq: Prereq::Occupations(1), effects: &[], points: 0, cooking: cook(3, 0, 0, 0, 0) },
+    CardDef { id: "herb_garden", name: "香草園", kind: Minor, cost: Goods { wood: 1, ..Goods::NONE },
+        prereq: Prereq::Fields(1), effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "rain_barrel", name: "雨水桶", kind: Minor, cost: Goods { wood: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(act(Action::Sow), Goods { grain: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "feed_trough", name: "飼料槽", kind: Minor, cost: Goods { wood: 2, ..Goods::NONE },
+        prereq: Prereq::Pastures(1), effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 1, cooking: None },
+    CardDef { id: "fishing_net", name: "漁網", kind: Minor, cost: Goods { reed: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(act(Action::River), Goods { food: 2, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "lumber_rack", name: "木料架", kind: Minor, cost: Goods { wood: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(act(Action::Forest), Goods { wood: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "brick_mold", name: "磚模", kind: Minor, cost: Goods { wood: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(act(Action::ClayPit), Goods { clay: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "stone_cart", name: "運石車", kind: Minor, cost: Goods { wood: 2, ..Goods::NONE },
+        prereq: Prereq::Occupations(1), effects: &[on(act(Action::Quarry), Goods { stone: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "garden_fence", name: "菜園圍籬", kind: Minor, cost: Goods { wood: 1, ..Goods::NONE },
+        prereq: Prereq::Fields(2), effects: &[], points: 1, cooking: None },
+    CardDef { id: "heirloom_chest", name: "傳家木箱", kind: Minor, cost: Goods { wood: 1, clay: 1, ..Goods::NONE },
+        prereq: Prereq::Rooms(3), effects: &[], points: 2, cooking: None },
+    CardDef { id: "seed_pouch", name: "種子袋", kind: Minor, cost: Goods { reed: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Build, Goods { grain: 1, veg: 1, ..Goods::NONE })], points: 0, cooking: None },
+    CardDef { id: "tiled_porch", name: "磚砌門廊", kind: Minor, cost: Goods { clay: 2, ..Goods::NONE },
+        prereq: Prereq::ClayHouse, effects: &[], points: 2, cooking: None },
+    CardDef { id: "wool_blanket", name: "羊毛毯", kind: Minor, cost: Goods { sheep: 1, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 1, cooking: None },
+    // ---- 大改良（公共池） ----
+    CardDef { id: "clay_stove", name: "土灶", kind: Major, cost: Goods { clay: 2, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[], points: 1, cooking: cook(2, 2, 2, 2, 3) },
+    CardDef { id: "great_stove", name: "大灶", kind: Major, cost: Goods { clay: 4, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[], points: 1, cooking: cook(2, 3, 2, 3, 4) },
+    CardDef { id: "stone_oven", name: "石窯", kind: Major, cost: Goods { clay: 1, stone: 3, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Build, Goods { food: 2, ..Goods::NONE })], points: 2, cooking: cook(4, 0, 0, 0, 0) },
+    CardDef { id: "wood_workshop", name: "木工坊", kind: Major, cost: Goods { wood: 2, stone: 2, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 2, cooking: None },
+    CardDef { id: "pottery", name: "陶坊", kind: Major, cost: Goods { clay: 2, stone: 2, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 2, cooking: None },
+    CardDef { id: "weavery", name: "編織坊", kind: Major, cost: Goods { reed: 2, stone: 2, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Harvest, Goods { food: 1, ..Goods::NONE })], points: 2, cooking: None },
+    CardDef { id: "village_well", name: "村井", kind: Major, cost: Goods { wood: 1, stone: 3, ..Goods::NONE },
+        prereq: Prereq::None, effects: &[on(Build, Goods { food: 4, ..Goods::NONE })], points: 4, cooking: None },
+];
+
+/// 選秀每包的職業 / 小改良張數（每人最後手牌 = 兩者相加）。
+pub const PACK_OCCUPATIONS: usize = 3;
+pub const PACK_MINORS: usize = 3;
+
+pub fn card(id: &str) -> Option<&'static CardDef> {
+    CARDS.iter().find(|c| c.id == id)
+}
+
+pub fn of_kind(kind: CardKind) -> impl Iterator<Item = &'static CardDef> {
+    CARDS.iter().filter(move |c| c.kind == kind)
+}
+
+/// 每人一包：洗牌後各發 `PACK_OCCUPATIONS` 職業 + `PACK_MINORS` 小改良。
+pub fn deal_packs(n: usize) -> Vec<Vec<String>> {
+    use rand::seq::SliceRandom;
+    let mut rng = rand::rng();
+    let mut occ: Vec<&str> = of_kind(Occupation).map(|c| c.id).collect();
+    let mut minor: Vec<&str> = of_kind(Minor).map(|c| c.id).collect();
+    occ.shuffle(&mut rng);
+    minor.shuffle(&mut rng);
+    (0..n)
+        .map(|i| {
+            let o = occ.iter().skip(i * PACK_OCCUPATIONS).take(PACK_OCCUPATIONS);
+            let m = minor.iter().skip(i * PACK_MINORS).take(PACK_MINORS);
+            o.chain(m).map(|s| s.to_string()).collect()
+        })
+        .collect()
+}
+
+/// 大改良池（開局全部可建）。
+pub fn major_pool() -> Vec<String> {
+    of_kind(Major).map(|c| c.id.to_string()).collect()
+}
+
+/// 擁有者是否滿足前置條件。
+pub fn prereq_met(f: &Farm, p: Prereq) -> bool {
+    match p {
+        Prereq::None => true,
+        Prereq::Occupations(n) => occupations_played(f) >= n as usize,
+        Prereq::Fields(n) => f.fields.len() >= n as usize,
+        Prereq::Rooms(n) => f.rooms >= n,
+        Prereq::Pastures(n) => f.pastures().len() >= n as usize,
+        Prereq::ClayHouse => f.house != House::Wood,
+    }
+}
+
+pub fn occupations_played(f: &Farm) -> usize {
+    f.played.iter().filter(|id| card(id).is_some_and(|c| c.kind == Occupation)).count()
+}
+
+/// 農場目前的煮食率：基礎率與所有已打出卡的逐項最大值。
+pub fn cooking_rates(f: &Farm) -> Cooking {
+    f.played
+        .iter()
+        .filter_map(|id| card(id)?.cooking)
+        .fold(f.cooking, Cooking::max)
+}
+
+/// 已打出卡的終局分數合計。
+pub fn card_points(f: &Farm) -> i32 {
+    f.played.iter().filter_map(|id| card(id)).map(|c| c.points).sum()
+}
@@ -2,39 +2,61 @@
 //!
 //! 機制原創重做、術語自訂，不含任何商業桌遊素材/文案/名稱。
 //!
-//! ## Phase-1 範圍與簡化（刻意）
-//! - **無職業 / 改良牌組**（家庭版定義）。
-//! - **柵欄幾何抽象**：牧場記為「一塊 N 格」+ 是否含畜舍，不做精確圍欄拼塊。
-//! - **允許基本煮食轉換**：收穫餵食不足時可把 穀/菜→1 糧、牲畜→2 糧 補足，避免無改良卡時必乞討。
+//! ## 兩種模式
+//! - **家庭版**（phase-1，預設）：無職業 / 改良牌組；允許基本煮食轉換（`Cooking::BASIC`：
+//!   穀/菜→1 糧、羊豬→2、牛→3），避免無改良卡時必乞討。
+//! - **卡牌版**（phase-2，`initial_card_state`）：開局先選秀手牌（`Phase::Drafting`），多兩個行動格
+//!   `Lessons`（打職業）/ `Improvements`（蓋大小改良）。卡牌資料與效果見 `cards`；
+//!   煮食率改由改良卡決定（基礎只剩 `Cooking::RAW`：穀菜生吃，牲畜要有灶才換得了糧）。
+//!
+//! ## 單人模式
+//! 1 人開局即為單人（`GameState::solo`）：沒有搶先手格、森林每輪只進 2 木、收穫每口要 3 糧；
+//! 終局分數對照 `SOLO_TARGETS` 評星。
+//!
+//! ## 簡化（刻意）
+//! - **只有柵欄與畜舍記位置**（3×5 格、逐邊圍籬，見 `yard`）；房與田只記數量，放得進未圍、無畜舍的格即可。
+//! - **牲畜不分欄**：全農場容量（各牧場 + 未圍畜舍 + 自宅 1）合計，不要求每欄單一畜種。
 //! - **行動揭示**用可調資料表 `ROUND_ACTIONS`，非官方精確排程。
-//! - 農場容量固定 15 格（房 + 田 + 牧場格 + 無圈畜舍 ≤ 15）。
+//! - 農場容量固定 15 格（房 + 田 + 牧場格 + 未圍畜舍 ≤ 15）。
+
+use serde::{Deserialize, Serialize};
+
+use super::cards::{self, CardKind, Cooking, Goods, Trigger};
+use super::yard;
 
-pub const FARM_TILES: u8 = 15;
+pub const FARM_TILES: u8 = yard::TILES as u8;
+/// 每家畜舍上限。
+pub const MAX_STABLES: u32 = 4;
+/// 收穫時每位家人要吃的糧（多人 / 單人）。
+pub const FOOD_PER_MEMBER: u32 = 2;
+pub const SOLO_FOOD_PER_MEMBER: u32 = 3;
+/// 單人模式的評星門檻（達到第 i 個 = i+1 星）。
+pub const SOLO_TARGETS: [i32; 3] = [20, 35, 50];
 pub const TOTAL_ROUNDS: u8 = 14;
 /// 收穫輪（每階段末）。
 pub const HARVEST_ROUNDS: [u8; 6] = [4, 7, 9, 11, 13, 14];
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Crop {
     Grain,
     Vegetable,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Animal {
     Sheep,
     Boar,
     Cattle,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum House {
     Wood,
     Clay,
     Stone,
 }
 
-#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Field {
     /// 田上作物與剩餘數量（None = 已犁未播）。
     pub crop: Option<(CropKind, u8)>,
@@ -42,21 +64,38 @@ pub struct Field {
 
 pub type CropKind = Crop;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// 一塊圍好的牧場（由 `Farm::fences` 推出，不另存）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Pasture {
-    pub tiles: u8,
-    pub stable: bool,
-    pub animal: Option<(Animal, u32)>,
+    /// 圍住的格（bitmask，見 `yard`）。
+    pub tiles: u16,
+    /// 其中的畜舍數。
+    pub stables: u32,
+}
+
+impl Pasture {
+    pub fn size(&self) -> u32 {
+        self.tiles.count_ones()
+    }
+
+    /// 每格 2 隻，每個畜舍再翻倍。
+    pub fn capacity(&self) -> u32 {
+        (self.size() * 2) << self.stables
+    }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Farm {
     pub house: House,
     pub rooms: u8,
     pub family: u8,
     pub fields: Vec<Field>,
-    pub pastures: Vec<Pasture>,
-    pub loose_stables: u8, // 未圈入牧場的畜舍
+    /// 已架的柵欄（邊 bitmask，見 `yard`；引擎保證永遠是合法圍法）。
+    #[serde(default)]
+    pub fences: u64,
+    /// 畜舍所在格（bitmask）。
+    #[serde(default)]
+    pub stables: u16,
     // 資源
     pub wood: u32,
     pub clay: u32,
@@ -69,6 +108,15 @@ pub struct Farm {
     pub cattle: u32,
     pub food: u32,
     pub begging: u32,
+    /// 手牌（卡牌版；選秀所得、尚未打出的職業 / 小改良 id）。
+    #[serde(default)]
+    pub hand: Vec<String>,
+    /// 已打出 / 建造的卡 id（依序）。
+    #[serde(default)]
+    pub played: Vec<String>,
+    /// 基礎煮食率（家庭版 `BASIC`、卡牌版 `RAW`）；實際用率再疊上已打出的改良，見 `cards::cooking_rates`。
+    #[serde(default)]
+    pub cooking: Cooking,
 }
 
 impl Farm {
@@ -78,17 +126,39 @@ impl Farm {
             rooms: 2,
             family: 2,
             fields: Vec::new(),
-            pastures: Vec::new(),
-            loose_stables: 0,
+            fences: 0,
+            stables: 0,
             wood: 0, clay: 0, reed: 0, stone: 0, grain: 0, veg: 0,
             sheep: 0, boar: 0, cattle: 0, food: 0, begging: 0,
+            hand: Vec::new(),
+            played: Vec::new(),
+            cooking: Cooking::BASIC,
         }
     }
 
-    /// 已使用的農場格數（房 + 田 + 牧場格 + 無圈畜舍）。
+    /// 目前圍出的牧場。
+    pub fn pastures(&self) -> Vec<Pasture> {
+        yard::pastures(self.fences)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|tiles| Pasture { tiles, stables: (tiles & self.stables).count_ones() })
+            .collect()
+    }
+
+    /// 被圍住的格（bitmask）。
+    pub fn enclosed(&self) -> u16 {
+        self.pastures().iter().fold(0, |m, p| m | p.tiles)
+    }
+
+    /// 未圈入牧場的畜舍數。
+    pub fn loose_stables(&self) -> u32 {
+        (self.stables & !self.enclosed()).count_ones()
+    }
+
+    /// 已使用的農場格數（房 + 田 + 牧場格 + 未圍畜舍）。
     pub fn used_tiles(&self) -> u8 {
-        let pasture_tiles: u8 = self.pastures.iter().map(|p| p.tiles).sum();
-        self.rooms + self.fields.len() as u8 + pasture_tiles + self.loose_stables
+        let yard_tiles = (self.enclosed() | self.stables).count_ones() as u8;
+        self.rooms + self.fields.len() as u8 + yard_tiles
     }
 
     pub fn free_tiles(&self) -> u8 {
@@ -104,7 +174,7 @@ impl Default for Farm {
 
 // ===== 行動格 =====
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Action {
     // 累積格（每輪未取則堆積）
     Forest,    // 木
@@ -126,6 +196,9 @@ pub enum Action {
     FamilyGrowth, // +1 家庭成員
     DayLabor,   // +2 糧
     StartPlayer, // 取先手 + 1 糧
+    // 卡牌版限定
+    Lessons,      // 打一張職業
+    Improvements, // 蓋一張大 / 小改良
 }
 
 impl Action {
@@ -149,6 +222,8 @@ impl Action {
             Action::FamilyGrowth => "family_growth",
             Action::DayLabor => "day_labor",
             Action::StartPlayer => "start_player",
+            Action::Lessons => "lessons",
+            Action::Improvements => "improvements",
         }
     }
 
@@ -172,6 +247,8 @@ impl Action {
             "family_growth" => Action::FamilyGrowth,
             "day_labor" => Action::DayLabor,
             "start_player" => Action::StartPlayer,
+            "lessons" => Action::Lessons,
+            "improvements" => Action::Improvements,
             _ => return None,
         })
     }
@@ -186,16 +263,6 @@ impl Crop {
     }
 }
 
-impl Animal {
-    pub fn as_str(self) -> &'static str {
-        match self {
-            Animal::Sheep => "sheep",
-            Animal::Boar => "boar",
-            Animal::Cattle => "cattle",
-        }
-    }
-}
-
 impl House {
     pub fn as_str(self) -> &'static str {
         match self {
@@ -218,6 +285,9 @@ pub const BASE_ACTIONS: [Action; 8] = [
     Action::BuildRooms,
 ];
 
+/// 卡牌版開局就有的行動格（家庭版沒有）。
+pub const CARD_ACTIONS: [Action; 2] = [Action::Lessons, Action::Improvements];
+
 /// 逐輪揭示的行動格（round R 揭示 index R-1，超出則該輪不新增）。
 pub const ROUND_ACTIONS: [Action; 11] = [
     Action::Reed,
@@ -242,8 +312,9 @@ fn is_accumulation(a: Action) -> bool {
 }
 
 /// 累積格每輪的進帳量。
-fn accum_increment(a: Action) -> u32 {
+fn accum_increment(a: Action, solo: bool) -> u32 {
     match a {
+        Action::Forest if solo => 2,
         Action::Forest => 3,
         Action::ClayPit => 1,
         Action::Reed => 1,
@@ -262,22 +333,24 @@ pub struct Input {
     pub grain_fields: u8,    // Sow：以穀播種的田數
     pub veg_fields: u8,      // Sow：以菜播種的田數
     pub rooms: u8,           // BuildRooms：蓋房數
-    pub stables: u8,         // BuildRooms：同時蓋畜舍數
-    pub pasture_tiles: u8,   // Fences：牧場格數
-    pub pasture_stable: bool, // Fences：是否含畜舍
+    pub stables: u16,        // BuildRooms：同時蓋畜舍的格（bitmask）
+    pub fences: u64,         // Fences：新架的柵欄邊（bitmask）
+    pub card: Option<&'static str>, // Lessons / Improvements：要打的卡（`cards::CARDS` 的 id）
 }
 
 // ===== 對局狀態 =====
 
 use std::collections::{HashMap, HashSet};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Phase {
+    /// 卡牌版開局選秀：各家同時從面前的牌包挑一張，挑完整包傳給下家。
+    Drafting,
     Placing,
     GameOver,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GameState {
     pub players: Vec<Farm>,
     pub n: usize,
@@ -291,11 +364,33 @@ pub struct GameState {
     unlocked: HashSet<Action>,   // 已揭示格
     accum: HashMap<Action, u32>, // 累積格存量
     next_start: Option<usize>,   // 本輪有人搶先手
+    /// 卡牌版。
+    #[serde(default)]
+    pub cards: bool,
+    /// 選秀中各座位面前的牌包。
+    #[serde(default)]
+    pub packs: Vec<Vec<String>>,
+    /// 本輪選秀各座位已挑的卡（全員挑完才揭曉、傳包）。
+    #[serde(default)]
+    pub picks: Vec<Option<String>>,
+    /// 尚未被建造的大改良（全場共用）。
+    #[serde(default)]
+    pub majors: Vec<String>,
+    /// 單人模式（1 人開局）。
+    #[serde(default)]
+    pub solo: bool,
 }
 
+/// 家庭版開局：直接進第 1 輪。
 pub fn initial_state(n: usize) -> GameState {
+    let mut gs = blank_state(n);
+    begin_round(&mut gs);
+    gs
+}
+
+fn blank_state(n: usize) -> GameState {
     let players = (0..n).map(|_| Farm::new()).collect();
-    let mut gs = GameState {
+    GameState {
         players,
         n,
         round: 0,
@@ -305,14 +400,68 @@ pub fn initial_state(n: usize) -> GameState {
         cursor: 0,
         workers_left: vec![0; n],
         occupied: HashSet::new(),
-        unlocked: BASE_ACTIONS.iter().copied().collect(),
+        // 單人沒有對手可搶先手
+        unlocked: BASE_ACTIONS.iter().copied().filter(|&a| n > 1 || a != Action::StartPlayer).collect(),
         accum: HashMap::new(),
         next_start: None,
-    };
-    begin_round(&mut gs);
+        solo: n == 1,
+        cards: false,
+        packs: Vec::new(),
+        picks: Vec::new(),
+        majors: Vec::new(),
+    }
+}
+
+/// 卡牌版開局：發下選秀牌包（`cards::deal_packs`），選完才進第 1 輪。
+pub fn initial_card_state(n: usize, packs: Vec<Vec<String>>) -> GameState {
+    let mut gs = blank_state(n);
+    gs.unlocked.extend(CARD_ACTIONS);
+    for f in gs.players.iter_mut() {
+        f.cooking = Cooking::RAW;
+    }
+    gs.cards = true;
+    gs.majors = cards::major_pool();
+    gs.picks = vec![None; n];
+    gs.packs = packs;
+    if gs.packs.iter().all(Vec::is_empty) {
+        begin_round(&mut gs);
+    } else {
+        gs.phase = Phase::Drafting;
+    }
     gs
 }
 
+/// 選秀：`seat` 從面前的牌包挑 `card`。全員挑完 → 入手牌、牌包傳給下家（seat+1）；
+/// 牌包挑空即結束選秀、進第 1 輪。
+pub fn draft_pick(gs: &mut GameState, seat: usize, card: &str) -> Result<(), &'static str> {
+    if gs.phase != Phase::Drafting {
+        return Err("wrong_phase");
+    }
+    if seat >= gs.n || !gs.packs[seat].iter().any(|c| c == card) {
+        return Err("bad_card");
+    }
+    if gs.picks[seat].is_some() {
+        return Err("already_picked");
+    }
+    gs.picks[seat] = Some(card.to_string());
+    if gs.picks.iter().any(Option::is_none) {
+        return Ok(());
+    }
+    for seat in 0..gs.n {
+        let pick = gs.picks[seat].take().unwrap();
+        gs.packs[seat].retain(|c| *c != pick);
+        gs.players[seat].hand.push(pick);
+    }
+    gs.packs.rotate_right(1);
+    if gs.packs.iter().all(Vec::is_empty) {
+        gs.packs.clear();
+        gs.picks.clear();
+        gs.phase = Phase::Placing;
+        begin_round(gs);
+    }
+    Ok(())
+}
+
 fn begin_round(gs: &mut GameState) {
     gs.round += 1;
     // 揭示本輪行動格
@@ -322,7 +471,7 @@ fn begin_round(gs: &mut GameState) {
     // 累積格進帳
     for &a in gs.unlocked.iter() {
         if is_accumulation(a) {
-            *gs.accum.entry(a).or_insert(0) += accum_increment(a);
+            *gs.accum.entry(a).or_insert(0) += accum_increment(a, gs.solo);
         }
     }
     gs.occupied.clear();
@@ -386,8 +535,10 @@ pub fn available_actions(gs: &GameState) -> Vec<Action> {
 
 /// 玩家在 `action` 放工人。驗證 → 套用 → 推進輪次（必要時收穫/進下一輪）。
 pub fn take_action(gs: &mut GameState, player: usize, action: Action, input: Input) -> Result<(), &'static str> {
-    if gs.phase != Phase::Placing {
-        return Err("game_over");
+    match gs.phase {
+        Phase::Placing => {}
+        Phase::Drafting => return Err("wrong_phase"),
+        Phase::GameOver => return Err("game_over"),
     }
     if current_player(gs) != Some(player) {
         return Err("not_your_turn");
@@ -401,9 +552,15 @@ pub fn take_action(gs: &mut GameState, player: usize, action: Action, input: Inp
 
     // 在 farm 副本上套用結構性效果，成功才寫回（保持失敗無副作用）
     let mut farm = gs.players[player].clone();
+    let mut majors = gs.majors.clone();
     let accum_amt = gs.accum.get(&action).copied().unwrap_or(0);
     apply_action(&mut farm, action, accum_amt, input)?;
+    if matches!(action, Action::Lessons | Action::Improvements) {
+        play_card(&mut farm, &mut majors, action, input.card)?;
+    }
+    fire(&mut farm, Trigger::Action(action));
     gs.players[player] = farm;
+    gs.majors = majors;
 
     // 副作用：累積格清零、先手、佔格
     if is_accumulation(action) {
@@ -425,16 +582,13 @@ pub fn take_action(gs: &mut GameState, player: usize, action: Action, input: Inp
 }
 
 fn animal_capacity(f: &Farm) -> u32 {
-    let pasture_cap: u32 = f
-        .pastures
-        .iter()
-        .map(|p| if p.stable { p.tiles as u32 * 4 } else { p.tiles as u32 * 2 })
-        .sum();
-    pasture_cap + f.loose_stables as u32 + 1 // 自宅可養 1
+    let pasture_cap: u32 = f.pastures().iter().map(Pasture::capacity).sum();
+    pasture_cap + f.loose_stables() + 1 // 未圍畜舍各 1、自宅可養 1
 }
 
-/// 取得動物入欄；超出容量的部分就地「煮食」換糧。
+/// 取得動物入欄；超出容量的部分就地「煮食」換糧（煮食率 0 則放生）。
 fn gain_animals(f: &mut Farm, animal: Animal, amt: u32) {
+    let rates = cards::cooking_rates(f);
     let cap = animal_capacity(f);
     let slot = match animal {
         Animal::Sheep => &mut f.sheep,
@@ -446,8 +600,9 @@ fn gain_animals(f: &mut Farm, animal: Animal, amt: u32) {
     if total > cap {
         let excess = total - cap;
         let food_val = match animal {
-            Animal::Sheep | Animal::Boar => 2,
-            Animal::Cattle => 3,
+            Animal::Sheep => rates.sheep,
+            Animal::Boar => rates.boar,
+            Animal::Cattle => rates.cattle,
         };
         let slot = match animal {
             Animal::Sheep => &mut f.sheep,
@@ -505,24 +660,42 @@ fn apply_action(f: &mut Farm, action: Action, accum: u32, input: Input) -> Resul
             f.veg -= input.veg_fields as u32;
         }
         Action::Fences => {
-            let t = input.pasture_tiles;
-            if t < 1 {
-                return Err("bad_pasture");
+            // 每段柵欄 1 木；架完整體仍須是合法圍法，且新圍進的格不能擠掉房 / 田
+            let new = input.fences;
+            if new == 0 || new >> yard::EDGES != 0 {
+                return Err("bad_fences");
             }
-            if f.free_tiles() < t {
-                return Err("no_space");
+            if new & f.fences != 0 {
+                return Err("already_fenced");
+            }
+            let fences = f.fences | new;
+            if fences.count_ones() > yard::MAX_FENCES {
+                return Err("no_fences_left");
             }
-            let cost = t as u32 + 1 + if input.pasture_stable { 2 } else { 0 };
+            yard::pastures(fences)?;
+            let cost = new.count_ones();
             if f.wood < cost {
                 return Err("no_wood");
             }
+            if (Farm { fences, ..f.clone() }).used_tiles() > FARM_TILES {
+                return Err("no_space");
+            }
             f.wood -= cost;
-            f.pastures.push(Pasture { tiles: t, stable: input.pasture_stable, animal: None });
+            f.fences = fences;
         }
         Action::BuildRooms => {
             let rc = input.rooms;
-            let sc = input.stables;
-            if f.free_tiles() < rc + sc {
+            let new = input.stables;
+            if new >> yard::TILES != 0 || new & f.stables != 0 {
+                return Err("bad_stables");
+            }
+            if (f.stables | new).count_ones() > MAX_STABLES {
+                return Err("no_stables_left");
+            }
+            let sc = new.count_ones() as u8;
+            // 蓋在牧場裡的畜舍不佔新格
+            let grown = (Farm { stables: f.stables | new, ..f.clone() }).used_tiles() - f.used_tiles();
+            if f.free_tiles() < rc + grown {
                 return Err("no_space");
             }
             let (mat_cost, reed_cost) = (5 * rc as u32, 2 * rc as u32);
@@ -542,7 +715,7 @@ fn apply_action(f: &mut Farm, action: Action, accum: u32, input: Input) -> Resul
             f.reed -= reed_cost;
             f.wood -= 2 * sc as u32;
             f.rooms += rc;
-            f.loose_stables += sc;
+            f.stables |= new;
         }
         Action::Renovate => match f.house {
             House::Wood => {
@@ -569,16 +742,110 @@ fn apply_action(f: &mut Farm, action: Action, accum: u32, input: Input) -> Resul
             }
             f.family += 1;
         }
+        // 要動公共大改良池，由 take_action 的 play_card 處理
+        Action::Lessons | Action::Improvements => {}
+    }
+    Ok(())
+}
+
+// ===== 卡牌 =====
+
+/// 打出 / 建造一張卡：檢查卡種對得上行動格、在手牌（或大改良池）裡、前置條件與費用，
+/// 付費後入 `played` 並觸發該卡的 `Build` 效果。職業第一張免費，之後每張 1 糧。
+fn play_card(f: &mut Farm, majors: &mut Vec<String>, action: Action, card: Option<&str>) -> Result<(), &'static str> {
+    let def = cards::card(card.ok_or("no_card")?).ok_or("bad_card")?;
+    let mut cost = def.cost;
+    match (action, def.kind) {
+        (Action::Lessons, CardKind::Occupation) => {
+            if cards::occupations_played(f) > 0 {
+                cost.food += 1;
+            }
+        }
+        (Action::Improvements, CardKind::Minor) => {}
+        (Action::Improvements, CardKind::Major) => {
+            if !majors.iter().any(|m| m == def.id) {
+                return Err("not_available");
+            }
+        }
+        _ => return Err("wrong_card_kind"),
+    }
+    if def.kind != CardKind::Major && !f.hand.iter().any(|c| c == def.id) {
+        return Err("not_in_hand");
+    }
+    if !cards::prereq_met(f, def.prereq) {
+        return Err("prereq_not_met");
+    }
+    pay(f, cost)?;
+    if def.kind == CardKind::Major {
+        majors.retain(|m| m != def.id);
+    } else {
+        f.hand.retain(|c| c != def.id);
+    }
+    f.played.push(def.id.to_string());
+    for e in def.effects.iter().filter(|e| e.trigger == Trigger::Build) {
+        gain(f, e.gain);
     }
     Ok(())
 }
 
+/// 付費（不夠則整筆不扣）。
+fn pay(f: &mut Farm, g: Goods) -> Result<(), &'static str> {
+    let have = [f.wood, f.clay, f.reed, f.stone, f.grain, f.veg, f.food, f.sheep, f.boar, f.cattle];
+    let need = [g.wood, g.clay, g.reed, g.stone, g.grain, g.veg, g.food, g.sheep, g.boar, g.cattle];
+    if have.iter().zip(need).any(|(&h, n)| h < n) {
+        return Err("cannot_afford");
+    }
+    f.wood -= g.wood;
+    f.clay -= g.clay;
+    f.reed -= g.reed;
+    f.stone -= g.stone;
+    f.grain -= g.grain;
+    f.veg -= g.veg;
+    f.food -= g.food;
+    f.sheep -= g.sheep;
+    f.boar -= g.boar;
+    f.cattle -= g.cattle;
+    Ok(())
+}
+
+/// 入帳；牲畜照常入欄（超出容量者煮掉或放生）。
+fn gain(f: &mut Farm, g: Goods) {
+    f.wood += g.wood;
+    f.clay += g.clay;
+    f.reed += g.reed;
+    f.stone += g.stone;
+    f.grain += g.grain;
+    f.veg += g.veg;
+    f.food += g.food;
+    for (animal, n) in [(Animal::Sheep, g.sheep), (Animal::Boar, g.boar), (Animal::Cattle, g.cattle)] {
+        if n > 0 {
+            gain_animals(f, animal, n);
+        }
+    }
+}
+
+/// 觸發已打出卡中所有符合 `trigger` 的效果。
+fn fire(f: &mut Farm, trigger: Trigger) {
+    let gains: Vec<Goods> = f
+        .played
+        .iter()
+        .filter_map(|id| cards::card(id))
+        .flat_map(|c| c.effects.iter())
+        .filter(|e| e.trigger == trigger)
+        .map(|e| e.gain)
+        .collect();
+    for g in gains {
+        gain(f, g);
+    }
+}
+
 // ===== 輪末 / 收穫 =====
 
 fn end_round(gs: &mut GameState) {
     if HARVEST_ROUNDS.contains(&gs.round) {
+        let per_member = if gs.solo { SOLO_FOOD_PER_MEMBER } else { FOOD_PER_MEMBER };
         for f in gs.players.iter_mut() {
-            harvest(f);
+            harvest(f, per_member);
         }
     }
     if gs.round >= TOTAL_ROUNDS {
@@ -588,8 +855,9 @@ fn end_round(gs: &mut GameState) {
     }
 }
 
-/// 收穫：① 田收成 ② 餵食（不足以資源/牲畜煮食補，再不足乞討）③ 牲畜繁殖。
-pub fn harvest(f: &mut Farm) {
+/// 收穫：① 田收成（之後觸發卡牌的收穫效果）② 餵食每口 `per_member` 糧（不足依煮食率以穀菜牲畜補，
+/// 再不足乞討）③ 牲畜繁殖。
+pub fn harvest(f: &mut Farm, per_member: u32) {
     // ① 田收成：每塊有作物的田產 1 單位入庫
     for field in f.fields.iter_mut() {
         if let Some((crop, count)) = field.crop {
@@ -601,20 +869,24 @@ pub fn harvest(f: &mut Farm) {
             field.crop = if left == 0 { None } else { Some((crop, left)) };
         }
     }
+    fire(f, Trigger::Harvest);
     // ② 餵食
-    let mut need = f.family as u32 * 2;
-    let pay = need.min(f.food);
-    f.food -= pay;
-    need -= pay;
-    while need > 0 && f.grain > 0 {
-        f.grain -= 1;
-        need -= 1;
-    }
-    while need > 0 && f.veg > 0 {
-        f.veg -= 1;
-        need -= 1;
-    }
-    for (count, val) in [(&mut f.sheep, 2u32), (&mut f.boar, 2), (&mut f.cattle, 3)] {
+    let rates = cards::cooking_rates(f);
+    let mut need = f.family as u32 * per_member;
+    let paid = need.min(f.food);
+    f.food -= paid;
+    need -= paid;
+    let stock = [
+        (&mut f.grain, rates.grain),
+        (&mut f.veg, rates.veg),
+        (&mut f.sheep, rates.sheep),
+        (&mut f.boar, rates.boar),
+        (&mut f.cattle, rates.cattle),
+    ];
+    for (count, val) in stock {
+        if val == 0 {
+            continue;
+        }
         while need > 0 && *count > 0 {
             *count -= 1;
             need = need.saturating_sub(val);
@@ -659,8 +931,43 @@ fn bucket(n: u32, t1: u32, t2: u32, t3: u32, t4: u32) -> i32 {
     }
 }
 
+/// 終局計分的逐項明細（完局落地 `farm_scores.breakdown` 也存這份）。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScoreBreakdown {
+    pub fields: i32,
+    pub pastures: i32,
+    pub grain: i32,
+    pub veg: i32,
+    pub sheep: i32,
+    pub boar: i32,
+    pub cattle: i32,
+    /// 圍進牧場的畜舍
+    pub fenced_stables: i32,
+    /// 房間材質
+    pub house: i32,
+    pub family: i32,
+    /// 乞討（負分）
+    pub begging: i32,
+    /// 空格（負分）
+    pub unused: i32,
+    /// 已打出卡的分數
+    pub cards: i32,
+}
+
+impl ScoreBreakdown {
+    pub fn total(&self) -> i32 {
+        self.fields + self.pastures + self.grain + self.veg + self.sheep + self.boar + self.cattle
+            + self.fenced_stables + self.house + self.family + self.begging + self.unused + self.cards
+    }
+}
+
 /// 單一農場最終分數。
 pub fn score(f: &Farm) -> i32 {
+    breakdown(f).total()
+}
+
+/// 逐項計分。
+pub fn breakdown(f: &Farm) -> ScoreBreakdown {
     let grain_total = f.grain
         + f.fields.iter().filter_map(|x| match x.crop {
             Some((Crop::Grain, c)) => Some(c as u32),
@@ -671,58 +978,64 @@ pub fn score(f: &Farm) -> i32 {
             Some((Crop::Vegetable, c)) => Some(c as u32),
             _ => None,
         }).sum::<u32>();
+    let pastures = f.pastures();
 
-    let mut s = 0i32;
-    // 田數：0-1→-1,2→1,3→2,4→3,5+→4
-    s += match f.fields.len() {
-        0 | 1 => -1,
-        2 => 1,
-        3 => 2,
-        4 => 3,
-        _ => 4,
-    };
-    // 牧場數：0→-1,1→1,2→2,3→3,4+→4
-    s += match f.pastures.len() {
-        0 => -1,
-        1 => 1,
-        2 => 2,
-        3 => 3,
-        _ => 4,
-    };
-    // 穀：0→-1,1-3→1,4-5→2,6-7→3,8+→4
-    s += bucket(grain_total, 4, 6, 8, 8);
-    // 菜：0→-1,1→1,2→2,3→3,4+→4
-    s += match veg_total {
-        0 => -1,
-        1 => 1,
-        2 => 2,
-        3 => 3,
-        _ => 4,
-    };
-    // 羊：1-3→1,4-5→2,6-7→3,8+→4
-    s += bucket(f.sheep, 4, 6, 8, 8);
-    // 豬：1-2→1,3-4→2,5-6→3,7+→4
-    s += bucket(f.boar, 3, 5, 7, 7);
-    // 牛：1→1,2-3→2,4-5→3,6+→4
-    s += bucket(f.cattle, 2, 4, 6, 6);
-    // 有圈畜舍：每個 +1
-    s += f.pastures.iter().filter(|p| p.stable).count() as i32;
-    // 房間材質：土 +1、石 +2（木 0）
-    s += match f.house {
-        House::Wood => 0,
-        House::Clay => f.rooms as i32,
-        House::Stone => f.rooms as i32 * 2,
-    };
-    // 家庭成員 +3
-    s += f.family as i32 * 3;
-    // 乞討 -3
-    s -= f.begging as i32 * 3;
-    // 空格 -1
-    s -= f.free_tiles() as i32;
-    s
+    ScoreBreakdown {
+        // 田數：0-1→-1,2→1,3→2,4→3,5+→4
+        fields: match f.fields.len() {
+            0 | 1 => -1,
+            2 => 1,
+            3 => 2,
+            4 => 3,
+            _ => 4,
+        },
+        // 牧場數：0→-1,1→1,2→2,3→3,4+→4
+        pastures: match pastures.len() {
+            0 => -1,
+            1 => 1,
+            2 => 2,
+            3 => 3,
+            _ => 4,
+        },
+        // 穀：0→-1,1-3→1,4-5→2,6-7→3,8+→4
+        grain: bucket(grain_total, 4, 6, 8, 8),
+        // 菜：0→-1,1→1,2→2,3→3,4+→4
+        veg: match veg_total {
+            0 => -1,
+            1 => 1,
+            2 => 2,
+            3 => 3,
+            _ => 4,
+        },
+        // 羊：1-3→1,4-5→2,6-7→3,8+→4
+        sheep: bucket(f.sheep, 4, 6, 8, 8),
+        // 豬：1-2→1,3-4→2,5-6→3,7+→4
+        boar: bucket(f.boar, 3, 5, 7, 7),
+        // 牛：1→1,2-3→2,4-5→3,6+→4
+        cattle: bucket(f.cattle, 2, 4, 6, 6),
+        // 圍進牧場的畜舍：每個 +1
+        fenced_stables: pastures.iter().map(|p| p.stables as i32).sum(),
+        // 房間材質：土 +1、石 +2（木 0）
+        house: match f.house {
+            House::Wood => 0,
+            House::Clay => f.rooms as i32,
+            House::Stone => f.rooms as i32 * 2,
+        },
+        // 家庭成員 +3
+        family: f.family as i32 * 3,
+        // 乞討 -3
+        begging: -(f.begging as i32 * 3),
+        // 空格 -1
+        unused: -(f.free_tiles() as i32),
+        cards: cards::card_points(f),
+    }
+}
+
+/// 單人評星：達到 `SOLO_TARGETS` 的幾個門檻（0–3）。
+pub fn solo_stars(score: i32) -> usize {
+    SOLO_TARGETS.iter().filter(|&&t| score >= t).count()
 }
 
-#[allow(dead_code)]
 pub fn final_scores(gs: &GameState) -> Vec<i32> {
     gs.players.iter().map(score).collect()
 }
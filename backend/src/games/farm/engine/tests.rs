@@ -1,4 +1,5 @@
 use super::*;
+use crate::games::farm::yard;
 
 #[test]
 fn initial_state_shape() {
@@ -52,7 +53,7 @@ fn harvest_field_yields_to_supply() {
     let mut f = Farm::new();
     f.family = 0; // 免餵食干擾
     f.fields.push(Field { crop: Some((Crop::Grain, 3)) });
-    harvest(&mut f);
+    harvest(&mut f, FOOD_PER_MEMBER);
     assert_eq!(f.grain, 1);
     assert_eq!(f.fields[0].crop, Some((Crop::Grain, 2)));
 }
@@ -60,7 +61,7 @@ fn harvest_field_yields_to_supply() {
 #[test]
 fn feeding_shortfall_creates_begging() {
     let mut f = Farm::new(); // family 2 → 需 4 糧，無任何食物
-    harvest(&mut f);
+    harvest(&mut f, FOOD_PER_MEMBER);
     assert_eq!(f.begging, 4);
     assert_eq!(f.food, 0);
 }
@@ -70,7 +71,7 @@ fn feeding_converts_grain_then_begs() {
     let mut f = Farm::new();
     f.family = 1; // 需 2
     f.grain = 1;
-    harvest(&mut f);
+    harvest(&mut f, FOOD_PER_MEMBER);
     assert_eq!(f.grain, 0); // 1 穀煮成 1 糧
     assert_eq!(f.begging, 1); // 仍缺 1
 }
@@ -80,9 +81,9 @@ fn animals_breed_when_pair_and_space() {
     let mut f = Farm::new();
     f.family = 1;
     f.food = 2; // 餵飽，不動到羊
-    f.pastures.push(Pasture { tiles: 2, stable: false, animal: None }); // 容量足
+    f.fences = yard::perimeter(0b11); // 2 格牧場，容量足
     f.sheep = 2;
-    harvest(&mut f);
+    harvest(&mut f, FOOD_PER_MEMBER);
     assert_eq!(f.sheep, 3);
     assert_eq!(f.begging, 0);
 }
@@ -125,3 +126,263 @@ fn accumulation_piles_and_resets() {
     take_action(&mut gs, 0, Action::Forest, Input::default()).unwrap();
     assert_eq!(gs.players[0].wood, 3);
 }
+
+// ---- 卡牌版 ----
+
+fn card_game(packs: Vec<Vec<&str>>) -> GameState {
+    let packs = packs.into_iter().map(|p| p.into_iter().map(String::from).collect()).collect();
+    initial_card_state(2, packs)
+}
+
+/// 兩人局選秀完、座位 0 是先手。
+fn drafted(hand0: &[&str]) -> GameState {
+    let mut gs = card_game(vec![vec![], vec![]]);
+    gs.players[0].hand = hand0.iter().map(|s| s.to_string()).collect();
+    gs
+}
+
+#[test]
+fn draft_passes_packs_until_empty() {
+    let mut gs = card_game(vec![vec!["woodcutter", "hand_mill"], vec!["fisher", "clay_pot"]]);
+    assert_eq!(gs.phase, Phase::Drafting);
+    assert_eq!(take_action(&mut gs, 0, Action::Forest, Input::default()), Err("wrong_phase"));
+    assert_eq!(draft_pick(&mut gs, 0, "fisher"), Err("bad_card"));
+    draft_pick(&mut gs, 0, "woodcutter").unwrap();
+    assert_eq!(draft_pick(&mut gs, 0, "hand_mill"), Err("already_picked"));
+    assert!(gs.players[0].hand.is_empty(), "全員挑完才入手牌");
+    draft_pick(&mut gs, 1, "clay_pot").unwrap();
+    // 牌包往下家傳：座位 0 拿到座位 1 剩下的
+    assert_eq!(gs.packs[0], vec!["fisher".to_string()]);
+    assert_eq!(gs.packs[1], vec!["hand_mill".to_string()]);
+    draft_pick(&mut gs, 0, "fisher").unwrap();
+    draft_pick(&mut gs, 1, "hand_mill").unwrap();
+    assert_eq!(gs.phase, Phase::Placing);
+    assert_eq!(gs.round, 1);
+    assert_eq!(gs.players[0].hand, vec!["woodcutter", "fisher"]);
+    assert_eq!(gs.players[1].hand, vec!["clay_pot", "hand_mill"]);
+}
+
+#[test]
+fn lessons_first_free_then_costs_food() {
+    let mut gs = drafted(&["woodcutter", "fisher"]);
+    gs.players[0].food = 0;
+    let play = |c| Input { card: Some(c), ..Default::default() };
+    take_action(&mut gs, 0, Action::Lessons, play("woodcutter")).unwrap();
+    assert_eq!(gs.players[0].played, vec!["woodcutter"]);
+    assert_eq!(gs.players[0].hand, vec!["fisher"]);
+
+    // 下一張職業要 1 糧，沒糧不能打（失敗無副作用）
+    gs.occupied.clear();
+    gs.cursor = 0;
+    gs.workers_left = vec![1, 1];
+    assert_eq!(take_action(&mut gs, 0, Action::Lessons, play("fisher")), Err("cannot_afford"));
+    assert_eq!(gs.players[0].hand, vec!["fisher"]);
+    gs.players[0].food = 1;
+    take_action(&mut gs, 0, Action::Lessons, play("fisher")).unwrap();
+    assert_eq!(gs.players[0].food, 0);
+}
+
+#[test]
+fn improvements_check_kind_hand_prereq_and_pool() {
+    let mut gs = drafted(&["woodcutter", "garden_fence", "hand_mill"]);
+    let play = |c| Input { card: Some(c), ..Default::default() };
+    assert_eq!(take_action(&mut gs, 0, Action::Improvements, play("woodcutter")), Err("wrong_card_kind"));
+    assert_eq!(take_action(&mut gs, 0, Action::Improvements, play("clay_pot")), Err("not_in_hand"));
+    assert_eq!(take_action(&mut gs, 0, Action::Improvements, play("garden_fence")), Err("prereq_not_met"));
+    assert_eq!(take_action(&mut gs, 0, Action::Improvements, Input::default()), Err("no_card"));
+
+    // 大改良從公共池建，建完別人就拿不到
+    gs.players[0].clay = 2;
+    take_action(&mut gs, 0, Action::Improvements, play("clay_stove")).unwrap();
+    assert!(!gs.majors.iter().any(|m| m == "clay_stove"));
+    assert_eq!(gs.players[0].clay, 0);
+    gs.players[1].clay = 2;
+    assert_eq!(take_action(&mut gs, 1, Action::Improvements, play("clay_stove")), Err("occupied"));
+    gs.occupied.clear();
+    assert_eq!(take_action(&mut gs, 1, Action::Improvements, play("clay_stove")), Err("not_available"));
+}
+
+#[test]
+fn action_and_build_triggers_pay_out() {
+    let mut f = Farm::new();
+    f.played = vec!["woodcutter".into()];
+    fire(&mut f, Trigger::Action(Action::Forest));
+    assert_eq!(f.wood, 1);
+    fire(&mut f, Trigger::Action(Action::ClayPit));
+    assert_eq!(f.clay, 0);
+
+    let mut gs = drafted(&[]);
+    gs.players[0].wood = 1;
+    gs.players[0].stone = 3;
+    let food = gs.players[0].food;
+    take_action(&mut gs, 0, Action::Improvements, Input { card: Some("village_well"), ..Default::default() }).unwrap();
+    assert_eq!(gs.players[0].food, food + 4);
+}
+
+#[test]
+fn harvest_trigger_fires_before_feeding() {
+    let mut f = Farm::new();
+    f.cooking = Cooking::RAW;
+    f.played = vec!["wood_workshop".into(), "pottery".into()];
+    f.food = 2;
+    harvest(&mut f, FOOD_PER_MEMBER);
+    assert_eq!(f.food, 0);
+    assert_eq!(f.begging, 0, "卡牌給的 2 糧 + 原有 2 糧剛好餵飽兩口");
+}
+
+#[test]
+fn raw_cooking_needs_improvements_for_animals() {
+    let mut f = Farm::new();
+    f.cooking = Cooking::RAW;
+    f.sheep = 2;
+    harvest(&mut f, FOOD_PER_MEMBER);
+    assert_eq!(f.begging, 4, "生食不能吃羊");
+
+    let mut f = Farm::new();
+    f.cooking = Cooking::RAW;
+    f.played = vec!["clay_stove".into()];
+    f.sheep = 2;
+    assert_eq!(cards::cooking_rates(&f).sheep, 2);
+    harvest(&mut f, FOOD_PER_MEMBER);
+    assert_eq!(f.begging, 0);
+    assert_eq!(f.sheep, 0);
+}
+
+#[test]
+fn card_points_add_to_score() {
+    let mut f = Farm::new();
+    let base = score(&f);
+    f.played = vec!["village_well".into(), "storyteller".into(), "woodcutter".into()];
+    assert_eq!(score(&f), base + 7);
+}
+
+// ---- 圍籬幾何 ----
+
+/// 左上 2×2（格 0,1,5,6）。
+const SQUARE: u16 = 1 << 0 | 1 << 1 | 1 << 5 | 1 << 6;
+
+fn fence_input(edges: u64) -> Input {
+    Input { fences: edges, ..Default::default() }
+}
+
+#[test]
+fn perimeter_of_single_tile_and_square() {
+    assert_eq!(yard::perimeter(1).count_ones(), 4);
+    assert_eq!(yard::perimeter(SQUARE).count_ones(), 8);
+    assert_eq!(yard::pastures(yard::perimeter(SQUARE)), Ok(vec![SQUARE]));
+}
+
+#[test]
+fn open_or_half_fences_rejected() {
+    // 缺一邊：圍不起來
+    let open = yard::perimeter(SQUARE) & !(1 << yard::h_edge(0, 0));
+    assert_eq!(yard::pastures(open), Err("not_closed"));
+    // 牧場內伸半截隔間（只隔開上排兩格間的直邊）
+    let half = yard::perimeter(SQUARE) | 1 << yard::v_edge(0, 1);
+    assert_eq!(yard::pastures(half), Err("bad_subdivision"));
+    // 完整隔成兩塊 1×2
+    let split = yard::perimeter(SQUARE) | 1 << yard::h_edge(1, 0) | 1 << yard::h_edge(1, 1);
+    assert_eq!(yard::pastures(split), Ok(vec![0b11, 1 << 5 | 1 << 6]));
+}
+
+#[test]
+fn fences_cost_one_wood_per_edge_and_must_close() {
+    let mut f = Farm::new();
+    f.wood = 20;
+    let open = yard::perimeter(SQUARE) & !(1 << yard::h_edge(0, 0));
+    assert_eq!(apply_action(&mut f, Action::Fences, 0, fence_input(open)), Err("not_closed"));
+    assert_eq!(f.wood, 20);
+    apply_action(&mut f, Action::Fences, 0, fence_input(yard::perimeter(SQUARE))).unwrap();
+    assert_eq!(f.wood, 12);
+    assert_eq!(f.pastures().len(), 1);
+    assert_eq!(f.used_tiles(), 2 + 4);
+
+    // 在既有牧場上加隔間：只付新邊
+    let split = 1 << yard::h_edge(1, 0) | 1 << yard::h_edge(1, 1);
+    apply_action(&mut f, Action::Fences, 0, fence_input(split)).unwrap();
+    assert_eq!(f.wood, 10);
+    assert_eq!(f.pastures().len(), 2);
+    assert_eq!(apply_action(&mut f, Action::Fences, 0, fence_input(split)), Err("already_fenced"));
+}
+
+#[test]
+fn fences_limited_by_supply_and_space() {
+    let mut f = Farm::new();
+    f.wood = 99;
+    // 整個農場一圈 = 16 段 > 15
+    let all = yard::perimeter((1 << yard::TILES) - 1);
+    assert_eq!(apply_action(&mut f, Action::Fences, 0, fence_input(all)), Err("no_fences_left"));
+    // 右邊 3×4 共 12 格（14 段），加上 2 間房 + 2 塊田 > 15
+    f.fields = vec![Field::default(); 2];
+    let right: u16 = (0..yard::TILES).filter(|t| t % yard::COLS > 0).fold(0, |m, t| m | 1 << t);
+    assert_eq!(apply_action(&mut f, Action::Fences, 0, fence_input(yard::perimeter(right))), Err("no_space"));
+}
+
+#[test]
+fn stables_double_pasture_capacity_and_score() {
+    let mut f = Farm::new();
+    f.wood = 20;
+    apply_action(&mut f, Action::Fences, 0, fence_input(yard::perimeter(SQUARE))).unwrap();
+    assert_eq!(animal_capacity(&f), 8 + 1);
+    let before = score(&f);
+    let used = f.used_tiles();
+    // 一個蓋在牧場裡、一個蓋在外面
+    let stables = 1 << 0 | 1 << 14;
+    apply_action(&mut f, Action::BuildRooms, 0, Input { stables, ..Default::default() }).unwrap();
+    assert_eq!(f.used_tiles(), used + 1, "牧場裡的畜舍不佔新格");
+    assert_eq!(f.loose_stables(), 1);
+    assert_eq!(animal_capacity(&f), 16 + 1 + 1);
+    // 圍進的畜舍 +1、多用一格少扣 1
+    assert_eq!(score(&f), before + 2);
+    assert_eq!(apply_action(&mut f, Action::BuildRooms, 0, Input { stables: 1, ..Default::default() }), Err("bad_stables"));
+}
+
+// ---- 單人模式 ----
+
+#[test]
+fn solo_has_no_start_player_and_less_wood() {
+    let gs = initial_state(1);
+    assert!(gs.solo);
+    assert!(!available_actions(&gs).contains(&Action::StartPlayer));
+    assert_eq!(accumulation(&gs).iter().find(|(a, _)| *a == Action::Forest), Some(&(Action::Forest, 2)));
+    let multi = initial_state(2);
+    assert!(!multi.solo);
+    assert!(available_actions(&multi).contains(&Action::StartPlayer));
+}
+
+#[test]
+fn solo_harvest_feeds_three_per_member() {
+    let mut f = Farm::new();
+    f.food = 4;
+    harvest(&mut f, SOLO_FOOD_PER_MEMBER);
+    assert_eq!(f.begging, 2, "兩口要 6 糧");
+
+    // 整局走到第一次收穫：每輪兩個工人拿日工(+2 糧)與穀種
+    let mut gs = initial_state(1);
+    while gs.round <= HARVEST_ROUNDS[0] {
+        take_action(&mut gs, 0, Action::DayLabor, Input::default()).unwrap();
+        take_action(&mut gs, 0, Action::GrainSeeds, Input::default()).unwrap();
+    }
+    // 8 糧餵掉 6，剩 2；4 穀沒動到
+    assert_eq!(gs.players[0].food, 2);
+    assert_eq!(gs.players[0].grain, 4);
+    assert_eq!(gs.players[0].begging, 0);
+}
+
+#[test]
+fn breakdown_sums_to_score_and_stars() {
+    let mut f = Farm::new();
+    f.fields = vec![Field::default(); 3];
+    f.sheep = 4;
+    f.house = House::Clay;
+    f.begging = 1;
+    let b = breakdown(&f);
+    assert_eq!(b.fields, 2);
+    assert_eq!(b.sheep, 2);
+    assert_eq!(b.house, 2);
+    assert_eq!(b.begging, -3);
+    assert_eq!(b.total(), score(&f));
+    assert_eq!(solo_stars(19), 0);
+    assert_eq!(solo_stars(SOLO_TARGETS[1]), 2);
+    assert_eq!(solo_stars(99), 3);
+}
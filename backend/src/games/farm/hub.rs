@@ -1,17 +1,30 @@
-//! 農場經營房型參數。大廳 / 房間狀態走 `common::room` 泛型框架。2–4 人，重啟即丟失。
+//! 農場經營房型參數。大廳 / 房間狀態走 `common::room` 泛型框架。1–4 人（1 人開局 = 單人模式），
+//! 對局中的房重啟後還原（見 `common::persist`）。
+
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
 
 use super::engine::GameState;
+use super::service;
 use crate::games::common::room::{RoomHub, RoomKind};
 
-pub const MIN_PLAYERS: usize = 2;
+pub const MIN_PLAYERS: usize = 1;
 pub const MAX_PLAYERS: usize = 4;
 
+/// 建房選項。
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct Options {
+    /// 卡牌版（開局選秀、職業 / 改良卡）；預設家庭版。
+    #[serde(default)]
+    pub cards: bool,
+}
+
 /// marker type：impl `RoomKind` 提供農場的靜態參數。
 pub struct FarmRoom;
 
 impl RoomKind for FarmRoom {
     type Playing = GameState;
-    type Options = ();
+    type Options = Options;
 
     const NAME: &'static str = super::NAME;
     const MIN_PLAYERS: usize = MIN_PLAYERS;
@@ -22,6 +35,22 @@ impl RoomKind for FarmRoom {
     fn default_room_name(id: u64) -> String {
         format!("農場 #{id}")
     }
+
+    fn parse_options(data: Option<&Value>) -> Options {
+        Options {
+            cards: data.and_then(|d| d.pointer("/options/cards")).and_then(|v| v.as_bool()).unwrap_or(false),
+        }
+    }
+
+    fn extend_room_snapshot(options: &Options, obj: &mut Map<String, Value>) {
+        obj.insert("options".into(), json!({ "cards": options.cards }));
+    }
+
+    /// 重連補送：完整盤面（帶 your_seat 與自己的手牌）。
+    fn resync(room: &Room, seat: usize) -> Vec<(&'static str, Value)> {
+        let RoomState::Playing(gs) = &room.state else { return Vec::new() };
+        vec![("state", service::seat_payload(gs, &service::state_payload(gs), seat))]
+    }
 }
 
 pub type FarmHub = RoomHub<FarmRoom>;
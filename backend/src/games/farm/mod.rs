@@ -1,9 +1,11 @@
-//! 農場經營（家庭版 worker-placement）。N 人、完全資訊、worker-placement。
-//! 機制原創、零桌遊素材。`engine` 純邏輯（可單測）；`hub` + `service` 為 WS 串接（同阿瓦隆模式）。
+//! 農場經營（worker-placement；家庭版 + 卡牌版）。N 人、除手牌外完全資訊。
+//! 機制原創、零桌遊素材。`engine` + `cards` + `yard` 純邏輯 / 資料（可單測）；`hub` + `service` 為 WS 串接（同阿瓦隆模式）。
 
 /// 遊戲代號（registry key / WS 信封 `game` 欄）的單一來源。
 pub const NAME: &str = "farm";
 
+pub mod cards;
 pub mod engine;
 pub mod hub;
 pub mod service;
+pub mod yard;
@@ -1,17 +1,22 @@
-//! 農場經營 WS 串接：動作 / 完整狀態廣播 / 斷線。
+//! 農場經營 WS 串接：選秀 / 動作 / 完整狀態廣播 / 卡表 / 斷線。
 //!
-//! 信封 `{ game:"farm", type, data }`。**完全資訊**：每次動作後廣播全盤狀態給房內所有人（無私有推送）。
+//! 信封 `{ game:"farm", type, data }`。**除手牌外完全資訊**：每次動作後廣播全盤狀態給房內所有人，
+//! 逐人注入自己的手牌與選秀牌包（別人只看得到張數）。
 //! 大廳 / 房間共通指令（join_lobby / list_rooms / create_room / join_room / leave_room）
-//! 走 `common::room` 泛型框架。
+//! 走 `common::room` 泛型框架。房主一人開局即單人模式。完局時登入會員的分數明細寫進 `farm_scores`。
 
 use std::net::SocketAddr;
 
 use serde_json::{json, Value};
 
+use super::cards::{self, CardDef, Prereq, Trigger};
 use super::engine::{self, Action, Farm, GameState, Input, Phase};
+use super::yard;
 use super::hub::{FarmHub, FarmRoom, Room, RoomState};
 use crate::games::common::room;
+use crate::services::farm_scores;
 use crate::state::AppState;
+use crate::structs::farm_scores::NewFarmScore;
 
 pub async fn handle(hub: &FarmHub, state: &AppState, who: SocketAddr, value: &Value) -> bool {
     let data = value.get("data");
@@ -22,6 +27,8 @@ pub async fn handle(hub: &FarmHub, state: &AppState, who: SocketAddr, value: &Va
     match typ {
         "start_game" => start_game(hub, state, who).await,
         "action" => action(hub, state, who, data).await,
+        "draft_pick" => draft_pick(hub, state, who, data).await,
+        "card_list" => card_list(state, who),
         _ => return false,
     }
     true
@@ -50,8 +57,14 @@ async fn start_game(hub: &FarmHub, state: &AppState, who: SocketAddr) {
             Err(e) => { err1(state, who, e); return; }
         };
         let room = h.rooms.get_mut(&room_id).unwrap();
-        let gs = engine::initial_state(room.players.len());
+        let n = room.players.len();
+        let gs = if room.options.cards {
+            engine::initial_card_state(n, cards::deal_packs(n))
+        } else {
+            engine::initial_state(n)
+        };
         room.state = RoomState::Playing(gs);
+        room::issue_reconnect_tokens(room, &mut outbox);
         let room = h.rooms.get(&room_id).unwrap();
         broadcast_state(room, &mut outbox);
         room::push_lobby_update(&h, &mut outbox);
@@ -73,6 +86,7 @@ async fn action(hub: &FarmHub, state: &AppState, who: SocketAddr, data: Option<&
     let input = parse_input(data.and_then(|d| d.get("input")));
 
     let mut outbox = Vec::new();
+    let mut finished = None;
     {
         let mut h = hub.lock().await;
         let Some((room_id, seat)) = room::playing_seat(&h, who) else { err1(state, who, "not_in_game"); return; };
@@ -88,42 +102,142 @@ async fn action(hub: &FarmHub, state: &AppState, who: SocketAddr, data: Option<&
         let room = h.rooms.get(&room_id).unwrap();
         broadcast_state(room, &mut outbox);
         if over {
-            // game_over 帶最終分數，並解散房
+            // game_over 帶最終分數與逐項明細（單人另帶評星），並解散房
             if let RoomState::Playing(gs) = &room.state {
-                let scores = engine::final_scores(gs);
-                let m = msg("game_over", json!({ "scores": scores }));
+                let m = msg("game_over", game_over_payload(gs));
                 for &p in &room.players { outbox.push((p, m.clone())); }
+                finished = Some((room.players.clone(), gs.clone()));
             }
             room::dissolve_room(&mut h, room_id, &mut outbox);
         }
     }
     room::flush(state, outbox);
+    if let Some((players, gs)) = finished {
+        save_scores(state, &players, &gs).await;
+    }
+}
+
+fn game_over_payload(gs: &GameState) -> Value {
+    let breakdowns: Vec<_> = gs.players.iter().map(engine::breakdown).collect();
+    let mut v = json!({
+        "scores": engine::final_scores(gs),
+        "breakdowns": breakdowns,
+        "solo": gs.solo,
+    });
+    if gs.solo {
+        let score = engine::score(&gs.players[0]);
+        v["stars"] = json!(engine::solo_stars(score));
+        v["targets"] = json!(engine::SOLO_TARGETS);
+    }
+    v
+}
+
+/// 完局分數落地：只寫綁了會員的座位（鎖已放，可以 await 連線表）。
+async fn save_scores(state: &AppState, players: &[SocketAddr], gs: &GameState) {
+    let mut rows = Vec::new();
+    for (seat, &p) in players.iter().enumerate() {
+        let Some(member_id) = state.member_of(p).await else { continue };
+        rows.push(NewFarmScore {
+            member_id,
+            breakdown: engine::breakdown(&gs.players[seat]),
+            solo: gs.solo,
+            players: gs.n as i32,
+            cards: gs.cards,
+        });
+    }
+    farm_scores::spawn_save(state.get_pool().clone(), rows);
+}
+
+/// 選秀挑牌。全員挑完才傳包，故每次挑完都廣播（讓大家看到誰已挑）。
+async fn draft_pick(hub: &FarmHub, state: &AppState, who: SocketAddr, data: Option<&Value>) {
+    let Some(card) = data.and_then(|d| d.get("card")).and_then(|v| v.as_str()) else {
+        err1(state, who, "bad_card");
+        return;
+    };
+    let mut outbox = Vec::new();
+    {
+        let mut h = hub.lock().await;
+        let Some((room_id, seat)) = room::playing_seat(&h, who) else { err1(state, who, "not_in_game"); return; };
+        let room = h.rooms.get_mut(&room_id).unwrap();
+        let RoomState::Playing(gs) = &mut room.state else { return; };
+        if let Err(e) = engine::draft_pick(gs, seat, card) {
+            err1(state, who, e);
+            return;
+        }
+        broadcast_state(room, &mut outbox);
+    }
+    room::flush(state, outbox);
+}
+
+/// 全卡表（前端顯示卡面用；不在房內也可查）。
+fn card_list(state: &AppState, who: SocketAddr) {
+    let list: Vec<Value> = cards::CARDS.iter().map(card_json).collect();
+    room::flush(state, vec![(who, msg("card_list", json!({ "cards": list })))]);
 }
 
 // ---- 狀態廣播 ----
 
 fn parse_input(v: Option<&Value>) -> Input {
     let g = |k: &str| v.and_then(|d| d.get(k)).and_then(|x| x.as_u64()).unwrap_or(0) as u8;
+    // 格 / 邊編號陣列 → bitmask（有超界編號 → 全部作廢，由引擎回 bad_*）
+    let ids = |k: &str, limit: usize| -> u64 {
+        let list: Vec<u64> = v
+            .and_then(|d| d.get(k))
+            .and_then(|x| x.as_array())
+            .map(|a| a.iter().filter_map(Value::as_u64).collect())
+            .unwrap_or_default();
+        yard::mask(&list, limit).unwrap_or(u64::MAX)
+    };
     Input {
         grain_fields: g("grain_fields"),
         veg_fields: g("veg_fields"),
         rooms: g("rooms"),
-        stables: g("stables"),
-        pasture_tiles: g("pasture_tiles"),
-        pasture_stable: v.and_then(|d| d.get("pasture_stable")).and_then(|x| x.as_bool()).unwrap_or(false),
+        stables: ids("stables", yard::TILES) as u16,
+        fences: ids("fences", yard::EDGES),
+        // 換成卡表裡的 &'static id（查無此卡 → None，由引擎回 no_card）
+        card: v.and_then(|d| d.get("card")).and_then(|x| x.as_str()).and_then(cards::card).map(|c| c.id),
     }
 }
 
+fn card_json(c: &CardDef) -> Value {
+    let prereq = match c.prereq {
+        Prereq::None => Value::Null,
+        Prereq::Occupations(n) => json!({ "type": "occupations", "count": n }),
+        Prereq::Fields(n) => json!({ "type": "fields", "count": n }),
+        Prereq::Rooms(n) => json!({ "type": "rooms", "count": n }),
+        Prereq::Pastures(n) => json!({ "type": "pastures", "count": n }),
+        Prereq::ClayHouse => json!({ "type": "clay_house" }),
+    };
+    let effects: Vec<Value> = c.effects.iter().map(|e| {
+        let (trigger, action) = match e.trigger {
+            Trigger::Harvest => ("harvest", None),
+            Trigger::Action(a) => ("action", Some(a.as_str())),
+            Trigger::Build => ("build", None),
+        };
+        json!({ "trigger": trigger, "action": action, "gain": e.gain })
+    }).collect();
+    json!({
+        "id": c.id,
+        "name": c.name,
+        "kind": c.kind,
+        "cost": c.cost,
+        "prereq": prereq,
+        "effects": effects,
+        "points": c.points,
+        "cooking": c.cooking,
+    })
+}
+
 fn farm_json(f: &Farm) -> Value {
     let fields: Vec<Value> = f.fields.iter().map(|fl| match fl.crop {
         Some((c, n)) => json!({ "crop": c.as_str(), "count": n }),
         None => Value::Null,
     }).collect();
-    let pastures: Vec<Value> = f.pastures.iter().map(|p| {
+    let pastures: Vec<Value> = f.pastures().iter().map(|p| {
         json!({
-            "tiles": p.tiles,
-            "stable": p.stable,
-            "animal": p.animal.map(|(a, n)| json!({ "kind": a.as_str(), "count": n })),
+            "tiles": yard::bits(p.tiles.into()),
+            "stables": p.stables,
+            "capacity": p.capacity(),
         })
     }).collect();
     json!({
@@ -132,23 +246,38 @@ fn farm_json(f: &Farm) -> Value {
         "family": f.family,
         "fields": fields,
         "pastures": pastures,
-        "loose_stables": f.loose_stables,
+        "fences": yard::bits(f.fences),
+        "stables": yard::bits(f.stables.into()),
+        "loose_stables": f.loose_stables(),
         "free_tiles": f.free_tiles(),
         "wood": f.wood, "clay": f.clay, "reed": f.reed, "stone": f.stone,
         "grain": f.grain, "veg": f.veg,
         "sheep": f.sheep, "boar": f.boar, "cattle": f.cattle,
         "food": f.food, "begging": f.begging,
+        "played": f.played,
+        "hand_count": f.hand.len(),
+        "cooking": cards::cooking_rates(f),
     })
 }
 
-fn state_payload(gs: &GameState) -> Value {
+pub(super) fn state_payload(gs: &GameState) -> Value {
     let players: Vec<Value> = gs.players.iter().map(farm_json).collect();
     let actions: Vec<&str> = engine::available_actions(gs).iter().map(|a| a.as_str()).collect();
     let accum: Vec<Value> = engine::accumulation(gs).iter()
         .map(|(a, n)| json!({ "action": a.as_str(), "amount": n })).collect();
+    let phase = match gs.phase {
+        Phase::Drafting => "drafting",
+        Phase::Placing => "placing",
+        Phase::GameOver => "game_over",
+    };
+    let picked: Vec<bool> = gs.picks.iter().map(Option::is_some).collect();
     json!({
         "round": gs.round,
-        "phase": if gs.phase == Phase::GameOver { "game_over" } else { "placing" },
+        "phase": phase,
+        "cards": gs.cards,
+        "solo": gs.solo,
+        "majors": gs.majors,
+        "picked": picked,
         "current_player": engine::current_player(gs),
         "starting_player": gs.starting_player,
         "players": players,
@@ -157,14 +286,20 @@ fn state_payload(gs: &GameState) -> Value {
     })
 }
 
+/// 逐人版本：公開盤面 + your_seat + 自己的手牌 / 選秀牌包。
+pub(super) fn seat_payload(gs: &GameState, base: &Value, seat: usize) -> Value {
+    let mut v = base.clone();
+    v["your_seat"] = json!(seat);
+    v["hand"] = json!(gs.players[seat].hand);
+    v["draft_pack"] = json!(gs.packs.get(seat));
+    v
+}
+
 fn broadcast_state(room: &Room, outbox: &mut Vec<(SocketAddr, String)>) {
     if let RoomState::Playing(gs) = &room.state {
         let base = state_payload(gs);
-        // 逐人注入 your_seat
         for (seat, &p) in room.players.iter().enumerate() {
-            let mut v = base.clone();
-            v["your_seat"] = json!(seat);
-            outbox.push((p, msg("state", v)));
+            outbox.push((p, msg("state", seat_payload(gs, &base, seat))));
         }
     }
 }
@@ -0,0 +1,128 @@
+//! 農場 3×5 格的圍籬幾何：格 / 邊編號、圍出的牧場、圍法驗證。純函式。
+//!
+//! - **格**：`row * COLS + col`（0..15），左上為 0。集合以 `u16` bitmask 表示。
+//! - **邊**：先橫後直。橫邊 `h_edge(r, c)` = 格 (r, c) 的上緣（r = ROWS 為最下緣），共 (ROWS+1)×COLS = 20；
+//!   直邊 `v_edge(r, c)` = 格 (r, c) 的左緣（c = COLS 為最右緣），共 ROWS×(COLS+1) = 18。集合以 `u64` bitmask 表示。
+//!
+//! 不經柵欄相連的格構成一個區域；區域對外（農場邊界）全圍上才算牧場。
+//! 合法圍法：每段柵欄兩側必屬不同區域（不能有伸進牧場內的半截隔間），且至少一側是牧場（不能有沒圍起來的散柵）。
+
+pub const ROWS: usize = 3;
+pub const COLS: usize = 5;
+pub const TILES: usize = ROWS * COLS;
+const H_EDGES: usize = (ROWS + 1) * COLS;
+pub const EDGES: usize = H_EDGES + ROWS * (COLS + 1);
+/// 每家柵欄上限（段）。
+pub const MAX_FENCES: u32 = 15;
+
+pub const fn tile(r: usize, c: usize) -> usize {
+    r * COLS + c
+}
+
+pub const fn h_edge(r: usize, c: usize) -> usize {
+    r * COLS + c
+}
+
+pub const fn v_edge(r: usize, c: usize) -> usize {
+    H_EDGES + r * (COLS + 1) + c
+}
+
+/// 邊兩側的格（None = 農場外）。
+fn sides(e: usize) -> [Option<usize>; 2] {
+    if e < H_EDGES {
+        let (r, c) = (e / COLS, e % COLS);
+        [(r > 0).then(|| tile(r - 1, c)), (r < ROWS).then(|| tile(r, c))]
+    } else {
+        let k = e - H_EDGES;
+        let (r, c) = (k / (COLS + 1), k % (COLS + 1));
+        [(c > 0).then(|| tile(r, c - 1)), (c < COLS).then(|| tile(r, c))]
+    }
+}
+
+/// 格的四條邊。
+fn edges_of(t: usize) -> [usize; 4] {
+    let (r, c) = (t / COLS, t % COLS);
+    [h_edge(r, c), h_edge(r + 1, c), v_edge(r, c), v_edge(r, c + 1)]
+}
+
+/// 把格集合整圈圍起來所需的邊（集合內外交界 + 貼農場邊界者）。測試組圍法用。
+#[cfg(test)]
+pub fn perimeter(tiles: u16) -> u64 {
+    let mut out = 0u64;
+    for t in (0..TILES).filter(|&t| tiles >> t & 1 == 1) {
+        for e in edges_of(t) {
+            let inside = |s: Option<usize>| s.is_some_and(|x| tiles >> x & 1 == 1);
+            let [a, b] = sides(e);
+            if inside(a) != inside(b) {
+                out |= 1 << e;
+            }
+        }
+    }
+    out
+}
+
+/// 依柵欄切出區域：回傳每格所屬區域編號與各區域是否圍合。
+fn regions(fences: u64) -> ([usize; TILES], Vec<bool>) {
+    const NONE: usize = usize::MAX;
+    let mut id = [NONE; TILES];
+    let mut closed = Vec::new();
+    for start in 0..TILES {
+        if id[start] != NONE {
+            continue;
+        }
+        let rid = closed.len();
+        let mut enclosed = true;
+        let mut stack = vec![start];
+        id[start] = rid;
+        while let Some(t) = stack.pop() {
+            for e in edges_of(t) {
+                if fences >> e & 1 == 1 {
+                    continue;
+                }
+                let [a, b] = sides(e);
+                match if a == Some(t) { b } else { a } {
+                    None => enclosed = false, // 無柵欄通到農場外
+                    Some(n) if id[n] == NONE => {
+                        id[n] = rid;
+                        stack.push(n);
+                    }
+                    Some(_) => {}
+                }
+            }
+        }
+        closed.push(enclosed);
+    }
+    (id, closed)
+}
+
+/// 圍出的牧場（各自的格 bitmask，依最小格號排序）；圍法不合法回錯誤。
+pub fn pastures(fences: u64) -> Result<Vec<u16>, &'static str> {
+    if fences >> EDGES != 0 {
+        return Err("bad_edge");
+    }
+    let (id, closed) = regions(fences);
+    for e in (0..EDGES).filter(|&e| fences >> e & 1 == 1) {
+        let [a, b] = sides(e).map(|s| s.map(|t| id[t]));
+        if a == b {
+            return Err("bad_subdivision");
+        }
+        if ![a, b].iter().any(|r| r.is_some_and(|r| closed[r])) {
+            return Err("not_closed");
+        }
+    }
+    let mut out = vec![0u16; closed.len()];
+    for (t, &r) in id.iter().enumerate() {
+        out[r] |= 1 << t;
+    }
+    Ok(out.into_iter().zip(closed).filter(|&(_, c)| c).map(|(m, _)| m).collect())
+}
+
+/// bitmask → 編號列表（遞增）。序列化給前端用。
+pub fn bits(mask: u64) -> Vec<u8> {
+    (0..64).filter(|&i| mask >> i & 1 == 1).collect()
+}
+
+/// 編號列表 → bitmask；有超出 `limit` 的編號回 None。
+pub fn mask(ids: &[u64], limit: usize) -> Option<u64> {
+    ids.iter().try_fold(0u64, |m, &i| ((i as usize) < limit).then(|| m | 1 << i))
+}
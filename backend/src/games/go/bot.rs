@@ -0,0 +1,306 @@
+//! 圍棋電腦：根節點 UCB1 + 隨機對局（flat Monte Carlo）。
+//!
+//! 每個候選點跑隨機下到底的對局，依數子勝率選點。隨機對局不填自己的眼、
+//! 無處可下就虛手，所以終局盤面的數子是準的。強度隨盤面變小而上升（小盤才下得像樣）。
+
+use std::time::{Duration, Instant};
+
+use rand::seq::SliceRandom;
+use rand::Rng;
+
+use super::engine::{self, GoState, Phase, Stone};
+use crate::games::common::bot::BotLevel;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GoMove {
+    Play(i8, i8),
+    Pass,
+}
+
+/// 點死子階段的回應。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MarkingReply {
+    /// 對方的提案與自己估的一致。
+    Accept,
+    /// 沒有提案、或提案不對：提出自己估的死子。
+    Propose(Vec<(i8, i8)>),
+}
+
+const ORTHO: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+const DIAG: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+fn cell(s: &GoState, c: i8, r: i8) -> Option<Option<Stone>> {
+    ((0..s.size).contains(&c) && (0..s.size).contains(&r)).then(|| s.board[r as usize][c as usize])
+}
+
+/// `color` 的真眼（四鄰皆己子；斜角敵子不超過容許數，邊角不容許）。
+fn is_own_eye(s: &GoState, c: i8, r: i8, color: Stone) -> bool {
+    if ORTHO.iter().any(|(dc, dr)| matches!(cell(s, c + dc, r + dr), Some(x) if x != Some(color))) {
+        return false;
+    }
+    let mut off_board = false;
+    let mut enemy = 0;
+    for (dc, dr) in DIAG {
+        match cell(s, c + dc, r + dr) {
+            None => off_board = true,
+            Some(Some(x)) if x != color => enemy += 1,
+            _ => {}
+        }
+    }
+    enemy < if off_board { 1 } else { 2 }
+}
+
+/// 快速合法判定：有空鄰點的空點必不自殺、也不會是劫（劫點四鄰皆子），其餘交給引擎。
+fn quick_legal(s: &GoState, c: i8, r: i8) -> bool {
+    if s.board[r as usize][c as usize].is_some() {
+        return false;
+    }
+    if ORTHO.iter().any(|(dc, dr)| cell(s, c + dc, r + dr) == Some(None)) {
+        return true;
+    }
+    engine::is_legal(s, c, r).is_ok()
+}
+
+fn empties(s: &GoState) -> Vec<(i8, i8)> {
+    let mut out = Vec::new();
+    for r in 0..s.size {
+        for c in 0..s.size {
+            if s.board[r as usize][c as usize].is_none() {
+                out.push((c, r));
+            }
+        }
+    }
+    out
+}
+
+/// 從 `s` 隨機下到雙虛手，回傳終局盤面。
+fn playout(mut s: GoState, rng: &mut impl Rng) -> GoState {
+    let mut free = empties(&s);
+    let max_moves = (s.size as usize * s.size as usize) * 3;
+    let mut moves = 0;
+    while s.passes < 2 && moves < max_moves {
+        let mut pool = free.len();
+        let mut played = None;
+        while pool > 0 {
+            let i = rng.random_range(0..pool);
+            let (c, r) = free[i];
+            if quick_legal(&s, c, r) && !is_own_eye(&s, c, r, s.turn) {
+                played = Some(i);
+                break;
+            }
+            // 此手不可下：換到池尾，不再抽到
+            pool -= 1;
+            free.swap(i, pool);
+        }
+        match played {
+            Some(i) => {
+                let (c, r) = free.swap_remove(i);
+                if !engine::apply(&mut s, c, r).is_empty() {
+                    free = empties(&s);
+                }
+            }
+            None => engine::pass(&mut s),
+        }
+        moves += 1;
+    }
+    s
+}
+
+/// 為行棋方選一步。沒有值得下的點（只剩自己的眼）就虛手；
+/// 對手剛虛手且此刻數子已贏，也跟著虛手收局。
+pub fn choose(state: &GoState, level: BotLevel) -> Option<GoMove> {
+    let me = state.turn;
+    if state.passes == 1 && engine::winner_on_board(state) == me {
+        return Some(GoMove::Pass);
+    }
+    let mut candidates: Vec<(i8, i8)> = empties(state)
+        .into_iter()
+        .filter(|&(c, r)| engine::is_legal(state, c, r).is_ok() && !is_own_eye(state, c, r, me))
+        .collect();
+    if candidates.is_empty() {
+        return Some(GoMove::Pass);
+    }
+    let (budget, max_playouts) = match level {
+        BotLevel::Easy => (Duration::from_millis(300), 2 * candidates.len()),
+        BotLevel::Normal => (Duration::from_secs(1), 50 * candidates.len()),
+        BotLevel::Hard => (Duration::from_millis(2500), usize::MAX),
+    };
+    let deadline = Instant::now() + budget;
+    let mut rng = rand::rng();
+    // 大盤第一輪可能就跑不完：打亂順序，時間到時至少是隨機一批點裡挑最好的
+    candidates.shuffle(&mut rng);
+    let mut wins = vec![0u32; candidates.len()];
+    let mut visits = vec![0u32; candidates.len()];
+    let mut total = 0usize;
+    while total < max_playouts && (total == 0 || Instant::now() < deadline) {
+        let i = if total < candidates.len() {
+            total
+        } else {
+            let ln = (total as f64).ln();
+            (0..candidates.len())
+                .max_by(|&a, &b| ucb(wins[a], visits[a], ln).total_cmp(&ucb(wins[b], visits[b], ln)))
+                .unwrap_or(0)
+        };
+        let (c, r) = candidates[i];
+        let mut s = state.clone();
+        engine::apply(&mut s, c, r);
+        if engine::winner_on_board(&playout(s, &mut rng)) == me {
+            wins[i] += 1;
+        }
+        visits[i] += 1;
+        total += 1;
+    }
+    // 訪問數相同（第一輪未跑完）時比勝場
+    let best = (0..candidates.len()).max_by_key(|&i| (visits[i], wins[i])).unwrap_or(0);
+    let (c, r) = candidates[best];
+    Some(GoMove::Play(c, r))
+}
+
+/// 點死子：從目前盤面跑隨機對局，過半數局裡那一點歸對方（被提或落在對方地裡）的子判為死子。
+pub fn dead_stones(state: &GoState, level: BotLevel) -> Vec<(i8, i8)> {
+    let playouts = match level {
+        BotLevel::Easy => 40,
+        BotLevel::Normal => 150,
+        BotLevel::Hard => 400,
+    };
+    let mut start = state.clone();
+    start.phase = Phase::Play;
+    start.passes = 0;
+    start.ko = None;
+    let mut rng = rand::rng();
+    let mut lost = [[0u32; engine::MAX_SIZE as usize]; engine::MAX_SIZE as usize];
+    for _ in 0..playouts {
+        let end = playout(start.clone(), &mut rng);
+        for r in 0..state.size {
+            for c in 0..state.size {
+                let Some(color) = state.board[r as usize][c as usize] else { continue };
+                // 隨機對局只留單點眼：空點歸四鄰的顏色
+                let owner = end.board[r as usize][c as usize].or_else(|| {
+                    ORTHO.iter().find_map(|(dc, dr)| cell(&end, c + dc, r + dr).flatten())
+                });
+                if owner == Some(color.opponent()) {
+                    lost[r as usize][c as usize] += 1;
+                }
+            }
+        }
+    }
+    let mut dead = Vec::new();
+    for r in 0..state.size {
+        for c in 0..state.size {
+            if lost[r as usize][c as usize] * 2 > playouts {
+                dead.push((c, r));
+            }
+        }
+    }
+    dead
+}
+
+/// 點死子階段：自己估一份死子（擴成整塊，與提案同一正規化），對方提案完全相同才同意，
+/// 否則反提自己那份 —— 不照單全收，免得對手把活棋點成死子白拿地。
+pub fn reply_marking(state: &GoState, level: BotLevel) -> MarkingReply {
+    let mine = dead_stones(state, level);
+    let Phase::Marking { dead: proposed, proposer: Some(_) } = &state.phase else {
+        return MarkingReply::Propose(mine);
+    };
+    let mut expanded = state.clone();
+    match engine::propose_dead(&mut expanded, &mine).map(|()| expanded.phase) {
+        Ok(Phase::Marking { dead, .. }) if dead == *proposed => MarkingReply::Accept,
+        _ => MarkingReply::Propose(mine),
+    }
+}
+
+fn ucb(wins: u32, visits: u32, ln_total: f64) -> f64 {
+    let n = visits.max(1) as f64;
+    wins as f64 / n + (2.0 * ln_total / n).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn put(s: &mut GoState, stones: &[(i8, i8)], color: Stone) {
+        for &(c, r) in stones {
+            s.board[r as usize][c as usize] = Some(color);
+        }
+    }
+
+    #[test]
+    fn own_eye_detection() {
+        let mut s = engine::initial_state();
+        // 角上黑眼 (0,0)
+        put(&mut s, &[(1, 0), (0, 1), (1, 1)], Stone::Black);
+        assert!(is_own_eye(&s, 0, 0, Stone::Black));
+        assert!(!is_own_eye(&s, 0, 0, Stone::White));
+        // 斜角有白子 → 邊角假眼
+        s.board[1][1] = Some(Stone::White);
+        assert!(!is_own_eye(&s, 0, 0, Stone::Black));
+    }
+
+    #[test]
+    fn passes_to_close_a_won_game() {
+        let mut s = engine::initial_state();
+        // 黑佔左半盤，白無子：白虛手後黑數子已贏 → 黑也虛手
+        for r in 0..s.size {
+            put(&mut s, &[(10, r)], Stone::Black);
+        }
+        s.passes = 1;
+        assert_eq!(choose(&s, BotLevel::Easy), Some(GoMove::Pass));
+    }
+
+    #[test]
+    fn passes_when_only_own_eyes_remain() {
+        let mut s = engine::initial_state();
+        for r in 0..s.size {
+            for c in 0..s.size {
+                s.board[r as usize][c as usize] = Some(Stone::Black);
+            }
+        }
+        s.board[0][0] = None;
+        s.board[5][5] = None;
+        assert_eq!(choose(&s, BotLevel::Normal), Some(GoMove::Pass));
+    }
+
+    #[test]
+    fn plays_a_legal_point() {
+        let mut s = engine::initial_state();
+        put(&mut s, &[(2, 3), (3, 2), (3, 4)], Stone::Black);
+        put(&mut s, &[(3, 3)], Stone::White);
+        let Some(GoMove::Play(c, r)) = choose(&s, BotLevel::Easy) else {
+            panic!("開放盤面不該虛手");
+        };
+        assert!(engine::is_legal(&s, c, r).is_ok());
+    }
+
+    #[test]
+    fn marks_lone_stone_in_enemy_area_dead() {
+        let mut s = engine::new_game(9, 0, engine::Scoring::Area);
+        // 黑築牆 col 4、白築牆 col 5；白孤子 (1,4) 深入黑地
+        for r in 0..9 {
+            put(&mut s, &[(4, r)], Stone::Black);
+            put(&mut s, &[(5, r)], Stone::White);
+        }
+        put(&mut s, &[(1, 4)], Stone::White);
+        assert_eq!(dead_stones(&s, BotLevel::Hard), vec![(1, 4)]);
+    }
+
+    #[test]
+    fn rejects_a_false_dead_stone_proposal() {
+        let mut s = engine::new_game(9, 0, engine::Scoring::Area);
+        for r in 0..9 {
+            put(&mut s, &[(4, r)], Stone::Black);
+            put(&mut s, &[(5, r)], Stone::White);
+        }
+        put(&mut s, &[(1, 4)], Stone::White);
+        engine::pass(&mut s);
+        engine::pass(&mut s);
+        let mut proposed = s.clone();
+        // 黑把白的整道牆點成死子 → 白（電腦）不同意，反提自己的孤子
+        engine::propose_dead(&mut proposed, &[(5, 0)]).unwrap();
+        assert_eq!(proposed.turn, Stone::White);
+        assert_eq!(reply_marking(&proposed, BotLevel::Hard), MarkingReply::Propose(vec![(1, 4)]));
+        // 如實提出白孤子 → 同意
+        let mut honest = s.clone();
+        engine::propose_dead(&mut honest, &[(1, 4)]).unwrap();
+        assert_eq!(reply_marking(&honest, BotLevel::Hard), MarkingReply::Accept);
+    }
+}
@@ -1,8 +1,16 @@
 //! 圍棋規則引擎 — 純函式，零 WS 依賴。提子（無氣移除）/ 禁自殺 / 簡單劫 / 虛手 /
-//! 雙虛手終局 + 數子（area scoring）+ 貼目。19 路，komi 7.5。
+//! 9・13・19 路 + 讓子 + 雙虛手後點死子（雙方同意才終局，可要求續下）+ 數子（area）或數目（territory）+ 貼目。
 
-pub const SIZE: i8 = 19;
-const KOMI_X2: i32 = 15; // 7.5 * 2（以倍數保持整數，杜絕和局）
+/// 盤面陣列一律 19×19，小盤只用左上 `size × size`。
+pub const MAX_SIZE: i8 = 19;
+/// 可選的盤面大小。
+pub const SIZES: [i8; 3] = [9, 13, 19];
+/// 讓子上限（星位數）。
+pub const MAX_HANDICAP: u8 = 9;
+/// 分先貼目 ×2（以倍數保持整數，恆為奇數 → 無和局）：數子 7.5、數目 6.5；讓子局一律 0.5。
+pub const KOMI_AREA_X2: i32 = 15;
+pub const KOMI_TERRITORY_X2: i32 = 13;
+pub const KOMI_HANDICAP_X2: i32 = 1;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Stone {
@@ -17,18 +25,70 @@ impl Stone {
             Stone::White => Stone::Black,
         }
     }
+
+    fn index(self) -> usize {
+        match self {
+            Stone::Black => 0,
+            Stone::White => 1,
+        }
+    }
+}
+
+pub type Board = [[Option<Stone>; MAX_SIZE as usize]; MAX_SIZE as usize];
+
+/// 計分法。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Scoring {
+    /// 數子（中國規則）：盤上活子 + 地。
+    Area,
+    /// 數目（日本規則）：地 + 提子 + 死子。
+    Territory,
+}
+
+impl Scoring {
+    pub fn parse(s: &str) -> Option<Scoring> {
+        match s {
+            "area" => Some(Scoring::Area),
+            "territory" => Some(Scoring::Territory),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Scoring::Area => "area",
+            Scoring::Territory => "territory",
+        }
+    }
 }
 
-pub type Board = [[Option<Stone>; SIZE as usize]; SIZE as usize];
+/// 對局階段。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Phase {
+    Play,
+    /// 雙虛手後點死子：`dead` 為 `proposer` 提出、待對方同意的死子（整塊）；`proposer` None = 還沒人提出。
+    Marking { dead: Vec<(i8, i8)>, proposer: Option<Stone> },
+    /// 雙方同意死子，依此計分終局。
+    Settled { dead: Vec<(i8, i8)> },
+}
 
 #[derive(Debug, Clone)]
 pub struct GoState {
+    pub size: i8,
     pub board: Board,
     pub turn: Stone,
     /// 劫爭禁著點（對手下一手不可下此點）。
     pub ko: Option<(i8, i8)>,
-    /// 連續虛手數，達 2 終局。
+    /// 連續虛手數，達 2 進入點死子。
     pub passes: u8,
+    /// 白方貼目 ×2。
+    pub komi_x2: i32,
+    pub scoring: Scoring,
+    /// 讓子數（0 = 分先），記譜用。
+    pub handicap: u8,
+    /// 提子數，依提子方索引（`[黑提的白子, 白提的黑子]`），數目計分用。
+    pub prisoners: [i32; 2],
+    pub phase: Phase,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -37,6 +97,12 @@ pub enum Reason {
     Occupied,
     Ko,
     Suicide,
+    /// 點死子階段不能落子 / 虛手；不在點死子階段不能提死子。
+    WrongPhase,
+    /// 死子清單裡有空點或越界。
+    NotAStone,
+    /// 沒有對方的提案可同意。
+    NoProposal,
 }
 
 impl Reason {
@@ -46,38 +112,84 @@ impl Reason {
             Reason::Occupied => "occupied",
             Reason::Ko => "ko",
             Reason::Suicide => "suicide",
+            Reason::WrongPhase => "wrong_phase",
+            Reason::NotAStone => "not_a_stone",
+            Reason::NoProposal => "no_proposal",
         }
     }
 }
 
+/// 19 路分先、數子計分（測試用的標準盤）。
+#[cfg(test)]
 pub fn initial_state() -> GoState {
-    GoState {
-        board: [[None; SIZE as usize]; SIZE as usize],
+    new_game(MAX_SIZE, 0, Scoring::Area)
+}
+
+/// 開局。`handicap` ≥ 2 時黑子先擺在星位、白先行、貼 0.5 目（1 視同 0：讓先不擺子）。
+/// 呼叫端保證 `size` ∈ `SIZES`、`handicap` ≤ `MAX_HANDICAP`。
+pub fn new_game(size: i8, handicap: u8, scoring: Scoring) -> GoState {
+    let mut state = GoState {
+        size,
+        board: [[None; MAX_SIZE as usize]; MAX_SIZE as usize],
         turn: Stone::Black, // 黑先
         ko: None,
         passes: 0,
+        komi_x2: match scoring {
+            Scoring::Area => KOMI_AREA_X2,
+            Scoring::Territory => KOMI_TERRITORY_X2,
+        },
+        scoring,
+        handicap: 0,
+        prisoners: [0, 0],
+        phase: Phase::Play,
+    };
+    if handicap >= 2 {
+        for (c, r) in handicap_points(size, handicap) {
+            state.board[r as usize][c as usize] = Some(Stone::Black);
+        }
+        state.handicap = handicap;
+        state.turn = Stone::White;
+        state.komi_x2 = KOMI_HANDICAP_X2;
     }
+    state
 }
 
-fn in_bounds(c: i8, r: i8) -> bool {
-    (0..SIZE).contains(&c) && (0..SIZE).contains(&r)
+/// 讓子星位（傳統擺法：先對角、再四角、單數加天元、6 子起加兩邊）。
+pub fn handicap_points(size: i8, handicap: u8) -> Vec<(i8, i8)> {
+    let edge = if size >= 13 { 3 } else { 2 };
+    let (lo, mid, hi) = (edge, size / 2, size - 1 - edge);
+    let corners = [(hi, lo), (lo, hi), (hi, hi), (lo, lo)];
+    let sides = [(lo, mid), (hi, mid), (mid, lo), (mid, hi)];
+    let n = handicap as usize;
+    let mut out: Vec<(i8, i8)> = corners.into_iter().take(n.min(4)).collect();
+    if n >= 6 {
+        out.extend(sides.into_iter().take(if n >= 8 { 4 } else { 2 }));
+    }
+    if n >= 5 && n % 2 == 1 {
+        out.push((mid, mid));
+    }
+    out
 }
 
-fn neighbors(c: i8, r: i8) -> Vec<(i8, i8)> {
+fn in_bounds(size: i8, c: i8, r: i8) -> bool {
+    (0..size).contains(&c) && (0..size).contains(&r)
+}
+
+fn neighbors(size: i8, c: i8, r: i8) -> Vec<(i8, i8)> {
     [(c + 1, r), (c - 1, r), (c, r + 1), (c, r - 1)]
         .into_iter()
-        .filter(|&(nc, nr)| in_bounds(nc, nr))
+        .filter(|&(nc, nr)| in_bounds(size, nc, nr))
         .collect()
 }
 
 /// 回傳 (c,r) 所屬同色連通塊的所有座標與其氣數。
-fn group_and_liberties(board: &Board, c: i8, r: i8) -> (Vec<(i8, i8)>, usize) {
+fn group_and_liberties(board: &Board, size: i8, c: i8, r: i8) -> (Vec<(i8, i8)>, usize) {
     let color = board[r as usize][c as usize];
     let mut stack = vec![(c, r)];
     let mut seen = vec![(c, r)];
     let mut libs = std::collections::HashSet::new();
     while let Some((cc, rr)) = stack.pop() {
-        for (nc, nr) in neighbors(cc, rr) {
+        for (nc, nr) in neighbors(size, cc, rr) {
             match board[nr as usize][nc as usize] {
                 None => {
                     libs.insert((nc, nr));
@@ -93,13 +205,43 @@ fn group_and_liberties(board: &Board, c: i8, r: i8) -> (Vec<(i8, i8)>, usize) {
     (seen, libs.len())
 }
 
+/// 盤上一塊同色連通塊。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    pub stone: Stone,
+    /// 依 (col, row) 排序。
+    pub points: Vec<(i8, i8)>,
+    pub liberties: usize,
+}
+
+/// 盤上所有連通塊，依各塊最小座標排序。
+pub fn groups(state: &GoState) -> Vec<Group> {
+    let mut seen = [[false; MAX_SIZE as usize]; MAX_SIZE as usize];
+    let mut out = Vec::new();
+    for c in 0..state.size {
+        for r in 0..state.size {
+            let Some(stone) = state.board[r as usize][c as usize] else { continue };
+            if seen[r as usize][c as usize] {
+                continue;
+            }
+            let (mut points, liberties) = group_and_liberties(&state.board, state.size, c, r);
+            for &(pc, pr) in &points {
+                seen[pr as usize][pc as usize] = true;
+            }
+            points.sort_unstable();
+            out.push(Group { stone, points, liberties });
+        }
+    }
+    out
+}
+
 /// 在 board 落子並提走無氣的對方連通塊，回傳被提座標。假設 (c,r) 原為空。
-fn place_and_capture(board: &mut Board, c: i8, r: i8, color: Stone) -> Vec<(i8, i8)> {
+fn place_and_capture(board: &mut Board, size: i8, c: i8, r: i8, color: Stone) -> Vec<(i8, i8)> {
     board[r as usize][c as usize] = Some(color);
     let mut captured = Vec::new();
-    for (nc, nr) in neighbors(c, r) {
+    for (nc, nr) in neighbors(size, c, r) {
         if board[nr as usize][nc as usize] == Some(color.opponent()) {
-            let (group, libs) = group_and_liberties(board, nc, nr);
+            let (group, libs) = group_and_liberties(board, size, nc, nr);
             if libs == 0 {
                 for (gc, gr) in group {
                     board[gr as usize][gc as usize] = None;
@@ -112,7 +254,10 @@ fn place_and_capture(board: &mut Board, c: i8, r: i8, color: Stone) -> Vec<(i8,
 }
 
 pub fn is_legal(state: &GoState, c: i8, r: i8) -> Result<(), Reason> {
-    if !in_bounds(c, r) {
+    if state.phase != Phase::Play {
+        return Err(Reason::WrongPhase);
+    }
+    if !in_bounds(state.size, c, r) {
         return Err(Reason::OutOfBounds);
     }
     if state.board[r as usize][c as usize].is_some() {
@@ -123,61 +268,113 @@ pub fn is_legal(state: &GoState, c: i8, r: i8) -> Result<(), Reason> {
     }
     // 模擬：落子提子後自group須有氣（否則自殺）
     let mut board = state.board;
-    place_and_capture(&mut board, c, r, state.turn);
-    let (_, libs) = group_and_liberties(&board, c, r);
+    place_and_capture(&mut board, state.size, c, r, state.turn);
+    let (_, libs) = group_and_liberties(&board, state.size, c, r);
     if libs == 0 {
         return Err(Reason::Suicide);
     }
     Ok(())
 }
 
-/// 落子（假設已合法）。回傳被提座標。更新劫點、清虛手數、換手。
+/// 落子（假設已合法）。回傳被提座標。更新劫點、提子數、清虛手數、換手。
 pub fn apply(state: &mut GoState, c: i8, r: i8) -> Vec<(i8, i8)> {
     let color = state.turn;
-    let captured = place_and_capture(&mut state.board, c, r, color);
+    let captured = place_and_capture(&mut state.board, state.size, c, r, color);
     // 簡單劫：恰提一子，且落子成單子且僅一氣 → 該提點設為劫禁
-    let (group, libs) = group_and_liberties(&state.board, c, r);
+    let (group, libs) = group_and_liberties(&state.board, state.size, c, r);
     state.ko = if captured.len() == 1 && group.len() == 1 && libs == 1 {
         Some(captured[0])
     } else {
         None
     };
+    state.prisoners[color.index()] += captured.len() as i32;
     state.passes = 0;
     state.turn = color.opponent();
     captured
 }
 
-/// 虛手。換手、累計虛手、清劫點。
+/// 虛手。換手、累計虛手、清劫點；第二次連續虛手進入點死子（輪到的一方先提）。
 pub fn pass(state: &mut GoState) {
     state.passes += 1;
     state.ko = None;
     state.turn = state.turn.opponent();
+    if state.passes >= 2 {
+        state.phase = Phase::Marking { dead: Vec::new(), proposer: None };
+    }
+}
+
+/// 點死子：行棋方提出死子清單（每點擴成整塊，去重排序），交給對方回應。
+pub fn propose_dead(state: &mut GoState, points: &[(i8, i8)]) -> Result<(), Reason> {
+    if !matches!(state.phase, Phase::Marking { .. }) {
+        return Err(Reason::WrongPhase);
+    }
+    let mut dead = Vec::new();
+    for &(c, r) in points {
+        if !in_bounds(state.size, c, r) || state.board[r as usize][c as usize].is_none() {
+            return Err(Reason::NotAStone);
+        }
+        if !dead.contains(&(c, r)) {
+            dead.extend(group_and_liberties(&state.board, state.size, c, r).0);
+        }
+    }
+    dead.sort_unstable();
+    dead.dedup();
+    state.phase = Phase::Marking { dead, proposer: Some(state.turn) };
+    state.turn = state.turn.opponent();
+    Ok(())
+}
+
+/// 同意對方的死子提案 → 終局。
+pub fn accept_dead(state: &mut GoState) -> Result<(), Reason> {
+    match &state.phase {
+        Phase::Marking { dead, proposer: Some(p) } if *p != state.turn => {
+            state.phase = Phase::Settled { dead: dead.clone() };
+            Ok(())
+        }
+        Phase::Marking { .. } => Err(Reason::NoProposal),
+        _ => Err(Reason::WrongPhase),
+    }
+}
+
+/// 不同意、要求續下：回到對局，由要求續下一方的對手先下（日本規則第 12 條）。
+pub fn resume(state: &mut GoState) -> Result<(), Reason> {
+    if !matches!(state.phase, Phase::Marking { .. }) {
+        return Err(Reason::WrongPhase);
+    }
+    state.phase = Phase::Play;
+    state.passes = 0;
+    state.turn = state.turn.opponent();
+    Ok(())
 }
 
-/// 數子（area scoring）：各方 = 己方棋子數 + 只被己方圍的空白地。回傳 (黑, 白)。
-pub fn score(board: &Board) -> (i32, i32) {
-    let mut black = 0;
-    let mut white = 0;
-    let mut visited = [[false; SIZE as usize]; SIZE as usize];
+/// 各方（盤上活子數, 地）。`dead` 的點視為空點、不算子；地 = 只被單一顏色圍住的空白區。
+fn stones_and_territory(state: &GoState, dead: &[(i8, i8)]) -> ([i32; 2], [i32; 2]) {
+    let mut board = state.board;
+    for &(c, r) in dead {
+        board[r as usize][c as usize] = None;
+    }
+    let size = state.size;
+    let mut stones = [0; 2];
+    let mut territory = [0; 2];
+    let mut visited = [[false; MAX_SIZE as usize]; MAX_SIZE as usize];
 
-    for r in 0..SIZE {
-        for c in 0..SIZE {
+    for r in 0..size {
+        for c in 0..size {
             match board[r as usize][c as usize] {
-                Some(Stone::Black) => black += 1,
-                Some(Stone::White) => white += 1,
+                Some(s) => stones[s.index()] += 1,
                 None => {
                     if visited[r as usize][c as usize] {
                         continue;
                     }
                     // 泛洪整塊空白，記錄邊界顏色
                     let mut stack = vec![(c, r)];
-                    let mut region = Vec::new();
+                    let mut region = 0;
                     let mut touch_black = false;
                     let mut touch_white = false;
                     visited[r as usize][c as usize] = true;
                     while let Some((cc, rr)) = stack.pop() {
-                        region.push((cc, rr));
-                        for (nc, nr) in neighbors(cc, rr) {
+                        region += 1;
+                        for (nc, nr) in neighbors(size, cc, rr) {
                             match board[nr as usize][nc as usize] {
                                 Some(Stone::Black) => touch_black = true,
                                 Some(Stone::White) => touch_white = true,
@@ -191,34 +388,67 @@ pub fn score(board: &Board) -> (i32, i32) {
                         }
                     }
                     if touch_black && !touch_white {
-                        black += region.len() as i32;
+                        territory[0] += region;
                     } else if touch_white && !touch_black {
-                        white += region.len() as i32;
+                        territory[1] += region;
                     } // 兩色皆鄰 = 中立，不計
                 }
             }
         }
     }
-    (black, white)
+    (stones, territory)
+}
+
+/// 數子（area scoring，不含貼目、不除死子）：各方 = 己方棋子數 + 只被己方圍的空白地。回傳 (黑, 白)。
+pub fn score(state: &GoState) -> (i32, i32) {
+    let (stones, territory) = stones_and_territory(state, &[]);
+    (stones[0] + territory[0], stones[1] + territory[1])
+}
+
+/// 依計分法與死子算出雙方得分 ×2（白方含貼目）。回傳 (黑, 白)。
+pub fn final_score_x2(state: &GoState, dead: &[(i8, i8)]) -> (i32, i32) {
+    let (stones, territory) = stones_and_territory(state, dead);
+    let (black, white) = match state.scoring {
+        Scoring::Area => (stones[0] + territory[0], stones[1] + territory[1]),
+        Scoring::Territory => {
+            // 死子算進對方提子
+            let mut prisoners = state.prisoners;
+            for &(c, r) in dead {
+                if let Some(s) = state.board[r as usize][c as usize] {
+                    prisoners[s.opponent().index()] += 1;
+                }
+            }
+            (territory[0] + prisoners[0], territory[1] + prisoners[1])
+        }
+    };
+    (black * 2, white * 2 + state.komi_x2)
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
     Continue,
-    /// 雙虛手終局，依數子 + 貼目判勝。
+    /// 雙方同意死子後終局，依計分 + 貼目判勝。
     Win(Stone),
 }
 
 pub fn status(state: &GoState) -> Outcome {
-    if state.passes < 2 {
-        return Outcome::Continue;
+    match &state.phase {
+        Phase::Settled { dead } => {
+            let (b, w) = final_score_x2(state, dead);
+            // 貼目 ×2 為奇數 → 無平手
+            Outcome::Win(if b > w { Stone::Black } else { Stone::White })
+        }
+        _ => Outcome::Continue,
     }
-    let (b, w) = score(&state.board);
-    // 倍數比較：白方 +komi。KOMI_X2 為奇數 → 無平手
-    if b * 2 > w * 2 + KOMI_X2 {
-        Outcome::Win(Stone::Black)
+}
+
+/// 此刻以數子（盤上皆活）終局的話誰贏（電腦估算用）。
+pub fn winner_on_board(state: &GoState) -> Stone {
+    let (b, w) = score(state);
+    if b * 2 > w * 2 + state.komi_x2 {
+        Stone::Black
     } else {
-        Outcome::Win(Stone::White)
+        Stone::White
     }
 }
 
@@ -81,15 +81,122 @@ fn ko_forbids_immediate_recapture() {
 }
 
 #[test]
-fn two_passes_end_game_white_wins_empty_by_komi() {
+fn two_passes_enter_marking_and_accept_settles() {
     let mut s = st();
     pass(&mut s); // 黑虛手
+    assert_eq!(s.phase, Phase::Play);
+    pass(&mut s); // 白虛手 → 點死子
+    assert!(matches!(s.phase, Phase::Marking { proposer: None, .. }));
     assert_eq!(status(&s), Outcome::Continue);
-    pass(&mut s); // 白虛手 → 終局
+    assert_eq!(is_legal(&s, 3, 3), Err(Reason::WrongPhase));
+    // 輪到黑：不能同意還沒人提的案
+    assert_eq!(accept_dead(&mut s), Err(Reason::NoProposal));
+    propose_dead(&mut s, &[]).unwrap();
+    // 提案方不能自己同意
+    s.turn = Stone::Black;
+    assert_eq!(accept_dead(&mut s), Err(Reason::NoProposal));
+    s.turn = Stone::White;
+    accept_dead(&mut s).unwrap();
     // 空盤 0:0，白 +7.5 → 白勝
     assert_eq!(status(&s), Outcome::Win(Stone::White));
 }
 
+#[test]
+fn dead_stones_expand_to_groups_and_count_for_opponent() {
+    let mut s = st();
+    // 黑佔第 0 列；白兩子孤立在黑地裡
+    for c in 0..MAX_SIZE {
+        b(&mut s, c, 0);
+    }
+    w(&mut s, 5, 5);
+    w(&mut s, 5, 6);
+    pass(&mut s);
+    pass(&mut s);
+    propose_dead(&mut s, &[(5, 5)]).unwrap();
+    assert!(matches!(&s.phase, Phase::Marking { dead, proposer: Some(Stone::Black) } if dead == &vec![(5, 5), (5, 6)]));
+    accept_dead(&mut s).unwrap();
+    let Phase::Settled { dead } = s.phase.clone() else { panic!("應已終局") };
+    // 白子移除後全盤皆黑
+    assert_eq!(final_score_x2(&s, &dead), (361 * 2, KOMI_AREA_X2));
+    assert_eq!(status(&s), Outcome::Win(Stone::Black));
+}
+
+#[test]
+fn proposing_an_empty_point_is_rejected() {
+    let mut s = st();
+    pass(&mut s);
+    pass(&mut s);
+    assert_eq!(propose_dead(&mut s, &[(4, 4)]), Err(Reason::NotAStone));
+    assert_eq!(propose_dead(&mut s, &[(30, 4)]), Err(Reason::NotAStone));
+}
+
+#[test]
+fn resume_returns_to_play_with_opponent_to_move() {
+    let mut s = st();
+    b(&mut s, 3, 3);
+    pass(&mut s);
+    pass(&mut s);
+    // 輪到黑，黑不同意 → 白先下
+    resume(&mut s).unwrap();
+    assert_eq!(s.phase, Phase::Play);
+    assert_eq!(s.passes, 0);
+    assert_eq!(s.turn, Stone::White);
+    assert!(is_legal(&s, 4, 4).is_ok());
+    assert_eq!(resume(&mut s), Err(Reason::WrongPhase));
+}
+
+#[test]
+fn small_board_limits_coordinates() {
+    let mut s = new_game(9, 0, Scoring::Area);
+    assert_eq!(is_legal(&s, 9, 0), Err(Reason::OutOfBounds));
+    assert!(is_legal(&s, 8, 8).is_ok());
+    b(&mut s, 4, 4);
+    assert_eq!(score(&s), (81, 0));
+}
+
+#[test]
+fn handicap_stones_on_star_points_and_white_first() {
+    let s = new_game(19, 4, Scoring::Area);
+    assert_eq!(s.turn, Stone::White);
+    assert_eq!(s.komi_x2, KOMI_HANDICAP_X2);
+    for (c, r) in [(3, 3), (15, 3), (3, 15), (15, 15)] {
+        assert_eq!(s.board[r as usize][c as usize], Some(Stone::Black));
+    }
+    assert_eq!(handicap_points(19, 9).len(), 9);
+    assert_eq!(handicap_points(19, 5).last(), Some(&(9, 9)));
+    assert!(handicap_points(9, 9).iter().all(|&(c, r)| (2..=6).contains(&c) && (2..=6).contains(&r)));
+    // 讓一子 = 讓先，不擺子
+    let s = new_game(13, 1, Scoring::Territory);
+    assert_eq!(s.turn, Stone::Black);
+    assert_eq!(s.komi_x2, KOMI_TERRITORY_X2);
+}
+
+#[test]
+fn territory_scoring_counts_prisoners_not_stones() {
+    let mut s = new_game(9, 0, Scoring::Territory);
+    // 黑牆 col 4，白牆 col 5：黑地 4×9=36、白地 3×9=27
+    for r in 0..9 {
+        b(&mut s, 4, r);
+        w(&mut s, 5, r);
+    }
+    s.prisoners = [2, 0];
+    assert_eq!(final_score_x2(&s, &[]), ((36 + 2) * 2, 27 * 2 + KOMI_TERRITORY_X2));
+    // 數子計分則算子：黑 45、白 36
+    s.scoring = Scoring::Area;
+    assert_eq!(final_score_x2(&s, &[]), (45 * 2, 36 * 2 + KOMI_TERRITORY_X2));
+}
+
+#[test]
+fn capture_counts_prisoners() {
+    let mut s = st();
+    w(&mut s, 1, 1);
+    b(&mut s, 0, 1);
+    b(&mut s, 2, 1);
+    b(&mut s, 1, 2);
+    apply(&mut s, 1, 0);
+    assert_eq!(s.prisoners, [1, 0]);
+}
+
 #[test]
 fn pass_then_play_resets_counter() {
     let mut s = st();
@@ -106,7 +213,7 @@ fn area_score_counts_stones_and_territory() {
     b(&mut s, 1, 0);
     b(&mut s, 1, 1);
     w(&mut s, 10, 10);
-    let (black, white) = score(&s.board);
+    let (black, white) = score(&s);
     assert_eq!(black, 4); // 3 子 + 角地 (0,0)
     assert_eq!(white, 1); // 1 子，大片空白為中立不計
 }
@@ -115,7 +222,21 @@ fn area_score_counts_stones_and_territory() {
 fn lone_color_owns_whole_board() {
     let mut s = st();
     b(&mut s, 3, 3); // 只有黑子 → 全盤皆黑（area scoring）
-    let (black, white) = score(&s.board);
+    let (black, white) = score(&s);
     assert_eq!(black, 361);
     assert_eq!(white, 0);
 }
+
+#[test]
+fn groups_lists_chains_with_liberties() {
+    let mut s = st();
+    b(&mut s, 0, 0);
+    b(&mut s, 1, 0);
+    w(&mut s, 0, 1);
+    w(&mut s, 5, 5);
+    let gs = groups(&s);
+    assert_eq!(gs.len(), 3);
+    assert_eq!(gs[0], Group { stone: Stone::Black, points: vec![(0, 0), (1, 0)], liberties: 2 });
+    assert_eq!(gs[1], Group { stone: Stone::White, points: vec![(0, 1)], liberties: 2 });
+    assert_eq!(gs[2].liberties, 4);
+}
@@ -2,11 +2,34 @@
 
 use serde_json::{json, Value};
 
-use super::engine::{self, GoState, Outcome, Stone};
+use super::bot::{self, GoMove, MarkingReply};
+use super::engine::{self, GoState, Outcome, Phase, Scoring, Stone};
+use super::notation;
+use crate::games::common::bot::{BotLevel, GameBot};
 use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+use crate::games::common::notation::{ExportMeta, Exported};
 
+#[derive(Clone)]
 pub struct GoGame(GoState);
 
+pub struct GoBot;
+
+impl GameBot<GoGame> for GoBot {
+    fn choose(engine: &GoGame, level: BotLevel) -> Option<Value> {
+        // 點死子：對方提案與自己估的一致才同意，否則反提一份
+        if let Phase::Marking { .. } = &engine.0.phase {
+            return Some(match bot::reply_marking(&engine.0, level) {
+                MarkingReply::Accept => json!({ "accept": true }),
+                MarkingReply::Propose(dead) => json!({ "dead": points_json(&dead) }),
+            });
+        }
+        bot::choose(&engine.0, level).map(|mv| match mv {
+            GoMove::Play(c, r) => json!({ "at": [c, r] }),
+            GoMove::Pass => json!({ "pass": true }),
+        })
+    }
+}
+
 fn to_common(s: Stone) -> Side {
     match s {
         Stone::Black => Side::First,
@@ -22,11 +45,39 @@ fn parse_at(v: Option<&Value>) -> Option<(i8, i8)> {
     Some((arr[0].as_i64()? as i8, arr[1].as_i64()? as i8))
 }
 
+fn flag(data: Option<&Value>, key: &str) -> bool {
+    data.and_then(|d| d.get(key)).and_then(|p| p.as_bool()) == Some(true)
+}
+
+fn points_json(points: &[(i8, i8)]) -> Vec<Value> {
+    points.iter().map(|&(c, r)| json!([c, r])).collect()
+}
+
 impl GameEngine for GoGame {
     const NAME: &'static str = "go";
+    type Bot = GoBot;
 
-    fn initial() -> Self {
-        GoGame(engine::initial_state())
+    /// `{"size": 9|13|19, "handicap": 0 | 2..=9, "scoring": "area"|"territory"}`，缺的欄位取預設（19 路分先數子）。
+    fn parse_options(v: &Value) -> Option<Value> {
+        let empty = serde_json::Map::new();
+        let o = if v.is_null() { &empty } else { v.as_object()? };
+        let size = o.get("size").map_or(Some(engine::MAX_SIZE as i64), Value::as_i64)?;
+        if !engine::SIZES.iter().any(|&s| s as i64 == size) {
+            return None;
+        }
+        let handicap = o.get("handicap").map_or(Some(0), Value::as_u64)?;
+        if handicap == 1 || handicap > engine::MAX_HANDICAP as u64 {
+            return None;
+        }
+        let scoring = o.get("scoring").map_or(Some("area"), Value::as_str).and_then(Scoring::parse)?;
+        Some(json!({ "size": size, "handicap": handicap, "scoring": scoring.as_str() }))
+    }
+
+    fn initial(options: &Value) -> Self {
+        let size = options["size"].as_i64().unwrap_or(engine::MAX_SIZE as i64) as i8;
+        let handicap = options["handicap"].as_u64().unwrap_or(0) as u8;
+        let scoring = options["scoring"].as_str().and_then(Scoring::parse).unwrap_or(Scoring::Area);
+        GoGame(engine::new_game(size, handicap, scoring))
     }
 
     fn turn(&self) -> Side {
@@ -41,12 +92,46 @@ impl GameEngine for GoGame {
     }
 
     fn try_move(&mut self, mover: Side, data: Option<&Value>) -> Result<Applied, String> {
+        // 點死子階段：提案 / 同意 / 續下
+        if let Some(points) = data.and_then(|d| d.get("dead")) {
+            let points = points
+                .as_array()
+                .and_then(|a| a.iter().map(|p| parse_at(Some(p))).collect::<Option<Vec<_>>>())
+                .ok_or("bad_coord")?;
+            engine::propose_dead(&mut self.0, &points).map_err(|e| e.as_str().to_string())?;
+            let Phase::Marking { dead, .. } = &self.0.phase else { unreachable!("提案後必在點死子階段") };
+            return Ok(Applied {
+                move_data: json!({ "dead": points_json(dead), "by": Self::side_label(mover) }),
+                extra: Vec::new(),
+                swap_seats: false,
+            });
+        }
+        if flag(data, "accept") {
+            engine::accept_dead(&mut self.0).map_err(|e| e.as_str().to_string())?;
+            return Ok(Applied {
+                move_data: json!({ "accept": true, "by": Self::side_label(mover) }),
+                extra: Vec::new(),
+                swap_seats: false,
+            });
+        }
+        if flag(data, "resume") {
+            engine::resume(&mut self.0).map_err(|e| e.as_str().to_string())?;
+            return Ok(Applied {
+                move_data: json!({ "resume": true, "by": Self::side_label(mover) }),
+                extra: Vec::new(),
+                swap_seats: false,
+            });
+        }
         // 虛手
-        if data.and_then(|d| d.get("pass")).and_then(|p| p.as_bool()) == Some(true) {
+        if flag(data, "pass") {
+            if self.0.phase != Phase::Play {
+                return Err(engine::Reason::WrongPhase.as_str().into());
+            }
             engine::pass(&mut self.0);
             return Ok(Applied {
                 move_data: json!({ "pass": true, "by": Self::side_label(mover) }),
                 extra: Vec::new(),
+                swap_seats: false,
             });
         }
         // 落子
@@ -63,6 +148,7 @@ impl GameEngine for GoGame {
                 "captured": captured_json,
             }),
             extra: Vec::new(),
+            swap_seats: false,
         })
     }
 
@@ -75,4 +161,55 @@ impl GameEngine for GoGame {
             },
         }
     }
+
+    fn snapshot(&self) -> Value {
+        let mut stones = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(s) = cell {
+                    stones.push(json!({ "at": [col, row], "color": Self::side_label(to_common(*s)) }));
+                }
+            }
+        }
+        let (phase, dead, proposer) = match &self.0.phase {
+            Phase::Play => ("play", Vec::new(), None),
+            Phase::Marking { dead, proposer } => ("marking", points_json(dead), proposer.map(|p| Self::side_label(to_common(p)))),
+            Phase::Settled { dead } => ("settled", points_json(dead), None),
+        };
+        json!({
+            "size": self.0.size,
+            "stones": stones,
+            "ko": self.0.ko.map(|(c, r)| json!([c, r])),
+            "passes": self.0.passes,
+            "komi": self.0.komi_x2 as f64 / 2.0,
+            "handicap": self.0.handicap,
+            "scoring": self.0.scoring.as_str(),
+            "prisoners": { "black": self.0.prisoners[0], "white": self.0.prisoners[1] },
+            "phase": phase,
+            "dead": dead,
+            "proposer": proposer,
+        })
+    }
+
+    fn export(plies: &[(&Self, &Value)], last: &Self, meta: &ExportMeta) -> Option<Exported> {
+        // 點死子的提案 / 同意 / 續下不是棋步，SGF 不記
+        let moves = plies
+            .iter()
+            .filter(|(_, data)| ["dead", "accept", "resume"].iter().all(|k| data.get(k).is_none()))
+            .map(|(pos, data)| {
+                let mv = if flag(Some(data), "pass") {
+                    GoMove::Pass
+                } else {
+                    parse_at(data.get("at")).map(|(c, r)| GoMove::Play(c, r))?
+                };
+                Some((pos.0.turn, mv))
+            })
+            .collect::<Option<Vec<_>>>()?;
+        Some(Exported {
+            format: "sgf",
+            extension: "sgf",
+            mime: "application/x-go-sgf",
+            text: notation::sgf(&moves, &last.0, meta),
+        })
+    }
 }
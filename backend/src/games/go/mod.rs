@@ -1,4 +1,6 @@
-//! 圍棋：純函式規則引擎（`engine`）與共用框架接口（`game`）。
+//! 圍棋：純函式規則引擎（`engine`）、電腦對手（`bot`）、記譜（`notation`）與共用框架接口（`game`）。
 
+pub mod bot;
 pub mod engine;
 pub mod game;
+pub mod notation;
@@ -0,0 +1,130 @@
+//! 圍棋記譜：SGF（FF[4]）。
+//!
+//! 座標 `[cr]`：c = 行（col 0 → `a`）、r = 列（row 0 → `a`）；虛手為空值 `[]`。
+//! 讓子以 `HA` + `AB` 擺子，計分法記在 `RU`（數子 `Chinese`、數目 `Japanese`）。
+
+use super::bot::GoMove;
+use super::engine::{self, GoState, Phase, Scoring, Stone};
+use crate::games::common::engine::Side;
+use crate::games::common::notation::ExportMeta;
+
+/// SGF 文字值跳脫（`]` 與 `\`）。
+fn escape(s: &str) -> String {
+    s.replace('\\', "\\\\").replace(']', "\\]")
+}
+
+fn point(c: i8, r: i8) -> String {
+    format!("{}{}", (b'a' + c as u8) as char, (b'a' + r as u8) as char)
+}
+
+fn half(x2: i32) -> String {
+    format!("{}", x2 as f64 / 2.0)
+}
+
+/// `RE[]` 值：計分終局為 `B+3.5`（依同意的死子與計分法），認輸 `B+R`、超時 `B+T`、其餘判負（斷線等）`B+F`，和局 `0`。
+fn result_value(winner: Option<Side>, reason: &str, last: &GoState) -> String {
+    let Some(winner) = winner else {
+        return "0".into();
+    };
+    let color = if winner == Side::First { "B" } else { "W" };
+    let how = match reason {
+        "score" => {
+            let dead: &[(i8, i8)] = match &last.phase {
+                Phase::Settled { dead } => dead,
+                _ => &[],
+            };
+            let (b, w) = engine::final_score_x2(last, dead);
+            half((b - w).abs())
+        }
+        "resign" => "R".into(),
+        "timeout" => "T".into(),
+        _ => "F".into(),
+    };
+    format!("{color}+{how}")
+}
+
+/// 整局 SGF。`moves` 依序附行棋方，`last` 為終局局面（盤面大小、讓子、貼目與計分用）。
+pub fn sgf(moves: &[(Stone, GoMove)], last: &GoState, meta: &ExportMeta) -> String {
+    let mut out = String::from("(;GM[1]FF[4]CA[UTF-8]");
+    let rules = match last.scoring {
+        Scoring::Area => "Chinese",
+        Scoring::Territory => "Japanese",
+    };
+    out.push_str(&format!("SZ[{}]KM[{}]RU[{rules}]", last.size, half(last.komi_x2)));
+    if last.handicap > 0 {
+        out.push_str(&format!("HA[{}]AB", last.handicap));
+        for (c, r) in engine::handicap_points(last.size, last.handicap) {
+            out.push_str(&format!("[{}]", point(c, r)));
+        }
+    }
+    out.push_str(&format!("GN[{}]", escape(&meta.event)));
+    out.push_str(&format!("DT[{}]", meta.date.format("%Y-%m-%d")));
+    out.push_str(&format!("PB[{}]PW[{}]", escape(&meta.players[0]), escape(&meta.players[1])));
+    if let Some(r) = &meta.result {
+        out.push_str(&format!("RE[{}]", result_value(r.winner, &r.reason, last)));
+    }
+    for (i, (stone, mv)) in moves.iter().enumerate() {
+        // 每 10 手換行，方便人讀
+        if i % 10 == 0 {
+            out.push('\n');
+        }
+        let color = if *stone == Stone::Black { 'B' } else { 'W' };
+        match *mv {
+            GoMove::Play(c, r) => out.push_str(&format!(";{color}[{}]", point(c, r))),
+            GoMove::Pass => out.push_str(&format!(";{color}[]")),
+        }
+    }
+    out.push_str(")\n");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::common::notation::ExportResult;
+
+    fn meta(result: Option<ExportResult>) -> ExportMeta {
+        ExportMeta {
+            event: "桌 [1]".into(),
+            date: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            players: ["?".into(), "?".into()],
+            result,
+            format: None,
+        }
+    }
+
+    #[test]
+    fn header_and_moves() {
+        let last = engine::initial_state();
+        let moves = [
+            (Stone::Black, GoMove::Play(3, 15)),
+            (Stone::White, GoMove::Play(15, 3)),
+            (Stone::Black, GoMove::Pass),
+        ];
+        let text = sgf(&moves, &last, &meta(None));
+        assert!(text.starts_with("(;GM[1]FF[4]CA[UTF-8]SZ[19]KM[7.5]RU[Chinese]GN[桌 [1\\]]"), "{text}");
+        assert!(text.contains(";B[dp];W[pd];B[])"), "{text}");
+        assert!(!text.contains("RE["));
+    }
+
+    #[test]
+    fn result_by_score_includes_komi() {
+        // 黑佔一整行（19 子）+ 以下全部地盤；白無子 → 黑 361 目，勝 353.5
+        let mut last = engine::initial_state();
+        for c in 0..last.size {
+            last.board[0][c as usize] = Some(Stone::Black);
+        }
+        let r = ExportResult { winner: Some(Side::First), reason: "score".into() };
+        assert!(sgf(&[], &last, &meta(Some(r))).contains("RE[B+353.5]"));
+        let r = ExportResult { winner: Some(Side::Second), reason: "resign".into() };
+        assert!(sgf(&[], &last, &meta(Some(r))).contains("RE[W+R]"));
+    }
+
+    #[test]
+    fn handicap_small_board_and_territory_rules() {
+        let last = engine::new_game(9, 2, Scoring::Territory);
+        let text = sgf(&[(Stone::White, GoMove::Play(4, 4))], &last, &meta(None));
+        assert!(text.contains("SZ[9]KM[0.5]RU[Japanese]HA[2]AB[gc][cg]"), "{text}");
+        assert!(text.contains(";W[ee])"), "{text}");
+    }
+}
@@ -0,0 +1,258 @@
+//! 五子棋電腦：alpha-beta + 五格窗評估。
+//!
+//! 候選步只取既有棋子周圍 2 格內的空點，依攻守價值排序後截前幾名（寬度換深度）。
+//! 有直接連五就只走它；對手有直接連五就只擋它。
+
+use std::time::Duration;
+
+use super::engine::{self, GomokuState, Rule, Stone, SIZE};
+use crate::games::common::bot::{self as search, BotLevel, SearchLimits, Searchable, MATE};
+
+const DIRS: [(i8, i8); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+/// 五格窗內有 k 顆同色子（且無對方子）的分值，k = 0..=5。
+const WINDOW_SCORE: [i32; 6] = [0, 1, 12, 150, 2_000, MATE];
+
+#[derive(Clone)]
+struct Pos {
+    state: GomokuState,
+    last: Option<(i8, i8)>,
+    width: usize,
+}
+
+fn at(s: &GomokuState, col: i8, row: i8) -> Option<Option<Stone>> {
+    ((0..SIZE).contains(&col) && (0..SIZE).contains(&row)).then(|| s.board[row as usize][col as usize])
+}
+
+/// `(col,row)` 在 `(dc,dr)` 方向上與 `stone` 相連的長度（含自身，假設該點為 `stone`）。
+fn run_len(s: &GomokuState, col: i8, row: i8, (dc, dr): (i8, i8), stone: Stone) -> u32 {
+    let mut len = 1;
+    for sign in [1, -1] {
+        let (mut c, mut r) = (col + dc * sign, row + dr * sign);
+        while at(s, c, r) == Some(Some(stone)) {
+            len += 1;
+            c += dc * sign;
+            r += dr * sign;
+        }
+    }
+    len
+}
+
+fn makes_five(s: &GomokuState, col: i8, row: i8, stone: Stone) -> bool {
+    DIRS.iter().any(|&d| s.rule.wins(stone, run_len(s, col, row, d, stone) as usize))
+}
+
+/// 連珠規則下黑方的禁手點不列入候選。
+fn allowed(s: &GomokuState, col: i8, row: i8, stone: Stone) -> bool {
+    s.rule != Rule::Renju || stone == Stone::White || engine::forbidden(&s.board, col, row, 1).is_none()
+}
+
+/// 空點對 `stone` 的價值：經過此點的各五格窗，若窗內無對方子，加上落子後的窗分。
+fn point_value(s: &GomokuState, col: i8, row: i8, stone: Stone) -> i32 {
+    let mut total = 0;
+    for (dc, dr) in DIRS {
+        for start in -4..=0 {
+            let mut mine = 1;
+            let mut blocked = false;
+            for k in start..start + 5 {
+                if k == 0 {
+                    continue;
+                }
+                match at(s, col + dc * k, row + dr * k) {
+                    None => blocked = true,
+                    Some(Some(x)) if x == stone => mine += 1,
+                    Some(Some(_)) => blocked = true,
+                    Some(None) => {}
+                }
+                if blocked {
+                    break;
+                }
+            }
+            if !blocked {
+                total += WINDOW_SCORE[mine.min(5)];
+            }
+        }
+    }
+    total
+}
+
+impl Searchable for Pos {
+    type Move = (i8, i8);
+
+    fn moves(&self) -> Vec<(i8, i8)> {
+        let s = &self.state;
+        if s.placed == 0 {
+            return vec![(SIZE / 2, SIZE / 2)];
+        }
+        let me = s.turn;
+        let mut scored = Vec::new();
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                if s.board[row as usize][col as usize].is_some()
+                    || !has_neighbor(s, col, row)
+                    || !allowed(s, col, row, me)
+                {
+                    continue;
+                }
+                if makes_five(s, col, row, me) {
+                    return vec![(col, row)];
+                }
+                let attack = point_value(s, col, row, me);
+                let defend = point_value(s, col, row, me.opponent());
+                scored.push(((col, row), attack + defend * 9 / 10));
+            }
+        }
+        // 對手下一手能連五：只考慮擋（擋不完就隨便擋一個，反正輸了）
+        let forced: Vec<_> = scored
+            .iter()
+            .filter(|((c, r), _)| makes_five(s, *c, *r, me.opponent()))
+            .map(|(m, _)| *m)
+            .collect();
+        if !forced.is_empty() {
+            return forced;
+        }
+        scored.sort_by_key(|&(_, v)| std::cmp::Reverse(v));
+        scored.truncate(self.width);
+        scored.into_iter().map(|(m, _)| m).collect()
+    }
+
+    fn play(&mut self, (col, row): (i8, i8)) {
+        engine::apply(&mut self.state, col, row);
+        self.last = Some((col, row));
+    }
+
+    fn evaluate(&self) -> i32 {
+        let s = &self.state;
+        let (mut black, mut white) = (0, 0);
+        for row in 0..SIZE {
+            for col in 0..SIZE {
+                for (dc, dr) in DIRS {
+                    if at(s, col + dc * 4, row + dr * 4).is_none() {
+                        continue;
+                    }
+                    let (mut b, mut w) = (0, 0);
+                    for k in 0..5 {
+                        match s.board[(row + dr * k) as usize][(col + dc * k) as usize] {
+                            Some(Stone::Black) => b += 1,
+                            Some(Stone::White) => w += 1,
+                            None => {}
+                        }
+                    }
+                    if w == 0 {
+                        black += WINDOW_SCORE[b];
+                    } else if b == 0 {
+                        white += WINDOW_SCORE[w];
+                    }
+                }
+            }
+        }
+        // 行棋方先手，同樣的威脅對他更值錢
+        match s.turn {
+            Stone::Black => black * 5 / 4 - white,
+            Stone::White => white * 5 / 4 - black,
+        }
+    }
+
+    fn terminal(&self) -> Option<i32> {
+        let (col, row) = self.last?;
+        if makes_five(&self.state, col, row, self.state.turn.opponent()) {
+            return Some(-MATE);
+        }
+        (self.state.placed as i32 >= SIZE as i32 * SIZE as i32).then_some(0)
+    }
+
+    fn no_moves_score(&self) -> i32 {
+        0
+    }
+}
+
+fn has_neighbor(s: &GomokuState, col: i8, row: i8) -> bool {
+    (-2..=2).any(|dr| (-2..=2).any(|dc| matches!(at(s, col + dc, row + dr), Some(Some(_)))))
+}
+
+/// Swap2 選色：看白方先下時的靜態評估，對白方有利就執白，否則執黑。
+pub fn choose_color(state: &GomokuState) -> Stone {
+    let mut s = state.clone();
+    s.turn = Stone::White;
+    let pos = Pos { state: s, last: None, width: 0 };
+    if pos.evaluate() >= 0 {
+        Stone::White
+    } else {
+        Stone::Black
+    }
+}
+
+/// 為行棋方選一個落點。
+pub fn choose(state: &GomokuState, level: BotLevel) -> Option<(i8, i8)> {
+    let (limits, width) = match level {
+        BotLevel::Easy => (SearchLimits { max_depth: 2, budget: Duration::from_millis(300), noise: 300 }, 8),
+        BotLevel::Normal => (SearchLimits { max_depth: 4, budget: Duration::from_secs(1), noise: 20 }, 10),
+        BotLevel::Hard => (SearchLimits { max_depth: 6, budget: Duration::from_millis(2500), noise: 0 }, 12),
+    };
+    let pos = Pos { state: state.clone(), last: None, width };
+    search::best_move(&pos, limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn board(black: &[(i8, i8)], white: &[(i8, i8)], turn: Stone) -> GomokuState {
+        let mut s = engine::initial_state();
+        for &(c, r) in black {
+            s.board[r as usize][c as usize] = Some(Stone::Black);
+        }
+        for &(c, r) in white {
+            s.board[r as usize][c as usize] = Some(Stone::White);
+        }
+        s.placed = (black.len() + white.len()) as u32;
+        s.turn = turn;
+        s
+    }
+
+    #[test]
+    fn opens_in_center() {
+        assert_eq!(choose(&engine::initial_state(), BotLevel::Easy), Some((7, 7)));
+    }
+
+    #[test]
+    fn completes_five() {
+        let s = board(&[(3, 7), (4, 7), (5, 7), (6, 7)], &[(3, 8), (4, 8), (5, 8), (9, 9)], Stone::Black);
+        let mv = choose(&s, BotLevel::Hard).unwrap();
+        assert!(mv == (7, 7) || mv == (2, 7), "got {mv:?}");
+    }
+
+    #[test]
+    fn blocks_four() {
+        let s = board(&[(3, 8), (10, 10), (11, 11)], &[(3, 7), (4, 7), (5, 7), (6, 7)], Stone::Black);
+        for level in [BotLevel::Easy, BotLevel::Normal, BotLevel::Hard] {
+            let mv = choose(&s, level).unwrap();
+            assert!(mv == (7, 7) || mv == (2, 7), "{level:?} got {mv:?}");
+        }
+    }
+
+    #[test]
+    fn blocks_open_three() {
+        // 白 (5,7)(6,7)(7,7) 兩頭空：不擋就變活四
+        let s = board(&[(5, 8), (6, 9)], &[(5, 7), (6, 7), (7, 7)], Stone::Black);
+        let mv = choose(&s, BotLevel::Normal).unwrap();
+        assert!([(4, 7), (8, 7), (3, 7), (9, 7)].contains(&mv), "got {mv:?}");
+    }
+
+    #[test]
+    fn renju_black_avoids_forbidden_point() {
+        // 黑橫 (5,7)(6,7)、直 (7,5)(7,6)：下 (7,7) 成雙活三（禁手）
+        let mut s = board(&[(5, 7), (6, 7), (7, 5), (7, 6)], &[(0, 0), (14, 0), (0, 14), (14, 14)], Stone::Black);
+        s.rule = Rule::Renju;
+        assert!(engine::is_legal(&s, 7, 7).is_err());
+        let mv = choose(&s, BotLevel::Normal).unwrap();
+        assert_ne!(mv, (7, 7));
+        assert!(engine::is_legal(&s, mv.0, mv.1).is_ok());
+    }
+
+    #[test]
+    fn swap2_picks_the_stronger_color() {
+        // 黑已成活三：選黑
+        let s = board(&[(6, 7), (7, 7), (8, 7)], &[(0, 0), (14, 14)], Stone::White);
+        assert_eq!(choose_color(&s), Stone::Black);
+    }
+}
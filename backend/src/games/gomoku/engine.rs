@@ -1,7 +1,13 @@
-//! 五子棋規則引擎 — 純函式，可單測。15×15，黑先，五子（含以上）連線勝，滿盤和。
+//! 五子棋規則引擎 — 純函式，可單測。15×15，黑先，滿盤和。
+//!
+//! 三種規則：自由（五子含以上勝）、標準（恰好五子才勝，長連不算）、連珠（黑方禁手：
+//! 三三 / 四四 / 長連不可下，黑恰好五子勝；白五子含以上勝）。可選 Swap2 開局：
+//! 先手座位擺三子（黑白黑），後手座位選執黑 / 執白 / 再擺兩子（白黑）交由先手座位選色。
 
 pub const SIZE: i8 = 15;
 const WIN_LEN: usize = 5;
+/// 禁手判定「活三」時，檢查補成活四的那一手本身是否禁手的遞迴深度。
+const FORBIDDEN_DEPTH: u8 = 2;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Stone {
@@ -18,6 +24,77 @@ impl Stone {
     }
 }
 
+/// 每桌的規則。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rule {
+    Freestyle,
+    Standard,
+    Renju,
+}
+
+impl Rule {
+    pub fn parse(s: &str) -> Option<Rule> {
+        match s {
+            "freestyle" => Some(Rule::Freestyle),
+            "standard" => Some(Rule::Standard),
+            "renju" => Some(Rule::Renju),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Rule::Freestyle => "freestyle",
+            Rule::Standard => "standard",
+            Rule::Renju => "renju",
+        }
+    }
+
+    /// `stone` 連成 `len` 子是否獲勝。
+    pub fn wins(self, stone: Stone, len: usize) -> bool {
+        match (self, stone) {
+            (Rule::Freestyle, _) | (Rule::Renju, Stone::White) => len >= WIN_LEN,
+            (Rule::Standard, _) | (Rule::Renju, Stone::Black) => len == WIN_LEN,
+        }
+    }
+}
+
+/// 開局階段。除 `Normal` 外都是 Swap2 的步驟。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Opening {
+    /// 依 `turn` 輪流落子（一般開局，或 Swap2 已選定顏色）。
+    Normal,
+    /// 先手座位連擺三子（黑白黑）。
+    PlaceThree,
+    /// 後手座位選：執黑 / 執白 / 再擺兩子。
+    Choose,
+    /// 後手座位加擺兩子（白黑）。
+    PlaceTwo,
+    /// 先手座位選色。
+    FinalChoose,
+}
+
+impl Opening {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Opening::Normal => "normal",
+            Opening::PlaceThree => "place_three",
+            Opening::Choose => "choose",
+            Opening::PlaceTwo => "place_two",
+            Opening::FinalChoose => "final_choose",
+        }
+    }
+}
+
+/// Swap2 選色時的選項。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Choice {
+    Black,
+    White,
+    /// 只有後手座位第一次選時可用。
+    PlaceTwo,
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Outcome {
     Continue,
@@ -28,15 +105,35 @@ pub enum Outcome {
 #[derive(Clone)]
 pub struct GomokuState {
     pub board: [[Option<Stone>; 15]; 15],
+    /// 下一顆子的顏色（Swap2 擺子階段也是黑白交替）。
     pub turn: Stone,
     pub placed: u32,
+    pub rule: Rule,
+    pub opening: Opening,
 }
 
+/// 自由規則、一般開局（測試用）。
+#[cfg(test)]
 pub fn initial_state() -> GomokuState {
+    new_game(Rule::Freestyle, false)
+}
+
+pub fn new_game(rule: Rule, swap2: bool) -> GomokuState {
     GomokuState {
         board: [[None; 15]; 15],
         turn: Stone::Black,
         placed: 0,
+        rule,
+        opening: if swap2 { Opening::PlaceThree } else { Opening::Normal },
+    }
+}
+
+/// 此刻該由哪個顏色的座位動作（黑 = 先手座位）。Swap2 開局期間與 `turn`（下一顆子的顏色）不同。
+pub fn actor(state: &GomokuState) -> Stone {
+    match state.opening {
+        Opening::Normal => state.turn,
+        Opening::PlaceThree | Opening::FinalChoose => Stone::Black,
+        Opening::Choose | Opening::PlaceTwo => Stone::White,
     }
 }
 
@@ -52,36 +149,162 @@ fn at(state: &GomokuState, col: i8, row: i8) -> Option<Stone> {
     }
 }
 
-/// 落子合法性：界內且空格。
+/// 落子合法性：界內、空格、不在選色階段，連珠規則下黑方不可下禁手。
 pub fn is_legal(state: &GomokuState, col: i8, row: i8) -> Result<(), &'static str> {
+    if matches!(state.opening, Opening::Choose | Opening::FinalChoose) {
+        return Err("choose_color");
+    }
     if !in_bounds(col, row) {
         return Err("bad_coord");
     }
     if state.board[row as usize][col as usize].is_some() {
         return Err("occupied");
     }
+    if state.rule == Rule::Renju && state.turn == Stone::Black {
+        if let Some(reason) = forbidden(&state.board, col, row, FORBIDDEN_DEPTH) {
+            return Err(reason);
+        }
+    }
     Ok(())
 }
 
-/// 套用落子（假設已合法）：放當前 turn 的子、計數、換手。
+/// 套用落子（假設已合法）：放當前 turn 的子、計數、換手，Swap2 擺滿三 / 五子時進入選色。
 pub fn apply(state: &mut GomokuState, col: i8, row: i8) {
     state.board[row as usize][col as usize] = Some(state.turn);
     state.placed += 1;
     state.turn = state.turn.opponent();
+    state.opening = match (state.opening, state.placed) {
+        (Opening::PlaceThree, 3) => Opening::Choose,
+        (Opening::PlaceTwo, 5) => Opening::FinalChoose,
+        (o, _) => o,
+    };
 }
 
-/// 任一方達 5（含以上）連線即勝；滿盤判和。與當前 turn 無關（掃整盤）。
-pub fn status(state: &GomokuState) -> Outcome {
-    const DIRS: [(i8, i8); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
-    for row in 0..SIZE {
-        for col in 0..SIZE {
-            let Some(stone) = at(state, col, row) else {
-                continue;
+/// Swap2 選色。回傳 true = 動作方選了另一色，兩座位要對調。選定後輪白方落子。
+pub fn choose(state: &mut GomokuState, choice: Choice) -> Result<bool, &'static str> {
+    let me = actor(state);
+    let chosen = match (state.opening, choice) {
+        (Opening::Choose, Choice::PlaceTwo) => {
+            state.opening = Opening::PlaceTwo;
+            return Ok(false);
+        }
+        (Opening::Choose | Opening::FinalChoose, Choice::Black) => Stone::Black,
+        (Opening::Choose | Opening::FinalChoose, Choice::White) => Stone::White,
+        (Opening::FinalChoose, Choice::PlaceTwo) => return Err("bad_choice"),
+        _ => return Err("not_choosing"),
+    };
+    state.opening = Opening::Normal;
+    Ok(chosen != me)
+}
+
+/// 一條線上的格子：0 空、1 黑、2 白或界外。index 5 為中心點。
+type Line = [u8; 11];
+
+fn line(board: &[[Option<Stone>; 15]; 15], col: i8, row: i8, (dc, dr): (i8, i8)) -> Line {
+    let mut out = [2; 11];
+    for (i, cell) in out.iter_mut().enumerate() {
+        let k = i as i8 - 5;
+        let (c, r) = (col + dc * k, row + dr * k);
+        if in_bounds(c, r) {
+            *cell = match board[r as usize][c as usize] {
+                None => 0,
+                Some(Stone::Black) => 1,
+                Some(Stone::White) => 2,
             };
-            for (dc, dr) in DIRS {
-                // 只從連線起點計（前一格非同色），避免重複計
+        }
+    }
+    out
+}
+
+/// 含 index `i` 的黑子連續段 `(start, end)`（閉區間）。
+fn run(l: &Line, i: usize) -> (usize, usize) {
+    let (mut s, mut e) = (i, i);
+    while s > 0 && l[s - 1] == 1 {
+        s -= 1;
+    }
+    while e < 10 && l[e + 1] == 1 {
+        e += 1;
+    }
+    (s, e)
+}
+
+/// 中心黑子在此線上的「四」數：補一手即恰好成五的不同組合數（活四算一個，`●_●●●_●` 算兩個）。
+fn fours(l: &Line) -> usize {
+    let mut seen: Vec<u16> = Vec::new();
+    for k in 1..10 {
+        if l[k] != 0 {
+            continue;
+        }
+        let mut t = *l;
+        t[k] = 1;
+        let (s, e) = run(&t, 5);
+        if e - s + 1 == WIN_LEN && (s..=e).contains(&k) {
+            let mask = (s..=e).filter(|&i| i != k).fold(0u16, |m, i| m | 1 << i);
+            if !seen.contains(&mask) {
+                seen.push(mask);
+            }
+        }
+    }
+    seen.len()
+}
+
+/// 中心黑子在此線上是否成「活三」：有一空點補上後成活四（兩端皆可恰好成五），且該點本身不是禁手。
+fn is_three(board: &[[Option<Stone>; 15]; 15], col: i8, row: i8, dir: (i8, i8), l: &Line, depth: u8) -> bool {
+    (1..10).any(|k| {
+        if l[k] != 0 {
+            return false;
+        }
+        let mut t = *l;
+        t[k] = 1;
+        let (s, e) = run(&t, 5);
+        let straight = e - s + 1 == 4
+            && (s..=e).contains(&k)
+            && t[s - 1] == 0 && t[e + 1] == 0 && t[s - 2] != 1 && t[e + 2] != 1;
+        if !straight {
+            return false;
+        }
+        if depth == 0 {
+            return true;
+        }
+        let off = k as i8 - 5;
+        let (qc, qr) = (col + dir.0 * off, row + dir.1 * off);
+        forbidden(board, qc, qr, depth - 1).is_none()
+    })
+}
+
+/// 連珠黑方禁手：在 `(col,row)` 下黑子會形成的禁手（恰好成五優先，不算禁手）。假設該點為空。
+pub fn forbidden(board: &[[Option<Stone>; 15]; 15], col: i8, row: i8, depth: u8) -> Option<&'static str> {
+    const DIRS: [(i8, i8); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+    let mut b = *board;
+    b[row as usize][col as usize] = Some(Stone::Black);
+    let lines = DIRS.map(|d| line(&b, col, row, d));
+    let lens = lines.map(|l| {
+        let (s, e) = run(&l, 5);
+        e - s + 1
+    });
+    if lens.contains(&WIN_LEN) {
+        return None;
+    }
+    if lens.iter().any(|&n| n > WIN_LEN) {
+        return Some("forbidden_overline");
+    }
+    if lines.iter().map(fours).sum::<usize>() >= 2 {
+        return Some("forbidden_double_four");
+    }
+    let threes = DIRS.iter().zip(&lines).filter(|(&d, l)| is_three(&b, col, row, d, l, depth)).count();
+    (threes >= 2).then_some("forbidden_double_three")
+}
+
+/// 盤上每段同色連線：(顏色, 起點, 方向, 長度)。每段只從起點算一次（前一格非同色）。
+fn runs(state: &GomokuState) -> impl Iterator<Item = (Stone, (i8, i8), (i8, i8), usize)> + '_ {
+    const DIRS: [(i8, i8); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+    (0..SIZE)
+        .flat_map(|row| (0..SIZE).map(move |col| (col, row)))
+        .filter_map(|(col, row)| at(state, col, row).map(|stone| (stone, col, row)))
+        .flat_map(move |(stone, col, row)| {
+            DIRS.into_iter().filter_map(move |(dc, dr)| {
                 if at(state, col - dc, row - dr) == Some(stone) {
-                    continue;
+                    return None;
                 }
                 let mut len = 0;
                 let (mut c, mut r) = (col, row);
@@ -90,11 +313,15 @@ pub fn status(state: &GomokuState) -> Outcome {
                     c += dc;
                     r += dr;
                 }
-                if len >= WIN_LEN {
-                    return Outcome::Win(stone);
-                }
-            }
-        }
+                Some((stone, (col, row), (dc, dr), len))
+            })
+        })
+}
+
+/// 任一方依規則連成五子即勝；滿盤判和。與當前 turn 無關（掃整盤）。
+pub fn status(state: &GomokuState) -> Outcome {
+    if let Some((stone, ..)) = runs(state).find(|&(stone, _, _, len)| state.rule.wins(stone, len)) {
+        return Outcome::Win(stone);
     }
     if state.placed as i32 >= (SIZE as i32) * (SIZE as i32) {
         Outcome::Draw
@@ -103,5 +330,64 @@ pub fn status(state: &GomokuState) -> Outcome {
     }
 }
 
+/// 盤上依規則成立的勝線：(顏色, 由起點依序的座標)。
+pub fn winning_lines(state: &GomokuState) -> Vec<(Stone, Vec<(i8, i8)>)> {
+    runs(state)
+        .filter(|&(stone, _, _, len)| state.rule.wins(stone, len))
+        .map(|(stone, (col, row), (dc, dr), len)| {
+            (stone, (0..len as i8).map(|k| (col + dc * k, row + dr * k)).collect())
+        })
+        .collect()
+}
+
+/// `stone` 下在 `(col,row)`（假設為空）是否依規則立即獲勝。恰好成五優先於禁手，不必另查。
+fn wins_at(state: &GomokuState, col: i8, row: i8, stone: Stone) -> bool {
+    const DIRS: [(i8, i8); 4] = [(1, 0), (0, 1), (1, 1), (1, -1)];
+    DIRS.iter().any(|&(dc, dr)| {
+        let count = |sign: i8| {
+            (1..).take_while(|&k| at(state, col + dc * k * sign, row + dr * k * sign) == Some(stone)).count()
+        };
+        state.rule.wins(stone, 1 + count(1) + count(-1))
+    })
+}
+
+fn empties(state: &GomokuState) -> impl Iterator<Item = (i8, i8)> + '_ {
+    (0..SIZE)
+        .flat_map(|row| (0..SIZE).map(move |col| (col, row)))
+        .filter(|&(col, row)| state.board[row as usize][col as usize].is_none())
+}
+
+/// `stone` 下一手即可獲勝的空點（不論輪到誰）。
+pub fn win_points(state: &GomokuState, stone: Stone) -> Vec<(i8, i8)> {
+    empties(state).filter(|&(c, r)| wins_at(state, c, r, stone)).collect()
+}
+
+/// `stone` 下了會成「四」的空點，與下完後的致勝點數（≥ 2 = 活四或雙四，擋不完）。
+/// 本身就能獲勝的點、連珠規則黑方的禁手點不列入。
+pub fn four_points(state: &GomokuState, stone: Stone) -> Vec<((i8, i8), usize)> {
+    empties(state)
+        .filter(|&(c, r)| !wins_at(state, c, r, stone))
+        .filter(|&(c, r)| {
+            !(state.rule == Rule::Renju && stone == Stone::Black && forbidden(&state.board, c, r, FORBIDDEN_DEPTH).is_some())
+        })
+        .filter_map(|(c, r)| {
+            let mut next = state.clone();
+            next.board[r as usize][c as usize] = Some(stone);
+            let n = win_points(&next, stone).len();
+            (n > 0).then_some(((c, r), n))
+        })
+        .collect()
+}
+
+/// 連珠規則下黑方的禁手點與原因；其他規則回空。
+pub fn forbidden_points(state: &GomokuState) -> Vec<((i8, i8), &'static str)> {
+    if state.rule != Rule::Renju {
+        return Vec::new();
+    }
+    empties(state)
+        .filter_map(|(c, r)| forbidden(&state.board, c, r, FORBIDDEN_DEPTH).map(|why| ((c, r), why)))
+        .collect()
+}
+
 #[cfg(test)]
 mod tests;
@@ -94,3 +94,144 @@ fn overline_six_still_wins() {
     );
     assert_eq!(status(&s), Outcome::Win(Stone::Black));
 }
+
+/// 直接擺子（不走合法性），`turn` 設為黑方。
+fn renju(black: &[(i8, i8)], white: &[(i8, i8)]) -> GomokuState {
+    let mut s = new_game(Rule::Renju, false);
+    for &(c, r) in black {
+        s.board[r as usize][c as usize] = Some(Stone::Black);
+    }
+    for &(c, r) in white {
+        s.board[r as usize][c as usize] = Some(Stone::White);
+    }
+    s.placed = (black.len() + white.len()) as u32;
+    s
+}
+
+#[test]
+fn standard_rule_overline_does_not_win() {
+    let mut s = new_game(Rule::Standard, false);
+    for c in 0..6 {
+        s.board[0][c] = Some(Stone::Black);
+    }
+    assert_eq!(status(&s), Outcome::Continue);
+    s.board[0][0] = None;
+    assert_eq!(status(&s), Outcome::Win(Stone::Black));
+}
+
+#[test]
+fn renju_double_three_is_forbidden() {
+    let s = renju(&[(5, 7), (6, 7), (7, 5), (7, 6)], &[]);
+    assert_eq!(is_legal(&s, 7, 7), Err("forbidden_double_three"));
+    // 其中一條被白擋住一端就不是活三
+    let s = renju(&[(5, 7), (6, 7), (7, 5), (7, 6)], &[(4, 7)]);
+    assert!(is_legal(&s, 7, 7).is_ok());
+}
+
+#[test]
+fn renju_split_three_counts() {
+    // 橫向跳三 ●_●● 也算活三
+    let s = renju(&[(4, 7), (6, 7), (7, 5), (7, 6)], &[]);
+    assert_eq!(is_legal(&s, 7, 7), Err("forbidden_double_three"));
+}
+
+#[test]
+fn renju_double_four_is_forbidden() {
+    // 橫三 + 直三（各有一端被擋）→ 下交點成兩個四
+    let s = renju(&[(4, 7), (5, 7), (6, 7), (7, 4), (7, 5), (7, 6)], &[(3, 7), (7, 3)]);
+    assert_eq!(is_legal(&s, 7, 7), Err("forbidden_double_four"));
+    // 同一線上的兩個四：●_●●●_● 中間補子
+    let s = renju(&[(3, 7), (5, 7), (7, 7), (9, 7)], &[]);
+    assert_eq!(is_legal(&s, 6, 7), Err("forbidden_double_four"));
+}
+
+#[test]
+fn renju_overline_is_forbidden_but_five_is_not() {
+    let s = renju(&[(0, 7), (1, 7), (2, 7), (4, 7), (5, 7)], &[]);
+    assert_eq!(is_legal(&s, 3, 7), Err("forbidden_overline"));
+    // 成五優先：同時形成雙三也可以下
+    let s = renju(&[(3, 7), (4, 7), (5, 7), (6, 7), (8, 5), (8, 6), (9, 8), (10, 9)], &[]);
+    assert!(is_legal(&s, 7, 7).is_ok());
+    // 白方不受禁手限制
+    let mut s = renju(&[], &[(0, 7), (1, 7), (2, 7), (4, 7), (5, 7)]);
+    s.turn = Stone::White;
+    assert!(is_legal(&s, 3, 7).is_ok());
+    apply(&mut s, 3, 7);
+    assert_eq!(status(&s), Outcome::Win(Stone::White));
+}
+
+#[test]
+fn swap2_place_three_then_choose() {
+    let mut s = new_game(Rule::Freestyle, true);
+    assert_eq!(actor(&s), Stone::Black);
+    place_seq(&mut s, &[(7, 7), (8, 8), (6, 8)]);
+    assert_eq!(s.opening, Opening::Choose);
+    assert_eq!(actor(&s), Stone::White);
+    assert_eq!(is_legal(&s, 0, 0), Err("choose_color"));
+    // 後手座位選黑 → 換座位，白方（原先手座位）落子
+    assert_eq!(choose(&mut s, Choice::Black), Ok(true));
+    assert_eq!(s.opening, Opening::Normal);
+    assert_eq!(actor(&s), Stone::White);
+    assert_eq!(choose(&mut s, Choice::White), Err("not_choosing"));
+}
+
+#[test]
+fn swap2_place_two_then_first_seat_chooses() {
+    let mut s = new_game(Rule::Freestyle, true);
+    place_seq(&mut s, &[(7, 7), (8, 8), (6, 8)]);
+    assert_eq!(choose(&mut s, Choice::PlaceTwo), Ok(false));
+    assert_eq!(actor(&s), Stone::White);
+    assert_eq!(s.turn, Stone::White); // 黑白交替：加擺的是白、黑各一
+    place_seq(&mut s, &[(9, 9), (5, 5)]);
+    assert_eq!(s.opening, Opening::FinalChoose);
+    assert_eq!(actor(&s), Stone::Black);
+    assert_eq!(choose(&mut s, Choice::PlaceTwo), Err("bad_choice"));
+    // 先手座位留黑 → 不換
+    assert_eq!(choose(&mut s, Choice::Black), Ok(false));
+    assert_eq!(actor(&s), Stone::White);
+}
+
+#[test]
+fn winning_lines_lists_coordinates() {
+    let mut s = initial_state();
+    for c in 2..7 {
+        s.board[3][c] = Some(Stone::White);
+    }
+    s.board[0][0] = Some(Stone::Black);
+    assert_eq!(winning_lines(&s), vec![(Stone::White, vec![(2, 3), (3, 3), (4, 3), (5, 3), (6, 3)])]);
+    // 標準規則的長連不算勝線
+    let mut s = new_game(Rule::Standard, false);
+    for c in 0..6 {
+        s.board[0][c] = Some(Stone::Black);
+    }
+    assert!(winning_lines(&s).is_empty());
+}
+
+#[test]
+fn win_and_four_points() {
+    // 黑活三 (5..8, 7)：兩端補上都成活四；白一子無威脅
+    let mut s = initial_state();
+    for c in 5..8 {
+        s.board[7][c] = Some(Stone::Black);
+    }
+    s.board[0][0] = Some(Stone::White);
+    assert!(win_points(&s, Stone::Black).is_empty());
+    let fours = four_points(&s, Stone::Black);
+    assert!(fours.contains(&((4, 7), 2)) && fours.contains(&((8, 7), 2)));
+    // 跳一格補成的是死四（只剩一個致勝點）
+    assert!(fours.contains(&((9, 7), 1)));
+    assert!(four_points(&s, Stone::White).is_empty());
+
+    // 補成四之後，兩端都是致勝點
+    s.board[7][8] = Some(Stone::Black);
+    assert_eq!(win_points(&s, Stone::Black), vec![(4, 7), (9, 7)]);
+}
+
+#[test]
+fn forbidden_points_only_under_renju() {
+    let s = renju(&[(5, 7), (6, 7), (7, 5), (7, 6)], &[]);
+    assert!(forbidden_points(&s).contains(&((7, 7), "forbidden_double_three")));
+    let mut s = s;
+    s.rule = Rule::Freestyle;
+    assert!(forbidden_points(&s).is_empty());
+}
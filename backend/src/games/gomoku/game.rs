@@ -2,11 +2,28 @@
 
 use serde_json::{json, Value};
 
-use super::engine::{self, GomokuState, Outcome, Stone};
+use super::bot;
+use super::engine::{self, Choice, GomokuState, Opening, Outcome, Rule, Stone};
+use super::notation;
+use crate::games::common::bot::{BotLevel, GameBot};
 use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+use crate::games::common::notation::{ExportMeta, Exported};
 
+#[derive(Clone)]
 pub struct GomokuGame(GomokuState);
 
+pub struct GomokuBot;
+
+impl GameBot<GomokuGame> for GomokuBot {
+    fn choose(engine: &GomokuGame, level: BotLevel) -> Option<Value> {
+        if matches!(engine.0.opening, Opening::Choose | Opening::FinalChoose) {
+            let color = bot::choose_color(&engine.0);
+            return Some(json!({ "choose": GomokuGame::side_label(to_common(color)) }));
+        }
+        bot::choose(&engine.0, level).map(|(col, row)| json!({ "at": [col, row] }))
+    }
+}
+
 fn to_common(s: Stone) -> Side {
     match s {
         Stone::Black => Side::First,
@@ -25,13 +42,25 @@ fn parse_at(v: Option<&Value>) -> Option<(i8, i8)> {
 
 impl GameEngine for GomokuGame {
     const NAME: &'static str = "gomoku";
+    type Bot = GomokuBot;
+
+    /// `{"rule": "freestyle"|"standard"|"renju", "swap2": bool}`，缺的欄位取預設（自由規則、一般開局）。
+    fn parse_options(v: &Value) -> Option<Value> {
+        let empty = serde_json::Map::new();
+        let o = if v.is_null() { &empty } else { v.as_object()? };
+        let rule = o.get("rule").map_or(Some("freestyle"), Value::as_str).and_then(Rule::parse)?;
+        let swap2 = o.get("swap2").map_or(Some(false), Value::as_bool)?;
+        Some(json!({ "rule": rule.as_str(), "swap2": swap2 }))
+    }
 
-    fn initial() -> Self {
-        GomokuGame(engine::initial_state())
+    fn initial(options: &Value) -> Self {
+        let rule = options["rule"].as_str().and_then(Rule::parse).unwrap_or(Rule::Freestyle);
+        GomokuGame(engine::new_game(rule, options["swap2"].as_bool() == Some(true)))
     }
 
+    /// Swap2 開局期間輪到的是動作的座位，不一定是下一顆子的顏色。
     fn turn(&self) -> Side {
-        to_common(self.0.turn)
+        to_common(engine::actor(&self.0))
     }
 
     fn side_label(side: Side) -> &'static str {
@@ -42,6 +71,26 @@ impl GameEngine for GomokuGame {
     }
 
     fn try_move(&mut self, mover: Side, data: Option<&Value>) -> Result<Applied, String> {
+        // Swap2 選色
+        if let Some(choice) = data.and_then(|d| d.get("choose")) {
+            let choice = match choice.as_str() {
+                Some("black") => Choice::Black,
+                Some("white") => Choice::White,
+                Some("place_two") => Choice::PlaceTwo,
+                _ => return Err("bad_choice".into()),
+            };
+            let swap_seats = engine::choose(&mut self.0, choice).map_err(|e| e.to_string())?;
+            let choice = match choice {
+                Choice::Black => "black",
+                Choice::White => "white",
+                Choice::PlaceTwo => "place_two",
+            };
+            return Ok(Applied {
+                move_data: json!({ "choose": choice, "by": Self::side_label(mover) }),
+                extra: Vec::new(),
+                swap_seats,
+            });
+        }
         let Some((col, row)) = parse_at(data.and_then(|d| d.get("at"))) else {
             return Err("bad_coord".into());
         };
@@ -50,6 +99,7 @@ impl GameEngine for GomokuGame {
         Ok(Applied {
             move_data: json!({ "at": [col, row], "by": Self::side_label(mover) }),
             extra: Vec::new(),
+            swap_seats: false,
         })
     }
 
@@ -63,4 +113,37 @@ impl GameEngine for GomokuGame {
             Outcome::Draw => GameStatus::Draw { reason: "draw_full" },
         }
     }
+
+    fn snapshot(&self) -> Value {
+        let mut stones = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(s) = cell {
+                    stones.push(json!({ "at": [col, row], "color": Self::side_label(to_common(*s)) }));
+                }
+            }
+        }
+        json!({
+            "stones": stones,
+            "rule": self.0.rule.as_str(),
+            "opening": self.0.opening.as_str(),
+            // 下一顆子的顏色（Swap2 擺子時與輪到的座位不同）
+            "stone": Self::side_label(to_common(self.0.turn)),
+        })
+    }
+
+    fn export(plies: &[(&Self, &Value)], last: &Self, meta: &ExportMeta) -> Option<Exported> {
+        // Swap2 選色不是棋步；落子本身仍是黑白交替
+        let moves = plies
+            .iter()
+            .filter(|(_, data)| data.get("choose").is_none())
+            .map(|(_, data)| parse_at(data.get("at")))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Exported {
+            format: "gomoku",
+            extension: "txt",
+            mime: "text/plain; charset=utf-8",
+            text: notation::record(&moves, last.0.rule, meta),
+        })
+    }
 }
@@ -0,0 +1,51 @@
+//! 五子棋記譜：簡單座標棋譜（PGN 風格 tag + 編號走步）。
+//!
+//! 座標同常見連珠軟體：行 a–o（左→右 = col 0–14）、列 1–15（row 0–14）。
+
+use super::engine::Rule;
+use crate::games::common::notation::{pgn_movetext, pgn_result, pgn_tag, ExportMeta};
+
+pub fn coord(col: i8, row: i8) -> String {
+    format!("{}{}", (b'a' + col as u8) as char, row + 1)
+}
+
+/// 整局棋譜。`moves` 為依序的落點（黑先）。
+pub fn record(moves: &[(i8, i8)], rule: Rule, meta: &ExportMeta) -> String {
+    let result = pgn_result(meta.result.as_ref());
+    let mut out = String::new();
+    pgn_tag(&mut out, "Game", "Gomoku");
+    pgn_tag(&mut out, "Event", &meta.event);
+    pgn_tag(&mut out, "Date", &meta.date.format("%Y.%m.%d").to_string());
+    pgn_tag(&mut out, "Black", &meta.players[0]);
+    pgn_tag(&mut out, "White", &meta.players[1]);
+    pgn_tag(&mut out, "Result", result);
+    pgn_tag(&mut out, "Rule", rule.as_str());
+    if let Some(r) = &meta.result {
+        pgn_tag(&mut out, "Termination", &r.reason);
+    }
+    out.push('\n');
+    let coords: Vec<String> = moves.iter().map(|&(c, r)| coord(c, r)).collect();
+    out.push_str(&pgn_movetext(&coords, result));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::common::engine::Side;
+    use crate::games::common::notation::ExportResult;
+
+    #[test]
+    fn record_lists_coordinates() {
+        let meta = ExportMeta {
+            event: "桌 #1".into(),
+            date: chrono::DateTime::from_timestamp(0, 0).unwrap(),
+            players: ["?".into(), "?".into()],
+            result: Some(ExportResult { winner: Some(Side::First), reason: "five_in_row".into() }),
+            format: None,
+        };
+        let text = record(&[(7, 7), (8, 8), (0, 14)], Rule::Renju, &meta);
+        assert!(text.contains("[Result \"1-0\"]\n[Rule \"renju\"]"));
+        assert!(text.ends_with("1. h8 i9 2. a15 1-0\n"), "{text}");
+    }
+}
@@ -9,4 +9,7 @@ pub mod farm;
 pub mod go;
 pub mod gomoku;
 pub mod registry;
+pub mod reversi;
+pub mod shogi;
+pub mod tournament;
 pub mod western_chess;
@@ -1,30 +1,40 @@
 //! 遊戲註冊表：用 enum 收斂所有遊戲 hub，避免 AppState / ws / routes 每加一款遊戲就改一次。
 //!
-//! 新增遊戲只需動本檔：① `AnyHub` 加 variant ② 三個 method 各加一臂 ③ `new()` 註冊一行。
+//! 新增遊戲只需動本檔：① `AnyHub` 加 variant ② 各 method 各加一臂 ③ `new()` 註冊一行。
 //! state.rs / ws.rs / routes.rs 全 game-agnostic，不必再改。
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 
+use chrono::{DateTime, Utc};
 use serde::Serialize;
 use serde_json::Value;
 use tokio::sync::Mutex;
+use tokio::time::Instant;
 
 use crate::games::avalon::hub::AvalonHub;
 use crate::games::avalon::service as avalon_service;
 use crate::games::banqi::game::BanqiGame;
 use crate::games::chess::game::ChessGame;
-use crate::games::common::engine::GameEngine;
-use crate::games::common::hub::{GameHub, HubInner, TableState};
-use crate::games::common::room::{RoomHub, RoomKind, RoomState};
+use crate::games::common::chat::ChatLine;
+use crate::games::common::clock::TimeControl;
+use crate::games::common::engine::{GameEngine, Side};
+use crate::games::common::hub::{GameHub, HubInner, TableState, BOT_SEAT};
+use crate::games::common::notation::{self, Exported};
+use crate::games::common::persist;
+use crate::games::common::room::{self, RoomHub, RoomKind, RoomState};
 use crate::games::common::service;
 use crate::games::farm::hub::FarmHub;
 use crate::games::farm::service as farm_service;
 use crate::games::go::game::GoGame;
 use crate::games::gomoku::game::GomokuGame;
+use crate::games::reversi::game::ReversiGame;
+use crate::games::shogi::game::ShogiGame;
+use crate::games::tournament::RoundPlan;
 use crate::games::western_chess::game::WesternChessGame;
-use crate::state::AppState;
+use crate::state::{AppState, DisplayTrackedConnection};
+use crate::structs::game_records::GameRecord;
 
 fn new_hub<E: GameEngine>() -> GameHub<E> {
     Arc::new(Mutex::new(HubInner::default()))
@@ -45,6 +55,8 @@ pub struct GameSummary {
     pub queued: usize,
     /// 訂閱大廳更新的連線數。
     pub lobby: usize,
+    /// 觀戰中的連線數（無觀戰機制的子系統為 0）。
+    pub spectators: usize,
 }
 
 /// 2 人泛型 hub 的統計。
@@ -65,6 +77,7 @@ async fn summarize_2p<E>(game: &'static str, hub: &GameHub<E>) -> GameSummary {
         players_in_game: playing * 2,
         queued: h.queue.len(),
         lobby: h.lobby.len(),
+        spectators: h.spectating.len(),
     }
 }
 
@@ -90,9 +103,154 @@ async fn summarize_room<K: RoomKind>(game: &'static str, hub: &RoomHub<K>) -> Ga
         players_in_game,
         queued: 0,
         lobby: h.lobby.len(),
+        spectators: 0,
     }
 }
 
+/// 後台對局檢視的一桌 / 一房（含玩家連線與盤面，只給有 `GameRead` 的後台看）。
+#[derive(Serialize)]
+pub struct LiveTable {
+    pub game: &'static str,
+    /// 桌 / 房 id（關桌、強制結束用）。
+    pub id: u64,
+    pub name: String,
+    /// `waiting` / `playing`。
+    pub status: &'static str,
+    /// 私人桌（憑邀請碼）。N 人房恆為 false。
+    pub private: bool,
+    pub options: Value,
+    pub seats: Vec<LiveSeat>,
+    /// 觀戰連線位址（N 人房無觀戰）。
+    pub spectators: Vec<String>,
+    /// 盤面：2 人桌為 `{turn, position, move_count}`，N 人房為完整對局狀態（含隱藏資訊）。等待中為 None。
+    pub board: Option<Value>,
+    pub started_at: Option<DateTime<Utc>>,
+    /// 對電腦局。
+    pub bot: bool,
+    /// 錦標賽對局的賽事 id（這類桌不可關，只能強制結束）。
+    pub tournament_id: Option<i64>,
+    /// 桌內最近聊天（含被過濾前的原文）。N 人房不留存，恆為空。
+    pub chat: Vec<ChatLine>,
+}
+
+#[derive(Serialize)]
+pub struct LiveSeat {
+    pub seat: usize,
+    /// 2 人桌為 side label（等待中為 `host`），N 人房為暱稱。
+    pub label: String,
+    /// 佔座連線；電腦座位或斷線等重連中為 None。
+    pub addr: Option<String>,
+    pub member_id: Option<i64>,
+    /// 斷線座位距重連期限的毫秒數。
+    pub reconnect_in_ms: Option<u64>,
+    /// 連線的握手資訊（IP / UA / 登入身分），由 service 層依 `addr` 補上。
+    pub connection: Option<DisplayTrackedConnection>,
+}
+
+impl LiveSeat {
+    fn new(seat: usize, label: String, addr: Option<SocketAddr>, deadline: Option<Instant>) -> Self {
+        LiveSeat {
+            seat,
+            label,
+            addr: addr.filter(|_| deadline.is_none()).map(|a| a.to_string()),
+            member_id: None,
+            reconnect_in_ms: deadline.map(|d| d.saturating_duration_since(Instant::now()).as_millis() as u64),
+            connection: None,
+        }
+    }
+}
+
+/// 2 人泛型 hub 的對局明細，依桌 id 排序。
+async fn live_2p<E: GameEngine>(game: &'static str, hub: &GameHub<E>) -> Vec<LiveTable> {
+    let h = hub.lock().await;
+    let mut out: Vec<LiveTable> = h
+        .tables
+        .values()
+        .map(|t| {
+            let mut live = LiveTable {
+                game,
+                id: t.id,
+                name: t.name.clone(),
+                status: "waiting",
+                private: t.invite.is_some(),
+                options: t.options.clone(),
+                seats: Vec::new(),
+                spectators: Vec::new(),
+                board: None,
+                started_at: None,
+                bot: false,
+                tournament_id: None,
+                chat: t.chat.iter().cloned().collect(),
+            };
+            match &t.state {
+                TableState::Waiting { host } => {
+                    let mut seat = LiveSeat::new(0, "host".into(), Some(*host), None);
+                    seat.member_id = h.members.get(host).copied();
+                    live.seats.push(seat);
+                }
+                TableState::Playing(g) => {
+                    live.status = "playing";
+                    for side in [Side::First, Side::Second] {
+                        let i = side.index();
+                        let addr = Some(g.seats[i]).filter(|&a| a != BOT_SEAT);
+                        let mut seat = LiveSeat::new(i, E::side_label(side).into(), addr, g.reconnect_deadline[i]);
+                        seat.member_id = g.members[i];
+                        live.seats.push(seat);
+                    }
+                    let mut spectators: Vec<String> = g.spectators.iter().map(|a| a.to_string()).collect();
+                    spectators.sort();
+                    live.spectators = spectators;
+                    live.board = Some(serde_json::json!({
+                        "turn": E::side_label(g.engine.turn()),
+                        "position": g.engine.snapshot(),
+                        "move_count": g.moves.len(),
+                    }));
+                    live.started_at = Some(g.started_at);
+                    live.bot = g.bot.is_some();
+                    live.tournament_id = g.tournament.map(|t| t.tournament_id);
+                }
+            }
+            live
+        })
+        .collect();
+    out.sort_by_key(|t| t.id);
+    out
+}
+
+/// N 人房泛型 hub 的對局明細，依房 id 排序。
+async fn live_room<K: RoomKind>(game: &'static str, hub: &RoomHub<K>) -> Vec<LiveTable> {
+    let h = hub.lock().await;
+    let mut out: Vec<LiveTable> = h
+        .rooms
+        .values()
+        .map(|r| LiveTable {
+            game,
+            id: r.id,
+            name: r.name.clone(),
+            status: if matches!(r.state, RoomState::Waiting) { "waiting" } else { "playing" },
+            private: false,
+            options: serde_json::to_value(&r.options).unwrap_or_default(),
+            seats: r
+                .players
+                .iter()
+                .enumerate()
+                .map(|(i, &p)| LiveSeat::new(i, r.names[i].clone(), Some(p), r.reconnect_deadline.get(i).copied().flatten()))
+                .collect(),
+            spectators: Vec::new(),
+            board: match &r.state {
+                RoomState::Waiting => None,
+                RoomState::Playing(s) => serde_json::to_value(s).ok(),
+            },
+            started_at: None,
+            bot: false,
+            tournament_id: None,
+            chat: Vec::new(),
+        })
+        .collect();
+    out.sort_by_key(|t| t.id);
+    out
+}
+
 /// 型別化的遊戲 hub。每臂為具體 `E`，match 後單型化呼叫泛型 `service::*`（無 dyn / 無 async-trait）。
 pub enum AnyHub {
     Chess(GameHub<ChessGame>),
@@ -100,6 +258,8 @@ pub enum AnyHub {
     Banqi(GameHub<BanqiGame>),
     WesternChess(GameHub<WesternChessGame>),
     Go(GameHub<GoGame>),
+    Reversi(GameHub<ReversiGame>),
+    Shogi(GameHub<ShogiGame>),
     /// 阿瓦隆：獨立子系統（非 GameEngine 2 人框架）。
     Avalon(AvalonHub),
     /// 農場經營：N 人 worker-placement，獨立子系統。
@@ -114,6 +274,8 @@ impl AnyHub {
             AnyHub::Banqi(h) => service::handle(h, state, who, value).await,
             AnyHub::WesternChess(h) => service::handle(h, state, who, value).await,
             AnyHub::Go(h) => service::handle(h, state, who, value).await,
+            AnyHub::Reversi(h) => service::handle(h, state, who, value).await,
+            AnyHub::Shogi(h) => service::handle(h, state, who, value).await,
             AnyHub::Avalon(h) => avalon_service::handle(h, state, who, value).await,
             AnyHub::Farm(h) => farm_service::handle(h, state, who, value).await,
         }
@@ -126,6 +288,8 @@ impl AnyHub {
             AnyHub::Banqi(h) => service::handle_disconnect(h, state, who).await,
             AnyHub::WesternChess(h) => service::handle_disconnect(h, state, who).await,
             AnyHub::Go(h) => service::handle_disconnect(h, state, who).await,
+            AnyHub::Reversi(h) => service::handle_disconnect(h, state, who).await,
+            AnyHub::Shogi(h) => service::handle_disconnect(h, state, who).await,
             AnyHub::Avalon(h) => avalon_service::handle_disconnect(h, state, who).await,
             AnyHub::Farm(h) => farm_service::handle_disconnect(h, state, who).await,
         }
@@ -139,11 +303,178 @@ impl AnyHub {
             AnyHub::Banqi(h) => summarize_2p(game, h).await,
             AnyHub::WesternChess(h) => summarize_2p(game, h).await,
             AnyHub::Go(h) => summarize_2p(game, h).await,
+            AnyHub::Reversi(h) => summarize_2p(game, h).await,
+            AnyHub::Shogi(h) => summarize_2p(game, h).await,
             AnyHub::Avalon(h) => summarize_room(game, h).await,
             AnyHub::Farm(h) => summarize_room(game, h).await,
         }
     }
 
+    /// 進行中對局的快照（重啟保存，見 `common::persist`）。
+    pub async fn save(&self) -> Value {
+        match self {
+            AnyHub::Chess(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Gomoku(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Banqi(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::WesternChess(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Go(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Reversi(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Shogi(h) => persist::save_tables(&*h.lock().await),
+            AnyHub::Avalon(h) => persist::save_rooms(&*h.lock().await),
+            AnyHub::Farm(h) => persist::save_rooms(&*h.lock().await),
+        }
+    }
+
+    /// 還原 `save` 的快照，回傳還原的桌 / 房數。
+    pub async fn restore(&self, state: &AppState, saved: Value) -> usize {
+        match self {
+            AnyHub::Chess(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Gomoku(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Banqi(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::WesternChess(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Go(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Reversi(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Shogi(h) => persist::restore_tables(h, state, saved).await,
+            AnyHub::Avalon(h) => persist::restore_rooms(h, state, saved).await,
+            AnyHub::Farm(h) => persist::restore_rooms(h, state, saved).await,
+        }
+    }
+
+    /// 後台對局檢視：每桌 / 房的玩家、觀戰與盤面。
+    pub async fn live(&self, game: &'static str) -> Vec<LiveTable> {
+        match self {
+            AnyHub::Chess(h) => live_2p(game, h).await,
+            AnyHub::Gomoku(h) => live_2p(game, h).await,
+            AnyHub::Banqi(h) => live_2p(game, h).await,
+            AnyHub::WesternChess(h) => live_2p(game, h).await,
+            AnyHub::Go(h) => live_2p(game, h).await,
+            AnyHub::Reversi(h) => live_2p(game, h).await,
+            AnyHub::Shogi(h) => live_2p(game, h).await,
+            AnyHub::Avalon(h) => live_room(game, h).await,
+            AnyHub::Farm(h) => live_room(game, h).await,
+        }
+    }
+
+    /// 後台檢視大廳最近聊天（含被過濾前的原文）。N 人房沒有大廳聊天，回空。
+    pub async fn lobby_chat(&self) -> Vec<ChatLine> {
+        fn of<E>(h: &HubInner<E>) -> Vec<ChatLine> {
+            h.chat.lobby.iter().cloned().collect()
+        }
+        match self {
+            AnyHub::Chess(h) => of(&*h.lock().await),
+            AnyHub::Gomoku(h) => of(&*h.lock().await),
+            AnyHub::Banqi(h) => of(&*h.lock().await),
+            AnyHub::WesternChess(h) => of(&*h.lock().await),
+            AnyHub::Go(h) => of(&*h.lock().await),
+            AnyHub::Reversi(h) => of(&*h.lock().await),
+            AnyHub::Shogi(h) => of(&*h.lock().await),
+            AnyHub::Avalon(_) | AnyHub::Farm(_) => Vec::new(),
+        }
+    }
+
+    /// 後台強制結束對局（`winner` 為 side label，None＝和棋）。N 人房無勝負可判，只能關房。
+    pub async fn force_end(&self, state: &AppState, id: u64, winner: Option<&str>) -> Result<(), &'static str> {
+        match self {
+            AnyHub::Chess(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Gomoku(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Banqi(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::WesternChess(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Go(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Reversi(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Shogi(h) => service::admin_end(h, state, id, winner).await,
+            AnyHub::Avalon(_) | AnyHub::Farm(_) => Err("unsupported_game"),
+        }
+    }
+
+    /// 後台關桌 / 關房（對局中即中止、不寫紀錄）。
+    pub async fn close(&self, state: &AppState, id: u64) -> Result<(), &'static str> {
+        match self {
+            AnyHub::Chess(h) => service::admin_close(h, state, id).await,
+            AnyHub::Gomoku(h) => service::admin_close(h, state, id).await,
+            AnyHub::Banqi(h) => service::admin_close(h, state, id).await,
+            AnyHub::WesternChess(h) => service::admin_close(h, state, id).await,
+            AnyHub::Go(h) => service::admin_close(h, state, id).await,
+            AnyHub::Reversi(h) => service::admin_close(h, state, id).await,
+            AnyHub::Shogi(h) => service::admin_close(h, state, id).await,
+            AnyHub::Avalon(h) => room::admin_close(h, state, id).await,
+            AnyHub::Farm(h) => room::admin_close(h, state, id).await,
+        }
+    }
+
+    /// 後台把連線踢出本遊戲（大廳 / 桌 / 房 / 觀戰）。
+    pub async fn kick(&self, state: &AppState, who: SocketAddr) -> Result<(), &'static str> {
+        match self {
+            AnyHub::Chess(h) => service::kick(h, state, who).await,
+            AnyHub::Gomoku(h) => service::kick(h, state, who).await,
+            AnyHub::Banqi(h) => service::kick(h, state, who).await,
+            AnyHub::WesternChess(h) => service::kick(h, state, who).await,
+            AnyHub::Go(h) => service::kick(h, state, who).await,
+            AnyHub::Reversi(h) => service::kick(h, state, who).await,
+            AnyHub::Shogi(h) => service::kick(h, state, who).await,
+            AnyHub::Avalon(h) => room::kick(h, state, who).await,
+            AnyHub::Farm(h) => room::kick(h, state, who).await,
+        }
+    }
+
+    /// 是否為 2 人 `GameEngine` 框架的遊戲（積分 / 錦標賽只涵蓋這類）。
+    /// 反向列 N 人房，新增 2 人遊戲不必改這裡。
+    pub fn is_two_player(&self) -> bool {
+        !matches!(self, AnyHub::Avalon(_) | AnyHub::Farm(_))
+    }
+
+    /// 錦標賽的計時制 / 規則選項（`data.time_control` + `data.options`，同建桌）。N 人房不支援。
+    pub fn parse_setup(&self, data: &Value) -> Result<(TimeControl, Value), &'static str> {
+        match self {
+            AnyHub::Chess(_) => service::parse_setup::<ChessGame>(Some(data)),
+            AnyHub::Gomoku(_) => service::parse_setup::<GomokuGame>(Some(data)),
+            AnyHub::Banqi(_) => service::parse_setup::<BanqiGame>(Some(data)),
+            AnyHub::WesternChess(_) => service::parse_setup::<WesternChessGame>(Some(data)),
+            AnyHub::Go(_) => service::parse_setup::<GoGame>(Some(data)),
+            AnyHub::Reversi(_) => service::parse_setup::<ReversiGame>(Some(data)),
+            AnyHub::Shogi(_) => service::parse_setup::<ShogiGame>(Some(data)),
+            AnyHub::Avalon(_) | AnyHub::Farm(_) => Err("unsupported_game"),
+        }
+    }
+
+    /// 錦標賽一輪開桌，回傳不戰的對陣（見 `service::seat_tournament_round`）。N 人房無事。
+    pub async fn seat_tournament_round(
+        &self,
+        state: &AppState,
+        plan: &RoundPlan,
+        conns: &[(SocketAddr, i64)],
+    ) -> Vec<(i64, [bool; 2])> {
+        match self {
+            AnyHub::Chess(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Gomoku(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Banqi(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::WesternChess(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Go(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Reversi(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Shogi(h) => service::seat_tournament_round(h, state, plan, conns).await,
+            AnyHub::Avalon(_) | AnyHub::Farm(_) => Vec::new(),
+        }
+    }
+
+    pub async fn announce_tournament(
+        &self,
+        state: &AppState,
+        entrants: &[i64],
+        conns: &[(SocketAddr, i64)],
+        typ: &str,
+        data: Value,
+    ) {
+        match self {
+            AnyHub::Chess(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Gomoku(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Banqi(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::WesternChess(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Go(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Reversi(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Shogi(h) => service::announce_tournament(h, state, entrants, conns, typ, data).await,
+            AnyHub::Avalon(_) | AnyHub::Farm(_) => {}
+        }
+    }
+
     pub fn spawn_watcher(&self, state: AppState) {
         match self {
             AnyHub::Chess(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
@@ -151,11 +482,26 @@ impl AnyHub {
             AnyHub::Banqi(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
             AnyHub::WesternChess(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
             AnyHub::Go(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
-            AnyHub::Avalon(_) | AnyHub::Farm(_) => return, // 無計時，不需 watcher
+            AnyHub::Reversi(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
+            AnyHub::Shogi(h) => tokio::spawn(service::timeout_watcher(h.clone(), state)),
+            // 無計時，只掃斷線寬限期
+            AnyHub::Avalon(h) => tokio::spawn(room::grace_watcher(h.clone(), state)),
+            AnyHub::Farm(h) => tokio::spawn(room::grace_watcher(h.clone(), state)),
         };
     }
 }
 
+/// 完局紀錄的記譜匯出，依 `rec.game` 分派。暗棋（翻子隨機，重播不出）、黑白棋 / 將棋（未接記譜）與 N 人房回 None。
+pub fn export_record(rec: &GameRecord, format: Option<String>) -> Option<Exported> {
+    match rec.game.as_str() {
+        ChessGame::NAME => notation::export_record::<ChessGame>(rec, format),
+        GomokuGame::NAME => notation::export_record::<GomokuGame>(rec, format),
+        WesternChessGame::NAME => notation::export_record::<WesternChessGame>(rec, format),
+        GoGame::NAME => notation::export_record::<GoGame>(rec, format),
+        _ => None,
+    }
+}
+
 /// 所有遊戲 hub，依 `game` 名索引。
 pub struct GameRegistry(HashMap<&'static str, AnyHub>);
 
@@ -167,6 +513,8 @@ impl GameRegistry {
         m.insert(BanqiGame::NAME, AnyHub::Banqi(new_hub()));
         m.insert(WesternChessGame::NAME, AnyHub::WesternChess(new_hub()));
         m.insert(GoGame::NAME, AnyHub::Go(new_hub()));
+        m.insert(ReversiGame::NAME, AnyHub::Reversi(new_hub()));
+        m.insert(ShogiGame::NAME, AnyHub::Shogi(new_hub()));
         m.insert(crate::games::avalon::NAME, AnyHub::Avalon(Arc::new(Mutex::new(Default::default()))));
         m.insert(crate::games::farm::NAME, AnyHub::Farm(Arc::new(Mutex::new(Default::default()))));
         GameRegistry(m)
@@ -180,6 +528,16 @@ impl GameRegistry {
         self.0.values()
     }
 
+    /// `(game 名, hub)` 全列（重啟保存依名字存 key）。
+    pub fn entries(&self) -> impl Iterator<Item = (&'static str, &AnyHub)> {
+        self.0.iter().map(|(&name, hub)| (name, hub))
+    }
+
+    /// `game` 是否為有積分的遊戲（已註冊的 2 人對戰）。
+    pub fn is_rated_game(&self, game: &str) -> bool {
+        self.get(game).is_some_and(AnyHub::is_two_player)
+    }
+
     /// 所有遊戲的即時統計快照，依 game 名排序（輸出穩定）。
     pub async fn summaries(&self) -> Vec<GameSummary> {
         let mut out = Vec::with_capacity(self.0.len());
@@ -196,3 +554,66 @@ impl Default for GameRegistry {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::common::bot::BotLevel;
+    use crate::games::common::hub::{Game, Table};
+    use std::collections::VecDeque;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn live_2p_hides_bot_and_disconnected_seats() {
+        let hub: GameHub<ChessGame> = new_hub();
+        let human = SocketAddr::from(([127, 0, 0, 1], 4000));
+        let watcher = SocketAddr::from(([127, 0, 0, 1], 4001));
+        let mut game = Game::new(human, BOT_SEAT, [Some(9), None], TimeControl::fischer(60_000, 0), Value::Null);
+        game.bot = Some(BotLevel::Easy);
+        game.spectators.insert(watcher);
+        game.reconnect_deadline[0] = Some(Instant::now() + Duration::from_secs(30));
+        {
+            let mut h = hub.lock().await;
+            h.tables.insert(
+                3,
+                Table {
+                    id: 3,
+                    name: "桌 #3".into(),
+                    time_control: game.clock.control,
+                    options: Value::Null,
+                    invite: Some("ABC234".into()),
+                    state: TableState::Playing(game),
+                    chat: VecDeque::new(),
+                },
+            );
+            h.tables.insert(
+                1,
+                Table {
+                    id: 1,
+                    name: "桌 #1".into(),
+                    time_control: TimeControl::fischer(60_000, 0),
+                    options: Value::Null,
+                    invite: None,
+                    state: TableState::Waiting { host: watcher },
+                    chat: VecDeque::new(),
+                },
+            );
+        }
+
+        let live = live_2p("chess", &hub).await;
+        assert_eq!(live.iter().map(|t| t.id).collect::<Vec<_>>(), [1, 3]);
+        assert_eq!(live[0].status, "waiting");
+        assert_eq!(live[0].seats[0].addr.as_deref(), Some("127.0.0.1:4001"));
+
+        let t = &live[1];
+        assert!(t.private && t.bot);
+        assert_eq!(t.spectators, ["127.0.0.1:4001"]);
+        assert_eq!(t.board.as_ref().unwrap()["move_count"], 0);
+        // 斷線等重連：不列位址、帶剩餘毫秒；電腦座位沒有連線
+        assert_eq!(t.seats[0].addr, None);
+        assert_eq!(t.seats[0].member_id, Some(9));
+        assert!(t.seats[0].reconnect_in_ms.is_some_and(|ms| ms > 20_000));
+        assert_eq!(t.seats[1].addr, None);
+        assert_eq!(t.seats[1].reconnect_in_ms, None);
+    }
+}
@@ -0,0 +1,162 @@
+//! 黑白棋規則引擎 — 純函式，可單測。8×8，黑先，中央交叉四子開局。
+//!
+//! 規則要點：
+//! - 落子須至少夾住一串對方子（八方向），被夾住的全部翻面。
+//! - 輪到的一方無處可下 → 自動虛手，由對方續下；雙方都無處可下 → 終局。
+//! - 終局以盤上子數定勝負，同數和局。
+
+pub const SIZE: i8 = 8;
+
+const DIRS: [(i8, i8); 8] = [(1, 0), (-1, 0), (0, 1), (0, -1), (1, 1), (1, -1), (-1, 1), (-1, -1)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Disc {
+    Black,
+    White,
+}
+
+impl Disc {
+    pub fn opponent(self) -> Disc {
+        match self {
+            Disc::Black => Disc::White,
+            Disc::White => Disc::Black,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Win(Disc),
+    Draw,
+}
+
+#[derive(Clone)]
+pub struct ReversiState {
+    pub board: [[Option<Disc>; 8]; 8], // board[row][col]
+    pub turn: Disc,
+}
+
+/// 一步落子的結果：被翻的子，以及對方是否因無處可下而被自動虛手（此時仍由落子方續下）。
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placed {
+    pub flipped: Vec<(i8, i8)>,
+    pub opponent_passed: bool,
+}
+
+pub fn initial_state() -> ReversiState {
+    let mut board = [[None; 8]; 8];
+    board[3][3] = Some(Disc::White);
+    board[4][4] = Some(Disc::White);
+    board[3][4] = Some(Disc::Black);
+    board[4][3] = Some(Disc::Black);
+    ReversiState { board, turn: Disc::Black }
+}
+
+fn in_bounds(col: i8, row: i8) -> bool {
+    (0..SIZE).contains(&col) && (0..SIZE).contains(&row)
+}
+
+fn at(state: &ReversiState, col: i8, row: i8) -> Option<Disc> {
+    if in_bounds(col, row) {
+        state.board[row as usize][col as usize]
+    } else {
+        None
+    }
+}
+
+/// `disc` 下在 `(col,row)` 會翻的子（假設該點為空）。空 = 不合法。
+fn flips(state: &ReversiState, col: i8, row: i8, disc: Disc) -> Vec<(i8, i8)> {
+    let mut out = Vec::new();
+    for (dc, dr) in DIRS {
+        let mut line = Vec::new();
+        let (mut c, mut r) = (col + dc, row + dr);
+        while at(state, c, r) == Some(disc.opponent()) {
+            line.push((c, r));
+            c += dc;
+            r += dr;
+        }
+        // 夾住：對方子串後面接著己方子
+        if !line.is_empty() && at(state, c, r) == Some(disc) {
+            out.extend(line);
+        }
+    }
+    out
+}
+
+/// `disc` 所有可下的點。
+pub fn legal_moves(state: &ReversiState, disc: Disc) -> Vec<(i8, i8)> {
+    let mut out = Vec::new();
+    for row in 0..SIZE {
+        for col in 0..SIZE {
+            if state.board[row as usize][col as usize].is_none() && !flips(state, col, row, disc).is_empty() {
+                out.push((col, row));
+            }
+        }
+    }
+    out
+}
+
+fn has_move(state: &ReversiState, disc: Disc) -> bool {
+    (0..SIZE).any(|row| {
+        (0..SIZE).any(|col| state.board[row as usize][col as usize].is_none() && !flips(state, col, row, disc).is_empty())
+    })
+}
+
+/// 落子合法性：界內、空格、至少翻一子。
+pub fn is_legal(state: &ReversiState, col: i8, row: i8) -> Result<(), &'static str> {
+    if !in_bounds(col, row) {
+        return Err("bad_coord");
+    }
+    if state.board[row as usize][col as usize].is_some() {
+        return Err("occupied");
+    }
+    if flips(state, col, row, state.turn).is_empty() {
+        return Err("no_flips");
+    }
+    Ok(())
+}
+
+/// 套用落子（假設已合法）：翻子、換手；對方無處可下而己方還能下時自動虛手，輪回己方。
+pub fn apply(state: &mut ReversiState, col: i8, row: i8) -> Placed {
+    let disc = state.turn;
+    let flipped = flips(state, col, row, disc);
+    state.board[row as usize][col as usize] = Some(disc);
+    for &(c, r) in &flipped {
+        state.board[r as usize][c as usize] = Some(disc);
+    }
+    let opponent_passed = !has_move(state, disc.opponent()) && has_move(state, disc);
+    if !opponent_passed {
+        state.turn = disc.opponent();
+    }
+    Placed { flipped, opponent_passed }
+}
+
+/// 盤上子數 (黑, 白)。
+pub fn counts(state: &ReversiState) -> (u32, u32) {
+    let mut black = 0;
+    let mut white = 0;
+    for d in state.board.iter().flatten().flatten() {
+        match d {
+            Disc::Black => black += 1,
+            Disc::White => white += 1,
+        }
+    }
+    (black, white)
+}
+
+/// 雙方都無處可下（含滿盤）→ 終局，子多者勝。與 turn 無關。
+pub fn status(state: &ReversiState) -> Outcome {
+    if has_move(state, state.turn) || has_move(state, state.turn.opponent()) {
+        return Outcome::Continue;
+    }
+    let (black, white) = counts(state);
+    match black.cmp(&white) {
+        std::cmp::Ordering::Greater => Outcome::Win(Disc::Black),
+        std::cmp::Ordering::Less => Outcome::Win(Disc::White),
+        std::cmp::Ordering::Equal => Outcome::Draw,
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,95 @@
+use super::*;
+
+fn board(black: &[(i8, i8)], white: &[(i8, i8)], turn: Disc) -> ReversiState {
+    let mut s = ReversiState { board: [[None; 8]; 8], turn };
+    for &(c, r) in black {
+        s.board[r as usize][c as usize] = Some(Disc::Black);
+    }
+    for &(c, r) in white {
+        s.board[r as usize][c as usize] = Some(Disc::White);
+    }
+    s
+}
+
+#[test]
+fn opening_has_four_moves_for_black() {
+    let s = initial_state();
+    assert_eq!(s.turn, Disc::Black);
+    assert_eq!(counts(&s), (2, 2));
+    let mut moves = legal_moves(&s, Disc::Black);
+    moves.sort();
+    assert_eq!(moves, vec![(2, 3), (3, 2), (4, 5), (5, 4)]);
+    assert_eq!(status(&s), Outcome::Continue);
+}
+
+#[test]
+fn placing_flips_sandwiched_discs() {
+    let mut s = initial_state();
+    let placed = apply(&mut s, 5, 4);
+    assert_eq!(placed, Placed { flipped: vec![(4, 4)], opponent_passed: false });
+    assert_eq!(s.board[4][4], Some(Disc::Black));
+    assert_eq!(counts(&s), (4, 1));
+    assert_eq!(s.turn, Disc::White);
+}
+
+#[test]
+fn flips_in_several_directions_at_once() {
+    // 黑下 (0,0)：往右夾 (1,0)(2,0)、往下夾 (0,1)、斜向夾 (1,1)
+    let mut s = board(&[(3, 0), (0, 2), (2, 2), (7, 7)], &[(1, 0), (2, 0), (0, 1), (1, 1), (6, 6)], Disc::Black);
+    let mut placed = apply(&mut s, 0, 0);
+    placed.flipped.sort();
+    assert_eq!(placed.flipped, vec![(0, 1), (1, 0), (1, 1), (2, 0)]);
+}
+
+#[test]
+fn rejects_out_of_bounds_occupied_and_non_flipping() {
+    let s = initial_state();
+    assert_eq!(is_legal(&s, 8, 0), Err("bad_coord"));
+    assert_eq!(is_legal(&s, 3, 3), Err("occupied"));
+    assert_eq!(is_legal(&s, 0, 0), Err("no_flips"));
+    // 隔著空格不算夾
+    let s = board(&[(0, 0)], &[(2, 0)], Disc::Black);
+    assert_eq!(is_legal(&s, 3, 0), Err("no_flips"));
+}
+
+#[test]
+fn opponent_without_moves_passes_automatically() {
+    // 黑下 (2,0) 翻掉白 (1,0) 後白只剩 (1,7)，夾不到任何黑子 → 白虛手，黑續下（還能吃 (1,7)）
+    let mut s = board(&[(0, 0), (0, 7)], &[(1, 0), (1, 7)], Disc::Black);
+    let placed = apply(&mut s, 2, 0);
+    assert!(placed.opponent_passed);
+    assert_eq!(s.turn, Disc::Black);
+    assert_eq!(status(&s), Outcome::Continue);
+}
+
+#[test]
+fn game_ends_when_neither_side_can_move() {
+    // 白子全被翻光 → 雙方皆無步
+    let mut s = board(&[(0, 0)], &[(1, 0)], Disc::Black);
+    let placed = apply(&mut s, 2, 0);
+    assert!(!placed.opponent_passed);
+    assert_eq!(status(&s), Outcome::Win(Disc::Black));
+}
+
+#[test]
+fn disc_count_decides_winner_or_draw() {
+    // 兩角各自孤立，誰都夾不到 → 終局比子數
+    let s = board(&[(0, 0), (0, 7)], &[(7, 7)], Disc::White);
+    assert_eq!(status(&s), Outcome::Win(Disc::Black));
+    let s = board(&[(0, 0)], &[(7, 7)], Disc::White);
+    assert_eq!(status(&s), Outcome::Draw);
+}
+
+#[test]
+fn full_board_ends_game() {
+    let mut s = board(&[], &[], Disc::Black);
+    for row in 0..8 {
+        for col in 0..8 {
+            s.board[row][col] = Some(if row < 4 { Disc::Black } else { Disc::White });
+        }
+    }
+    s.board[7][7] = Some(Disc::Black);
+    assert_eq!(status(&s), Outcome::Win(Disc::Black));
+    s.board[0][0] = Some(Disc::White);
+    assert_eq!(status(&s), Outcome::Draw);
+}
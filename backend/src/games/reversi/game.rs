@@ -0,0 +1,94 @@
+//! 黑白棋接上共用對戰框架：`impl GameEngine for ReversiGame`。
+
+use serde_json::{json, Value};
+
+use super::engine::{self, Disc, Outcome, ReversiState};
+use crate::games::common::bot::NoBot;
+use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+
+#[derive(Clone)]
+pub struct ReversiGame(ReversiState);
+
+fn to_common(d: Disc) -> Side {
+    match d {
+        Disc::Black => Side::First,
+        Disc::White => Side::Second,
+    }
+}
+
+/// 解析 `[col, row]`。
+fn parse_at(v: Option<&Value>) -> Option<(i8, i8)> {
+    let arr = v?.as_array()?;
+    if arr.len() != 2 {
+        return None;
+    }
+    Some((arr[0].as_i64()? as i8, arr[1].as_i64()? as i8))
+}
+
+impl GameEngine for ReversiGame {
+    const NAME: &'static str = "reversi";
+    type Bot = NoBot;
+
+    fn initial(_options: &Value) -> Self {
+        ReversiGame(engine::initial_state())
+    }
+
+    fn turn(&self) -> Side {
+        to_common(self.0.turn)
+    }
+
+    fn side_label(side: Side) -> &'static str {
+        match side {
+            Side::First => "black",
+            Side::Second => "white",
+        }
+    }
+
+    fn try_move(&mut self, mover: Side, data: Option<&Value>) -> Result<Applied, String> {
+        let Some((col, row)) = parse_at(data.and_then(|d| d.get("at"))) else {
+            return Err("bad_coord".into());
+        };
+        engine::is_legal(&self.0, col, row).map_err(|e| e.to_string())?;
+        let placed = engine::apply(&mut self.0, col, row);
+        let flipped: Vec<Value> = placed.flipped.iter().map(|&(c, r)| json!([c, r])).collect();
+        // 對方無處可下被自動虛手：另推一則事件，前端提示「對方虛手、你續下」
+        let mut extra = Vec::new();
+        if placed.opponent_passed {
+            extra.push(("pass", json!({ "color": Self::side_label(mover.opponent()) })));
+        }
+        Ok(Applied {
+            move_data: json!({ "at": [col, row], "by": Self::side_label(mover), "flipped": flipped }),
+            extra,
+            swap_seats: false,
+        })
+    }
+
+    fn status(&self) -> GameStatus {
+        match engine::status(&self.0) {
+            Outcome::Continue => GameStatus::Ongoing,
+            Outcome::Win(disc) => GameStatus::Win {
+                winner: to_common(disc),
+                reason: "disc_count",
+            },
+            Outcome::Draw => GameStatus::Draw { reason: "draw_count" },
+        }
+    }
+
+    fn snapshot(&self) -> Value {
+        let mut discs = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(d) = cell {
+                    discs.push(json!({ "at": [col, row], "color": Self::side_label(to_common(*d)) }));
+                }
+            }
+        }
+        let (black, white) = engine::counts(&self.0);
+        let legal: Vec<Value> = engine::legal_moves(&self.0, self.0.turn).iter().map(|&(c, r)| json!([c, r])).collect();
+        json!({
+            "discs": discs,
+            "counts": { "black": black, "white": white },
+            "legal": legal,
+        })
+    }
+}
@@ -0,0 +1,4 @@
+//! 黑白棋（Reversi / Othello）：純函式規則引擎（`engine`）與共用框架接口（`game`）。
+
+pub mod engine;
+pub mod game;
@@ -0,0 +1,400 @@
+//! 將棋規則引擎 — 純函式，零 WS 依賴。本將棋走法 + 持駒打入 + 敵陣三段成駒（走不動的位置強制成）
+//! + 二步 / 打ち歩詰め / 行き所のない駒 禁手 + 王手 / 詰み（無合法步即負）
+//! + 千日手（同一局面第 4 次出現：和；一方每步皆王手則該方判負）。
+
+use super::types::{Color, Coord, Move, Piece, PieceKind, PlyMark, State, HAND_KINDS};
+use crate::games::common::zobrist;
+
+const N_SENNICHITE: usize = 4; // 同一局面第 4 次出現 → 千日手
+
+// Zobrist 鍵配置：2 方 × 8 種 × 成/不成 × 81 格、2 方 × 7 種持駒 × 0–18 枚、後手行棋。
+const Z_HAND: usize = 32 * 81;
+const Z_GOTE: usize = Z_HAND + 2 * 7 * 19;
+static ZOBRIST: [u64; Z_GOTE + 1] = zobrist::table(); // 2.9k 個鍵，放 static 免得每次使用都展開
+
+fn raw(kind: PieceKind, color: Color) -> Piece {
+    Piece { kind, color, promoted: false }
+}
+
+pub fn initial_state() -> State {
+    use PieceKind::*;
+    let mut board = [[None; 9]; 9];
+    let back = [Lance, Knight, Silver, Gold, King, Gold, Silver, Knight, Lance];
+    for (c, &kind) in back.iter().enumerate() {
+        board[0][c] = Some(raw(kind, Color::Sente));
+        board[8][c] = Some(raw(kind, Color::Gote));
+        board[2][c] = Some(raw(Pawn, Color::Sente));
+        board[6][c] = Some(raw(Pawn, Color::Gote));
+    }
+    // 角在自己的左手邊、飛在右手邊（先手 8八角 / 2八飛，後手點對稱）
+    board[1][1] = Some(raw(Bishop, Color::Sente));
+    board[1][7] = Some(raw(Rook, Color::Sente));
+    board[7][7] = Some(raw(Bishop, Color::Gote));
+    board[7][1] = Some(raw(Rook, Color::Gote));
+    let mut state = State { board, turn: Color::Sente, hands: [[0; 7]; 2], hash: 0, history: Vec::new() };
+    state.hash = position_hash(&state);
+    state
+}
+
+/// 局面 Zobrist hash：盤上棋子（含成 / 不成）、雙方持駒數、行棋方。
+pub fn position_hash(state: &State) -> u64 {
+    let mut h = 0;
+    for (r, line) in state.board.iter().enumerate() {
+        for (c, cell) in line.iter().enumerate() {
+            if let Some(p) = cell {
+                let piece = (p.color as usize * 8 + p.kind as usize) * 2 + p.promoted as usize;
+                h ^= ZOBRIST[piece * 81 + r * 9 + c];
+            }
+        }
+    }
+    for (side, hand) in state.hands.iter().enumerate() {
+        for (k, &n) in hand.iter().enumerate() {
+            if n > 0 {
+                h ^= ZOBRIST[Z_HAND + (side * 7 + k) * 19 + (n as usize).min(18)];
+            }
+        }
+    }
+    if state.turn == Color::Gote {
+        h ^= ZOBRIST[Z_GOTE];
+    }
+    h
+}
+
+/// 目前局面（含本次）出現過幾次。
+pub fn repetitions(state: &State) -> usize {
+    1 + state.history.iter().filter(|m| m.hash == state.hash).count()
+}
+
+pub fn turn(state: &State) -> Color {
+    state.turn
+}
+
+pub fn hand(state: &State, color: Color, kind: PieceKind) -> u8 {
+    state.hands[color as usize][kind as usize]
+}
+
+fn on_board(c: i8, r: i8) -> bool {
+    (0..9).contains(&c) && (0..9).contains(&r)
+}
+
+fn at(state: &State, c: i8, r: i8) -> Option<Piece> {
+    if on_board(c, r) {
+        state.board[r as usize][c as usize]
+    } else {
+        None
+    }
+}
+
+/// 前進方向的 row 增量：先手往 row 8、後手往 row 0。
+fn forward(color: Color) -> i8 {
+    match color {
+        Color::Sente => 1,
+        Color::Gote => -1,
+    }
+}
+
+/// 距對方底線的段數（0 = 最後一段）。
+fn ranks_left(color: Color, r: i8) -> i8 {
+    match color {
+        Color::Sente => 8 - r,
+        Color::Gote => r,
+    }
+}
+
+/// 敵陣三段。
+fn in_zone(color: Color, r: i8) -> bool {
+    ranks_left(color, r) <= 2
+}
+
+/// 到了這一段就再也走不動（步 / 香在最後一段、桂在最後兩段）：盤上走到此處必須成，持駒不可打入。
+fn dead_end(kind: PieceKind, color: Color, r: i8) -> bool {
+    match kind {
+        PieceKind::Pawn | PieceKind::Lance => ranks_left(color, r) == 0,
+        PieceKind::Knight => ranks_left(color, r) <= 1,
+        _ => false,
+    }
+}
+
+// 步向以先手為準（dr > 0 為前進），後手上下翻轉。
+const GOLD_STEPS: [(i8, i8); 6] = [(-1, 1), (0, 1), (1, 1), (-1, 0), (1, 0), (0, -1)];
+const SILVER_STEPS: [(i8, i8); 5] = [(-1, 1), (0, 1), (1, 1), (-1, -1), (1, -1)];
+const KNIGHT_STEPS: [(i8, i8); 2] = [(-1, 2), (1, 2)];
+const DIAG: [(i8, i8); 4] = [(1, 1), (1, -1), (-1, 1), (-1, -1)];
+const ORTHO: [(i8, i8); 4] = [(1, 0), (-1, 0), (0, 1), (0, -1)];
+
+fn steps(c: i8, r: i8, f: i8, dirs: &[(i8, i8)], out: &mut Vec<Coord>) {
+    for &(dc, dr) in dirs {
+        let (tc, tr) = (c + dc, r + dr * f);
+        if on_board(tc, tr) {
+            out.push((tc, tr));
+        }
+    }
+}
+
+fn slides(state: &State, c: i8, r: i8, f: i8, dirs: &[(i8, i8)], out: &mut Vec<Coord>) {
+    for &(dc, dr) in dirs {
+        let (mut tc, mut tr) = (c + dc, r + dr * f);
+        while on_board(tc, tr) {
+            out.push((tc, tr));
+            if state.board[tr as usize][tc as usize].is_some() {
+                break;
+            }
+            tc += dc;
+            tr += dr * f;
+        }
+    }
+}
+
+/// (c,r) 上的子 `p` 能到的所有格（含被己方子佔住的格，呼叫端自行過濾）。
+fn reach(state: &State, c: i8, r: i8, p: Piece, out: &mut Vec<Coord>) {
+    use PieceKind::*;
+    let f = forward(p.color);
+    match (p.kind, p.promoted) {
+        (King, _) => {
+            steps(c, r, f, &ORTHO, out);
+            steps(c, r, f, &DIAG, out);
+        }
+        // 金與成駒（と / 成香 / 成桂 / 成銀）同金的走法
+        (Gold, _) | (Pawn | Lance | Knight | Silver, true) => steps(c, r, f, &GOLD_STEPS, out),
+        (Pawn, false) => steps(c, r, f, &[(0, 1)], out),
+        (Lance, false) => slides(state, c, r, f, &[(0, 1)], out),
+        (Knight, false) => steps(c, r, f, &KNIGHT_STEPS, out),
+        (Silver, false) => steps(c, r, f, &SILVER_STEPS, out),
+        (Bishop, promoted) => {
+            slides(state, c, r, f, &DIAG, out);
+            if promoted {
+                steps(c, r, f, &ORTHO, out); // 馬
+            }
+        }
+        (Rook, promoted) => {
+            slides(state, c, r, f, &ORTHO, out);
+            if promoted {
+                steps(c, r, f, &DIAG, out); // 龍
+            }
+        }
+    }
+}
+
+fn find_king(state: &State, color: Color) -> Option<Coord> {
+    for r in 0..9 {
+        for c in 0..9 {
+            if let Some(p) = state.board[r][c] {
+                if p.kind == PieceKind::King && p.color == color {
+                    return Some((c as i8, r as i8));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// `sq` 是否被 `by` 方任一子攻擊。
+pub fn is_attacked(state: &State, sq: Coord, by: Color) -> bool {
+    let mut targets = Vec::new();
+    for r in 0..9i8 {
+        for c in 0..9i8 {
+            let Some(p) = at(state, c, r) else { continue };
+            if p.color != by {
+                continue;
+            }
+            targets.clear();
+            reach(state, c, r, p, &mut targets);
+            if targets.contains(&sq) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+pub fn is_in_check(state: &State, color: Color) -> bool {
+    match find_king(state, color) {
+        Some(k) => is_attacked(state, k, color.opponent()),
+        None => true,
+    }
+}
+
+/// 該筋已有己方未成的步（二步）。
+fn has_pawn_on_file(state: &State, color: Color, c: i8) -> bool {
+    (0..9).any(|r| state.board[r][c as usize] == Some(raw(PieceKind::Pawn, color)))
+}
+
+/// 打入的棋盤面限制（不看王手 / 打步詰）：持駒、空格、行き所のない駒、二步。
+fn drop_rule(state: &State, kind: PieceKind, to: Coord) -> Result<(), &'static str> {
+    let me = state.turn;
+    if kind == PieceKind::King || hand(state, me, kind) == 0 {
+        return Err("not_in_hand");
+    }
+    if at(state, to.0, to.1).is_some() {
+        return Err("occupied");
+    }
+    if dead_end(kind, me, to.1) {
+        return Err("dead_piece");
+    }
+    if kind == PieceKind::Pawn && has_pawn_on_file(state, me, to.0) {
+        return Err("nifu");
+    }
+    Ok(())
+}
+
+/// 打入的完整檢查，給 `game` 回具體的禁手原因；送將（未解王手）回 `illegal`。
+pub fn drop_error(state: &State, kind: PieceKind, to: Coord) -> Option<&'static str> {
+    let mv = Move::Drop { kind, to };
+    if let Err(e) = drop_rule(state, kind, to) {
+        Some(e)
+    } else if leaves_king_in_check(state, mv) {
+        Some("illegal")
+    } else if is_uchifuzume(state, mv) {
+        Some("uchifuzume")
+    } else {
+        None
+    }
+}
+
+/// 產生行棋方所有「偽合法」走步（含成 / 不成、打入；已排除行き所のない駒 與二步），未過濾送將與打步詰。
+fn pseudo_moves(state: &State, with_drops: bool) -> Vec<Move> {
+    let mut out = Vec::new();
+    let me = state.turn;
+    let mut targets = Vec::new();
+    for r in 0..9i8 {
+        for c in 0..9i8 {
+            let Some(p) = at(state, c, r) else { continue };
+            if p.color != me {
+                continue;
+            }
+            targets.clear();
+            reach(state, c, r, p, &mut targets);
+            for &(tc, tr) in &targets {
+                if at(state, tc, tr).is_some_and(|t| t.color == me) {
+                    continue;
+                }
+                let (from, to) = ((c, r), (tc, tr));
+                if !p.promoted && p.kind.promotable() && (in_zone(me, r) || in_zone(me, tr)) {
+                    out.push(Move::Board { from, to, promote: true });
+                    if !dead_end(p.kind, me, tr) {
+                        out.push(Move::Board { from, to, promote: false });
+                    }
+                } else {
+                    out.push(Move::Board { from, to, promote: false });
+                }
+            }
+        }
+    }
+    if with_drops {
+        for kind in HAND_KINDS {
+            for r in 0..9i8 {
+                for c in 0..9i8 {
+                    if drop_rule(state, kind, (c, r)).is_ok() {
+                        out.push(Move::Drop { kind, to: (c, r) });
+                    }
+                }
+            }
+        }
+    }
+    out
+}
+
+fn leaves_king_in_check(state: &State, mv: Move) -> bool {
+    let mut next = state.clone();
+    apply(&mut next, mv);
+    is_in_check(&next, state.turn)
+}
+
+/// 打ち歩詰め：打步將軍且對方無路可逃。步的王手緊貼著王、無法合駒，只看對方盤上走子即可。
+fn is_uchifuzume(state: &State, mv: Move) -> bool {
+    if !matches!(mv, Move::Drop { kind: PieceKind::Pawn, .. }) {
+        return false;
+    }
+    let mut next = state.clone();
+    apply(&mut next, mv);
+    let them = next.turn;
+    is_in_check(&next, them)
+        && pseudo_moves(&next, false).into_iter().all(|reply| leaves_king_in_check(&next, reply))
+}
+
+/// 全合法走步（已過濾送將與打步詰）。
+pub fn legal_moves(state: &State) -> Vec<Move> {
+    pseudo_moves(state, true)
+        .into_iter()
+        .filter(|&mv| !leaves_king_in_check(state, mv) && !is_uchifuzume(state, mv))
+        .collect()
+}
+
+/// 套用走步（假設已合法）：移子 / 打入、吃子收進持駒（成駒還原）、切換行棋方。回傳被吃的棋種。
+/// 搜尋 / 合法性模擬用；對局中走子用 `play`（記千日手紀錄）。
+pub fn apply(state: &mut State, mv: Move) -> Option<PieceKind> {
+    let me = state.turn;
+    let mut captured = None;
+    match mv {
+        Move::Board { from, to, promote } => {
+            let mut piece = state.board[from.1 as usize][from.0 as usize].take().expect("from empty");
+            if let Some(t) = state.board[to.1 as usize][to.0 as usize] {
+                state.hands[me as usize][t.kind as usize] += 1;
+                captured = Some(t.kind);
+            }
+            piece.promoted |= promote;
+            state.board[to.1 as usize][to.0 as usize] = Some(piece);
+        }
+        Move::Drop { kind, to } => {
+            state.hands[me as usize][kind as usize] -= 1;
+            state.board[to.1 as usize][to.0 as usize] = Some(raw(kind, me));
+        }
+    }
+    state.turn = me.opponent();
+    state.hash = position_hash(state);
+    captured
+}
+
+/// 對局中實際走一步：`apply` 後記下千日手判定資訊（`PlyMark`）。
+pub fn play(state: &mut State, mv: Move) -> Option<PieceKind> {
+    let hash = state.hash;
+    let captured = apply(state, mv);
+    let check = is_in_check(state, state.turn);
+    state.history.push(PlyMark { hash, check });
+    captured
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Continue,
+    Checkmate { winner: Color },
+    /// 未被將但無合法步：將棋沒有逼和，無步可走方判負。
+    NoMoves { winner: Color },
+    /// 千日手：和。
+    Sennichite,
+    /// 連續王手の千日手：每步皆王手的一方判負。
+    PerpetualCheck { loser: Color },
+}
+
+/// 目前局面第 4 次出現時的裁決；未達次數回 None。
+/// 看從第一次出現到現在的所有步：只有一方每步皆王手 → 該方負，否則和。
+fn sennichite(state: &State) -> Option<Outcome> {
+    let first = state.history.iter().position(|m| m.hash == state.hash)?;
+    let cycle = &state.history[first..];
+    if 1 + cycle.iter().filter(|m| m.hash == state.hash).count() < N_SENNICHITE {
+        return None;
+    }
+    // 同一局面行棋方相同 → cycle[0] 是目前行棋方走的，之後雙方交替
+    let mine = cycle.iter().step_by(2).all(|m| m.check);
+    let theirs = cycle.iter().skip(1).step_by(2).all(|m| m.check);
+    Some(match (mine, theirs) {
+        (true, false) => Outcome::PerpetualCheck { loser: state.turn },
+        (false, true) => Outcome::PerpetualCheck { loser: state.turn.opponent() },
+        _ => Outcome::Sennichite,
+    })
+}
+
+pub fn status(state: &State) -> Outcome {
+    if legal_moves(state).is_empty() {
+        let winner = state.turn.opponent();
+        return if is_in_check(state, state.turn) {
+            Outcome::Checkmate { winner }
+        } else {
+            Outcome::NoMoves { winner }
+        };
+    }
+    sennichite(state).unwrap_or(Outcome::Continue)
+}
+
+#[cfg(test)]
+mod tests;
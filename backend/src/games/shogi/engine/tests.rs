@@ -0,0 +1,240 @@
+use super::*;
+use crate::games::shogi::types::{Color, Move, Piece, PieceKind, State};
+
+fn empty() -> State {
+    State { board: [[None; 9]; 9], turn: Color::Sente, hands: [[0; 7]; 2], hash: 0, history: Vec::new() }
+}
+
+fn put(s: &mut State, c: i8, r: i8, kind: PieceKind, color: Color) {
+    s.board[r as usize][c as usize] = Some(Piece { kind, color, promoted: false });
+}
+
+fn give(s: &mut State, color: Color, kind: PieceKind) {
+    s.hands[color as usize][kind as usize] += 1;
+}
+
+fn m(from: (i8, i8), to: (i8, i8)) -> Move {
+    Move::Board { from, to, promote: false }
+}
+
+fn has_drop(s: &State, kind: PieceKind, to: (i8, i8)) -> bool {
+    legal_moves(s).contains(&Move::Drop { kind, to })
+}
+
+/// 兩王遠離中央的空盤（先手王 (8,0)、後手王 (0,8)）。
+fn kings() -> State {
+    let mut s = empty();
+    put(&mut s, 8, 0, PieceKind::King, Color::Sente);
+    put(&mut s, 0, 8, PieceKind::King, Color::Gote);
+    s
+}
+
+#[test]
+fn initial_has_40_pieces_and_30_moves() {
+    let s = initial_state();
+    let n = s.board.iter().flatten().filter(|p| p.is_some()).count();
+    assert_eq!(n, 40);
+    assert_eq!(s.turn, Color::Sente);
+    assert_eq!(legal_moves(&s).len(), 30);
+    assert_eq!(status(&s), Outcome::Continue);
+}
+
+#[test]
+fn capture_goes_to_hand_unpromoted() {
+    let mut s = kings();
+    put(&mut s, 4, 4, PieceKind::Rook, Color::Sente);
+    s.board[7][4] = Some(Piece { kind: PieceKind::Silver, color: Color::Gote, promoted: true });
+    let captured = apply(&mut s, m((4, 4), (4, 7)));
+    assert_eq!(captured, Some(PieceKind::Silver));
+    assert_eq!(hand(&s, Color::Sente, PieceKind::Silver), 1);
+    // 吃進來的成銀打回盤上是未成的銀
+    s.turn = Color::Sente;
+    apply(&mut s, Move::Drop { kind: PieceKind::Silver, to: (2, 2) });
+    assert_eq!(s.board[2][2], Some(Piece { kind: PieceKind::Silver, color: Color::Sente, promoted: false }));
+    assert_eq!(hand(&s, Color::Sente, PieceKind::Silver), 0);
+}
+
+#[test]
+fn drops_need_a_piece_in_hand_and_an_empty_square() {
+    let mut s = kings();
+    put(&mut s, 4, 4, PieceKind::Gold, Color::Gote);
+    assert!(!has_drop(&s, PieceKind::Gold, (3, 3)));
+    assert_eq!(drop_error(&s, PieceKind::Gold, (3, 3)), Some("not_in_hand"));
+    give(&mut s, Color::Sente, PieceKind::Gold);
+    assert!(has_drop(&s, PieceKind::Gold, (3, 3)));
+    assert_eq!(drop_error(&s, PieceKind::Gold, (4, 4)), Some("occupied"));
+}
+
+#[test]
+fn pawn_lance_knight_cannot_be_dropped_where_they_never_move() {
+    let mut s = kings();
+    give(&mut s, Color::Sente, PieceKind::Pawn);
+    give(&mut s, Color::Sente, PieceKind::Lance);
+    give(&mut s, Color::Sente, PieceKind::Knight);
+    assert_eq!(drop_error(&s, PieceKind::Pawn, (4, 8)), Some("dead_piece"));
+    assert_eq!(drop_error(&s, PieceKind::Lance, (4, 8)), Some("dead_piece"));
+    assert_eq!(drop_error(&s, PieceKind::Knight, (4, 7)), Some("dead_piece"));
+    assert!(has_drop(&s, PieceKind::Pawn, (4, 7)));
+    assert!(has_drop(&s, PieceKind::Knight, (4, 6)));
+    // 後手方向相反
+    s.turn = Color::Gote;
+    give(&mut s, Color::Gote, PieceKind::Knight);
+    assert_eq!(drop_error(&s, PieceKind::Knight, (4, 1)), Some("dead_piece"));
+    assert!(has_drop(&s, PieceKind::Knight, (4, 2)));
+}
+
+#[test]
+fn nifu_forbids_a_second_unpromoted_pawn_on_a_file() {
+    let mut s = kings();
+    put(&mut s, 4, 3, PieceKind::Pawn, Color::Sente);
+    s.board[5][5] = Some(Piece { kind: PieceKind::Pawn, color: Color::Sente, promoted: true });
+    put(&mut s, 6, 6, PieceKind::Pawn, Color::Gote);
+    give(&mut s, Color::Sente, PieceKind::Pawn);
+    assert_eq!(drop_error(&s, PieceKind::Pawn, (4, 5)), Some("nifu"));
+    assert!(!has_drop(&s, PieceKind::Pawn, (4, 5)));
+    // と金與對方的步不算
+    assert!(has_drop(&s, PieceKind::Pawn, (5, 3)));
+    assert!(has_drop(&s, PieceKind::Pawn, (6, 3)));
+}
+
+#[test]
+fn promotion_is_forced_only_where_the_piece_could_not_move_again() {
+    let mut s = kings();
+    put(&mut s, 2, 7, PieceKind::Pawn, Color::Sente);
+    put(&mut s, 4, 5, PieceKind::Knight, Color::Sente);
+    put(&mut s, 6, 5, PieceKind::Silver, Color::Sente);
+    let moves = legal_moves(&s);
+    let options = |from: (i8, i8), to: (i8, i8)| -> Vec<bool> {
+        moves
+            .iter()
+            .filter_map(|mv| match *mv {
+                Move::Board { from: f, to: t, promote } if f == from && t == to => Some(promote),
+                _ => None,
+            })
+            .collect()
+    };
+    assert_eq!(options((2, 7), (2, 8)), vec![true]); // 步到最後一段：必成
+    assert_eq!(options((4, 5), (3, 7)), vec![true]); // 桂到倒數第二段：必成
+    assert_eq!(options((6, 5), (6, 6)), vec![true, false]); // 銀入敵陣：可選
+    assert_eq!(options((6, 5), (7, 4)), vec![false]); // 敵陣外：不可成
+}
+
+#[test]
+fn leaving_the_zone_may_promote_and_promoted_pieces_move_like_gold() {
+    let mut s = kings();
+    put(&mut s, 4, 6, PieceKind::Silver, Color::Sente);
+    assert!(legal_moves(&s).contains(&Move::Board { from: (4, 6), to: (3, 5), promote: true }));
+    apply(&mut s, Move::Board { from: (4, 6), to: (3, 5), promote: true });
+    s.turn = Color::Sente;
+    let targets: Vec<_> = legal_moves(&s)
+        .into_iter()
+        .filter_map(|mv| match mv {
+            Move::Board { from: (3, 5), to, .. } => Some(to),
+            _ => None,
+        })
+        .collect();
+    // 成銀：前三、左右、正後方；不能斜退
+    assert!(targets.contains(&(2, 5)) && targets.contains(&(3, 4)));
+    assert!(!targets.contains(&(2, 4)) && !targets.contains(&(4, 4)));
+}
+
+#[test]
+fn cannot_leave_king_in_check() {
+    let mut s = kings();
+    put(&mut s, 8, 3, PieceKind::Gold, Color::Sente);
+    put(&mut s, 8, 7, PieceKind::Lance, Color::Gote); // 香隔著金瞄準先手王
+    assert!(!legal_moves(&s).contains(&m((8, 3), (7, 3))));
+    assert!(legal_moves(&s).contains(&m((8, 3), (8, 4))));
+}
+
+/// 後手王 (4,8) 被自己的香夾住，先手金 (4,6) 看住 (3,7)/(4,7)/(5,7)。
+fn pawn_drop_mate_setup() -> State {
+    let mut s = empty();
+    put(&mut s, 0, 0, PieceKind::King, Color::Sente);
+    put(&mut s, 4, 8, PieceKind::King, Color::Gote);
+    put(&mut s, 3, 8, PieceKind::Lance, Color::Gote);
+    put(&mut s, 5, 8, PieceKind::Lance, Color::Gote);
+    put(&mut s, 4, 6, PieceKind::Gold, Color::Sente);
+    give(&mut s, Color::Sente, PieceKind::Pawn);
+    s
+}
+
+#[test]
+fn uchifuzume_forbids_mating_with_a_dropped_pawn() {
+    let s = pawn_drop_mate_setup();
+    assert_eq!(drop_error(&s, PieceKind::Pawn, (4, 7)), Some("uchifuzume"));
+    assert!(!has_drop(&s, PieceKind::Pawn, (4, 7)));
+}
+
+#[test]
+fn pawn_drop_check_is_fine_when_the_king_can_escape() {
+    let mut s = pawn_drop_mate_setup();
+    s.board[8][5] = None; // 王可逃往 (5,8)
+    assert_eq!(drop_error(&s, PieceKind::Pawn, (4, 7)), None);
+    assert!(has_drop(&s, PieceKind::Pawn, (4, 7)));
+}
+
+#[test]
+fn dropping_a_gold_to_mate_is_checkmate() {
+    let mut s = pawn_drop_mate_setup();
+    give(&mut s, Color::Sente, PieceKind::Gold);
+    let mv = Move::Drop { kind: PieceKind::Gold, to: (4, 7) };
+    assert!(legal_moves(&s).contains(&mv));
+    play(&mut s, mv);
+    assert!(is_in_check(&s, Color::Gote));
+    assert!(s.history.last().unwrap().check);
+    assert_eq!(status(&s), Outcome::Checkmate { winner: Color::Sente });
+}
+
+#[test]
+fn hands_and_side_to_move_are_part_of_the_position() {
+    let mut a = kings();
+    give(&mut a, Color::Sente, PieceKind::Pawn);
+    let mut b = kings();
+    give(&mut b, Color::Gote, PieceKind::Pawn);
+    assert_ne!(position_hash(&a), position_hash(&b));
+    let mut c = a.clone();
+    c.turn = Color::Gote;
+    assert_ne!(position_hash(&a), position_hash(&c));
+}
+
+/// 先手金 (0,0)⇄(1,0)、後手王 (8,8)⇄(7,8)：四步一輪回到原局面，無王手。
+fn quiet_shuffle(s: &mut State) {
+    for mv in [m((0, 0), (1, 0)), m((8, 8), (7, 8)), m((1, 0), (0, 0)), m((7, 8), (8, 8))] {
+        play(s, mv);
+    }
+}
+
+#[test]
+fn fourth_repetition_is_sennichite() {
+    let mut s = empty();
+    put(&mut s, 4, 0, PieceKind::King, Color::Sente);
+    put(&mut s, 0, 0, PieceKind::Gold, Color::Sente);
+    put(&mut s, 8, 8, PieceKind::King, Color::Gote);
+    s.hash = position_hash(&s);
+    quiet_shuffle(&mut s);
+    quiet_shuffle(&mut s);
+    assert_eq!(repetitions(&s), 3);
+    assert_eq!(status(&s), Outcome::Continue);
+    quiet_shuffle(&mut s);
+    assert_eq!(repetitions(&s), 4);
+    assert_eq!(status(&s), Outcome::Sennichite);
+}
+
+#[test]
+fn perpetual_check_loses() {
+    // 先手飛在 5、4 筋間來回，每步都將後手王
+    let mut s = empty();
+    put(&mut s, 0, 0, PieceKind::King, Color::Sente);
+    put(&mut s, 5, 0, PieceKind::Rook, Color::Sente);
+    put(&mut s, 4, 8, PieceKind::King, Color::Gote);
+    s.hash = position_hash(&s);
+    for _ in 0..3 {
+        for mv in [m((5, 0), (4, 0)), m((4, 8), (5, 8)), m((4, 0), (5, 0)), m((5, 8), (4, 8))] {
+            assert!(legal_moves(&s).contains(&mv));
+            play(&mut s, mv);
+        }
+    }
+    assert_eq!(repetitions(&s), 4);
+    assert_eq!(status(&s), Outcome::PerpetualCheck { loser: Color::Sente });
+}
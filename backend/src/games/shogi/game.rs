@@ -0,0 +1,187 @@
+//! 將棋接上共用對戰框架：`impl GameEngine for ShogiGame`。
+
+use serde_json::{json, Map, Value};
+
+use super::engine::{self, Outcome};
+use super::types::{Color, Move, PieceKind, State, HAND_KINDS};
+use crate::games::common::bot::NoBot;
+use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+
+#[derive(Clone)]
+pub struct ShogiGame(State);
+
+fn to_common(c: Color) -> Side {
+    match c {
+        Color::Sente => Side::First,
+        Color::Gote => Side::Second,
+    }
+}
+
+fn parse_coord(v: Option<&Value>) -> Option<(i8, i8)> {
+    let arr = v?.as_array()?;
+    if arr.len() != 2 {
+        return None;
+    }
+    let c = arr[0].as_i64()?;
+    let r = arr[1].as_i64()?;
+    ((0..9).contains(&c) && (0..9).contains(&r)).then_some((c as i8, r as i8))
+}
+
+fn kind_str(k: PieceKind) -> &'static str {
+    match k {
+        PieceKind::Pawn => "pawn",
+        PieceKind::Lance => "lance",
+        PieceKind::Knight => "knight",
+        PieceKind::Silver => "silver",
+        PieceKind::Gold => "gold",
+        PieceKind::Bishop => "bishop",
+        PieceKind::Rook => "rook",
+        PieceKind::King => "king",
+    }
+}
+
+/// 可打入的棋種（王不算）。
+fn parse_hand_kind(s: &str) -> Option<PieceKind> {
+    HAND_KINDS.into_iter().find(|&k| kind_str(k) == s)
+}
+
+impl ShogiGame {
+    /// 盤上走子：在合法步中比對 from/to；`promote` 省略時能不成就不成（強制成的位置自動成）。
+    fn board_move(&self, data: Option<&Value>, to: (i8, i8)) -> Result<Move, String> {
+        let Some(from) = parse_coord(data.and_then(|d| d.get("from"))) else {
+            return Err("bad_coord".into());
+        };
+        let promote = match data.and_then(|d| d.get("promote")) {
+            None | Some(Value::Null) => None,
+            Some(v) => Some(v.as_bool().ok_or("bad_promote")?),
+        };
+        match self.0.board[from.1 as usize][from.0 as usize] {
+            None => return Err("no_piece".into()),
+            Some(p) if p.color != self.0.turn => return Err("wrong_piece".into()),
+            _ => {}
+        }
+        let candidates: Vec<bool> = engine::legal_moves(&self.0)
+            .into_iter()
+            .filter_map(|mv| match mv {
+                Move::Board { from: f, to: t, promote } if f == from && t == to => Some(promote),
+                _ => None,
+            })
+            .collect();
+        if candidates.is_empty() {
+            return Err("illegal".into());
+        }
+        let promote = match promote {
+            Some(want) if candidates.contains(&want) => want,
+            Some(_) => return Err("bad_promote".into()),
+            None => !candidates.contains(&false),
+        };
+        Ok(Move::Board { from, to, promote })
+    }
+}
+
+impl GameEngine for ShogiGame {
+    const NAME: &'static str = "shogi";
+    type Bot = NoBot;
+
+    fn initial(_options: &Value) -> Self {
+        ShogiGame(engine::initial_state())
+    }
+
+    fn turn(&self) -> Side {
+        to_common(engine::turn(&self.0))
+    }
+
+    fn side_label(side: Side) -> &'static str {
+        match side {
+            Side::First => "sente",
+            Side::Second => "gote",
+        }
+    }
+
+    fn try_move(&mut self, _mover: Side, data: Option<&Value>) -> Result<Applied, String> {
+        let Some(to) = parse_coord(data.and_then(|d| d.get("to"))) else {
+            return Err("bad_coord".into());
+        };
+        let mv = match data.and_then(|d| d.get("drop")) {
+            Some(k) => {
+                let kind = k.as_str().and_then(parse_hand_kind).ok_or("bad_piece")?;
+                if let Some(reason) = engine::drop_error(&self.0, kind, to) {
+                    return Err(reason.into());
+                }
+                Move::Drop { kind, to }
+            }
+            None => self.board_move(data, to)?,
+        };
+
+        let captured = engine::play(&mut self.0, mv);
+
+        let mut md = Map::new();
+        match mv {
+            Move::Board { from, promote, .. } => {
+                md.insert("from".into(), json!([from.0, from.1]));
+                md.insert("promote".into(), json!(promote));
+            }
+            Move::Drop { kind, .. } => {
+                md.insert("drop".into(), json!(kind_str(kind)));
+            }
+        }
+        md.insert("to".into(), json!([to.0, to.1]));
+        if let Some(k) = captured {
+            md.insert("captured".into(), json!(kind_str(k)));
+        }
+
+        let mut extra = Vec::new();
+        if matches!(engine::status(&self.0), Outcome::Continue) && engine::is_in_check(&self.0, self.0.turn) {
+            extra.push(("check", json!({ "side": Self::side_label(to_common(self.0.turn)) })));
+        }
+
+        Ok(Applied { move_data: Value::Object(md), extra, swap_seats: false })
+    }
+
+    fn status(&self) -> GameStatus {
+        match engine::status(&self.0) {
+            Outcome::Continue => GameStatus::Ongoing,
+            Outcome::Checkmate { winner } => GameStatus::Win {
+                winner: to_common(winner),
+                reason: "checkmate",
+            },
+            Outcome::NoMoves { winner } => GameStatus::Win {
+                winner: to_common(winner),
+                reason: "no_moves",
+            },
+            Outcome::Sennichite => GameStatus::Draw { reason: "sennichite" },
+            Outcome::PerpetualCheck { loser } => GameStatus::Win {
+                winner: to_common(loser.opponent()),
+                reason: "perpetual_check",
+            },
+        }
+    }
+
+    fn snapshot(&self) -> Value {
+        let mut pieces = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(p) = cell {
+                    pieces.push(json!({
+                        "at": [col, row],
+                        "color": Self::side_label(to_common(p.color)),
+                        "kind": kind_str(p.kind),
+                        "promoted": p.promoted,
+                    }));
+                }
+            }
+        }
+        let hand = |color: Color| -> Value {
+            HAND_KINDS
+                .into_iter()
+                .map(|k| (kind_str(k).to_string(), json!(engine::hand(&self.0, color, k))))
+                .collect::<Map<_, _>>()
+                .into()
+        };
+        json!({
+            "pieces": pieces,
+            "hands": { "sente": hand(Color::Sente), "gote": hand(Color::Gote) },
+            "repetitions": engine::repetitions(&self.0),
+        })
+    }
+}
@@ -0,0 +1,5 @@
+//! 將棋：純函式規則引擎（`engine` + `types`）與共用框架接口（`game`）。
+
+pub mod engine;
+pub mod game;
+pub mod types;
@@ -0,0 +1,88 @@
+//! 將棋核心型別 — 純資料。
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Sente,
+    Gote,
+}
+
+impl Color {
+    pub fn opponent(self) -> Color {
+        match self {
+            Color::Sente => Color::Gote,
+            Color::Gote => Color::Sente,
+        }
+    }
+}
+
+/// 棋種。前 7 種可持駒（`HAND_KINDS`），順序即 `State::hands` 的索引。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PieceKind {
+    Pawn,
+    Lance,
+    Knight,
+    Silver,
+    Gold,
+    Bishop,
+    Rook,
+    King,
+}
+
+impl PieceKind {
+    /// 可升變（金、王不可）。
+    pub fn promotable(self) -> bool {
+        !matches!(self, PieceKind::Gold | PieceKind::King)
+    }
+}
+
+pub const HAND_KINDS: [PieceKind; 7] = [
+    PieceKind::Pawn,
+    PieceKind::Lance,
+    PieceKind::Knight,
+    PieceKind::Silver,
+    PieceKind::Gold,
+    PieceKind::Bishop,
+    PieceKind::Rook,
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Piece {
+    pub kind: PieceKind,
+    pub color: Color,
+    /// 成駒（と / 成香 / 成桂 / 成銀 / 馬 / 龍）。
+    pub promoted: bool,
+}
+
+/// `[col, row]`：col 0–8 為先手視角由左到右（9 筋 → 1 筋），row 0–8（先手底線 row 0）。
+pub type Coord = (i8, i8);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Move {
+    /// 盤上走子；`promote` 為這步是否成。
+    Board { from: Coord, to: Coord, promote: bool },
+    /// 打入持駒。
+    Drop { kind: PieceKind, to: Coord },
+}
+
+pub type Board = [[Option<Piece>; 9]; 9];
+
+#[derive(Debug, Clone)]
+pub struct State {
+    pub board: Board,
+    pub turn: Color,
+    /// 持駒數 `[先手, 後手][HAND_KINDS 索引]`。
+    pub hands: [[u8; 7]; 2],
+    /// 目前局面的 Zobrist hash（見 `engine::position_hash`）。
+    pub hash: u64,
+    /// 開局以來每一步的紀錄（`engine::play` 寫入），判千日手用。持駒可再打入，吃子後仍可能回到舊局面，不清空。
+    pub history: Vec<PlyMark>,
+}
+
+/// 一步的千日手判定資訊。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PlyMark {
+    /// 走這步之前的局面 hash（含持駒、行棋方）。
+    pub hash: u64,
+    /// 這步王手。
+    pub check: bool,
+}
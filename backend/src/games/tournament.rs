@@ -0,0 +1,436 @@
+//! 錦標賽的純計算：循環賽 / 瑞士制配對、積分與小分（Buchholz、Sonneborn-Berger）。
+//!
+//! 零 DB / WS 依賴：參賽者以會員 id 表示、順序即報名順序（同分時的最後依據），
+//! 狀態由 `services::game_tournaments` 從 Postgres 讀出後餵進來。
+
+use std::collections::{HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use super::common::clock::TimeControl;
+
+/// 賽制。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// 單循環：每人與其他人各下一盤。
+    RoundRobin,
+    /// 瑞士制：每輪依積分配對、不重複對手，輪數由主辦指定。
+    Swiss,
+}
+
+impl Format {
+    pub fn parse(s: &str) -> Option<Self> {
+        match s {
+            "round_robin" => Some(Format::RoundRobin),
+            "swiss" => Some(Format::Swiss),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Format::RoundRobin => "round_robin",
+            Format::Swiss => "swiss",
+        }
+    }
+}
+
+/// 一組對陣：`first` 執先手；`second` 為 None 是輪空（記勝）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pairing {
+    pub first: i64,
+    pub second: Option<i64>,
+}
+
+/// 已完成的一盤（含輪空與不戰）。分數為 1 / 0.5 / 0。
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GameResult {
+    pub first: i64,
+    pub second: Option<i64>,
+    pub first_score: f64,
+    pub second_score: f64,
+}
+
+/// 單循環的總輪數：偶數人 n-1 輪；奇數人補一個輪空位，n 輪。
+pub fn round_robin_rounds(players: usize) -> u32 {
+    if players.is_multiple_of(2) {
+        players.saturating_sub(1) as u32
+    } else {
+        players as u32
+    }
+}
+
+/// 開賽時定下的總輪數：循環賽依人數；瑞士制取主辦指定的輪數，但不超過單循環的輪數 ——
+/// 再多就一定有人得重賽。
+pub fn total_rounds(format: Format, requested: u32, players: usize) -> u32 {
+    match format {
+        Format::RoundRobin => round_robin_rounds(players),
+        Format::Swiss => requested.min(round_robin_rounds(players)),
+    }
+}
+
+/// 單循環第 `round` 輪（1 起算）的對陣。圓桌法：第一人固定、其餘每輪轉一格；
+/// 奇數人補一個輪空位。固定那人的先後手逐輪交替，其餘對陣依桌次交替，整體先後手大致平均。
+pub fn round_robin_pairings(players: &[i64], round: u32) -> Vec<Pairing> {
+    let mut slots: Vec<Option<i64>> = players.iter().copied().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let n = slots.len();
+    if n < 2 {
+        return Vec::new();
+    }
+    let r = (round.max(1) as usize - 1) % (n - 1);
+    // 第 0 格固定，其餘 n-1 格右轉 r 格
+    let mut order = vec![slots[0]];
+    order.extend((0..n - 1).map(|i| slots[1 + (i + n - 1 - r) % (n - 1)]));
+    (0..n / 2)
+        .filter_map(|board| {
+            let (a, b) = (order[board], order[n - 1 - board]);
+            let swap = if board == 0 { r % 2 == 1 } else { board % 2 == 1 };
+            let (first, second) = if swap { (b, a) } else { (a, b) };
+            match (first, second) {
+                (Some(f), s) => Some(Pairing { first: f, second: s }),
+                (None, Some(s)) => Some(Pairing { first: s, second: None }),
+                (None, None) => None,
+            }
+        })
+        .collect()
+}
+
+/// 每人目前積分。
+fn points(players: &[i64], results: &[GameResult]) -> HashMap<i64, f64> {
+    let mut pts: HashMap<i64, f64> = players.iter().map(|&p| (p, 0.0)).collect();
+    for r in results {
+        *pts.entry(r.first).or_default() += r.first_score;
+        if let Some(s) = r.second {
+            *pts.entry(s).or_default() += r.second_score;
+        }
+    }
+    pts
+}
+
+/// 瑞士制下一輪的對陣。
+///
+/// 依積分排序（同分依報名順序），由上往下替每人找分數最接近、尚未交手的對手，走不通就回溯。
+/// 奇數人數時輪空位排在最後一起搜，所以輪空落在最低分、尚未輪空過的人身上，
+/// 但若這樣會讓其他人排不開，也會改由別人輪空。
+/// 搜尋有步數上限（`MATCH_BUDGET`）；排不出或超過上限就依序放寬：先允許重賽，再允許重複輪空。
+/// 先後手：至今執先次數較少者執先，相同則排名高者執先。
+pub fn swiss_pairings(players: &[i64], results: &[GameResult]) -> Vec<Pairing> {
+    let pts = points(players, results);
+    let mut played: HashSet<(i64, i64)> = HashSet::new();
+    let mut firsts: HashMap<i64, u32> = HashMap::new();
+    let mut had_bye: HashSet<i64> = HashSet::new();
+    for r in results {
+        match r.second {
+            Some(s) => {
+                played.insert((r.first, s));
+                played.insert((s, r.first));
+                *firsts.entry(r.first).or_default() += 1;
+            }
+            None => {
+                had_bye.insert(r.first);
+            }
+        }
+    }
+
+    let mut ranked: Vec<i64> = players.to_vec();
+    // sort_by 是穩定排序：同分保留報名順序
+    ranked.sort_by(|a, b| pts[b].total_cmp(&pts[a]));
+
+    let mut slots: Vec<Option<i64>> = ranked.into_iter().map(Some).collect();
+    if slots.len() % 2 == 1 {
+        slots.push(None);
+    }
+    let fresh = |a: i64, b: Option<i64>| match b {
+        Some(b) => !played.contains(&(a, b)),
+        None => !had_bye.contains(&a),
+    };
+    let no_second_bye = |a: i64, b: Option<i64>| b.is_some() || !had_bye.contains(&a);
+    let anything = |_: i64, _: Option<i64>| true;
+    let rules: [&dyn Fn(i64, Option<i64>) -> bool; 3] = [&fresh, &no_second_bye, &anything];
+    let pairs = rules.into_iter().find_map(|allowed| match_players(&slots, allowed, &mut MATCH_BUDGET.clone()));
+
+    let mut out = Vec::new();
+    let mut games = Vec::new();
+    for (a, b) in pairs.unwrap_or_default() {
+        match b {
+            None => out.push(Pairing { first: a, second: None }),
+            Some(b) => games.push((a, b)),
+        }
+    }
+    for (a, b) in games {
+        let (fa, fb) = (firsts.get(&a).copied().unwrap_or(0), firsts.get(&b).copied().unwrap_or(0));
+        let (first, second) = if fb < fa { (b, a) } else { (a, b) };
+        out.push(Pairing { first, second: Some(second) });
+    }
+    out
+}
+
+/// `match_players` 每次配對的搜尋步數上限。一般人數下遠用不到；只防最壞情形的指數回溯
+/// 卡住 `settle` 的交易 —— 超過就當這組條件排不出，改用較寬的條件。
+const MATCH_BUDGET: u32 = 10_000;
+
+/// 依序替排名最高的未配對者配人（回溯法），回傳 `(較高排名, 較低排名)`；None 為輪空位。
+/// `allowed(a, b)` 決定這組能不能配；每試一組扣一步 `budget`，扣完即放棄回 None。
+fn match_players(
+    slots: &[Option<i64>],
+    allowed: &dyn Fn(i64, Option<i64>) -> bool,
+    budget: &mut u32,
+) -> Option<Vec<(i64, Option<i64>)>> {
+    let Some((&top, rest)) = slots.split_first() else {
+        return Some(Vec::new());
+    };
+    for (i, &opp) in rest.iter().enumerate() {
+        let (a, b) = match (top, opp) {
+            (Some(a), b) => (a, b),
+            (None, Some(b)) => (b, None),
+            (None, None) => continue,
+        };
+        if !allowed(a, b) {
+            continue;
+        }
+        if *budget == 0 {
+            return None;
+        }
+        *budget -= 1;
+        let mut remaining = rest.to_vec();
+        remaining.remove(i);
+        if let Some(mut tail) = match_players(&remaining, allowed, budget) {
+            tail.insert(0, (a, b));
+            return Some(tail);
+        }
+    }
+    None
+}
+
+/// 名次表的一列。
+#[derive(Debug, Clone, Serialize)]
+pub struct Standing {
+    /// 積分與兩項小分都相同者同名次（1, 2, 2, 4）。
+    pub rank: u32,
+    pub member_id: i64,
+    pub points: f64,
+    /// 對手們的積分總和（輪空不計）。
+    pub buchholz: f64,
+    /// 對每位對手：本人得分 × 對手積分，加總。
+    pub sonneborn_berger: f64,
+    /// 已完成的盤數（含輪空）。
+    pub games: u32,
+}
+
+/// 依積分 → Buchholz → Sonneborn-Berger 排名；全部相同依報名順序列出、名次並列。
+pub fn standings(players: &[i64], results: &[GameResult]) -> Vec<Standing> {
+    let pts = points(players, results);
+    let mut rows: Vec<Standing> = players
+        .iter()
+        .map(|&p| Standing { rank: 0, member_id: p, points: pts[&p], buchholz: 0.0, sonneborn_berger: 0.0, games: 0 })
+        .collect();
+    let index: HashMap<i64, usize> = players.iter().enumerate().map(|(i, &p)| (p, i)).collect();
+    let mut credit = |me: i64, opp: Option<i64>, score: f64| {
+        let Some(&i) = index.get(&me) else { return };
+        rows[i].games += 1;
+        if let Some(opp_pts) = opp.and_then(|o| pts.get(&o)) {
+            rows[i].buchholz += opp_pts;
+            rows[i].sonneborn_berger += score * opp_pts;
+        }
+    };
+    for r in results {
+        credit(r.first, r.second, r.first_score);
+        if let Some(s) = r.second {
+            credit(s, Some(r.first), r.second_score);
+        }
+    }
+    let key = |s: &Standing| (s.points, s.buchholz, s.sonneborn_berger);
+    rows.sort_by(|a, b| {
+        let (ka, kb) = (key(a), key(b));
+        kb.0.total_cmp(&ka.0).then(kb.1.total_cmp(&ka.1)).then(kb.2.total_cmp(&ka.2))
+    });
+    for i in 0..rows.len() {
+        rows[i].rank = if i > 0 && key(&rows[i]) == key(&rows[i - 1]) { rows[i - 1].rank } else { i as u32 + 1 };
+    }
+    rows
+}
+
+/// 一輪要開的桌（`services::game_tournaments` 組好、交給對應遊戲的 hub 開局）。
+pub struct RoundPlan {
+    pub tournament_id: i64,
+    pub name: String,
+    pub round: i32,
+    pub time_control: TimeControl,
+    pub options: Value,
+    pub games: Vec<PlannedGame>,
+    /// 所有參賽者（廣播 `tournament_round` 用）。
+    pub entrants: Vec<i64>,
+}
+
+/// 一組對陣：`id` 為 `game_tournament_pairings.id`，玩家為 (會員 id, 顯示名)。
+pub struct PlannedGame {
+    pub id: i64,
+    pub first: (i64, String),
+    pub second: Option<(i64, String)>,
+}
+
+/// 對局中的錦標賽身分（`Game::tournament`），完局時回報結果用。重啟保存跟著對局存。
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct TournamentSeat {
+    pub tournament_id: i64,
+    pub pairing_id: i64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(first: i64, second: i64, first_score: f64) -> GameResult {
+        GameResult { first, second: Some(second), first_score, second_score: 1.0 - first_score }
+    }
+
+    #[test]
+    fn round_robin_meets_everyone_once() {
+        for n in 2..=7 {
+            let players: Vec<i64> = (1..=n).collect();
+            let rounds = round_robin_rounds(players.len());
+            let mut met = HashSet::new();
+            let mut byes = 0;
+            for round in 1..=rounds {
+                let pairings = round_robin_pairings(&players, round);
+                let mut seen = HashSet::new();
+                for p in &pairings {
+                    assert!(seen.insert(p.first));
+                    match p.second {
+                        Some(s) => {
+                            assert!(seen.insert(s));
+                            assert!(met.insert((p.first.min(s), p.first.max(s))), "n={n} 重複對陣");
+                        }
+                        None => byes += 1,
+                    }
+                }
+                assert_eq!(seen.len(), players.len());
+            }
+            assert_eq!(met.len() as i64, n * (n - 1) / 2);
+            assert_eq!(byes, if n % 2 == 1 { n } else { 0 });
+        }
+    }
+
+    #[test]
+    fn swiss_rounds_are_capped_by_the_field() {
+        assert_eq!(total_rounds(Format::Swiss, 20, 4), 3);
+        assert_eq!(total_rounds(Format::Swiss, 20, 5), 5);
+        assert_eq!(total_rounds(Format::Swiss, 4, 30), 4);
+        assert_eq!(total_rounds(Format::RoundRobin, 0, 6), 5);
+    }
+
+    #[test]
+    fn swiss_first_round_pairs_by_registration_order() {
+        let pairings = swiss_pairings(&[1, 2, 3, 4], &[]);
+        assert_eq!(pairings, vec![Pairing { first: 1, second: Some(2) }, Pairing { first: 3, second: Some(4) }]);
+    }
+
+    #[test]
+    fn swiss_avoids_rematches_and_gives_bye_to_lowest() {
+        // 1 勝 2、3 勝 4、5 輪空 → 第二輪：5 已輪空過，由最低分的 4 輪空；1 不可再遇 2
+        let results = vec![
+            result(1, 2, 1.0),
+            result(3, 4, 1.0),
+            GameResult { first: 5, second: None, first_score: 1.0, second_score: 0.0 },
+        ];
+        let pairings = swiss_pairings(&[1, 2, 3, 4, 5], &results);
+        assert!(pairings.contains(&Pairing { first: 4, second: None }));
+        for p in &pairings {
+            if let Some(s) = p.second {
+                assert_ne!((p.first.min(s), p.first.max(s)), (1, 2));
+                assert_ne!((p.first.min(s), p.first.max(s)), (3, 4));
+            }
+        }
+        assert_eq!(pairings.len(), 3);
+    }
+
+    /// 四人不重賽的配對。
+    fn match_four(played: &HashSet<(i64, i64)>) -> Option<Vec<(i64, Option<i64>)>> {
+        let allowed = |a: i64, b: Option<i64>| b.is_some_and(|b| !played.contains(&(a, b)));
+        match_players(&[Some(1), Some(2), Some(3), Some(4)], &allowed, &mut MATCH_BUDGET.clone())
+    }
+
+    #[test]
+    fn matching_backtracks_when_the_greedy_choice_strands_the_last_pair() {
+        // 1-2 先配會讓 3-4 重賽 → 退回改配 1-3、2-4
+        let played = HashSet::from([(3, 4), (4, 3), (1, 4), (4, 1)]);
+        assert_eq!(match_four(&played), Some(vec![(1, Some(3)), (2, Some(4))]));
+        let all = HashSet::from([(1, 2), (2, 1), (1, 3), (3, 1), (1, 4), (4, 1)]);
+        assert_eq!(match_four(&all), None);
+    }
+
+    #[test]
+    fn bye_moves_up_when_the_lowest_pick_strands_the_rest() {
+        // 5 已輪空過、也下過 1、2、3 → 若照舊給最低分的 4 輪空，5 只能重賽；
+        // 改由 3 輪空，1-2、5-4 都是新對手
+        let results = vec![
+            result(1, 5, 1.0),
+            result(2, 5, 1.0),
+            result(3, 5, 1.0),
+            GameResult { first: 5, second: None, first_score: 1.0, second_score: 0.0 },
+        ];
+        let pairings = swiss_pairings(&[1, 2, 3, 4, 5], &results);
+        assert_eq!(
+            pairings,
+            vec![
+                Pairing { first: 3, second: None },
+                Pairing { first: 1, second: Some(2) },
+                Pairing { first: 5, second: Some(4) },
+            ]
+        );
+    }
+
+    #[test]
+    fn exhausted_search_falls_back_to_rematches() {
+        // 十人、每人都與其他所有人下過：嚴格條件無解，搜尋受上限保護，放寬後仍給出完整的一輪
+        let players: Vec<i64> = (1..=10).collect();
+        let results: Vec<GameResult> =
+            players.iter().flat_map(|&a| players.iter().filter(move |&&b| a < b).map(move |&b| result(a, b, 0.5))).collect();
+        let pairings = swiss_pairings(&players, &results);
+        assert_eq!(pairings.len(), 5);
+        let seen: HashSet<i64> = pairings.iter().flat_map(|p| [Some(p.first), p.second]).flatten().collect();
+        assert_eq!(seen.len(), 10);
+
+        // 只准同奇偶相配、兩邊各 21 人：無解，而且不設上限要試上天文數字的組合
+        let mut budget = MATCH_BUDGET;
+        let slots: Vec<Option<i64>> = (1..=42).map(Some).collect();
+        assert_eq!(match_players(&slots, &|a, b| b.is_some_and(|b| (a + b) % 2 == 0), &mut budget), None);
+        assert_eq!(budget, 0, "無解的搜尋必須在上限內停下");
+    }
+
+    #[test]
+    fn standings_break_ties_with_buchholz_then_sonneborn_berger() {
+        // 1 勝 2、2 勝 3、3 勝 1（三人各 1 分）；4 輸給 1、和 2、和 3
+        let results = vec![
+            result(1, 2, 1.0),
+            result(2, 3, 1.0),
+            result(3, 1, 1.0),
+            result(4, 1, 0.0),
+            result(2, 4, 0.5),
+            result(3, 4, 0.5),
+        ];
+        let table = standings(&[1, 2, 3, 4], &results);
+        let order: Vec<i64> = table.iter().map(|s| s.member_id).collect();
+        // 1: 2 分；2、3: 1.5 分且 Buchholz 相同，3 的 SB 較高（3 贏的是 2 分的 1、2 贏的是 1.5 分的 3）
+        assert_eq!(table[0].member_id, 1);
+        assert_eq!(table[0].points, 2.0);
+        assert_eq!(order[3], 4);
+        let two = table.iter().find(|s| s.member_id == 2).unwrap();
+        let three = table.iter().find(|s| s.member_id == 3).unwrap();
+        assert_eq!(two.buchholz, three.buchholz);
+        assert_eq!(two.sonneborn_berger, 1.5 + 0.5 * 1.0);
+        assert_eq!(three.sonneborn_berger, 2.0 + 0.5 * 1.0);
+        assert_eq!(order[1], 3);
+        assert_eq!(table.iter().map(|s| s.games).sum::<u32>(), 12);
+    }
+
+    #[test]
+    fn identical_scores_share_a_rank() {
+        let table = standings(&[1, 2, 3], &[result(1, 2, 0.5)]);
+        assert_eq!(table.iter().map(|s| s.rank).collect::<Vec<_>>(), vec![1, 1, 3]);
+    }
+}
@@ -0,0 +1,174 @@
+//! 西洋棋電腦：alpha-beta + 子力 / 位置評估，吃子依 MVV-LVA 排序並做靜態搜尋。
+
+use std::time::Duration;
+
+use super::engine;
+use super::types::{Color, Move, PieceKind, State};
+use crate::games::common::bot::{self as search, BotLevel, SearchLimits, Searchable, MATE};
+
+fn value(k: PieceKind) -> i32 {
+    match k {
+        PieceKind::Pawn => 100,
+        PieceKind::Knight => 320,
+        PieceKind::Bishop => 330,
+        PieceKind::Rook => 500,
+        PieceKind::Queen => 900,
+        PieceKind::King => 0,
+    }
+}
+
+/// 位置分：中心化（馬、象、后、中局王以外），兵另加推進分。`row` 已轉成己方視角（0 = 底線）。
+fn placement(k: PieceKind, col: i8, row: i8) -> i32 {
+    // 離中心的棋盤距離 0..=3
+    let center = (2 * col - 7).abs().max((2 * row - 7).abs()) / 2;
+    match k {
+        PieceKind::Pawn => row as i32 * 8 + if (2..=5).contains(&col) { 5 } else { 0 },
+        PieceKind::Knight => (3 - center as i32) * 10,
+        PieceKind::Bishop | PieceKind::Queen => (3 - center as i32) * 4,
+        PieceKind::Rook => if row == 6 { 15 } else { 0 },
+        // 王躲在底線（終局不調，夠用）
+        PieceKind::King => if row == 0 { 10 } else { -(row as i32) * 10 },
+    }
+}
+
+#[derive(Clone)]
+struct Pos(State);
+
+impl Pos {
+    fn victim(&self, mv: &Move) -> Option<PieceKind> {
+        let s = &self.0;
+        match s.board[mv.to.1 as usize][mv.to.0 as usize] {
+            Some(p) => Some(p.kind),
+            None if s.ep == Some(mv.to)
+                && s.board[mv.from.1 as usize][mv.from.0 as usize].is_some_and(|p| p.kind == PieceKind::Pawn) =>
+            {
+                Some(PieceKind::Pawn)
+            }
+            None => None,
+        }
+    }
+
+    /// MVV-LVA 排序鍵（越大越先）；升變也算吵。
+    fn order_key(&self, mv: &Move) -> i32 {
+        let attacker = self.0.board[mv.from.1 as usize][mv.from.0 as usize].map_or(0, |p| value(p.kind));
+        let capture = self.victim(mv).map_or(0, |v| value(v) * 10 - attacker / 10);
+        capture + mv.promo.map_or(0, value)
+    }
+
+    fn sorted(&self, mut moves: Vec<Move>) -> Vec<Move> {
+        moves.sort_by_cached_key(|mv| std::cmp::Reverse(self.order_key(mv)));
+        moves
+    }
+}
+
+impl Searchable for Pos {
+    type Move = Move;
+
+    fn moves(&self) -> Vec<Move> {
+        self.sorted(engine::legal_moves(&self.0))
+    }
+
+    fn play(&mut self, mv: Move) {
+        engine::apply(&mut self.0, mv);
+    }
+
+    fn evaluate(&self) -> i32 {
+        let mut score = 0;
+        for (r, line) in self.0.board.iter().enumerate() {
+            for (c, cell) in line.iter().enumerate() {
+                let Some(p) = cell else { continue };
+                let row = match p.color {
+                    Color::White => r as i8,
+                    Color::Black => 7 - r as i8,
+                };
+                let v = value(p.kind) + placement(p.kind, c as i8, row);
+                score += if p.color == self.0.turn { v } else { -v };
+            }
+        }
+        score
+    }
+
+    /// 自動和，或已可提和的三次重複（搜尋裡視同和，避免贏棋時繞圈、輸棋時錯過和局）。
+    fn terminal(&self) -> Option<i32> {
+        (matches!(engine::status(&self.0), engine::Outcome::Draw(_)) || engine::repetitions(&self.0) >= 3)
+            .then_some(0)
+    }
+
+    fn no_moves_score(&self) -> i32 {
+        if engine::is_in_check(&self.0, self.0.turn) {
+            -MATE
+        } else {
+            0
+        }
+    }
+
+    fn noisy_moves(&self) -> Vec<Move> {
+        let moves = engine::legal_moves(&self.0)
+            .into_iter()
+            .filter(|mv| self.victim(mv).is_some() || mv.promo == Some(PieceKind::Queen))
+            .collect();
+        self.sorted(moves)
+    }
+}
+
+/// 為行棋方選一步。
+pub fn choose(state: &State, level: BotLevel) -> Option<Move> {
+    let limits = match level {
+        BotLevel::Easy => SearchLimits { max_depth: 1, budget: Duration::from_millis(300), noise: 120 },
+        BotLevel::Normal => SearchLimits { max_depth: 3, budget: Duration::from_secs(1), noise: 15 },
+        BotLevel::Hard => SearchLimits { max_depth: 6, budget: Duration::from_millis(2500), noise: 0 },
+    };
+    search::best_move(&Pos(state.clone()), limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::western_chess::types::Piece;
+
+    fn empty(turn: Color) -> State {
+        let mut s = engine::initial_state();
+        s.board = [[None; 8]; 8];
+        s.castling = [false; 4];
+        s.turn = turn;
+        s
+    }
+
+    fn put(s: &mut State, (c, r): (i8, i8), kind: PieceKind, color: Color) {
+        s.board[r as usize][c as usize] = Some(Piece { kind, color });
+    }
+
+    #[test]
+    fn finds_back_rank_mate() {
+        // 白 Ra1、Kg1；黑 Kg8，兵 f7 g7 h7 → Ra8#
+        let mut s = empty(Color::White);
+        put(&mut s, (0, 0), PieceKind::Rook, Color::White);
+        put(&mut s, (6, 0), PieceKind::King, Color::White);
+        put(&mut s, (6, 7), PieceKind::King, Color::Black);
+        for c in 5..8 {
+            put(&mut s, (c, 6), PieceKind::Pawn, Color::Black);
+        }
+        let mv = choose(&s, BotLevel::Normal).unwrap();
+        assert_eq!((mv.from, mv.to), ((0, 0), (0, 7)));
+    }
+
+    #[test]
+    fn takes_hanging_queen() {
+        let mut s = empty(Color::Black);
+        put(&mut s, (4, 0), PieceKind::King, Color::White);
+        put(&mut s, (3, 3), PieceKind::Queen, Color::White);
+        put(&mut s, (4, 7), PieceKind::King, Color::Black);
+        put(&mut s, (2, 5), PieceKind::Knight, Color::Black);
+        for level in [BotLevel::Normal, BotLevel::Hard] {
+            let mv = choose(&s, level).unwrap();
+            assert_eq!(mv.to, (3, 3), "{level:?}");
+        }
+    }
+
+    #[test]
+    fn opening_move_is_legal() {
+        let s = engine::initial_state();
+        let mv = choose(&s, BotLevel::Easy).unwrap();
+        assert!(engine::legal_moves(&s).contains(&mv));
+    }
+}
@@ -1,9 +1,17 @@
 //! 西洋棋規則引擎 — 純函式，零 WS 依賴。標準走法 + 王車易位 + 吃過路兵 + 升變
-//! + 將軍/將死/逼和 + 50 步和 + 子力不足和。
+//! + 將軍/將死/逼和 + 子力不足和 + 重複局面 / 無吃子步數和（FIDE：3 次 / 50 步可提和、5 次 / 75 步自動和）。
 
 use super::types::{ApplyInfo, Castle, Color, Move, Piece, PieceKind, State};
+use crate::games::common::zobrist;
 
-const N_HALFMOVE_DRAW: u32 = 100; // 50 步（雙方各 50）無吃子/無兵走 → 和
+const N_HALFMOVE_CLAIM: u32 = 100; // 50 步（雙方各 50）無吃子/無兵走 → 可提和
+const N_HALFMOVE_DRAW: u32 = 150; // 75 步 → 自動和
+
+// Zobrist 鍵配置：12 種棋子 × 64 格、4 個易位權、8 個過路兵 file、黑方行棋。
+const Z_CASTLING: usize = 12 * 64;
+const Z_EP: usize = Z_CASTLING + 4;
+const Z_BLACK: usize = Z_EP + 8;
+const ZOBRIST: [u64; Z_BLACK + 1] = zobrist::table();
 
 pub fn initial_state() -> State {
     use Color::*;
@@ -16,13 +24,60 @@ pub fn initial_state() -> State {
         board[1][c] = Some(Piece { kind: Pawn, color: White });
         board[6][c] = Some(Piece { kind: Pawn, color: Black });
     }
-    State {
+    let mut state = State {
         board,
         turn: White,
         castling: [true; 4], // WK, WQ, BK, BQ
         ep: None,
         halfmove: 0,
+        hash: 0,
+        history: Vec::new(),
+    };
+    state.hash = position_hash(&state);
+    state
+}
+
+/// 局面 Zobrist hash：棋子、行棋方、易位權，以及「真的吃得到」的過路兵
+/// （行棋方有兵在旁邊才算，否則 ep 格不影響局面是否相同）。
+pub fn position_hash(state: &State) -> u64 {
+    let mut h = 0;
+    for (r, line) in state.board.iter().enumerate() {
+        for (c, cell) in line.iter().enumerate() {
+            if let Some(p) = cell {
+                let piece = p.color as usize * 6 + p.kind as usize;
+                h ^= ZOBRIST[piece * 64 + r * 8 + c];
+            }
+        }
+    }
+    for (i, &right) in state.castling.iter().enumerate() {
+        if right {
+            h ^= ZOBRIST[Z_CASTLING + i];
+        }
+    }
+    if let Some((c, r)) = state.ep {
+        // ep 格在被吃兵後方：吃方的兵與被吃兵同列
+        let pawn_r = match state.turn {
+            Color::White => r - 1,
+            Color::Black => r + 1,
+        };
+        let capturable = [c - 1, c + 1].into_iter().any(|pc| {
+            (0..8).contains(&pc)
+                && state.board[pawn_r as usize][pc as usize]
+                    == Some(Piece { kind: PieceKind::Pawn, color: state.turn })
+        });
+        if capturable {
+            h ^= ZOBRIST[Z_EP + c as usize];
+        }
     }
+    if state.turn == Color::Black {
+        h ^= ZOBRIST[Z_BLACK];
+    }
+    h
+}
+
+/// 目前局面（含本次）出現過幾次。
+pub fn repetitions(state: &State) -> usize {
+    1 + state.history.iter().filter(|&&h| h == state.hash).count()
 }
 
 pub fn turn(state: &State) -> Color {
@@ -330,14 +385,17 @@ pub fn apply(state: &mut State, mv: Move) -> ApplyInfo {
         None
     };
 
-    // 50 步計數
+    // 50 步計數；吃子 / 兵走後不可能再回到之前的局面，重複紀錄一併清掉
     if piece.kind == PieceKind::Pawn || captured || ep_capture {
         state.halfmove = 0;
+        state.history.clear();
     } else {
         state.halfmove = state.halfmove.saturating_add(1);
+        state.history.push(state.hash);
     }
 
     state.turn = me.opponent();
+    state.hash = position_hash(state);
     info
 }
 
@@ -369,7 +427,34 @@ pub enum Outcome {
     Continue,
     Checkmate { winner: Color },
     Stalemate,
-    Draw, // 50 步 / 子力不足
+    Draw(DrawReason),
+}
+
+/// 和棋原因。前兩者自動成立（`status`），後兩者須由一方提出（`claimable_draw`）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DrawReason {
+    /// 同一局面第 5 次出現。
+    Fivefold,
+    /// 75 步無吃子 / 無兵走。
+    SeventyFiveMoves,
+    InsufficientMaterial,
+    /// 同一局面第 3 次出現（可提和）。
+    Repetition,
+    /// 50 步無吃子 / 無兵走（可提和）。
+    FiftyMoves,
+}
+
+impl DrawReason {
+    /// `game_over.reason` 字串。
+    pub fn as_str(self) -> &'static str {
+        match self {
+            DrawReason::Fivefold => "fivefold",
+            DrawReason::SeventyFiveMoves => "seventy_five_moves",
+            DrawReason::InsufficientMaterial => "insufficient_material",
+            DrawReason::Repetition => "repetition",
+            DrawReason::FiftyMoves => "fifty_moves",
+        }
+    }
 }
 
 fn insufficient_material(state: &State) -> bool {
@@ -396,11 +481,28 @@ pub fn status(state: &State) -> Outcome {
             Outcome::Stalemate
         };
     }
-    if state.halfmove >= N_HALFMOVE_DRAW || insufficient_material(state) {
-        return Outcome::Draw;
+    if repetitions(state) >= 5 {
+        return Outcome::Draw(DrawReason::Fivefold);
+    }
+    if state.halfmove >= N_HALFMOVE_DRAW {
+        return Outcome::Draw(DrawReason::SeventyFiveMoves);
+    }
+    if insufficient_material(state) {
+        return Outcome::Draw(DrawReason::InsufficientMaterial);
     }
     Outcome::Continue
 }
 
+/// 目前局面可提和的理由（3 次重複優先於 50 步）；對局已結束時無意義，呼叫端先看 `status`。
+pub fn claimable_draw(state: &State) -> Option<DrawReason> {
+    if repetitions(state) >= 3 {
+        Some(DrawReason::Repetition)
+    } else if state.halfmove >= N_HALFMOVE_CLAIM {
+        Some(DrawReason::FiftyMoves)
+    } else {
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests;
@@ -8,6 +8,8 @@ fn empty() -> State {
         castling: [false; 4],
         ep: None,
         halfmove: 0,
+        hash: 0,
+        history: Vec::new(),
     }
 }
 
@@ -111,10 +113,13 @@ fn stalemate_detected() {
 }
 
 #[test]
-fn fifty_move_rule_draws() {
+fn fifty_moves_claimable_seventy_five_automatic() {
     let mut s = initial_state();
     s.halfmove = 100;
-    assert_eq!(status(&s), Outcome::Draw);
+    assert_eq!(status(&s), Outcome::Continue);
+    assert_eq!(claimable_draw(&s), Some(DrawReason::FiftyMoves));
+    s.halfmove = 150;
+    assert_eq!(status(&s), Outcome::Draw(DrawReason::SeventyFiveMoves));
 }
 
 #[test]
@@ -122,7 +127,71 @@ fn insufficient_material_draws() {
     let mut s = empty();
     put(&mut s, 4, 0, PieceKind::King, Color::White);
     put(&mut s, 4, 7, PieceKind::King, Color::Black);
-    assert_eq!(status(&s), Outcome::Draw);
+    assert_eq!(status(&s), Outcome::Draw(DrawReason::InsufficientMaterial));
+}
+
+/// 雙方馬跳出去再跳回來 = 初始局面再出現一次。
+fn knight_shuffle(s: &mut State) {
+    for (from, to) in [((6, 0), (5, 2)), ((6, 7), (5, 5)), ((5, 2), (6, 0)), ((5, 5), (6, 7))] {
+        apply(s, m(from, to));
+    }
+}
+
+#[test]
+fn threefold_claimable_fivefold_automatic() {
+    let mut s = initial_state();
+    knight_shuffle(&mut s);
+    assert_eq!(repetitions(&s), 2);
+    assert_eq!(claimable_draw(&s), None);
+    knight_shuffle(&mut s);
+    assert_eq!(repetitions(&s), 3);
+    assert_eq!(claimable_draw(&s), Some(DrawReason::Repetition));
+    assert_eq!(status(&s), Outcome::Continue);
+    knight_shuffle(&mut s);
+    knight_shuffle(&mut s);
+    assert_eq!(status(&s), Outcome::Draw(DrawReason::Fivefold));
+}
+
+#[test]
+fn hash_is_incremental_and_pawn_moves_clear_repetition_history() {
+    let mut s = initial_state();
+    knight_shuffle(&mut s);
+    assert_eq!(s.hash, initial_state().hash);
+    apply(&mut s, m((4, 1), (4, 3)));
+    assert!(s.history.is_empty());
+    assert_eq!(s.hash, position_hash(&s));
+}
+
+#[test]
+fn castling_rights_change_the_position() {
+    // 王走出去再走回來：棋子位置相同，但易位權沒了 → 不同局面
+    let mut s = empty();
+    put(&mut s, 4, 0, PieceKind::King, Color::White);
+    put(&mut s, 7, 0, PieceKind::Rook, Color::White);
+    put(&mut s, 4, 7, PieceKind::King, Color::Black);
+    s.castling = [true, false, false, false];
+    s.hash = position_hash(&s);
+    let start = s.hash;
+    for (from, to) in [((4, 0), (4, 1)), ((4, 7), (4, 6)), ((4, 1), (4, 0)), ((4, 6), (4, 7))] {
+        apply(&mut s, m(from, to));
+    }
+    assert_ne!(s.hash, start);
+    assert_eq!(repetitions(&s), 1);
+}
+
+#[test]
+fn en_passant_only_counts_when_capturable() {
+    // e4 之後黑方沒有兵能吃過路兵 → 與「無 ep 格」的同一盤面相同
+    let mut s = initial_state();
+    apply(&mut s, m((4, 1), (4, 3)));
+    let mut no_ep = s.clone();
+    no_ep.ep = None;
+    assert_eq!(position_hash(&s), position_hash(&no_ep));
+    // 黑兵在 d4 時 ep 可吃 → 不同
+    put(&mut s, 3, 3, PieceKind::Pawn, Color::Black);
+    let mut no_ep = s.clone();
+    no_ep.ep = None;
+    assert_ne!(position_hash(&s), position_hash(&no_ep));
 }
 
 #[test]
@@ -2,12 +2,30 @@
 
 use serde_json::{json, Value};
 
+use super::bot;
 use super::engine;
+use super::notation;
 use super::types::{Castle, Color, Move, PieceKind, State};
+use crate::games::common::bot::{BotLevel, GameBot};
 use crate::games::common::engine::{Applied, GameEngine, GameStatus, Side};
+use crate::games::common::notation::{ExportMeta, Exported};
 
+#[derive(Clone)]
 pub struct WesternChessGame(State);
 
+pub struct WesternChessBot;
+
+impl GameBot<WesternChessGame> for WesternChessBot {
+    fn choose(engine: &WesternChessGame, level: BotLevel) -> Option<Value> {
+        let mv = bot::choose(&engine.0, level)?;
+        let mut data = json!({ "from": [mv.from.0, mv.from.1], "to": [mv.to.0, mv.to.1] });
+        if let Some(k) = mv.promo {
+            data["promo"] = json!(promo_str(k));
+        }
+        Some(data)
+    }
+}
+
 fn to_common(c: Color) -> Side {
     match c {
         Color::White => Side::First,
@@ -36,6 +54,28 @@ fn parse_promo(v: Option<&Value>) -> Result<Option<PieceKind>, ()> {
     }
 }
 
+/// 紀錄裡的一步（`move_made` data）還原成引擎的 `Move`；升變缺省為后（同 `try_move`）。
+fn recorded_move(state: &State, data: &Value) -> Option<Move> {
+    let from = parse_coord(data.get("from"))?;
+    let to = parse_coord(data.get("to"))?;
+    let promo = parse_promo(Some(data)).ok()?;
+    engine::legal_moves(state)
+        .into_iter()
+        .filter(|mv| mv.from == from && mv.to == to)
+        .find(|mv| mv.promo.is_none() || mv.promo == Some(promo.unwrap_or(PieceKind::Queen)))
+}
+
+fn kind_str(k: PieceKind) -> &'static str {
+    match k {
+        PieceKind::Pawn => "pawn",
+        PieceKind::Knight => "knight",
+        PieceKind::Bishop => "bishop",
+        PieceKind::Rook => "rook",
+        PieceKind::Queen => "queen",
+        PieceKind::King => "king",
+    }
+}
+
 fn promo_str(k: PieceKind) -> &'static str {
     match k {
         PieceKind::Queen => "q",
@@ -48,8 +88,9 @@ fn promo_str(k: PieceKind) -> &'static str {
 
 impl GameEngine for WesternChessGame {
     const NAME: &'static str = "western_chess";
+    type Bot = WesternChessBot;
 
-    fn initial() -> Self {
+    fn initial(_options: &Value) -> Self {
         WesternChessGame(engine::initial_state())
     }
 
@@ -118,13 +159,16 @@ impl GameEngine for WesternChessGame {
         }
 
         let mut extra = Vec::new();
-        if matches!(engine::status(&self.0), engine::Outcome::Continue)
-            && engine::is_in_check(&self.0, self.0.turn)
-        {
-            extra.push(("check", json!({ "side": Self::side_label(to_common(self.0.turn)) })));
+        if matches!(engine::status(&self.0), engine::Outcome::Continue) {
+            if engine::is_in_check(&self.0, self.0.turn) {
+                extra.push(("check", json!({ "side": Self::side_label(to_common(self.0.turn)) })));
+            }
+            if let Some(r) = engine::claimable_draw(&self.0) {
+                extra.push(("draw_claimable", json!({ "reason": r.as_str() })));
+            }
         }
 
-        Ok(Applied { move_data: Value::Object(md), extra })
+        Ok(Applied { move_data: Value::Object(md), extra, swap_seats: false })
     }
 
     fn status(&self) -> GameStatus {
@@ -135,7 +179,46 @@ impl GameEngine for WesternChessGame {
                 reason: "checkmate",
             },
             engine::Outcome::Stalemate => GameStatus::Draw { reason: "stalemate" },
-            engine::Outcome::Draw => GameStatus::Draw { reason: "draw" },
+            engine::Outcome::Draw(r) => GameStatus::Draw { reason: r.as_str() },
         }
     }
+
+    fn claimable_draw(&self) -> Option<&'static str> {
+        engine::claimable_draw(&self.0).map(|r| r.as_str())
+    }
+
+    fn snapshot(&self) -> Value {
+        let mut pieces = Vec::new();
+        for (row, line) in self.0.board.iter().enumerate() {
+            for (col, cell) in line.iter().enumerate() {
+                if let Some(p) = cell {
+                    pieces.push(json!({
+                        "at": [col, row],
+                        "color": Self::side_label(to_common(p.color)),
+                        "kind": kind_str(p.kind),
+                    }));
+                }
+            }
+        }
+        json!({
+            "pieces": pieces,
+            "castling": self.0.castling,
+            "ep": self.0.ep.map(|(c, r)| json!([c, r])),
+            "halfmove": self.0.halfmove,
+            "claimable_draw": engine::claimable_draw(&self.0).map(|r| r.as_str()),
+        })
+    }
+
+    fn export(plies: &[(&Self, &Value)], _last: &Self, meta: &ExportMeta) -> Option<Exported> {
+        let moves = plies
+            .iter()
+            .map(|(g, data)| recorded_move(&g.0, data).map(|mv| (&g.0, mv)))
+            .collect::<Option<Vec<_>>>()?;
+        Some(Exported {
+            format: "pgn",
+            extension: "pgn",
+            mime: "application/x-chess-pgn",
+            text: notation::pgn(&moves, meta),
+        })
+    }
 }
@@ -1,5 +1,7 @@
-//! 西洋棋：純函式規則引擎（`engine` + `types`）與共用框架接口（`game`）。
+//! 西洋棋：純函式規則引擎（`engine` + `types`）、電腦對手（`bot`）、記譜（`notation`）與共用框架接口（`game`）。
 
+pub mod bot;
 pub mod engine;
 pub mod game;
+pub mod notation;
 pub mod types;
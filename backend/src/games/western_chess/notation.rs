@@ -0,0 +1,358 @@
+//! 西洋棋記譜：SAN（標準代數記法）與 PGN 匯出、UCI 座標走步、FEN 讀入。
+
+use super::engine::{self, Outcome};
+use super::types::{Color, Move, Piece, PieceKind, State};
+use crate::games::common::notation::{pgn_movetext, pgn_result, pgn_tag, ExportMeta};
+
+fn file_char(col: i8) -> char {
+    (b'a' + col as u8) as char
+}
+
+fn rank_char(row: i8) -> char {
+    (b'1' + row as u8) as char
+}
+
+fn square(c: i8, r: i8) -> String {
+    format!("{}{}", file_char(c), rank_char(r))
+}
+
+fn letter(k: PieceKind) -> &'static str {
+    match k {
+        PieceKind::Pawn => "",
+        PieceKind::Knight => "N",
+        PieceKind::Bishop => "B",
+        PieceKind::Rook => "R",
+        PieceKind::Queen => "Q",
+        PieceKind::King => "K",
+    }
+}
+
+/// `mv` 在 `state`（走之前）的 SAN，例如 `e4`、`Nbd7`、`exd6`、`e8=Q+`、`O-O-O#`。假設 `mv` 合法。
+pub fn san(state: &State, mv: Move) -> String {
+    let piece = state.board[mv.from.1 as usize][mv.from.0 as usize].expect("合法步的起點必有子");
+    let mut out = if piece.kind == PieceKind::King && (mv.to.0 - mv.from.0).abs() == 2 {
+        if mv.to.0 > mv.from.0 { "O-O" } else { "O-O-O" }.to_string()
+    } else {
+        let capture = state.board[mv.to.1 as usize][mv.to.0 as usize].is_some()
+            || (piece.kind == PieceKind::Pawn && mv.from.0 != mv.to.0);
+        let mut s = letter(piece.kind).to_string();
+        if piece.kind == PieceKind::Pawn {
+            if capture {
+                s.push(file_char(mv.from.0));
+            }
+        } else {
+            // 同種子也能到同一格：先用起點 file、不夠再用 rank、都撞才兩者並列
+            let rivals: Vec<Move> = engine::legal_moves(state)
+                .into_iter()
+                .filter(|o| {
+                    o.to == mv.to
+                        && o.from != mv.from
+                        && state.board[o.from.1 as usize][o.from.0 as usize].map(|p| p.kind) == Some(piece.kind)
+                })
+                .collect();
+            if !rivals.is_empty() {
+                if rivals.iter().all(|o| o.from.0 != mv.from.0) {
+                    s.push(file_char(mv.from.0));
+                } else if rivals.iter().all(|o| o.from.1 != mv.from.1) {
+                    s.push(rank_char(mv.from.1));
+                } else {
+                    s.push_str(&square(mv.from.0, mv.from.1));
+                }
+            }
+        }
+        if capture {
+            s.push('x');
+        }
+        s.push_str(&square(mv.to.0, mv.to.1));
+        if let Some(k) = mv.promo {
+            s.push('=');
+            s.push_str(letter(k));
+        }
+        s
+    };
+    let mut next = state.clone();
+    engine::apply(&mut next, mv);
+    if matches!(engine::status(&next), Outcome::Checkmate { .. }) {
+        out.push('#');
+    } else if engine::is_in_check(&next, next.turn) {
+        out.push('+');
+    }
+    out
+}
+
+/// PGN `Termination` tag。
+fn termination(reason: &str) -> &'static str {
+    match reason {
+        "timeout" => "time forfeit",
+        "disconnect" => "abandoned",
+        _ => "normal",
+    }
+}
+
+/// 完整 PGN（Seven Tag Roster + `Termination`）。`plies` 為每步之前的局面與該步。
+pub fn pgn(plies: &[(&State, Move)], meta: &ExportMeta) -> String {
+    let result = pgn_result(meta.result.as_ref());
+    let mut out = String::new();
+    pgn_tag(&mut out, "Event", &meta.event);
+    pgn_tag(&mut out, "Site", "?");
+    pgn_tag(&mut out, "Date", &meta.date.format("%Y.%m.%d").to_string());
+    pgn_tag(&mut out, "Round", "-");
+    pgn_tag(&mut out, "White", &meta.players[0]);
+    pgn_tag(&mut out, "Black", &meta.players[1]);
+    pgn_tag(&mut out, "Result", result);
+    if let Some(r) = &meta.result {
+        pgn_tag(&mut out, "Termination", termination(&r.reason));
+    }
+    out.push('\n');
+    let sans: Vec<String> = plies.iter().map(|(state, mv)| san(state, *mv)).collect();
+    out.push_str(&pgn_movetext(&sans, result));
+    out
+}
+
+/// UCI 座標走步：`e2e4`、升變 `e7e8q`。
+pub fn uci(mv: Move) -> String {
+    let mut s = format!("{}{}", square(mv.from.0, mv.from.1), square(mv.to.0, mv.to.1));
+    if let Some(k) = mv.promo {
+        s.push_str(&letter(k).to_ascii_lowercase());
+    }
+    s
+}
+
+/// 在 `state` 的合法步中找 UCI 字串對應的那一步（不分大小寫；升變須寫明子種）。
+pub fn parse_uci(state: &State, s: &str) -> Option<Move> {
+    let s = s.trim().to_ascii_lowercase();
+    engine::legal_moves(state).into_iter().find(|&mv| uci(mv) == s)
+}
+
+fn parse_square(s: &str) -> Option<(i8, i8)> {
+    match s.as_bytes() {
+        &[f @ b'a'..=b'h', r @ b'1'..=b'8'] => Some(((f - b'a') as i8, (r - b'1') as i8)),
+        _ => None,
+    }
+}
+
+/// 由 FEN 建局面。易位權 / 過路兵 / 半步數三欄可省略（視為 `- - 0`），回合數不讀。
+/// 讀入的局面沒有歷史，重複局面從這裡起算。雙方須恰有一王、底線不能有兵、非行棋方不能正被將。
+pub fn from_fen(fen: &str) -> Result<State, &'static str> {
+    use PieceKind::*;
+    let mut fields = fen.split_whitespace();
+    let ranks: Vec<&str> = fields.next().ok_or("bad_fen_board")?.split('/').collect();
+    if ranks.len() != 8 {
+        return Err("bad_fen_board");
+    }
+    let mut board = [[None; 8]; 8];
+    for (i, rank) in ranks.iter().enumerate() {
+        let r = 7 - i;
+        let mut c = 0;
+        for ch in rank.chars() {
+            if let Some(n @ 1..=8) = ch.to_digit(10) {
+                c += n as usize;
+                continue;
+            }
+            let kind = match ch.to_ascii_lowercase() {
+                'p' => Pawn,
+                'n' => Knight,
+                'b' => Bishop,
+                'r' => Rook,
+                'q' => Queen,
+                'k' => King,
+                _ => return Err("bad_fen_board"),
+            };
+            if c >= 8 {
+                return Err("bad_fen_board");
+            }
+            let color = if ch.is_ascii_uppercase() { Color::White } else { Color::Black };
+            board[r][c] = Some(Piece { kind, color });
+            c += 1;
+        }
+        if c != 8 {
+            return Err("bad_fen_board");
+        }
+    }
+
+    let turn = match fields.next() {
+        None | Some("w") => Color::White,
+        Some("b") => Color::Black,
+        _ => return Err("bad_fen_turn"),
+    };
+
+    // 易位權須王、車仍在原位
+    let mut castling = [false; 4];
+    match fields.next() {
+        None | Some("-") => {}
+        Some(rights) => {
+            for ch in rights.chars() {
+                let (i, color, rook_col) = match ch {
+                    'K' => (0, Color::White, 7),
+                    'Q' => (1, Color::White, 0),
+                    'k' => (2, Color::Black, 7),
+                    'q' => (3, Color::Black, 0),
+                    _ => return Err("bad_fen_castling"),
+                };
+                let home = if color == Color::White { 0 } else { 7 };
+                if board[home][4] != Some(Piece { kind: King, color })
+                    || board[home][rook_col] != Some(Piece { kind: Rook, color })
+                {
+                    return Err("bad_fen_castling");
+                }
+                castling[i] = true;
+            }
+        }
+    }
+
+    // 過路兵目標格：白方行棋在第 6 橫列、黑方在第 3 橫列
+    let ep = match fields.next() {
+        None | Some("-") => None,
+        Some(sq) => {
+            let (c, r) = parse_square(sq).ok_or("bad_fen_ep")?;
+            if r != if turn == Color::White { 5 } else { 2 } {
+                return Err("bad_fen_ep");
+            }
+            Some((c, r))
+        }
+    };
+
+    let halfmove = match fields.next() {
+        None => 0,
+        Some(n) => n.parse().map_err(|_| "bad_fen_clock")?,
+    };
+
+    let count = |kind, color| board.iter().flatten().filter(|&&p| p == Some(Piece { kind, color })).count();
+    if count(King, Color::White) != 1 || count(King, Color::Black) != 1 {
+        return Err("bad_fen_kings");
+    }
+    if board[0].iter().chain(&board[7]).any(|p| p.is_some_and(|p| p.kind == Pawn)) {
+        return Err("bad_fen_pawns");
+    }
+
+    let mut state = State { board, turn, castling, ep, halfmove, hash: 0, history: Vec::new() };
+    if engine::is_in_check(&state, turn.opponent()) {
+        return Err("bad_fen_check");
+    }
+    state.hash = engine::position_hash(&state);
+    Ok(state)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn mv(from: &str, to: &str) -> Move {
+        let sq = |s: &str| {
+            let b = s.as_bytes();
+            ((b[0] - b'a') as i8, (b[1] - b'1') as i8)
+        };
+        Move { from: sq(from), to: sq(to), promo: None }
+    }
+
+    /// 依序走 `moves`，回傳每步的 SAN。
+    fn play(moves: &[(&str, &str)]) -> Vec<String> {
+        let mut s = engine::initial_state();
+        let mut out = Vec::new();
+        for &(f, t) in moves {
+            let m = mv(f, t);
+            out.push(san(&s, m));
+            engine::apply(&mut s, m);
+        }
+        out
+    }
+
+    #[test]
+    fn scholars_mate() {
+        let sans = play(&[
+            ("e2", "e4"), ("e7", "e5"),
+            ("d1", "h5"), ("b8", "c6"),
+            ("f1", "c4"), ("g8", "f6"),
+            ("h5", "f7"),
+        ]);
+        assert_eq!(sans, ["e4", "e5", "Qh5", "Nc6", "Bc4", "Nf6", "Qxf7#"]);
+    }
+
+    #[test]
+    fn castling_and_captures() {
+        let sans = play(&[
+            ("e2", "e4"), ("e7", "e5"),
+            ("g1", "f3"), ("b8", "c6"),
+            ("f1", "b5"), ("f7", "f6"),
+            ("e1", "g1"), ("d8", "e7"),
+            ("b5", "c6"), ("d7", "c6"),
+        ]);
+        assert_eq!(sans[6], "O-O");
+        assert_eq!(sans[8], "Bxc6");
+        assert_eq!(sans[9], "dxc6");
+    }
+
+    #[test]
+    fn disambiguates_by_file_then_rank() {
+        let mut s = engine::initial_state();
+        s.board = [[None; 8]; 8];
+        s.castling = [false; 4];
+        let put = |s: &mut State, c: i8, r: i8, kind: PieceKind, color: Color| {
+            s.board[r as usize][c as usize] = Some(Piece { kind, color });
+        };
+        put(&mut s, 4, 0, PieceKind::King, Color::White);
+        put(&mut s, 4, 7, PieceKind::King, Color::Black);
+        // 兩車同 rank → 用 file
+        put(&mut s, 0, 3, PieceKind::Rook, Color::White);
+        put(&mut s, 7, 3, PieceKind::Rook, Color::White);
+        assert_eq!(san(&s, mv("a4", "d4")), "Rad4");
+        // 兩車同 file → 用 rank
+        s.board[3][7] = None;
+        put(&mut s, 0, 6, PieceKind::Rook, Color::White);
+        assert_eq!(san(&s, mv("a4", "a5")), "R4a5");
+    }
+
+    #[test]
+    fn promotion_suffix() {
+        let mut s = engine::initial_state();
+        s.board = [[None; 8]; 8];
+        s.castling = [false; 4];
+        s.board[0][0] = Some(Piece { kind: PieceKind::King, color: Color::White });
+        s.board[7][7] = Some(Piece { kind: PieceKind::King, color: Color::Black });
+        s.board[6][1] = Some(Piece { kind: PieceKind::Pawn, color: Color::White });
+        let m = Move { from: (1, 6), to: (1, 7), promo: Some(PieceKind::Queen) };
+        assert_eq!(san(&s, m), "b8=Q+");
+    }
+
+    #[test]
+    fn fen_start_position_matches_initial_state() {
+        let s = from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR w KQkq - 0 1").unwrap();
+        let init = engine::initial_state();
+        assert_eq!(s.board, init.board);
+        assert_eq!(s.castling, init.castling);
+        assert_eq!(s.hash, init.hash);
+        // 後四欄可省略
+        assert_eq!(from_fen("rnbqkbnr/pppppppp/8/8/8/8/PPPPPPPP/RNBQKBNR").unwrap().castling, [false; 4]);
+    }
+
+    #[test]
+    fn fen_reads_ep_and_clock() {
+        let s = from_fen("rnbqkbnr/ppp1pppp/8/3pP3/8/8/PPPP1PPP/RNBQKBNR w KQkq d6 0 3").unwrap();
+        assert_eq!(s.ep, Some((3, 5)));
+        let ep = parse_uci(&s, "e5d6").unwrap();
+        assert_eq!(engine::apply(&mut s.clone(), ep).ep_capture, Some((3, 4)));
+        assert_eq!(from_fen("8/8/8/8/8/8/8/K6k b - - 37 80").unwrap().halfmove, 37);
+    }
+
+    #[test]
+    fn fen_rejects_bad_positions() {
+        assert_eq!(from_fen("8/8/8/8/8/8/8/K7 w").unwrap_err(), "bad_fen_kings");
+        assert_eq!(from_fen("8/8/8/8/8/8/8/K6k x").unwrap_err(), "bad_fen_turn");
+        assert_eq!(from_fen("8/8/8/8/8/8/8/K5k w").unwrap_err(), "bad_fen_board");
+        assert_eq!(from_fen("8/8/8/8/8/8/8/K6k w K").unwrap_err(), "bad_fen_castling");
+        assert_eq!(from_fen("P7/8/8/8/8/8/8/K6k w").unwrap_err(), "bad_fen_pawns");
+        // 輪白走但黑王正被將
+        assert_eq!(from_fen("7k/8/8/8/8/8/8/K6R w").unwrap_err(), "bad_fen_check");
+    }
+
+    #[test]
+    fn uci_round_trip_with_promotion() {
+        let s = from_fen("8/1P6/8/8/8/8/8/K6k w").unwrap();
+        let m = parse_uci(&s, "B7B8N").unwrap();
+        assert_eq!(m.promo, Some(PieceKind::Knight));
+        assert_eq!(uci(m), "b7b8n");
+        // 升變沒寫子種不算
+        assert!(parse_uci(&s, "b7b8").is_none());
+        assert!(parse_uci(&engine::initial_state(), "e2e5").is_none());
+    }
+}
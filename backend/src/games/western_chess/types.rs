@@ -52,8 +52,12 @@ pub struct State {
     pub castling: [bool; 4],
     /// 過路兵目標格（可被斜吃的空格）。
     pub ep: Option<Coord>,
-    /// 無吃子 / 無兵走 的半步數（50 步和）。
+    /// 無吃子 / 無兵走 的半步數（50 步可提和、75 步自動和）。
     pub halfmove: u32,
+    /// 目前局面的 Zobrist hash（見 `engine::position_hash`）。
+    pub hash: u64,
+    /// 上次吃子 / 兵走之後出現過的局面 hash（不含目前局面），判重複用。
+    pub history: Vec<u64>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -42,7 +42,7 @@ async fn main() {
         .with(logging::DbLogLayer::new(log_tx))
         .init();
 
-    let app = routes::app(log_rx).await;
+    let (app, state) = routes::app(log_rx).await;
 
     // 設定伺服器監聽的主機與埠號
     let host = var("APP_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()); // 預設監聽所有 IP
@@ -64,6 +64,9 @@ async fn main() {
     .await
     .unwrap();
 
+    // WS 連線不在 graceful 等待之列，此刻對局都還在記憶體：存一份最新快照，下次開機還原
+    games::common::persist::save_all(&state).await;
+
     tracing::info!("server stopped, flushing logs");
     // `log_writer` 的 buffer 最多攢 500ms 才落地，而 DbLogLayer 的 sender 活在全域
     // subscriber 裡永遠不會被 drop（收不到 channel 關閉），沒辦法 join 它。
@@ -20,6 +20,8 @@ const WEBAUTHN_MAX_REQUESTS: i64 = 10;
 const MESSAGES_MAX_REQUESTS: i64 = 5;
 // blog 留言:同為公開寫入,獨立 bucket 不與訪客留言互搶(討論串可能連續發幾則)
 const COMMENTS_MAX_REQUESTS: i64 = 10;
+// 局面分析:公開的純 CPU 端點(重播走步 + 列合法步 / 威脅),與 tools 同級但獨立 bucket,研究時連點不吃掉 tools 額度
+const ANALYSIS_MAX_REQUESTS: i64 = 30;
 const WINDOW_SECS: i64 = 60;
 
 pub async fn tools_rate_limit(
@@ -30,6 +32,14 @@ pub async fn tools_rate_limit(
     rate_limit(state, req, next, "tools", TOOLS_MAX_REQUESTS).await
 }
 
+pub async fn analysis_rate_limit(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Result<Response<Body>, AppError> {
+    rate_limit(state, req, next, "analysis", ANALYSIS_MAX_REQUESTS).await
+}
+
 pub async fn auth_rate_limit(
     State(state): State<AppState>,
     req: Request,
@@ -2,6 +2,10 @@ pub mod app_settings;
 pub mod audit_logs;
 pub mod blog_comments;
 pub mod blogs;
+pub mod farm_scores;
+pub mod game_ratings;
+pub mod game_records;
+pub mod game_tournaments;
 pub mod gov_tenders;
 pub mod logs;
 pub mod images;
@@ -0,0 +1,83 @@
+use crate::{
+    errors::AppError,
+    structs::farm_scores::{FarmBest, FarmLeaderboardMe, FarmLeaderboardRow, NewFarmScore},
+};
+use sqlx::{Pool, Postgres};
+
+pub async fn insert(pool: &Pool<Postgres>, rec: &NewFarmScore) -> Result<(), AppError> {
+    sqlx::query(
+        "INSERT INTO farm_scores (member_id, score, breakdown, solo, players, cards)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(rec.member_id)
+    .bind(rec.breakdown.total())
+    .bind(sqlx::types::Json(&rec.breakdown))
+    .bind(rec.solo)
+    .bind(rec.players)
+    .bind(rec.cards)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// 每位會員在該模式（單人 / 多人 × 卡牌版 / 家庭版）的最高分（同分取較早達成者）與完局數
+const BEST_PER_MEMBER: &str = "SELECT DISTINCT ON (member_id) member_id, score, created_at,
+            COUNT(*) OVER (PARTITION BY member_id) AS games
+     FROM farm_scores WHERE solo = $1 AND cards = $2
+     ORDER BY member_id, score DESC, created_at";
+
+pub async fn leaderboard_top(
+    pool: &Pool<Postgres>,
+    solo: bool,
+    cards: bool,
+    limit: i64,
+) -> Result<Vec<FarmLeaderboardRow>, AppError> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT RANK() OVER (ORDER BY b.score DESC) AS rank,
+                m.name, m.avatar_url, b.score, b.games, b.created_at AS achieved_at
+         FROM ({BEST_PER_MEMBER}) b
+         JOIN members m ON m.id = b.member_id
+         ORDER BY b.score DESC, b.created_at LIMIT $3"
+    ))
+    .bind(solo)
+    .bind(cards)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// 自己在該模式的名次與最高分；沒有完局回 None
+pub async fn leaderboard_me(
+    pool: &Pool<Postgres>,
+    solo: bool,
+    cards: bool,
+    member_id: i64,
+) -> Result<Option<FarmLeaderboardMe>, AppError> {
+    let row = sqlx::query_as(&format!(
+        "SELECT rank, score FROM (
+            SELECT member_id, RANK() OVER (ORDER BY score DESC) AS rank, score
+            FROM ({BEST_PER_MEMBER}) b
+         ) r WHERE member_id = $3"
+    ))
+    .bind(solo)
+    .bind(cards)
+    .bind(member_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
+
+/// 各模式的個人最佳（單人 / 多人 × 卡牌版 / 家庭版，每種一筆）
+pub async fn bests(pool: &Pool<Postgres>, member_id: i64) -> Result<Vec<FarmBest>, AppError> {
+    let rows = sqlx::query_as(
+        "SELECT DISTINCT ON (solo, cards) solo, score, breakdown, players, cards,
+                COUNT(*) OVER (PARTITION BY solo, cards) AS games, created_at
+         FROM farm_scores WHERE member_id = $1
+         ORDER BY solo DESC, cards, score DESC, created_at",
+    )
+    .bind(member_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
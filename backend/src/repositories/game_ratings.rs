@@ -0,0 +1,113 @@
+use crate::{
+    errors::AppError,
+    structs::game_ratings::{GameRating, RatingLeaderboardMe, RatingLeaderboardRow},
+};
+use sqlx::{PgConnection, Pool, Postgres};
+
+const COLS: &str = "game, rating, games, wins, losses, draws, updated_at";
+
+/// 取目前積分並鎖列（沒有列就先以預設值建一列）。由 caller 持有 transaction。
+///
+/// 兩位對手請**依 member_id 由小到大**呼叫，同一對人同時結束兩盤時才不會互鎖。
+pub async fn lock_rating_in_tx(
+    conn: &mut PgConnection,
+    member_id: i64,
+    game: &str,
+) -> Result<i32, AppError> {
+    sqlx::query(
+        "INSERT INTO game_ratings (member_id, game) VALUES ($1, $2)
+         ON CONFLICT (member_id, game) DO NOTHING",
+    )
+    .bind(member_id)
+    .bind(game)
+    .execute(&mut *conn)
+    .await?;
+    let (rating,): (i32,) = sqlx::query_as(
+        "SELECT rating FROM game_ratings WHERE member_id = $1 AND game = $2 FOR UPDATE",
+    )
+    .bind(member_id)
+    .bind(game)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(rating)
+}
+
+/// 套用一局結果。`score` 為 1.0 / 0.5 / 0.0（勝 / 和 / 負）。
+pub async fn apply_result_in_tx(
+    conn: &mut PgConnection,
+    member_id: i64,
+    game: &str,
+    delta: i32,
+    score: f64,
+) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE game_ratings
+         SET rating = rating + $3,
+             games = games + 1,
+             wins = wins + ($4 = 1.0)::int,
+             losses = losses + ($4 = 0.0)::int,
+             draws = draws + ($4 = 0.5)::int,
+             updated_at = NOW()
+         WHERE member_id = $1 AND game = $2",
+    )
+    .bind(member_id)
+    .bind(game)
+    .bind(delta)
+    .bind(score)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+/// 會員各遊戲的積分（沒下過計分局的遊戲不出現）
+pub async fn member_ratings(
+    pool: &Pool<Postgres>,
+    member_id: i64,
+) -> Result<Vec<GameRating>, AppError> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {COLS} FROM game_ratings WHERE member_id = $1 AND games > 0 ORDER BY game"
+    ))
+    .bind(member_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn leaderboard_top(
+    pool: &Pool<Postgres>,
+    game: &str,
+    limit: i64,
+) -> Result<Vec<RatingLeaderboardRow>, AppError> {
+    let rows = sqlx::query_as(
+        "SELECT RANK() OVER (ORDER BY g.rating DESC) AS rank,
+                m.name, m.avatar_url, g.rating, g.games, g.wins, g.losses, g.draws
+         FROM game_ratings g
+         JOIN members m ON m.id = g.member_id
+         WHERE g.game = $1 AND g.games > 0
+         ORDER BY g.rating DESC, g.updated_at LIMIT $2",
+    )
+    .bind(game)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+/// 自己在該遊戲的名次；沒下過計分局回 None
+pub async fn leaderboard_me(
+    pool: &Pool<Postgres>,
+    game: &str,
+    member_id: i64,
+) -> Result<Option<RatingLeaderboardMe>, AppError> {
+    let row = sqlx::query_as(
+        "SELECT rank, rating, games FROM (
+            SELECT member_id, RANK() OVER (ORDER BY rating DESC) AS rank, rating, games
+            FROM game_ratings WHERE game = $1 AND games > 0
+         ) r WHERE member_id = $2",
+    )
+    .bind(game)
+    .bind(member_id)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row)
+}
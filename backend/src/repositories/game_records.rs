@@ -0,0 +1,134 @@
+use crate::{
+    errors::AppError,
+    structs::game_records::{GameRecord, GameRecordSummary, MemberGameHistoryRow, NewGameRecord},
+};
+use sqlx::{PgConnection, Pool, Postgres};
+
+const SUMMARY_COLS: &str = "id, game, table_name, winner, reason, move_count, started_at, ended_at";
+const COLS: &str = "id, game, table_name, winner, reason, moves, move_count, initial_clock_ms, \
+                    time_control, options, final_clock, started_at, ended_at";
+
+/// 寫入一盤完局紀錄，回傳新 id。由 caller 持有 transaction —— 計分局要與
+/// `game_ratings` 的更新同生同死，否則戰績上的積分變化與目前積分會對不起來。
+pub async fn insert_in_tx(
+    conn: &mut PgConnection,
+    rec: &NewGameRecord,
+    rating_changes: Option<[i32; 2]>,
+) -> Result<i64, AppError> {
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO game_records
+             (game, table_name, winner, reason, moves, move_count, initial_clock_ms, final_clock, started_at,
+              first_member_id, second_member_id, winner_seat, first_rating_change, second_rating_change,
+              time_control, options)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16)
+         RETURNING id",
+    )
+    .bind(rec.game)
+    .bind(&rec.table_name)
+    .bind(rec.winner)
+    .bind(&rec.reason)
+    .bind(sqlx::types::Json(&rec.moves))
+    .bind(rec.moves.len() as i32)
+    .bind(rec.initial_clock_ms)
+    .bind(&rec.final_clock)
+    .bind(rec.started_at)
+    .bind(rec.members[0])
+    .bind(rec.members[1])
+    .bind(rec.winner_seat)
+    .bind(rating_changes.map(|c| c[0]))
+    .bind(rating_changes.map(|c| c[1]))
+    .bind(&rec.time_control)
+    .bind(&rec.options)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(id)
+}
+
+/// 完局列表（新到舊），`game` 為 None 時不篩
+pub async fn list(
+    pool: &Pool<Postgres>,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<GameRecordSummary>, AppError> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {SUMMARY_COLS} FROM game_records
+         WHERE ($1::text IS NULL OR game = $1)
+         ORDER BY ended_at DESC, id DESC LIMIT $2 OFFSET $3"
+    ))
+    .bind(game)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn count(pool: &Pool<Postgres>, game: Option<&str>) -> Result<i64, AppError> {
+    let (total,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM game_records WHERE ($1::text IS NULL OR game = $1)")
+            .bind(game)
+            .fetch_one(pool)
+            .await?;
+    Ok(total)
+}
+
+/// 單盤完整紀錄；不存在回 RowNotFound（→ 404）
+pub async fn get(pool: &Pool<Postgres>, id: i64) -> Result<GameRecord, AppError> {
+    let row = sqlx::query_as(&format!("SELECT {COLS} FROM game_records WHERE id = $1"))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row)
+}
+
+/// 會員參與過的對局（新到舊）。result / 對手 / 積分變化一律換算成查詢者視角。
+pub async fn member_history(
+    pool: &Pool<Postgres>,
+    member_id: i64,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<MemberGameHistoryRow>, AppError> {
+    let rows = sqlx::query_as(
+        "SELECT r.id, r.game,
+                CASE WHEN r.winner_seat IS NULL THEN 'draw'
+                     WHEN (r.winner_seat = 0) = COALESCE(r.first_member_id = $1, false) THEN 'win'
+                     ELSE 'loss' END AS result,
+                r.reason,
+                o.name AS opponent_name,
+                CASE WHEN r.first_member_id = $1 THEN r.first_rating_change
+                     ELSE r.second_rating_change END AS rating_change,
+                r.move_count, r.ended_at
+         FROM game_records r
+         LEFT JOIN members o ON o.id = CASE WHEN r.first_member_id = $1
+                                            THEN r.second_member_id ELSE r.first_member_id END
+         WHERE (r.first_member_id = $1 OR r.second_member_id = $1)
+           AND ($2::text IS NULL OR r.game = $2)
+         ORDER BY r.ended_at DESC, r.id DESC LIMIT $3 OFFSET $4",
+    )
+    .bind(member_id)
+    .bind(game)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn count_member_history(
+    pool: &Pool<Postgres>,
+    member_id: i64,
+    game: Option<&str>,
+) -> Result<i64, AppError> {
+    let (total,): (i64,) = sqlx::query_as(
+        "SELECT COUNT(*) FROM game_records
+         WHERE (first_member_id = $1 OR second_member_id = $1)
+           AND ($2::text IS NULL OR game = $2)",
+    )
+    .bind(member_id)
+    .bind(game)
+    .fetch_one(pool)
+    .await?;
+    Ok(total)
+}
@@ -0,0 +1,237 @@
+use crate::{
+    errors::AppError,
+    games::tournament::Standing,
+    structs::game_tournaments::{GameTournament, TournamentEntrant, TournamentPairing},
+};
+use serde_json::Value;
+use sqlx::{PgConnection, Pool, Postgres};
+
+const COLS: &str = "t.id, t.game, t.name, t.format, t.rounds, t.time_control, t.options, t.status,
+    t.current_round,
+    (SELECT COUNT(*) FROM game_tournament_entries e WHERE e.tournament_id = t.id) AS entrants,
+    t.created_at, t.started_at, t.finished_at";
+
+const PAIRING_COLS: &str = "p.id, p.round, p.first_member_id, f.name AS first_name,
+    p.second_member_id, s.name AS second_name, p.first_score, p.second_score, p.forfeit";
+
+pub async fn insert(
+    pool: &Pool<Postgres>,
+    game: &str,
+    name: &str,
+    format: &str,
+    rounds: i32,
+    time_control: &Value,
+    options: &Value,
+) -> Result<i64, AppError> {
+    let (id,): (i64,) = sqlx::query_as(
+        "INSERT INTO game_tournaments (game, name, format, rounds, time_control, options)
+         VALUES ($1, $2, $3, $4, $5, $6) RETURNING id",
+    )
+    .bind(game)
+    .bind(name)
+    .bind(format)
+    .bind(rounds)
+    .bind(time_control)
+    .bind(options)
+    .fetch_one(pool)
+    .await?;
+    Ok(id)
+}
+
+/// 錦標賽列表（新到舊），`game` 為 None 時不篩
+pub async fn list(
+    pool: &Pool<Postgres>,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Vec<GameTournament>, AppError> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {COLS} FROM game_tournaments t
+         WHERE ($1::text IS NULL OR t.game = $1)
+         ORDER BY t.created_at DESC, t.id DESC LIMIT $2 OFFSET $3"
+    ))
+    .bind(game)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    Ok(rows)
+}
+
+pub async fn count(pool: &Pool<Postgres>, game: Option<&str>) -> Result<i64, AppError> {
+    let (total,): (i64,) =
+        sqlx::query_as("SELECT COUNT(*) FROM game_tournaments WHERE ($1::text IS NULL OR game = $1)")
+            .bind(game)
+            .fetch_one(pool)
+            .await?;
+    Ok(total)
+}
+
+/// 不存在回 RowNotFound（→ 404）
+pub async fn get(pool: &Pool<Postgres>, id: i64) -> Result<GameTournament, AppError> {
+    let row = sqlx::query_as(&format!("SELECT {COLS} FROM game_tournaments t WHERE t.id = $1"))
+        .bind(id)
+        .fetch_one(pool)
+        .await?;
+    Ok(row)
+}
+
+/// 取錦標賽；`lock` 時 FOR UPDATE（報名 / 開賽 / 回寫結果都先鎖這列，彼此序列化）。
+/// 不存在回 RowNotFound（→ 404）。
+pub async fn get_in_tx(conn: &mut PgConnection, id: i64, lock: bool) -> Result<GameTournament, AppError> {
+    let lock = if lock { "FOR UPDATE OF t" } else { "" };
+    let row = sqlx::query_as(&format!("SELECT {COLS} FROM game_tournaments t WHERE t.id = $1 {lock}"))
+        .bind(id)
+        .fetch_one(&mut *conn)
+        .await?;
+    Ok(row)
+}
+
+/// 報名者，依報名先後（瑞士制同分時的排序依據）
+pub async fn entrants_in_tx(conn: &mut PgConnection, id: i64) -> Result<Vec<TournamentEntrant>, AppError> {
+    let rows = sqlx::query_as(
+        "SELECT e.member_id, m.name, m.avatar_url
+         FROM game_tournament_entries e JOIN members m ON m.id = e.member_id
+         WHERE e.tournament_id = $1
+         ORDER BY e.registered_at, e.member_id",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows)
+}
+
+/// 報名；已報名回 false
+pub async fn register_in_tx(conn: &mut PgConnection, id: i64, member_id: i64) -> Result<bool, AppError> {
+    let done = sqlx::query(
+        "INSERT INTO game_tournament_entries (tournament_id, member_id) VALUES ($1, $2)
+         ON CONFLICT DO NOTHING",
+    )
+    .bind(id)
+    .bind(member_id)
+    .execute(&mut *conn)
+    .await?;
+    Ok(done.rows_affected() > 0)
+}
+
+/// 取消報名；本來就沒報名回 false
+pub async fn unregister_in_tx(conn: &mut PgConnection, id: i64, member_id: i64) -> Result<bool, AppError> {
+    let done = sqlx::query("DELETE FROM game_tournament_entries WHERE tournament_id = $1 AND member_id = $2")
+        .bind(id)
+        .bind(member_id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(done.rows_affected() > 0)
+}
+
+/// 全部對陣（依輪次、建立順序）
+pub async fn pairings_in_tx(conn: &mut PgConnection, id: i64) -> Result<Vec<TournamentPairing>, AppError> {
+    let rows = sqlx::query_as(&format!(
+        "SELECT {PAIRING_COLS}
+         FROM game_tournament_pairings p
+         JOIN members f ON f.id = p.first_member_id
+         LEFT JOIN members s ON s.id = p.second_member_id
+         WHERE p.tournament_id = $1
+         ORDER BY p.round, p.id"
+    ))
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+    Ok(rows)
+}
+
+/// 建立一組對陣；輪空（`second` 為 None）當場記 1 分完成
+pub async fn insert_pairing_in_tx(
+    conn: &mut PgConnection,
+    id: i64,
+    round: i32,
+    first: i64,
+    second: Option<i64>,
+) -> Result<i64, AppError> {
+    let (pairing_id,): (i64,) = sqlx::query_as(
+        "INSERT INTO game_tournament_pairings
+             (tournament_id, round, first_member_id, second_member_id, first_score, finished_at)
+         VALUES ($1, $2, $3, $4,
+                 CASE WHEN $4::bigint IS NULL THEN 1.0 END,
+                 CASE WHEN $4::bigint IS NULL THEN NOW() END)
+         RETURNING id",
+    )
+    .bind(id)
+    .bind(round)
+    .bind(first)
+    .bind(second)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok(pairing_id)
+}
+
+/// 寫入一組對陣的結果；只寫尚未完成的（重複回報 / 後台與完局同時登錄時後到者回 false）
+pub async fn set_result_in_tx(
+    conn: &mut PgConnection,
+    id: i64,
+    pairing_id: i64,
+    first_score: f64,
+    second_score: f64,
+    forfeit: bool,
+) -> Result<bool, AppError> {
+    let done = sqlx::query(
+        "UPDATE game_tournament_pairings
+         SET first_score = $3, second_score = $4, forfeit = $5, finished_at = NOW()
+         WHERE tournament_id = $1 AND id = $2 AND first_score IS NULL",
+    )
+    .bind(id)
+    .bind(pairing_id)
+    .bind(first_score)
+    .bind(second_score)
+    .bind(forfeit)
+    .execute(&mut *conn)
+    .await?;
+    Ok(done.rows_affected() > 0)
+}
+
+/// 開賽：定下總輪數，進入第 1 輪
+pub async fn start_in_tx(conn: &mut PgConnection, id: i64, rounds: i32) -> Result<(), AppError> {
+    sqlx::query(
+        "UPDATE game_tournaments
+         SET status = 'running', rounds = $2, current_round = 1, started_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(id)
+    .bind(rounds)
+    .execute(&mut *conn)
+    .await?;
+    Ok(())
+}
+
+pub async fn set_round_in_tx(conn: &mut PgConnection, id: i64, round: i32) -> Result<(), AppError> {
+    sqlx::query("UPDATE game_tournaments SET current_round = $2 WHERE id = $1")
+        .bind(id)
+        .bind(round)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
+
+/// 完賽：寫入每位參賽者的最終名次與分數
+pub async fn finish_in_tx(conn: &mut PgConnection, id: i64, standings: &[Standing]) -> Result<(), AppError> {
+    for s in standings {
+        sqlx::query(
+            "UPDATE game_tournament_entries
+             SET final_rank = $3, points = $4, buchholz = $5, sonneborn_berger = $6
+             WHERE tournament_id = $1 AND member_id = $2",
+        )
+        .bind(id)
+        .bind(s.member_id)
+        .bind(s.rank as i32)
+        .bind(s.points)
+        .bind(s.buchholz)
+        .bind(s.sonneborn_berger)
+        .execute(&mut *conn)
+        .await?;
+    }
+    sqlx::query("UPDATE game_tournaments SET status = 'finished', finished_at = NOW() WHERE id = $1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await?;
+    Ok(())
+}
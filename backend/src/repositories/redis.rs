@@ -209,6 +209,30 @@ pub async fn consume_ws_ticket(
     Ok(value)
 }
 
+/// WS 會員連線票：與 admin 票同 TTL、同一次性，value 為 member_id。
+/// key 前綴與 admin 票分開 —— 拿會員票去握手不可能被當成 admin 身分。
+pub async fn set_ws_member_ticket(
+    pool: &RedisPool<RedisConnectionManager>,
+    ticket: &str,
+    member_id: i64,
+) -> Result<(), crate::errors::AppError> {
+    let mut conn = get_redis_conn(pool).await?;
+    let key = format!("ws:member_ticket:{}", ticket);
+    conn.set_ex::<_, _, ()>(key, member_id, WS_TICKET_TTL_SECS).await?;
+    Ok(())
+}
+
+/// 取出並刪除 WS 會員票，回傳 member_id；不存在或已用過回 None
+pub async fn consume_ws_member_ticket(
+    pool: &RedisPool<RedisConnectionManager>,
+    ticket: &str,
+) -> Result<Option<i64>, crate::errors::AppError> {
+    let mut conn = get_redis_conn(pool).await?;
+    let key = format!("ws:member_ticket:{}", ticket);
+    let value: Option<i64> = redis::cmd("GETDEL").arg(&key).query_async(&mut *conn).await?;
+    Ok(value)
+}
+
 pub async fn cache_get(
     pool: &RedisPool<RedisConnectionManager>,
     key: &str,
@@ -350,6 +374,24 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn ws_member_ticket_is_one_shot() {
+        let Some(p) = pool().await else { return };
+        let ticket = uuid::Uuid::new_v4().to_string();
+        set_ws_member_ticket(&p, &ticket, 42).await.unwrap();
+        assert_eq!(consume_ws_member_ticket(&p, &ticket).await.unwrap(), Some(42));
+        assert_eq!(
+            consume_ws_member_ticket(&p, &ticket).await.unwrap(),
+            None,
+            "WS 會員票必須用過即失效"
+        );
+        assert_eq!(
+            consume_ws_ticket(&p, &ticket).await.unwrap(),
+            None,
+            "會員票不可被當成 admin 票消費"
+        );
+    }
+
     #[tokio::test]
     async fn oauth_state_consumes_exactly_once() {
         let Some(p) = pool().await else { return };
@@ -11,6 +11,7 @@ mod admin_blogs;
 mod audit_logs;
 mod auth;
 mod blogs;
+mod games;
 mod logs;
 mod images;
 mod invoices;
@@ -107,7 +108,8 @@ pub(super) fn with_member_auth(state: AppState, router: Router<AppState>) -> Rou
         ))
 }
 
-pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> Router {
+/// 回傳 Router 與 AppState：`main` 在 serve 結束（優雅關機）後還要用 state 存遊戲快照。
+pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> (Router, AppState) {
     let (state, audit_rx) = AppState::new().await;
 
     sqlx::migrate!("./migrations")
@@ -145,6 +147,10 @@ pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> Router {
         tokio::spawn(crate::services::torrents::sync_active(state.clone()));
     }
 
+    // 重啟前進行中的對局從 Redis 快照還原（須在 watcher 之前：還原的座位帶重連期限），之後定期存
+    crate::games::common::persist::restore_all(&state).await;
+    tokio::spawn(crate::games::common::persist::snapshot_loop(state.clone()));
+
     // 遊戲計時掃描：偵測行棋方時鐘耗盡卻無人走步 → 主動判負（每遊戲一個 watcher）
     for hub in state.games().all() {
         hub.spawn_watcher(state.clone());
@@ -155,11 +161,12 @@ pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> Router {
     // 複製成 bool 讓 span 的閉包不必抓整個 AppState（閉包要 Clone + Send + Sync）
     let trust_cf_header = state.get_config().trust_cf_header;
 
-    Router::new()
+    let router = Router::new()
         .nest("/admin", admin::new(state.clone()))
         .nest("/blogs", with_feature(state.clone(), Feature::Blog, blogs::new(state.clone())))
         .nest("/tools", with_feature(state.clone(), Feature::Tools, tools::new(state.clone())))
         .nest("/ws", ws::new(state.clone()))
+        .nest("/games", with_feature(state.clone(), Feature::Games, games::new(state.clone())))
         .nest("/roster", with_feature(state.clone(), Feature::Roster, roster::new(state.clone())))
         .nest("/members", members::new(state.clone()))
         .nest("/messages", with_feature(state.clone(), Feature::Message, messages::new(state.clone())))
@@ -168,6 +175,7 @@ pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> Router {
         .nest("/member/invoices", with_feature(state.clone(), Feature::Invoices, invoices::new(state.clone())))
         .nest("/member/lotto", with_feature(state.clone(), Feature::Lotto, lotto::new(state.clone())))
         .nest("/member/vocab", with_feature(state.clone(), Feature::Vocab, vocab::new(state.clone())))
+        .nest("/member/games", with_feature(state.clone(), Feature::Games, games::member(state.clone())))
         .nest("/oauth", oauth::new(state.clone()))
         .nest("/logs", logs::new(state.clone()))
         .nest("/metrics", metrics::new(state.clone()))
@@ -284,5 +292,6 @@ pub async fn app(log_rx: mpsc::Receiver<LogEntry>) -> Router {
         .layer(middleware::from_fn(
             crate::middleware::request_id::request_id,
         ))
-        .with_state(state)
+        .with_state(state.clone());
+    (router, state)
 }
@@ -1,22 +1,48 @@
-use crate::extract::Json;
+use crate::extract::{Json, Path, Query};
 use crate::{
     errors::AppError,
-    games::registry::GameSummary,
+    games::{
+        common::chat::ChatLine,
+        registry::{GameSummary, LiveTable},
+    },
+    services::{game_moderation as moderation_service, game_tournaments as tournaments_service},
     state::AppState,
-    structs::{auth::AuthenticatedUser, roles::Perm},
+    structs::{
+        auth::AuthenticatedUser,
+        game_moderation::{EndTableQuery, LiveQuery},
+        game_tournaments::{
+            GameTournament, NewGameTournament, SetPairingResult, TournamentDetail, TournamentQuery,
+        },
+        pagination::{PageQuery, Paginated},
+        roles::Perm,
+    },
 };
 use axum::{
     extract::{Extension, State},
-    routing::get,
+    http::StatusCode,
+    routing::{delete, get, post, put},
     Router
 };
 
 pub fn new(state: AppState) -> Router<AppState> {
-    super::with_auth(state, Router::new().route("/", get(games_overview)))
+    super::with_auth(
+        state,
+        Router::new()
+            .route("/", get(games_overview))
+            .route("/live", get(live_tables))
+            .route("/{game}/chat", get(lobby_chat))
+            .route("/{game}/tables/{id}", delete(close_table))
+            .route("/{game}/tables/{id}/end", post(end_table))
+            .route("/{game}/connections/{addr}/kick", post(kick_connection))
+            .route("/tournaments", get(list_tournaments).post(create_tournament))
+            .route("/tournaments/{id}", get(tournament_detail))
+            .route("/tournaments/{id}/start", post(start_tournament))
+            .route("/tournaments/{id}/pairings/{pairing_id}", put(set_pairing_result)),
+    )
 }
 
 /// 即時對局總覽：每款遊戲的等待 / 進行中桌數、在玩人數、排隊與大廳訂閱數。
-/// 記憶體狀態，重啟即歸零；匿名對戰，不含玩家身份。
+/// 記憶體狀態（進行中對局重啟會還原，見 `games::common::persist`）；只有計數，不含玩家身份。
 async fn games_overview(
     Extension(auth_user): Extension<AuthenticatedUser>,
     State(state): State<AppState>,
@@ -24,3 +50,111 @@ async fn games_overview(
     auth_user.require_permission(Perm::GameRead)?;
     Ok(Json(state.games().summaries().await))
 }
+
+/// 即時對局明細：每桌 / 房的玩家（含連線 IP / UA / 登入身分）、觀戰者、盤面與桌內最近聊天。
+async fn live_tables(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Query(q): Query<LiveQuery>,
+) -> Result<Json<Vec<LiveTable>>, AppError> {
+    auth_user.require_permission(Perm::GameRead)?;
+    Ok(Json(moderation_service::live(&state, q.game.as_deref()).await?))
+}
+
+/// 大廳最近聊天（各桌的聊天在 `/live` 的 `chat`）。被過濾詞遮掉的訊息附 `original` 原文。
+async fn lobby_chat(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path(game): Path<String>,
+) -> Result<Json<Vec<ChatLine>>, AppError> {
+    auth_user.require_permission(Perm::GameRead)?;
+    Ok(Json(moderation_service::lobby_chat(&state, &game).await?))
+}
+
+/// 強制結束對局並判定結果（`?winner=` 座位標籤，不帶為和局）。照常寫局紀錄，reason `admin`。
+/// 只限 2 人對戰；N 人房用關房。
+async fn end_table(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path((game, id)): Path<(String, u64)>,
+    Query(q): Query<EndTableQuery>,
+) -> Result<StatusCode, AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    moderation_service::end_table(&state, &game, id, q.winner.as_deref()).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 關桌 / 關房：對局中即中止、不寫紀錄，在座者收 `table_closed` / `room_closed`（reason `closed_by_admin`）。
+async fn close_table(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path((game, id)): Path<(String, u64)>,
+) -> Result<StatusCode, AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    moderation_service::close_table(&state, &game, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// 把連線（`addr` 同 `/ws/connections`）踢出該遊戲：對戰中判負（reason `kicked`），房內則中止整局。
+async fn kick_connection(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path((game, addr)): Path<(String, String)>,
+) -> Result<StatusCode, AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    moderation_service::kick(&state, &game, &addr).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn list_tournaments(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Query(q): Query<TournamentQuery>,
+) -> Result<Json<Paginated<GameTournament>>, AppError> {
+    auth_user.require_permission(Perm::GameRead)?;
+    let (limit, offset) = PageQuery { page: q.page, per_page: q.per_page }.to_limit_offset(20);
+    Ok(Json(
+        tournaments_service::list(state.get_pool(), q.game.as_deref(), limit, offset).await?,
+    ))
+}
+
+/// 建賽（報名中）。循環賽的輪數開賽時依人數算；瑞士制須指定 `rounds`。
+async fn create_tournament(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Json(req): Json<NewGameTournament>,
+) -> Result<(StatusCode, Json<GameTournament>), AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    let tournament = tournaments_service::create(&state, req).await?;
+    Ok((StatusCode::CREATED, Json(tournament)))
+}
+
+async fn tournament_detail(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<TournamentDetail>, AppError> {
+    auth_user.require_permission(Perm::GameRead)?;
+    Ok(Json(tournaments_service::detail(state.get_pool(), id).await?))
+}
+
+/// 開賽：截止報名、排第 1 輪並替在線的參賽者開桌（不在線者該盤判不戰）。
+async fn start_tournament(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<Json<TournamentDetail>, AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    Ok(Json(tournaments_service::start(&state, id).await?))
+}
+
+/// 手動登錄一組對陣的結果（記為不戰）。服務重啟後卡住的對局靠這裡收尾。
+async fn set_pairing_result(
+    Extension(auth_user): Extension<AuthenticatedUser>,
+    State(state): State<AppState>,
+    Path((id, pairing_id)): Path<(i64, i64)>,
+    Json(req): Json<SetPairingResult>,
+) -> Result<Json<TournamentDetail>, AppError> {
+    auth_user.require_permission(Perm::GameManage)?;
+    Ok(Json(tournaments_service::set_result(&state, id, pairing_id, req).await?))
+}
@@ -0,0 +1,236 @@
+use crate::extract::{Json, Path, Query};
+use crate::{
+    errors::{AppError, AuthError},
+    middleware::{auth, rate_limit},
+    services::{
+        farm_scores as farm_scores_service, game_analysis as analysis_service, game_ratings as ratings_service, game_records as records_service,
+        game_tournaments as tournaments_service,
+    },
+    state::AppState,
+    structs::{
+        farm_scores::{FarmBest, FarmLeaderboardQuery, FarmLeaderboardResponse},
+        game_analysis::{
+            ChessAnalysis, ChessAnalysisRequest, GoAnalysis, GoAnalysisRequest, GomokuAnalysis,
+            GomokuAnalysisRequest,
+        },
+        game_ratings::{
+            GameRating, MemberGameHistoryQuery, RatingLeaderboardQuery, RatingLeaderboardResponse,
+        },
+        game_records::{GameRecord, GameRecordQuery, GameRecordSummary, MemberGameHistoryRow},
+        game_tournaments::{GameTournament, TournamentDetail, TournamentQuery},
+        members::AuthenticatedMember,
+        pagination::{PageQuery, Paginated},
+    },
+};
+use axum::{
+    extract::{Extension, State},
+    http::{header, StatusCode},
+    middleware,
+    response::IntoResponse,
+    routing::{get, post},
+    Router
+};
+use serde::Deserialize;
+
+/// 公開端：完局紀錄（2 人對戰與阿瓦隆；列表 + 單盤重播）、錦標賽與局面分析。列表與重播不含玩家身份；
+/// 會員戰績與錦標賽報名走 `/member/games`。
+pub fn new(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/records", get(list_records))
+        .route("/records/{id}", get(record_detail))
+        .route("/records/{id}/export", get(export_record))
+        .route("/tournaments", get(list_tournaments))
+        .route("/tournaments/{id}", get(tournament_detail))
+        .nest("/analysis", analysis(state))
+}
+
+/// 局面分析（無狀態，不需登入）：西洋棋 / 象棋合法步與將軍、將死、重複局面，圍棋提子與估分，五子棋威脅與勝線。
+fn analysis(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/western_chess", post(analyze_western_chess))
+        .route("/chess", post(analyze_xiangqi))
+        .route("/go", post(analyze_go))
+        .route("/gomoku", post(analyze_gomoku))
+        .layer(middleware::from_fn_with_state(
+            state,
+            rate_limit::analysis_rate_limit,
+        ))
+}
+
+async fn analyze_western_chess(
+    Json(req): Json<ChessAnalysisRequest>,
+) -> Result<Json<ChessAnalysis>, AppError> {
+    Ok(Json(analysis_service::run(move || analysis_service::western_chess(req)).await?))
+}
+
+async fn analyze_xiangqi(
+    Json(req): Json<ChessAnalysisRequest>,
+) -> Result<Json<ChessAnalysis>, AppError> {
+    Ok(Json(analysis_service::run(move || analysis_service::xiangqi(req)).await?))
+}
+
+async fn analyze_go(Json(req): Json<GoAnalysisRequest>) -> Result<Json<GoAnalysis>, AppError> {
+    Ok(Json(analysis_service::run(move || analysis_service::go(req)).await?))
+}
+
+async fn analyze_gomoku(
+    Json(req): Json<GomokuAnalysisRequest>,
+) -> Result<Json<GomokuAnalysis>, AppError> {
+    Ok(Json(analysis_service::run(move || analysis_service::gomoku(req)).await?))
+}
+
+async fn list_records(
+    Query(q): Query<GameRecordQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Paginated<GameRecordSummary>>, AppError> {
+    let (limit, offset) = PageQuery { page: q.page, per_page: q.per_page }.to_limit_offset(20);
+    let records =
+        records_service::list(state.get_pool(), q.game.as_deref(), limit, offset).await?;
+    Ok(Json(records))
+}
+
+/// 單盤完整紀錄：`moves[i]` 即第 i+1 步的 `move_made` data，前端逐步套用即可重播。
+/// 阿瓦隆的 `moves` 是終局 `game_over` 的 `timeline`（setup + 每次提名 / 投票 / 任務 / 查驗 / 刺殺）。
+async fn record_detail(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<GameRecord>, AppError> {
+    Ok(Json(records_service::get(state.get_pool(), id).await?))
+}
+
+#[derive(Deserialize)]
+struct ExportQuery {
+    /// 有多種記法的遊戲用（象棋 `wxf` / `iccs`），省略 = 預設
+    format: Option<String>,
+}
+
+/// 記譜下載：西洋棋 PGN、圍棋 SGF、象棋 WXF / ICCS、五子棋座標譜。暗棋不支援（422）。
+async fn export_record(
+    Path(id): Path<i64>,
+    Query(q): Query<ExportQuery>,
+    State(state): State<AppState>,
+) -> Result<impl IntoResponse, AppError> {
+    let exported = records_service::export(state.get_pool(), id, q.format).await?;
+    let disposition = format!("attachment; filename=\"game-{id}.{}\"", exported.extension);
+    Ok((
+        [
+            (header::CONTENT_TYPE, exported.mime.to_string()),
+            (header::CONTENT_DISPOSITION, disposition),
+        ],
+        exported.text,
+    ))
+}
+
+async fn list_tournaments(
+    Query(q): Query<TournamentQuery>,
+    State(state): State<AppState>,
+) -> Result<Json<Paginated<GameTournament>>, AppError> {
+    let (limit, offset) = PageQuery { page: q.page, per_page: q.per_page }.to_limit_offset(20);
+    Ok(Json(
+        tournaments_service::list(state.get_pool(), q.game.as_deref(), limit, offset).await?,
+    ))
+}
+
+/// 錦標賽：目前名次（積分 → Buchholz → Sonneborn-Berger）與各輪對陣
+async fn tournament_detail(
+    Path(id): Path<i64>,
+    State(state): State<AppState>,
+) -> Result<Json<TournamentDetail>, AppError> {
+    Ok(Json(tournaments_service::detail(state.get_pool(), id).await?))
+}
+
+/// `/member/games`：積分榜與農場高分榜（訪客也能看）、自己的積分 / 戰績 / 農場最佳、錦標賽報名（需 member）。
+/// 同 vocab 走選擇性驗證 —— 榜單公開，登入才多回自己的名次。
+pub fn member(state: AppState) -> Router<AppState> {
+    Router::new()
+        .route("/leaderboard", get(leaderboard))
+        .route("/me", get(me))
+        .route("/history", get(history))
+        .route("/farm/leaderboard", get(farm_leaderboard))
+        .route("/farm/best", get(farm_best))
+        .route("/tournaments/{id}/entry", post(register).delete(unregister))
+        .layer(middleware::from_fn_with_state(
+            state,
+            auth::authorize_member_optional,
+        ))
+}
+
+/// 選擇性驗證下取出 member_id(訪客為 None)
+fn caller(member: Option<Extension<AuthenticatedMember>>) -> Option<i64> {
+    member.map(|Extension(m)| m.member_id)
+}
+
+async fn leaderboard(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+    Query(q): Query<RatingLeaderboardQuery>,
+) -> Result<Json<RatingLeaderboardResponse>, AppError> {
+    Ok(Json(
+        ratings_service::leaderboard(&state, caller(member), &q.game).await?,
+    ))
+}
+
+/// 自己各遊戲的目前積分
+async fn me(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<GameRating>>, AppError> {
+    let mid = caller(member).ok_or(AppError::AuthError(AuthError::Unauthorized))?;
+    Ok(Json(ratings_service::me(&state, mid).await?))
+}
+
+/// 自己的對局紀錄（新到舊，`?game=` 篩選）。`id` 可直接丟給 `/games/records/{id}` 重播。
+async fn history(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+    Query(q): Query<MemberGameHistoryQuery>,
+) -> Result<Json<Paginated<MemberGameHistoryRow>>, AppError> {
+    let mid = caller(member).ok_or(AppError::AuthError(AuthError::Unauthorized))?;
+    let (limit, offset) = PageQuery { page: q.page, per_page: q.per_page }.to_limit_offset(20);
+    Ok(Json(
+        records_service::member_history(state.get_pool(), mid, q.game.as_deref(), limit, offset)
+            .await?,
+    ))
+}
+
+/// 農場高分榜（`?mode=solo|multi`，預設單人；`&cards=true` 看卡牌版，預設家庭版）：
+/// 每位會員取最高分那局；登入時多回自己的名次
+async fn farm_leaderboard(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+    Query(q): Query<FarmLeaderboardQuery>,
+) -> Result<Json<FarmLeaderboardResponse>, AppError> {
+    Ok(Json(
+        farm_scores_service::leaderboard(&state, caller(member), q.mode, q.cards).await?,
+    ))
+}
+
+/// 自己的農場最佳（單人 / 多人 × 卡牌版 / 家庭版各一筆，含逐項計分）
+async fn farm_best(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+) -> Result<Json<Vec<FarmBest>>, AppError> {
+    let mid = caller(member).ok_or(AppError::AuthError(AuthError::Unauthorized))?;
+    Ok(Json(farm_scores_service::bests(&state, mid).await?))
+}
+
+/// 報名錦標賽（只在報名期）。開賽後每輪在線才會開桌，不在線該盤判負。
+async fn register(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    let mid = caller(member).ok_or(AppError::AuthError(AuthError::Unauthorized))?;
+    tournaments_service::register(state.get_pool(), id, mid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn unregister(
+    member: Option<Extension<AuthenticatedMember>>,
+    State(state): State<AppState>,
+    Path(id): Path<i64>,
+) -> Result<StatusCode, AppError> {
+    let mid = caller(member).ok_or(AppError::AuthError(AuthError::Unauthorized))?;
+    tournaments_service::unregister(state.get_pool(), id, mid).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
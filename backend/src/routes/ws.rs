@@ -3,8 +3,8 @@ use crate::{
     errors::{AppError, RequestError, SystemError},
     middleware::auth,
     services::{stats as stats_service, ws as ws_service},
-    state::{AppState, DisplayTrackedConnection, TrackedConnection},
-    structs::{auth::AuthenticatedUser, roles::Perm},
+    state::{to_iso, AppState, DisplayTrackedConnection, TrackedConnection},
+    structs::{auth::AuthenticatedUser, members::AuthenticatedMember, roles::Perm},
 };
 use axum::{
     body::Bytes,
@@ -40,11 +40,8 @@ const PONG_TIMEOUT_SECONDS: u64 = PING_INTERVAL_SECONDS * 2 + 15;
 #[derive(serde::Deserialize)]
 struct WsQuery {
     ticket: Option<String>,
-}
-
-/// 連線時間對外一律用固定寬度的 ISO-8601 毫秒 UTC 字串
-fn to_iso(t: SystemTime) -> String {
-    chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+    /// 會員票（`POST /ws/member_ticket` 換發）：對戰計分 / 戰績用
+    member_ticket: Option<String>,
 }
 
 pub fn new(state: AppState) -> Router<AppState> {
@@ -56,13 +53,22 @@ pub fn new(state: AppState) -> Router<AppState> {
         .route("/messages", post(send_message))
         .route("/ticket", post(create_ws_ticket))
         .layer(middleware::from_fn_with_state(
-            state,
+            state.clone(),
             auth::authorize_and_load,
         ));
 
+    // 會員換票：同 admin 票不進 audit（每次重連都打）
+    let member_routes = Router::new()
+        .route("/member_ticket", post(create_ws_member_ticket))
+        .layer(middleware::from_fn_with_state(
+            state,
+            auth::authorize_member,
+        ));
+
     Router::new()
         .route("/", any(ws_handler))
         .merge(admin_routes)
+        .merge(member_routes)
 }
 
 async fn ws_handler(
@@ -84,6 +90,10 @@ async fn ws_handler(
         Some(ticket) => ws_service::consume_ticket(&state, &ticket).await,
         None => None,
     };
+    let member_id = match query.member_ticket {
+        Some(ticket) => ws_service::consume_member_ticket(&state, &ticket).await,
+        None => None,
+    };
     // 與 middleware/rate_limit.rs 同一條規則（同一個函式）：只有確定流量都經 Cloudflare
     // （TRUST_CF_HEADER=true）才信任這個 header
     let real_ip = crate::utils::net::client_ip(
@@ -106,10 +116,20 @@ async fn ws_handler(
         });
     }
 
-    ws.on_upgrade(move |socket| handle_socket(socket, addr, state, user_email, real_ip, user_agent))
+    ws.on_upgrade(move |socket| {
+        handle_socket(socket, addr, state, user_email, member_id, real_ip, user_agent)
+    })
 }
 
-async fn handle_socket(socket: WebSocket, who: SocketAddr, state: AppState, user_email: Option<String>, real_ip: String, user_agent: String) {
+async fn handle_socket(
+    socket: WebSocket,
+    who: SocketAddr,
+    state: AppState,
+    user_email: Option<String>,
+    member_id: Option<i64>,
+    real_ip: String,
+    user_agent: String,
+) {
     let (sender, receiver) = socket.split();
     let sender_arc = Arc::new(Mutex::new(sender));
 
@@ -118,6 +138,7 @@ async fn handle_socket(socket: WebSocket, who: SocketAddr, state: AppState, user
         connected_at,
         sender: sender_arc.clone(),
         user_email: user_email.clone(),
+        member_id,
         real_ip: real_ip.clone(),
         user_agent: user_agent.clone(),
     };
@@ -135,6 +156,7 @@ async fn handle_socket(socket: WebSocket, who: SocketAddr, state: AppState, user
             "addr": who.to_string(),
             "real_ip": real_ip,
             "user_email": user_email,
+            "member_id": member_id,
             "connected_at": to_iso(connected_at),
             "user_agent": user_agent,
         }),
@@ -297,13 +319,7 @@ async fn list_connections(
         let connections = state.get_connections().lock().await;
         connections
             .iter()
-            .map(|(addr, info)| DisplayTrackedConnection {
-                addr: addr.to_string(),
-                connected_at: to_iso(info.connected_at),
-                user_email: info.user_email.clone(),
-                real_ip: info.real_ip.clone(),
-                user_agent: info.user_agent.clone(),
-            })
+            .map(|(&addr, info)| info.display(addr))
             .collect()
     };
 
@@ -381,6 +397,16 @@ async fn create_ws_ticket(
     Ok(Json(serde_json::json!({ "ticket": ticket })))
 }
 
+/// 換發 WS 會員連線票（30 秒 TTL）。帶著它握手的連線在 2 人對戰中會綁定會員身分，
+/// 完局計入積分與個人戰績；不換票照樣能匿名對戰。
+async fn create_ws_member_ticket(
+    Extension(member): Extension<AuthenticatedMember>,
+    State(state): State<AppState>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let ticket = ws_service::issue_member_ticket(&state, member.member_id).await?;
+    Ok(Json(serde_json::json!({ "ticket": ticket })))
+}
+
 async fn cleanup_connection(state: &AppState, who: SocketAddr) {
     // 各遊戲斷線清理：在佇列就移除；在對局就判對手勝（斷線即判敗）
     for hub in state.games().all() {
@@ -4,6 +4,12 @@ pub mod audit_logs;
 pub mod auth;
 pub mod blog_comments;
 pub mod blogs;
+pub mod farm_scores;
+pub mod game_analysis;
+pub mod game_moderation;
+pub mod game_ratings;
+pub mod game_records;
+pub mod game_tournaments;
 pub mod gov_tenders;
 pub mod images;
 pub mod invoice_lottery;
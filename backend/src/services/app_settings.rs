@@ -117,6 +117,24 @@ fn validate_home_features(value: &str) -> Result<(), AppError> {
     Ok(())
 }
 
+/// game_chat_filter_words 驗證：JSON 字串陣列，每項 1–32 字元、最多 200 項。
+/// 比對不分大小寫、命中逐字換成 `*`（見 `games::common::chat::censor`）。
+fn validate_game_chat_filter_words(value: &str) -> Result<(), AppError> {
+    let items: Vec<String> = serde_json::from_str(value)
+        .map_err(|_| unprocessable("game_chat_filter_words 必須是 JSON 字串陣列"))?;
+
+    if items.len() > 200 {
+        return Err(unprocessable("game_chat_filter_words 最多 200 項"));
+    }
+    for item in &items {
+        let n = item.trim().chars().count();
+        if n == 0 || n > 32 {
+            return Err(unprocessable("game_chat_filter_words 項目須為 1–32 字元的字串"));
+        }
+    }
+    Ok(())
+}
+
 /// enabled_features 驗證：`all`，或全部是合法 feature key 的不重複 JSON 字串陣列。
 /// 與 home_features 相反這裡驗 key 名 —— feature key 權威在後端 Feature enum，
 /// 未知 key = 打錯字或前後端不同步，直接擋下。
@@ -235,6 +253,13 @@ fn validate(key: &str, value: &str) -> Result<(), AppError> {
         // 上限對齊 libwebp 單邊上限 16383
         return validate_int_range(key, value, 64, 16383);
     }
+    if key == "game_reconnect_grace_secs" {
+        // 0 = 斷線立即判負；上限 10 分鐘，再長等於讓對手乾等
+        return validate_int_range(key, value, 0, 600);
+    }
+    if key == crate::games::common::chat::FILTER_SETTING {
+        return validate_game_chat_filter_words(value);
+    }
     if key == "image_client_compress" {
         return match value {
             "true" | "false" => Ok(()),
@@ -431,6 +456,16 @@ mod tests {
         assert!(validate("home_features", r#"["not_a_feature"]"#).is_ok());
     }
 
+    #[test]
+    fn game_chat_filter_words_validation() {
+        assert!(validate("game_chat_filter_words", "[]").is_ok());
+        assert!(validate("game_chat_filter_words", r#"["笨蛋","idiot"]"#).is_ok());
+        assert!(validate("game_chat_filter_words", "idiot").is_err());
+        assert!(validate("game_chat_filter_words", r#"["ok"," "]"#).is_err());
+        let long = format!(r#"["{}"]"#, "字".repeat(33));
+        assert!(validate("game_chat_filter_words", &long).is_err());
+    }
+
     #[test]
     fn enabled_features_accepts_all_or_valid_keys() {
         assert!(validate("enabled_features", "all").is_ok());
@@ -548,6 +583,16 @@ mod tests {
         assert!(validate("image_client_compress", "yes").is_err());
     }
 
+    #[test]
+    fn game_reconnect_grace_validates() {
+        assert!(validate("game_reconnect_grace_secs", "60").is_ok());
+        assert!(validate("game_reconnect_grace_secs", "0").is_ok());
+        assert!(validate("game_reconnect_grace_secs", "600").is_ok());
+        assert!(validate("game_reconnect_grace_secs", "601").is_err());
+        assert!(validate("game_reconnect_grace_secs", "-1").is_err());
+        assert!(validate("game_reconnect_grace_secs", "1m").is_err());
+    }
+
     #[test]
     fn home_features_rejects_bad_shape() {
         assert!(validate("home_features", "not json").is_err());
@@ -0,0 +1,43 @@
+//! 農場經營完局分數：落地（登入會員才寫）、高分榜、個人最佳。
+
+use crate::{
+    errors::AppError,
+    repositories::farm_scores as repo,
+    state::AppState,
+    structs::farm_scores::{FarmBest, FarmLeaderboardResponse, FarmMode, NewFarmScore},
+};
+use sqlx::{Pool, Postgres};
+
+const LEADERBOARD_SIZE: i64 = 50;
+
+/// 同 `game_records::spawn_save`：呼叫端在 WS 處理流程裡，不等 DB。寫失敗只丟這一筆，留 ERROR 供追查。
+pub fn spawn_save(pool: Pool<Postgres>, rows: Vec<NewFarmScore>) {
+    if rows.is_empty() {
+        return;
+    }
+    tokio::spawn(async move {
+        for rec in &rows {
+            if let Err(e) = repo::insert(&pool, rec).await {
+                tracing::error!("farm 完局分數寫入失敗（member {}）: {:?}", rec.member_id, e);
+            }
+        }
+    });
+}
+
+pub async fn leaderboard(
+    state: &AppState,
+    member_id: Option<i64>,
+    mode: FarmMode,
+    cards: bool,
+) -> Result<FarmLeaderboardResponse, AppError> {
+    let top = repo::leaderboard_top(state.get_pool(), mode.is_solo(), cards, LEADERBOARD_SIZE).await?;
+    let me = match member_id {
+        Some(mid) => repo::leaderboard_me(state.get_pool(), mode.is_solo(), cards, mid).await?,
+        None => None,
+    };
+    Ok(FarmLeaderboardResponse { top, me })
+}
+
+pub async fn bests(state: &AppState, member_id: i64) -> Result<Vec<FarmBest>, AppError> {
+    repo::bests(state.get_pool(), member_id).await
+}
@@ -0,0 +1,415 @@
+//! 賽後研究用的局面分析：無狀態，輸入局面（起始局面 + 走步）回傳引擎的判讀。
+//! 全部是純 CPU 工作，照 `routes::tools::convert_text` 的慣例丟 `spawn_blocking`（見 `run`）。
+
+use crate::{
+    errors::{unprocessable, AppError, SystemError},
+    games::{
+        chess::{engine as xq, notation as xq_notation, types as xq_types},
+        go::engine as go,
+        gomoku::engine as gomoku,
+        western_chess::{engine as wc, notation as wc_notation, types as wc_types},
+    },
+    structs::game_analysis::{
+        AnalysisMove, AnalysisResult, ChessAnalysis, ChessAnalysisRequest, GoAnalysis, GoAnalysisRequest,
+        GoCapture, GoGroup, GoPrisoners, GoScore, GomokuAnalysis, GomokuAnalysisRequest, GomokuForbidden,
+        GomokuFour, GomokuLine, GomokuThreats, Point, StoneColor,
+    },
+};
+
+/// 一次最多重播的步數。
+const MAX_MOVES: usize = 1000;
+
+/// 在 blocking 執行緒跑分析。
+pub async fn run<T: Send + 'static>(
+    f: impl FnOnce() -> Result<T, AppError> + Send + 'static,
+) -> Result<T, AppError> {
+    tokio::task::spawn_blocking(f)
+        .await
+        .map_err(|e| SystemError::Internal(format!("局面分析執行失敗: {e}")))?
+}
+
+fn check_len(n: usize) -> Result<(), AppError> {
+    if n > MAX_MOVES {
+        return Err(unprocessable(format!("moves 最多 {MAX_MOVES} 步")));
+    }
+    Ok(())
+}
+
+fn illegal(ply: usize, mv: impl std::fmt::Display) -> AppError {
+    unprocessable(format!("第 {ply} 步 {mv} 不合法"))
+}
+
+fn over(ply: usize) -> AppError {
+    unprocessable(format!("第 {ply} 步之前對局已結束"))
+}
+
+// ---- 西洋棋 ----
+
+fn wc_label(c: wc_types::Color) -> &'static str {
+    match c {
+        wc_types::Color::White => "white",
+        wc_types::Color::Black => "black",
+    }
+}
+
+fn wc_result(state: &wc_types::State) -> Option<AnalysisResult> {
+    match wc::status(state) {
+        wc::Outcome::Continue => None,
+        wc::Outcome::Checkmate { winner } => {
+            Some(AnalysisResult { winner: Some(wc_label(winner)), reason: "checkmate" })
+        }
+        wc::Outcome::Stalemate => Some(AnalysisResult { winner: None, reason: "stalemate" }),
+        wc::Outcome::Draw(r) => Some(AnalysisResult { winner: None, reason: r.as_str() }),
+    }
+}
+
+pub fn western_chess(req: ChessAnalysisRequest) -> Result<ChessAnalysis, AppError> {
+    check_len(req.moves.len())?;
+    let mut state = match &req.fen {
+        Some(fen) => wc_notation::from_fen(fen).map_err(|r| unprocessable(format!("FEN 不合法（{r}）")))?,
+        None => wc::initial_state(),
+    };
+    for (i, s) in req.moves.iter().enumerate() {
+        if wc_result(&state).is_some() {
+            return Err(over(i + 1));
+        }
+        let mv = wc_notation::parse_uci(&state, s).ok_or_else(|| illegal(i + 1, s))?;
+        wc::apply(&mut state, mv);
+    }
+
+    let result = wc_result(&state);
+    let legal_moves = wc::legal_moves(&state)
+        .into_iter()
+        .map(|mv| AnalysisMove {
+            from: [mv.from.0, mv.from.1],
+            to: [mv.to.0, mv.to.1],
+            coord: wc_notation::uci(mv),
+            notation: wc_notation::san(&state, mv),
+        })
+        .collect();
+    Ok(ChessAnalysis {
+        turn: wc_label(state.turn),
+        check: wc::is_in_check(&state, state.turn),
+        claimable_draw: if result.is_none() { wc::claimable_draw(&state).map(|r| r.as_str()) } else { None },
+        result,
+        legal_moves,
+        repetitions: wc::repetitions(&state),
+    })
+}
+
+// ---- 象棋 ----
+
+fn xq_label(s: xq_types::Side) -> &'static str {
+    match s {
+        xq_types::Side::Red => "red",
+        xq_types::Side::Black => "black",
+    }
+}
+
+/// reason 字串同 `games::chess::game` 的 `status`。
+fn xq_result(state: &xq_types::GameState) -> Option<AnalysisResult> {
+    use xq_types::Status;
+    let win = |side, reason| Some(AnalysisResult { winner: Some(xq_label(side)), reason });
+    match xq::game_status(state) {
+        Status::Ongoing => None,
+        Status::Checkmate { winner } => win(winner, "checkmate"),
+        Status::Stalemate { loser } => win(loser.opponent(), "stalemate"),
+        Status::Draw => Some(AnalysisResult { winner: None, reason: "draw_60" }),
+        Status::RepetitionDraw => Some(AnalysisResult { winner: None, reason: "repetition" }),
+        Status::PerpetualCheck { loser } => win(loser.opponent(), "perpetual_check"),
+        Status::PerpetualChase { loser } => win(loser.opponent(), "perpetual_chase"),
+    }
+}
+
+pub fn xiangqi(req: ChessAnalysisRequest) -> Result<ChessAnalysis, AppError> {
+    check_len(req.moves.len())?;
+    let mut state = match &req.fen {
+        Some(fen) => xq_notation::from_fen(fen).map_err(|r| unprocessable(format!("FEN 不合法（{r}）")))?,
+        None => xq::initial_state(),
+    };
+    for (i, s) in req.moves.iter().enumerate() {
+        if xq_result(&state).is_some() {
+            return Err(over(i + 1));
+        }
+        let mv = xq_notation::parse_iccs(&state, s).ok_or_else(|| illegal(i + 1, s))?;
+        xq::play(&mut state, mv);
+    }
+
+    let result = xq_result(&state);
+    let legal_moves = xq::all_legal_moves(&state)
+        .into_iter()
+        .map(|mv| AnalysisMove {
+            from: [mv.from.col, mv.from.row],
+            to: [mv.to.col, mv.to.row],
+            coord: xq_notation::iccs(mv),
+            notation: xq_notation::wxf(&state, mv),
+        })
+        .collect();
+    Ok(ChessAnalysis {
+        turn: xq_label(state.turn),
+        check: xq::is_in_check(&state, state.turn),
+        result,
+        legal_moves,
+        repetitions: xq::repetitions(&state),
+        claimable_draw: None,
+    })
+}
+
+// ---- 圍棋 ----
+
+fn go_label(s: go::Stone) -> &'static str {
+    match s {
+        go::Stone::Black => "black",
+        go::Stone::White => "white",
+    }
+}
+
+fn go_points(points: &[(i8, i8)]) -> Vec<Point> {
+    points.iter().map(|&(c, r)| [c, r]).collect()
+}
+
+pub fn go(req: GoAnalysisRequest) -> Result<GoAnalysis, AppError> {
+    check_len(req.moves.len())?;
+    if !go::SIZES.contains(&req.size) {
+        return Err(unprocessable("size 只接受 9 / 13 / 19"));
+    }
+    if req.handicap > go::MAX_HANDICAP {
+        return Err(unprocessable(format!("handicap 最多 {}", go::MAX_HANDICAP)));
+    }
+    let mut state = go::new_game(req.size, req.handicap, go::Scoring::Area);
+    let in_board = |[c, r]: Point| (0..req.size).contains(&c) && (0..req.size).contains(&r);
+    for (stones, color) in [(&req.black, go::Stone::Black), (&req.white, go::Stone::White)] {
+        for &p in stones {
+            if !in_board(p) || state.board[p[1] as usize][p[0] as usize].is_some() {
+                return Err(unprocessable(format!("擺子 [{}, {}] 越界或重複", p[0], p[1])));
+            }
+            state.board[p[1] as usize][p[0] as usize] = Some(color);
+        }
+    }
+    if go::groups(&state).iter().any(|g| g.liberties == 0) {
+        return Err(unprocessable("擺子後有無氣的塊"));
+    }
+    if let Some(turn) = req.turn {
+        state.turn = match turn {
+            StoneColor::Black => go::Stone::Black,
+            StoneColor::White => go::Stone::White,
+        };
+    }
+
+    let mut captures = Vec::new();
+    for (i, mv) in req.moves.iter().enumerate() {
+        let ply = i + 1;
+        let Some([c, r]) = *mv else {
+            if state.phase != go::Phase::Play {
+                return Err(over(ply));
+            }
+            go::pass(&mut state);
+            continue;
+        };
+        go::is_legal(&state, c, r).map_err(|e| match e {
+            go::Reason::WrongPhase => over(ply),
+            e => unprocessable(format!("第 {ply} 步 [{c}, {r}] 不合法（{}）", e.as_str())),
+        })?;
+        let by = state.turn;
+        let before = go::groups(&state);
+        let captured = go::apply(&mut state, c, r);
+        if !captured.is_empty() {
+            let groups = before
+                .into_iter()
+                .filter(|g| captured.contains(&g.points[0]))
+                .map(|g| go_points(&g.points))
+                .collect();
+            captures.push(GoCapture { ply, by: go_label(by), groups });
+        }
+    }
+
+    let groups = go::groups(&state);
+    let mut dead = Vec::new();
+    for &p in &req.dead {
+        let g = groups
+            .iter()
+            .find(|g| g.points.contains(&(p[0], p[1])))
+            .ok_or_else(|| unprocessable(format!("死子 [{}, {}] 不是棋子", p[0], p[1])))?;
+        dead.extend(&g.points);
+    }
+    dead.sort_unstable();
+    dead.dedup();
+
+    let (b, w) = go::final_score_x2(&state, &dead);
+    let leader = if b > w { go::Stone::Black } else { go::Stone::White };
+    Ok(GoAnalysis {
+        turn: go_label(state.turn),
+        ko: state.ko.map(|(c, r)| [c, r]),
+        captures,
+        prisoners: GoPrisoners { black: state.prisoners[0], white: state.prisoners[1] },
+        atari: groups
+            .iter()
+            .filter(|g| g.liberties == 1)
+            .map(|g| GoGroup { color: go_label(g.stone), points: go_points(&g.points) })
+            .collect(),
+        score: GoScore {
+            black: b as f64 / 2.0,
+            white: w as f64 / 2.0,
+            komi: state.komi_x2 as f64 / 2.0,
+            leader: go_label(leader),
+            margin: (b - w).abs() as f64 / 2.0,
+        },
+    })
+}
+
+// ---- 五子棋 ----
+
+fn gomoku_label(s: gomoku::Stone) -> &'static str {
+    match s {
+        gomoku::Stone::Black => "black",
+        gomoku::Stone::White => "white",
+    }
+}
+
+fn threats(state: &gomoku::GomokuState, stone: gomoku::Stone) -> GomokuThreats {
+    GomokuThreats {
+        wins: gomoku::win_points(state, stone).into_iter().map(|(c, r)| [c, r]).collect(),
+        fours: gomoku::four_points(state, stone)
+            .into_iter()
+            .map(|((c, r), wins)| GomokuFour { at: [c, r], wins })
+            .collect(),
+    }
+}
+
+pub fn gomoku(req: GomokuAnalysisRequest) -> Result<GomokuAnalysis, AppError> {
+    check_len(req.moves.len())?;
+    let rule = match req.rule.as_deref() {
+        None => gomoku::Rule::Freestyle,
+        Some(s) => gomoku::Rule::parse(s)
+            .ok_or_else(|| unprocessable("rule 只接受 freestyle / standard / renju"))?,
+    };
+    let mut state = gomoku::new_game(rule, false);
+    for (i, &[c, r]) in req.moves.iter().enumerate() {
+        if gomoku::status(&state) != gomoku::Outcome::Continue {
+            return Err(over(i + 1));
+        }
+        gomoku::is_legal(&state, c, r)
+            .map_err(|e| unprocessable(format!("第 {} 步 [{c}, {r}] 不合法（{e}）", i + 1)))?;
+        gomoku::apply(&mut state, c, r);
+    }
+
+    let result = match gomoku::status(&state) {
+        gomoku::Outcome::Continue => None,
+        gomoku::Outcome::Win(s) => Some(AnalysisResult { winner: Some(gomoku_label(s)), reason: "five_in_row" }),
+        gomoku::Outcome::Draw => Some(AnalysisResult { winner: None, reason: "draw_full" }),
+    };
+    Ok(GomokuAnalysis {
+        turn: gomoku_label(state.turn),
+        result,
+        lines: gomoku::winning_lines(&state)
+            .into_iter()
+            .map(|(s, points)| GomokuLine { color: gomoku_label(s), points: go_points(&points) })
+            .collect(),
+        black: threats(&state, gomoku::Stone::Black),
+        white: threats(&state, gomoku::Stone::White),
+        forbidden: gomoku::forbidden_points(&state)
+            .into_iter()
+            .map(|((c, r), reason)| GomokuForbidden { at: [c, r], reason })
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chess(fen: Option<&str>, moves: &[&str]) -> ChessAnalysisRequest {
+        ChessAnalysisRequest { fen: fen.map(Into::into), moves: moves.iter().map(|&m| m.into()).collect() }
+    }
+
+    #[test]
+    fn western_chess_mate_and_repetition() {
+        let a = western_chess(chess(None, &[])).unwrap();
+        assert_eq!(a.legal_moves.len(), 20);
+        assert_eq!(a.repetitions, 1);
+
+        let mate = western_chess(chess(None, &["f2f3", "e7e5", "g2g4", "d8h4"])).unwrap();
+        assert!(mate.check);
+        assert!(mate.legal_moves.is_empty());
+        assert_eq!(mate.result, Some(AnalysisResult { winner: Some("black"), reason: "checkmate" }));
+
+        let shuffle = ["g1f3", "g8f6", "f3g1", "f6g8"];
+        let moves: Vec<&str> = shuffle.iter().chain(&shuffle).copied().collect();
+        let a = western_chess(chess(None, &moves)).unwrap();
+        assert_eq!(a.repetitions, 3);
+        assert_eq!(a.claimable_draw, Some("repetition"));
+
+        assert!(western_chess(chess(None, &["e2e5"])).is_err());
+        assert!(western_chess(chess(Some("not a fen"), &[])).is_err());
+        // 將死後不能再走
+        assert!(western_chess(chess(None, &["f2f3", "e7e5", "g2g4", "d8h4", "a2a3"])).is_err());
+    }
+
+    #[test]
+    fn xiangqi_legal_moves_and_check() {
+        let a = xiangqi(chess(None, &[])).unwrap();
+        assert_eq!(a.turn, "red");
+        assert_eq!(a.legal_moves.len(), 44);
+        assert!(a.legal_moves.iter().any(|m| m.coord == "H2-E2" && m.notation == "C2.5"));
+
+        // 紅車直衝九宮將軍
+        let a = xiangqi(chess(Some("4k4/9/9/9/9/9/9/9/9/3K4R w"), &["i0i9"])).unwrap();
+        assert!(a.check);
+        assert_eq!(a.turn, "black");
+        assert!(a.result.is_none());
+    }
+
+    #[test]
+    fn go_reports_captures_atari_and_score() {
+        let req = GoAnalysisRequest {
+            size: 9,
+            handicap: 0,
+            black: vec![[1, 0], [0, 1]],
+            white: vec![[0, 0], [5, 5]],
+            turn: Some(StoneColor::White),
+            moves: vec![None, Some([2, 2])],
+            dead: vec![[5, 5]],
+        };
+        // 白 (0,0) 被黑兩子包住，擺完就無氣
+        assert!(go(req).is_err());
+
+        let req = GoAnalysisRequest {
+            size: 9,
+            handicap: 0,
+            black: vec![[1, 0]],
+            white: vec![[0, 0], [5, 5]],
+            turn: None,
+            moves: vec![Some([0, 1])],
+            dead: vec![[5, 5]],
+        };
+        let a = go(req).unwrap();
+        assert_eq!(a.captures.len(), 1);
+        assert_eq!(a.captures[0].groups, vec![vec![[0, 0]]]);
+        assert_eq!(a.prisoners.black, 1);
+        // 白 (5,5) 當死子 → 全盤只剩黑
+        assert_eq!(a.score.black, 81.0);
+        assert_eq!(a.score.komi, 7.5);
+        assert_eq!(a.score.leader, "black");
+    }
+
+    #[test]
+    fn gomoku_threats_and_lines() {
+        let req = GomokuAnalysisRequest {
+            rule: None,
+            moves: vec![[5, 7], [0, 0], [6, 7], [0, 1], [7, 7], [0, 2], [8, 7]],
+        };
+        let a = gomoku(req).unwrap();
+        assert_eq!(a.turn, "white");
+        assert_eq!(a.black.wins, vec![[4, 7], [9, 7]]);
+        assert!(a.result.is_none());
+
+        let req = GomokuAnalysisRequest {
+            rule: Some("standard".into()),
+            moves: vec![[5, 7], [0, 0], [6, 7], [0, 1], [7, 7], [0, 2], [8, 7], [0, 3], [9, 7]],
+        };
+        let a = gomoku(req).unwrap();
+        assert_eq!(a.result, Some(AnalysisResult { winner: Some("black"), reason: "five_in_row" }));
+        assert_eq!(a.lines[0].points, vec![[5, 7], [6, 7], [7, 7], [8, 7], [9, 7]]);
+        assert!(gomoku(GomokuAnalysisRequest { rule: Some("x".into()), moves: vec![] }).is_err());
+    }
+}
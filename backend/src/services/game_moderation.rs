@@ -0,0 +1,85 @@
+//! 後台即時對局管理：檢視桌 / 房與聊天、強制結束、關桌、踢人。
+//!
+//! 操作本身在各 hub（`AnyHub::{force_end, close, kick}`）；這裡只負責找 hub、補連線資訊、
+//! 把 hub 的 reason 轉成 HTTP 錯誤。稽核由 admin 路由的 audit middleware 統一記
+//! （路徑帶遊戲 / 桌 id / 連線位址，判給誰在 query）。
+
+use std::net::SocketAddr;
+
+use crate::{
+    errors::{self, AppError, RequestError},
+    games::{
+        common::chat::ChatLine,
+        registry::{AnyHub, LiveTable},
+    },
+    state::AppState,
+};
+
+fn hub<'a>(state: &'a AppState, game: &str) -> Result<(&'static str, &'a AnyHub), AppError> {
+    state
+        .games()
+        .entries()
+        .find(|(name, _)| *name == game)
+        .ok_or_else(|| RequestError::NotFound.into())
+}
+
+/// hub 回的 reason → HTTP 錯誤。
+fn rejected(reason: &'static str) -> AppError {
+    match reason {
+        "no_such_table" | "no_such_room" | "not_in_game" => RequestError::NotFound.into(),
+        "not_playing" => RequestError::Conflict("此桌不在對局中".into()).into(),
+        "tournament_table" => RequestError::Conflict("錦標賽對局不可關桌，請改用強制結束".into()).into(),
+        "unsupported_game" => errors::unprocessable("N 人房沒有勝負可判，請改用關房"),
+        "bad_winner" => errors::unprocessable("winner 須為該遊戲的座位標籤，或不帶表示和局"),
+        other => errors::unprocessable(other),
+    }
+}
+
+/// 進行中與等待中的桌 / 房，依 (game, id) 排序；在線座位補上連線的握手資訊。
+pub async fn live(state: &AppState, game: Option<&str>) -> Result<Vec<LiveTable>, AppError> {
+    let mut out = Vec::new();
+    match game {
+        Some(g) => {
+            let (name, hub) = hub(state, g)?;
+            out = hub.live(name).await;
+        }
+        None => {
+            for (name, hub) in state.games().entries() {
+                out.extend(hub.live(name).await);
+            }
+        }
+    }
+    out.sort_by_key(|t| (t.game, t.id));
+
+    let conns = state.get_connections().lock().await;
+    for seat in out.iter_mut().flat_map(|t| t.seats.iter_mut()) {
+        let Some(addr) = seat.addr.as_deref().and_then(|a| a.parse::<SocketAddr>().ok()) else { continue };
+        if let Some(info) = conns.get(&addr) {
+            seat.member_id = seat.member_id.or(info.member_id);
+            seat.connection = Some(info.display(addr));
+        }
+    }
+    Ok(out)
+}
+
+/// 大廳最近聊天（桌內聊天在 `live` 各桌的 `chat`）。
+pub async fn lobby_chat(state: &AppState, game: &str) -> Result<Vec<ChatLine>, AppError> {
+    let (_, hub) = hub(state, game)?;
+    Ok(hub.lobby_chat().await)
+}
+
+pub async fn end_table(state: &AppState, game: &str, id: u64, winner: Option<&str>) -> Result<(), AppError> {
+    let (_, hub) = hub(state, game)?;
+    hub.force_end(state, id, winner).await.map_err(rejected)
+}
+
+pub async fn close_table(state: &AppState, game: &str, id: u64) -> Result<(), AppError> {
+    let (_, hub) = hub(state, game)?;
+    hub.close(state, id).await.map_err(rejected)
+}
+
+pub async fn kick(state: &AppState, game: &str, addr: &str) -> Result<(), AppError> {
+    let (_, hub) = hub(state, game)?;
+    let who: SocketAddr = addr.parse().map_err(|_| errors::unprocessable("連線位址格式錯誤"))?;
+    hub.kick(state, who).await.map_err(rejected)
+}
@@ -0,0 +1,112 @@
+use crate::{
+    errors::{unprocessable, AppError},
+    repositories::game_ratings as repo,
+    state::AppState,
+    structs::game_ratings::{GameRating, RatingLeaderboardResponse},
+};
+use sqlx::PgConnection;
+
+/// Elo K 值。固定值讓雙方增減對稱（一方加多少另一方扣多少），積分總量守恆。
+const ELO_K: f64 = 32.0;
+
+const LEADERBOARD_SIZE: i64 = 50;
+
+/// Elo 期望分：A 對 B 的預期得分（0..1）
+fn expected_score(rating_a: i32, rating_b: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf(f64::from(rating_b - rating_a) / 400.0))
+}
+
+/// 一局後的積分變化 `[a, b]`（純函式，可測）。`score_a` 為 A 的得分 1.0 / 0.5 / 0.0。
+///
+/// B 的變化直接取 A 的相反數而不是各自四捨五入 —— 各自捨入會讓總量一局漂移 ±1。
+pub fn elo_deltas(rating_a: i32, rating_b: i32, score_a: f64) -> [i32; 2] {
+    let delta = (ELO_K * (score_a - expected_score(rating_a, rating_b))).round() as i32;
+    [delta, -delta]
+}
+
+/// 計分局結算：鎖雙方積分列、算 Elo、寫回，回傳 `[先手, 後手]` 的積分變化。
+/// 由 caller 持有 transaction（與 game_records 同生同死，見 `services::game_records`）。
+///
+/// `winner_seat`：0 先手勝 / 1 後手勝 / None 和局。
+pub async fn apply_in_tx(
+    conn: &mut PgConnection,
+    game: &str,
+    members: [i64; 2],
+    winner_seat: Option<i16>,
+) -> Result<[i32; 2], AppError> {
+    // 依 member_id 排序上鎖，避免同一對人同時結束兩盤時互鎖
+    let (lo, hi) = if members[0] < members[1] { (0, 1) } else { (1, 0) };
+    let mut ratings = [0; 2];
+    ratings[lo] = repo::lock_rating_in_tx(conn, members[lo], game).await?;
+    ratings[hi] = repo::lock_rating_in_tx(conn, members[hi], game).await?;
+
+    let score_first = match winner_seat {
+        Some(0) => 1.0,
+        Some(_) => 0.0,
+        None => 0.5,
+    };
+    let deltas = elo_deltas(ratings[0], ratings[1], score_first);
+    repo::apply_result_in_tx(conn, members[0], game, deltas[0], score_first).await?;
+    repo::apply_result_in_tx(conn, members[1], game, deltas[1], 1.0 - score_first).await?;
+    Ok(deltas)
+}
+
+/// 訪客也能看榜；登入時額外回自己的名次。`game` 必須是已註冊的 2 人對戰遊戲。
+pub async fn leaderboard(
+    state: &AppState,
+    member_id: Option<i64>,
+    game: &str,
+) -> Result<RatingLeaderboardResponse, AppError> {
+    if !state.games().is_rated_game(game) {
+        return Err(unprocessable(format!("不支援的遊戲：{game}")));
+    }
+    let top = repo::leaderboard_top(state.get_pool(), game, LEADERBOARD_SIZE).await?;
+    let me = match member_id {
+        Some(mid) => repo::leaderboard_me(state.get_pool(), game, mid).await?,
+        None => None,
+    };
+    Ok(RatingLeaderboardResponse { top, me })
+}
+
+pub async fn me(state: &AppState, member_id: i64) -> Result<Vec<GameRating>, AppError> {
+    repo::member_ratings(state.get_pool(), member_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::elo_deltas;
+
+    #[test]
+    fn equal_ratings_move_half_k() {
+        assert_eq!(elo_deltas(1500, 1500, 1.0), [16, -16]);
+        assert_eq!(elo_deltas(1500, 1500, 0.0), [-16, 16]);
+        assert_eq!(elo_deltas(1500, 1500, 0.5), [0, 0]);
+    }
+
+    /// 高分贏低分加得少、爆冷加得多
+    #[test]
+    fn upset_gains_more_than_expected_win() {
+        let favourite = elo_deltas(1800, 1400, 1.0)[0];
+        let underdog = elo_deltas(1400, 1800, 1.0)[0];
+        assert!(favourite < underdog, "{favourite} 應小於 {underdog}");
+        assert!(favourite > 0);
+    }
+
+    /// 和局時低分方加分、高分方扣分
+    #[test]
+    fn draw_favours_lower_rated() {
+        let [hi, lo] = elo_deltas(1700, 1500, 0.5);
+        assert!(hi < 0 && lo > 0);
+    }
+
+    /// 雙方變化永遠互為相反數（積分總量守恆）
+    #[test]
+    fn deltas_are_zero_sum() {
+        for (a, b) in [(1500, 1500), (1234, 1876), (2100, 900)] {
+            for s in [0.0, 0.5, 1.0] {
+                let [da, db] = elo_deltas(a, b, s);
+                assert_eq!(da + db, 0);
+            }
+        }
+    }
+}
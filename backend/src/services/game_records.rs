@@ -0,0 +1,76 @@
+use crate::{
+    errors::{self, AppError},
+    games::{common::notation::Exported, registry},
+    repositories::game_records as repo,
+    services::game_ratings,
+    structs::{
+        game_records::{GameRecord, GameRecordSummary, MemberGameHistoryRow, NewGameRecord},
+        pagination::Paginated,
+    },
+};
+use sqlx::{Pool, Postgres};
+
+/// 完局落地（fire-and-forget）。
+///
+/// 呼叫端是 `games::common::service::end_game`：同步函式、而且還握著 hub 的鎖，
+/// 不能在那裡 await DB。對局結束的頻率低（一盤幾分鐘一次），每盤一個 task 不值得
+/// 另開 batch writer。寫失敗只丟這一盤的紀錄（含積分），留 ERROR 供追查。
+pub fn spawn_save(pool: Pool<Postgres>, rec: NewGameRecord) {
+    tokio::spawn(async move {
+        if let Err(e) = save(&pool, &rec).await {
+            tracing::error!("{} 完局紀錄寫入失敗: {:?}", rec.game, e);
+        }
+    });
+}
+
+/// 計分局先結算積分再寫紀錄，同一個 transaction
+async fn save(pool: &Pool<Postgres>, rec: &NewGameRecord) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let changes = match rec.rated_pair() {
+        Some(members) => {
+            Some(game_ratings::apply_in_tx(&mut tx, rec.game, members, rec.winner_seat).await?)
+        }
+        None => None,
+    };
+    repo::insert_in_tx(&mut tx, rec, changes).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+pub async fn list(
+    pool: &Pool<Postgres>,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Paginated<GameRecordSummary>, AppError> {
+    // count 與 list 併發跑：序列 await 是白吃一倍延遲（範本同 services/logs.rs）
+    let (data, total) = tokio::try_join!(
+        repo::list(pool, game, limit, offset),
+        repo::count(pool, game),
+    )?;
+    Ok(Paginated::new(data, total))
+}
+
+pub async fn get(pool: &Pool<Postgres>, id: i64) -> Result<GameRecord, AppError> {
+    repo::get(pool, id).await
+}
+
+/// 單盤記譜匯出（`format` 見各遊戲 `GameEngine::export`）。不支援的遊戲回 422。
+pub async fn export(pool: &Pool<Postgres>, id: i64, format: Option<String>) -> Result<Exported, AppError> {
+    let rec = repo::get(pool, id).await?;
+    registry::export_record(&rec, format).ok_or_else(|| errors::unprocessable("此遊戲不支援記譜匯出"))
+}
+
+pub async fn member_history(
+    pool: &Pool<Postgres>,
+    member_id: i64,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Paginated<MemberGameHistoryRow>, AppError> {
+    let (data, total) = tokio::try_join!(
+        repo::member_history(pool, member_id, game, limit, offset),
+        repo::count_member_history(pool, member_id, game),
+    )?;
+    Ok(Paginated::new(data, total))
+}
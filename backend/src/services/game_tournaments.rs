@@ -0,0 +1,376 @@
+use std::collections::HashMap;
+
+use crate::{
+    errors::{self, AppError, RequestError, SystemError},
+    games::{
+        common::clock::TimeControl,
+        tournament::{self, Format, GameResult, Pairing, PlannedGame, RoundPlan, Standing, TournamentSeat},
+    },
+    repositories::game_tournaments as repo,
+    state::AppState,
+    structs::{
+        game_tournaments::{
+            GameTournament, NewGameTournament, SetPairingResult, TournamentDetail, TournamentEntrant,
+            TournamentPairing, TournamentStandingRow,
+        },
+        pagination::Paginated,
+    },
+};
+use serde_json::{json, Map, Value};
+use sqlx::{PgConnection, Pool, Postgres};
+
+const MAX_NAME_CHARS: usize = 60;
+const MAX_SWISS_ROUNDS: i32 = 20;
+
+/// 一組對陣的結果。
+enum Outcome {
+    /// 勝方會員。完局回報用 —— Swap2 選色後座位可能對調，依會員而非對陣表的先後手。
+    Won(i64),
+    /// 依對陣表的先後手（不戰 / 後台登錄）。
+    FirstWins,
+    SecondWins,
+    Draw,
+    DoubleForfeit,
+}
+
+impl Outcome {
+    /// `(先手得分, 後手得分)`
+    fn scores(&self, p: &TournamentPairing) -> (f64, f64) {
+        match self {
+            Outcome::Won(m) if *m == p.first_member_id => (1.0, 0.0),
+            Outcome::Won(_) | Outcome::SecondWins => (0.0, 1.0),
+            Outcome::FirstWins => (1.0, 0.0),
+            Outcome::Draw => (0.5, 0.5),
+            Outcome::DoubleForfeit => (0.0, 0.0),
+        }
+    }
+}
+
+/// 結果回寫後的下一步（交給 `run` 推進）。
+enum Advance {
+    /// 整輪完成、開下一輪。
+    Round(RoundPlan),
+    /// 最後一輪完成：推 `tournament_finished`。
+    Finished {
+        tournament_id: i64,
+        name: String,
+        entrants: Vec<i64>,
+        standings: Vec<TournamentStandingRow>,
+    },
+}
+
+pub async fn create(state: &AppState, input: NewGameTournament) -> Result<GameTournament, AppError> {
+    let hub = state
+        .games()
+        .get(&input.game)
+        .filter(|h| h.is_two_player())
+        .ok_or_else(|| errors::unprocessable("錦標賽只支援 2 人對戰遊戲"))?;
+    let format = Format::parse(&input.format)
+        .ok_or_else(|| errors::unprocessable("format 須為 round_robin 或 swiss"))?;
+    let name = input.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_NAME_CHARS {
+        return Err(errors::unprocessable(format!("名稱須為 1–{MAX_NAME_CHARS} 字")));
+    }
+    let rounds = match format {
+        Format::RoundRobin => 0,
+        Format::Swiss => input
+            .rounds
+            .filter(|r| (1..=MAX_SWISS_ROUNDS).contains(r))
+            .ok_or_else(|| errors::unprocessable(format!("瑞士制須指定輪數 1–{MAX_SWISS_ROUNDS}")))?,
+    };
+    // 計時制 / 規則選項與建桌同一套驗證
+    let mut setup = Map::new();
+    if let Some(tc) = input.time_control {
+        setup.insert("time_control".into(), tc);
+    }
+    if let Some(options) = input.options {
+        setup.insert("options".into(), options);
+    }
+    let (time_control, options) = hub
+        .parse_setup(&Value::Object(setup))
+        .map_err(|reason| errors::unprocessable(format!("計時制或規則選項不合法（{reason}）")))?;
+
+    let pool = state.get_pool();
+    let id = repo::insert(pool, &input.game, name, format.as_str(), rounds, &time_control.to_json(), &options)
+        .await?;
+    repo::get(pool, id).await
+}
+
+pub async fn list(
+    pool: &Pool<Postgres>,
+    game: Option<&str>,
+    limit: i64,
+    offset: i64,
+) -> Result<Paginated<GameTournament>, AppError> {
+    let (data, total) = tokio::try_join!(repo::list(pool, game, limit, offset), repo::count(pool, game))?;
+    Ok(Paginated::new(data, total))
+}
+
+/// 單一錦標賽 + 目前名次 + 全部對陣。名次由已完成的對陣現算（完賽後與寫入的最終名次一致）。
+pub async fn detail(pool: &Pool<Postgres>, id: i64) -> Result<TournamentDetail, AppError> {
+    // 三張表讀同一個快照：不然讀到一半開了下一輪，名次與對陣會對不上
+    let mut tx = pool.begin().await?;
+    let t = repo::get_in_tx(&mut tx, id, false).await?;
+    let entrants = repo::entrants_in_tx(&mut tx, id).await?;
+    let pairings = repo::pairings_in_tx(&mut tx, id).await?;
+    tx.commit().await?;
+    let table = tournament::standings(&member_ids(&entrants), &finished(&pairings));
+    Ok(TournamentDetail { tournament: t, standings: standing_rows(&table, &entrants), pairings })
+}
+
+/// 報名（只在報名期；重複報名視為成功）
+pub async fn register(pool: &Pool<Postgres>, id: i64, member_id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let t = repo::get_in_tx(&mut tx, id, true).await?;
+    if t.status != "registering" {
+        return Err(RequestError::Conflict("報名已截止".into()).into());
+    }
+    repo::register_in_tx(&mut tx, id, member_id).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 取消報名（只在報名期）
+pub async fn unregister(pool: &Pool<Postgres>, id: i64, member_id: i64) -> Result<(), AppError> {
+    let mut tx = pool.begin().await?;
+    let t = repo::get_in_tx(&mut tx, id, true).await?;
+    if t.status != "registering" {
+        return Err(RequestError::Conflict("已開賽，無法取消報名".into()).into());
+    }
+    if !repo::unregister_in_tx(&mut tx, id, member_id).await? {
+        return Err(RequestError::NotFound.into());
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// 開賽：截止報名、定下總輪數（循環賽依人數；瑞士制下修到不必重賽的輪數）、排第 1 輪並開桌。
+pub async fn start(state: &AppState, id: i64) -> Result<TournamentDetail, AppError> {
+    let pool = state.get_pool();
+    let mut tx = pool.begin().await?;
+    let mut t = repo::get_in_tx(&mut tx, id, true).await?;
+    if t.status != "registering" {
+        return Err(RequestError::Conflict("錦標賽已開賽".into()).into());
+    }
+    let entrants = repo::entrants_in_tx(&mut tx, id).await?;
+    if entrants.len() < 2 {
+        return Err(errors::unprocessable("至少需 2 人報名才能開賽"));
+    }
+    let format = Format::parse(&t.format).unwrap_or(Format::Swiss);
+    t.rounds = tournament::total_rounds(format, t.rounds.max(1) as u32, entrants.len()) as i32;
+    repo::start_in_tx(&mut tx, id, t.rounds).await?;
+    let plan = open_round(&mut tx, &t, 1, &entrants, &[]).await?;
+    tx.commit().await?;
+    run(state, Some((t.game, Advance::Round(plan)))).await;
+    detail(pool, id).await
+}
+
+/// 後台手動登錄一組對陣的結果（重啟後卡住的對局、爭議判定）。登錄後照常推進下一輪。
+pub async fn set_result(
+    state: &AppState,
+    id: i64,
+    pairing_id: i64,
+    input: SetPairingResult,
+) -> Result<TournamentDetail, AppError> {
+    let outcome = match input.result.as_str() {
+        "first" => Outcome::FirstWins,
+        "second" => Outcome::SecondWins,
+        "draw" => Outcome::Draw,
+        "double_forfeit" => Outcome::DoubleForfeit,
+        _ => return Err(errors::unprocessable("result 須為 first / second / draw / double_forfeit")),
+    };
+    let pool = state.get_pool();
+    let current = detail(pool, id).await?;
+    let pairing = current.pairings.iter().find(|p| p.id == pairing_id).ok_or(RequestError::NotFound)?;
+    if current.tournament.status != "running" || pairing.first_score.is_some() {
+        return Err(RequestError::Conflict("此對陣已有結果".into()).into());
+    }
+    let next = settle(pool, id, vec![(pairing_id, outcome, true)]).await?;
+    run(state, next).await;
+    detail(pool, id).await
+}
+
+/// 完局回報（fire-and-forget）。呼叫端是 `games::common::service::end_game`（同步、握著 hub 鎖），
+/// 同 `game_records::spawn_save` 另開 task。`winner` 為勝方會員，None 表和棋。
+pub fn spawn_result(state: AppState, seat: TournamentSeat, winner: Option<i64>) {
+    spawn_settle(state, seat, winner.map_or(Outcome::Draw, Outcome::Won), false);
+}
+
+/// 對局中止、雙方都沒回來（重啟還原後無人重連）：該盤記雙方不戰，這輪才收得了尾。
+pub fn spawn_abandoned(state: AppState, seat: TournamentSeat) {
+    spawn_settle(state, seat, Outcome::DoubleForfeit, true);
+}
+
+fn spawn_settle(state: AppState, seat: TournamentSeat, outcome: Outcome, forfeit: bool) {
+    tokio::spawn(async move {
+        match settle(state.get_pool(), seat.tournament_id, vec![(seat.pairing_id, outcome, forfeit)]).await {
+            Ok(next) => run(&state, next).await,
+            Err(e) => tracing::error!(
+                "錦標賽 #{} 對陣 #{} 結果寫入失敗，該輪需由後台登錄結果: {:?}",
+                seat.tournament_id,
+                seat.pairing_id,
+                e
+            ),
+        }
+    });
+}
+
+/// 寫入結果 `(pairing_id, 結果, 是否不戰)`；目前這輪全部完成就排下一輪（或完賽寫最終名次）。
+///
+/// 鎖錦標賽列序列化：同一輪最後兩盤同時結束時只有一邊會看到整輪完成。
+/// 已有結果的對陣不覆寫；錦標賽不在進行中則整批忽略。
+async fn settle(
+    pool: &Pool<Postgres>,
+    id: i64,
+    reports: Vec<(i64, Outcome, bool)>,
+) -> Result<Option<(String, Advance)>, AppError> {
+    let mut tx = pool.begin().await?;
+    let t = repo::get_in_tx(&mut tx, id, true).await?;
+    if t.status != "running" {
+        return Ok(None);
+    }
+    let pairings = repo::pairings_in_tx(&mut tx, id).await?;
+    for (pairing_id, outcome, forfeit) in reports {
+        let Some(p) = pairings.iter().find(|p| p.id == pairing_id) else {
+            continue;
+        };
+        let (first_score, second_score) = outcome.scores(p);
+        repo::set_result_in_tx(&mut tx, id, pairing_id, first_score, second_score, forfeit).await?;
+    }
+    let pairings = repo::pairings_in_tx(&mut tx, id).await?;
+    if pairings.iter().any(|p| p.round == t.current_round && p.first_score.is_none()) {
+        tx.commit().await?;
+        return Ok(None);
+    }
+    let entrants = repo::entrants_in_tx(&mut tx, id).await?;
+    let results = finished(&pairings);
+    let advance = if t.current_round >= t.rounds {
+        let table = tournament::standings(&member_ids(&entrants), &results);
+        repo::finish_in_tx(&mut tx, id, &table).await?;
+        Advance::Finished {
+            tournament_id: id,
+            name: t.name.clone(),
+            entrants: member_ids(&entrants),
+            standings: standing_rows(&table, &entrants),
+        }
+    } else {
+        Advance::Round(open_round(&mut tx, &t, t.current_round + 1, &entrants, &results).await?)
+    };
+    tx.commit().await?;
+    Ok(Some((t.game, advance)))
+}
+
+/// 推進賽程：開桌、把缺席的對陣判不戰；不戰讓整輪直接完成時接著排下一輪，直到有對局在下或完賽。
+async fn run(state: &AppState, mut next: Option<(String, Advance)>) {
+    while let Some((game, advance)) = next.take() {
+        let Some(hub) = state.games().get(&game) else {
+            return;
+        };
+        match advance {
+            Advance::Round(plan) => {
+                let conns = state.member_connections().await;
+                let reports: Vec<_> = hub
+                    .seat_tournament_round(state, &plan, &conns)
+                    .await
+                    .into_iter()
+                    .map(|(pairing_id, present)| {
+                        let outcome = match present {
+                            [true, false] => Outcome::FirstWins,
+                            [false, true] => Outcome::SecondWins,
+                            _ => Outcome::DoubleForfeit,
+                        };
+                        (pairing_id, outcome, true)
+                    })
+                    .collect();
+                if reports.is_empty() {
+                    return;
+                }
+                match settle(state.get_pool(), plan.tournament_id, reports).await {
+                    Ok(n) => next = n,
+                    Err(e) => {
+                        tracing::error!("錦標賽 #{} 不戰結果寫入失敗: {:?}", plan.tournament_id, e);
+                        return;
+                    }
+                }
+            }
+            Advance::Finished { tournament_id, name, entrants, standings } => {
+                let conns = state.member_connections().await;
+                let data = json!({ "tournament_id": tournament_id, "name": name, "standings": standings });
+                hub.announce_tournament(state, &entrants, &conns, "tournament_finished", data).await;
+            }
+        }
+    }
+}
+
+/// 排第 `round` 輪並寫入對陣，回傳交給 hub 開桌的計畫。
+async fn open_round(
+    conn: &mut PgConnection,
+    t: &GameTournament,
+    round: i32,
+    entrants: &[TournamentEntrant],
+    results: &[GameResult],
+) -> Result<RoundPlan, AppError> {
+    let players = member_ids(entrants);
+    let names: HashMap<i64, &str> = entrants.iter().map(|e| (e.member_id, e.name.as_str())).collect();
+    let player = |m: i64| (m, names.get(&m).copied().unwrap_or_default().to_string());
+    let pairings: Vec<Pairing> = match Format::parse(&t.format) {
+        Some(Format::RoundRobin) => tournament::round_robin_pairings(&players, round as u32),
+        _ => tournament::swiss_pairings(&players, results),
+    };
+    let mut games = Vec::with_capacity(pairings.len());
+    for p in pairings {
+        let id = repo::insert_pairing_in_tx(conn, t.id, round, p.first, p.second).await?;
+        games.push(PlannedGame { id, first: player(p.first), second: p.second.map(player) });
+    }
+    repo::set_round_in_tx(conn, t.id, round).await?;
+    let time_control = TimeControl::parse(&t.time_control)
+        .ok_or_else(|| SystemError::Internal(format!("錦標賽 #{} 計時制損毀", t.id)))?;
+    Ok(RoundPlan {
+        tournament_id: t.id,
+        name: t.name.clone(),
+        round,
+        time_control,
+        options: t.options.clone(),
+        games,
+        entrants: players,
+    })
+}
+
+fn member_ids(entrants: &[TournamentEntrant]) -> Vec<i64> {
+    entrants.iter().map(|e| e.member_id).collect()
+}
+
+/// 已完成的對陣（含輪空與不戰）
+fn finished(pairings: &[TournamentPairing]) -> Vec<GameResult> {
+    pairings
+        .iter()
+        .filter_map(|p| {
+            Some(GameResult {
+                first: p.first_member_id,
+                second: p.second_member_id,
+                first_score: p.first_score?,
+                second_score: p.second_score.unwrap_or(0.0),
+            })
+        })
+        .collect()
+}
+
+/// 名次表換上顯示名（會員 id 不公開）
+fn standing_rows(table: &[Standing], entrants: &[TournamentEntrant]) -> Vec<TournamentStandingRow> {
+    let by_id: HashMap<i64, &TournamentEntrant> = entrants.iter().map(|e| (e.member_id, e)).collect();
+    table
+        .iter()
+        .filter_map(|s| {
+            let e = by_id.get(&s.member_id)?;
+            Some(TournamentStandingRow {
+                rank: s.rank,
+                name: e.name.clone(),
+                avatar_url: e.avatar_url.clone(),
+                points: s.points,
+                buchholz: s.buchholz,
+                sonneborn_berger: s.sonneborn_berger,
+                games: s.games,
+            })
+        })
+        .collect()
+}
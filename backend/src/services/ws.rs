@@ -2,6 +2,7 @@
 //!
 //! JWT **不走** WS URL query —— query 會進 access log（`routes.rs` 的 request span），
 //! 所以 admin 身分改用「登入中換一張 30 秒有效的 UUID 票 → 握手時一次性消費」。
+//! 會員身分（對戰計分用）走同一套機制，另一組 key。
 
 use crate::{errors::AppError, repositories::redis, state::AppState};
 
@@ -26,3 +27,21 @@ pub async fn consume_ticket(state: &AppState, ticket: &str) -> Option<String> {
         .ok()
         .flatten()
 }
+
+/// 發一張會員連線票（呼叫端已由 `authorize_member` 驗過身分）。
+///
+/// 2 人對戰靠它把 WS 連線綁到會員：握手帶 `?member_ticket=` 的連線，完局時才會計入積分
+/// 與個人戰績。與 admin 票分開發、分開消費，身分不會混用。
+pub async fn issue_member_ticket(state: &AppState, member_id: i64) -> Result<String, AppError> {
+    let ticket = uuid::Uuid::new_v4().to_string();
+    redis::set_ws_member_ticket(state.get_redis_pool(), &ticket, member_id).await?;
+    Ok(ticket)
+}
+
+/// 消費會員票，回傳 member_id。失敗一律 `None` = 匿名（同 `consume_ticket`）。
+pub async fn consume_member_ticket(state: &AppState, ticket: &str) -> Option<i64> {
+    redis::consume_ws_member_ticket(state.get_redis_pool(), ticket)
+        .await
+        .ok()
+        .flatten()
+}
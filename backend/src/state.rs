@@ -100,10 +100,31 @@ pub struct TrackedConnection {
     pub connected_at: std::time::SystemTime,
     pub sender: WsSender,
     pub user_email: Option<String>,
+    /// 握手時帶會員票綁定的會員（對戰計分用）；None = 匿名
+    pub member_id: Option<i64>,
     pub real_ip: String,
     pub user_agent: String,
 }
 
+impl TrackedConnection {
+    /// 對外（`GET /ws/connections`、後台對局檢視）的一列。
+    pub fn display(&self, addr: SocketAddr) -> DisplayTrackedConnection {
+        DisplayTrackedConnection {
+            addr: addr.to_string(),
+            connected_at: to_iso(self.connected_at),
+            user_email: self.user_email.clone(),
+            member_id: self.member_id,
+            real_ip: self.real_ip.clone(),
+            user_agent: self.user_agent.clone(),
+        }
+    }
+}
+
+/// 連線時間對外一律用固定寬度的 ISO-8601 毫秒 UTC 字串
+pub fn to_iso(t: std::time::SystemTime) -> String {
+    chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339_opts(chrono::SecondsFormat::Millis, true)
+}
+
 #[derive(Serialize)]
 pub struct DisplayTrackedConnection {
     pub addr: String,
@@ -111,6 +132,7 @@ pub struct DisplayTrackedConnection {
     /// 固定寬度，字典序 == 時間序，可直接拿來排序。
     pub connected_at: String,
     pub user_email: Option<String>,
+    pub member_id: Option<i64>,
     pub real_ip: String,
     pub user_agent: String,
 }
@@ -308,6 +330,17 @@ impl AppState {
         });
     }
 
+    /// 連線綁定的會員（握手時帶會員票才有）；連線不存在或匿名回 None。
+    pub async fn member_of(&self, addr: SocketAddr) -> Option<i64> {
+        self.0.connections.lock().await.get(&addr).and_then(|c| c.member_id)
+    }
+
+    /// 所有綁了會員的連線 `(addr, member_id)`（錦標賽開桌找人用）。
+    pub async fn member_connections(&self) -> Vec<(SocketAddr, i64)> {
+        let conns = self.0.connections.lock().await;
+        conns.iter().filter_map(|(&addr, c)| Some((addr, c.member_id?))).collect()
+    }
+
     /// 送出一整批 outbox（遊戲框架的統一出口）。
     ///
     /// 同一收件人的多則訊息會併成一次有序送出（見 [`AppState::send_many`]），
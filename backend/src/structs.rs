@@ -4,7 +4,13 @@ pub mod auth;
 pub mod config;
 pub mod blog_comments;
 pub mod blogs;
+pub mod farm_scores;
 pub mod features;
+pub mod game_analysis;
+pub mod game_moderation;
+pub mod game_ratings;
+pub mod game_records;
+pub mod game_tournaments;
 pub mod gov_tenders;
 pub mod images;
 pub mod invoices;
@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+use crate::games::farm::engine::ScoreBreakdown;
+
+/// 完局落地一列（farm service 完局時組好交給 `farm_scores::spawn_save`）
+pub struct NewFarmScore {
+    pub member_id: i64,
+    pub breakdown: ScoreBreakdown,
+    pub solo: bool,
+    pub players: i32,
+    pub cards: bool,
+}
+
+/// 排行模式：單人 / 多人分開排
+#[derive(Deserialize, Clone, Copy, PartialEq, Default, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum FarmMode {
+    #[default]
+    Solo,
+    Multi,
+}
+
+impl FarmMode {
+    pub fn is_solo(self) -> bool {
+        self == FarmMode::Solo
+    }
+}
+
+/// GET /member/games/farm/leaderboard 的 query
+#[derive(Deserialize)]
+pub struct FarmLeaderboardQuery {
+    #[serde(default)]
+    pub mode: FarmMode,
+    /// 卡牌版與家庭版規則不同、分數不可比，分開排；預設家庭版
+    #[serde(default)]
+    pub cards: bool,
+}
+
+/// 高分榜一列：每位會員只取最高分那局（name / avatar 取自 members，公開顯示）
+#[derive(Serialize, FromRow)]
+pub struct FarmLeaderboardRow {
+    pub rank: i64,
+    pub name: String,
+    pub avatar_url: Option<String>,
+    pub score: i32,
+    pub games: i64,
+    pub achieved_at: DateTime<Utc>,
+}
+
+/// 自己的名次（登入且該模式 + 版本有完局才有）
+#[derive(Serialize, FromRow)]
+pub struct FarmLeaderboardMe {
+    pub rank: i64,
+    pub score: i32,
+}
+
+/// GET /member/games/farm/leaderboard 回傳
+#[derive(Serialize)]
+pub struct FarmLeaderboardResponse {
+    pub top: Vec<FarmLeaderboardRow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub me: Option<FarmLeaderboardMe>,
+}
+
+/// 個人最佳（單人 / 多人 × 卡牌版 / 家庭版各一筆，含該局逐項明細）
+#[derive(Serialize, FromRow)]
+pub struct FarmBest {
+    pub solo: bool,
+    pub score: i32,
+    pub breakdown: Value,
+    pub players: i32,
+    pub cards: bool,
+    pub games: i64,
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,179 @@
+use serde::{Deserialize, Serialize};
+
+/// 座標一律 `[col, row]`，與對戰 WS 的 `at` / `from` / `to` 相同。
+pub type Point = [i8; 2];
+
+/// POST /games/analysis/western_chess 與 /games/analysis/chess（象棋）的 body
+#[derive(Deserialize)]
+pub struct ChessAnalysisRequest {
+    /// 起始局面 FEN；省略 = 標準開局
+    pub fen: Option<String>,
+    /// 從起始局面依序走的步（西洋棋 UCI `e2e4` / `e7e8q`、象棋 ICCS `h2e2` / `H2-E2`）。
+    /// 重複局面只看得到這段歷史，FEN 本身不帶
+    #[serde(default)]
+    pub moves: Vec<String>,
+}
+
+/// 對局已結束時的結果；`reason` 字串同對戰的 `game_over.reason`
+#[derive(Serialize, Debug, PartialEq)]
+pub struct AnalysisResult {
+    /// None = 和
+    pub winner: Option<&'static str>,
+    pub reason: &'static str,
+}
+
+#[derive(Serialize)]
+pub struct AnalysisMove {
+    pub from: Point,
+    pub to: Point,
+    /// 座標記法（西洋棋 UCI、象棋 ICCS），可原樣放進下一次請求的 `moves`
+    pub coord: String,
+    /// 顯示用記法（西洋棋 SAN、象棋 WXF）
+    pub notation: String,
+}
+
+/// 西洋棋 / 象棋局面分析（`moves` 走完之後的局面）
+#[derive(Serialize)]
+pub struct ChessAnalysis {
+    pub turn: &'static str,
+    /// 行棋方正被將
+    pub check: bool,
+    /// 對局已結束才有
+    pub result: Option<AnalysisResult>,
+    pub legal_moves: Vec<AnalysisMove>,
+    /// 目前局面（含本次）出現過幾次，從起始局面起算
+    pub repetitions: usize,
+    /// 西洋棋可提和的理由（`repetition` / `fifty_moves`）；象棋的重複局面直接裁決，恆為 None
+    pub claimable_draw: Option<&'static str>,
+}
+
+#[derive(Deserialize, Clone, Copy, PartialEq, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum StoneColor {
+    Black,
+    White,
+}
+
+/// POST /games/analysis/go 的 body
+#[derive(Deserialize)]
+pub struct GoAnalysisRequest {
+    /// 9 / 13 / 19
+    #[serde(default = "default_go_size")]
+    pub size: i8,
+    /// 讓子數（星位擺子、白先、貼 0.5）
+    #[serde(default)]
+    pub handicap: u8,
+    /// 額外擺上的子（同 SGF 的 AB / AW），擺完不能有無氣的塊
+    #[serde(default)]
+    pub black: Vec<Point>,
+    #[serde(default)]
+    pub white: Vec<Point>,
+    /// 擺子後先行方；省略 = 依讓子規則（分先黑先、讓子白先）
+    pub turn: Option<StoneColor>,
+    /// 依序落子，null = 虛手
+    #[serde(default)]
+    pub moves: Vec<Option<Point>>,
+    /// 估分時視為死子的點（擴成整塊）
+    #[serde(default)]
+    pub dead: Vec<Point>,
+}
+
+fn default_go_size() -> i8 {
+    19
+}
+
+/// 一步提掉的子，依塊分組
+#[derive(Serialize)]
+pub struct GoCapture {
+    /// 第幾步（1 起算）
+    pub ply: usize,
+    pub by: &'static str,
+    pub groups: Vec<Vec<Point>>,
+}
+
+#[derive(Serialize)]
+pub struct GoGroup {
+    pub color: &'static str,
+    pub points: Vec<Point>,
+}
+
+#[derive(Serialize)]
+pub struct GoScore {
+    pub black: f64,
+    pub white: f64,
+    pub komi: f64,
+    pub leader: &'static str,
+    pub margin: f64,
+}
+
+/// 圍棋局面分析：提子紀錄、叫吃中的塊與數子估分
+#[derive(Serialize)]
+pub struct GoAnalysis {
+    pub turn: &'static str,
+    /// 劫爭禁著點
+    pub ko: Option<Point>,
+    pub captures: Vec<GoCapture>,
+    /// 各方提掉的子數
+    pub prisoners: GoPrisoners,
+    /// 只剩一氣、下一手就能被提的塊
+    pub atari: Vec<GoGroup>,
+    /// 數子（area）估分：盤上活子 + 只被單色圍住的空點，扣掉 `dead`；白方含貼目
+    pub score: GoScore,
+}
+
+#[derive(Serialize)]
+pub struct GoPrisoners {
+    pub black: i32,
+    pub white: i32,
+}
+
+/// POST /games/analysis/gomoku 的 body
+#[derive(Deserialize)]
+pub struct GomokuAnalysisRequest {
+    /// freestyle / standard / renju；省略 = freestyle
+    pub rule: Option<String>,
+    /// 依序落點（黑先）
+    #[serde(default)]
+    pub moves: Vec<Point>,
+}
+
+#[derive(Serialize)]
+pub struct GomokuLine {
+    pub color: &'static str,
+    pub points: Vec<Point>,
+}
+
+#[derive(Serialize)]
+pub struct GomokuFour {
+    pub at: Point,
+    /// 下完後的致勝點數；≥ 2 = 活四或雙四，對方擋不完
+    pub wins: usize,
+}
+
+/// 一方的威脅：不論輪到誰都列出
+#[derive(Serialize)]
+pub struct GomokuThreats {
+    /// 下一手即可獲勝的點
+    pub wins: Vec<Point>,
+    /// 下了會成四的點
+    pub fours: Vec<GomokuFour>,
+}
+
+#[derive(Serialize)]
+pub struct GomokuForbidden {
+    pub at: Point,
+    pub reason: &'static str,
+}
+
+/// 五子棋局面分析
+#[derive(Serialize)]
+pub struct GomokuAnalysis {
+    pub turn: &'static str,
+    pub result: Option<AnalysisResult>,
+    /// 盤上已成立的勝線
+    pub lines: Vec<GomokuLine>,
+    pub black: GomokuThreats,
+    pub white: GomokuThreats,
+    /// 連珠規則下黑方的禁手點
+    pub forbidden: Vec<GomokuForbidden>,
+}
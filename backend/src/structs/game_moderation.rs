@@ -0,0 +1,15 @@
+use serde::Deserialize;
+
+/// `GET /admin/games/live?game=`；不帶 game 列全部遊戲
+#[derive(Deserialize)]
+pub struct LiveQuery {
+    pub game: Option<String>,
+}
+
+/// `POST /admin/games/{game}/tables/{id}/end?winner=`。
+/// 勝方放 query 而非 body：稽核只記路徑與 query，判給誰才查得到
+#[derive(Deserialize)]
+pub struct EndTableQuery {
+    /// 勝方座位標籤（red / black / first ...）；不帶 = 和局
+    pub winner: Option<String>,
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+/// 會員在單一遊戲的目前積分（DB 列）
+#[derive(Serialize, FromRow)]
+pub struct GameRating {
+    pub game: String,
+    pub rating: i32,
+    pub games: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub draws: i32,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 積分榜一列（name / avatar 取自 members，公開顯示）
+#[derive(Serialize, FromRow)]
+pub struct RatingLeaderboardRow {
+    pub rank: i64,
+    pub name: String,
+    pub avatar_url: Option<String>,
+    pub rating: i32,
+    pub games: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub draws: i32,
+}
+
+/// 自己的名次（登入且該遊戲下過計分局才有）
+#[derive(Serialize, FromRow)]
+pub struct RatingLeaderboardMe {
+    pub rank: i64,
+    pub rating: i32,
+    pub games: i32,
+}
+
+/// GET /member/games/leaderboard 回傳
+#[derive(Serialize)]
+pub struct RatingLeaderboardResponse {
+    pub top: Vec<RatingLeaderboardRow>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub me: Option<RatingLeaderboardMe>,
+}
+
+/// GET /member/games/leaderboard 的 query（game 必填）
+#[derive(Deserialize)]
+pub struct RatingLeaderboardQuery {
+    pub game: String,
+}
+
+/// GET /member/games/history 的篩選 + 分頁（page / per_page 刻意不 flatten，見 `PageQuery`）
+#[derive(Deserialize)]
+pub struct MemberGameHistoryQuery {
+    pub game: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
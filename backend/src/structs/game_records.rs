@@ -0,0 +1,94 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+/// 完局紀錄列表項（不含 moves —— 列表只需要知道「哪盤、誰贏、怎麼結束」）
+#[derive(Serialize, FromRow)]
+pub struct GameRecordSummary {
+    pub id: i64,
+    pub game: String,
+    pub table_name: String,
+    /// 勝方座位標籤（red / black / first ...）；None = 和局
+    pub winner: Option<String>,
+    pub reason: String,
+    pub move_count: i32,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// 會員個人戰績一列（`GET /member/games/history`）
+#[derive(Serialize, FromRow)]
+pub struct MemberGameHistoryRow {
+    pub id: i64,
+    pub game: String,
+    /// win / loss / draw（以查詢者視角）
+    pub result: String,
+    pub reason: String,
+    /// 對手會員名；匿名對手為 None
+    pub opponent_name: Option<String>,
+    /// 本局積分變化；非計分局為 None
+    pub rating_change: Option<i32>,
+    pub move_count: i32,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// 單盤完整紀錄（重播用）
+#[derive(Serialize, FromRow)]
+pub struct GameRecord {
+    pub id: i64,
+    pub game: String,
+    pub table_name: String,
+    pub winner: Option<String>,
+    pub reason: String,
+    /// 每一步 `move_made` 的 data，依序排列（阿瓦隆為終局時間軸）
+    pub moves: Value,
+    pub move_count: i32,
+    pub initial_clock_ms: i64,
+    /// 計時制（`{type, base_ms, ...}`，見 `games::common::clock::TimeControl`）；計時制上線前的舊紀錄為 None
+    pub time_control: Option<Value>,
+    /// 規則選項（見 `GameEngine::parse_options`，例如圍棋 `{size, handicap, scoring}`）；無選項的遊戲與舊紀錄為 null
+    pub options: Value,
+    /// 終局時雙方剩餘時間 `{label: ms}`；不計時局為 null
+    pub final_clock: Value,
+    pub started_at: DateTime<Utc>,
+    pub ended_at: DateTime<Utc>,
+}
+
+/// `GET /games/records` 的篩選 + 分頁（page / per_page 刻意不 flatten，見 `PageQuery`）
+#[derive(Deserialize)]
+pub struct GameRecordQuery {
+    pub game: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
+
+/// 對局結束時由 `games::common::service::end_game`（阿瓦隆為 `games::avalon::service`）組出、待寫入的一筆
+pub struct NewGameRecord {
+    pub game: &'static str,
+    pub table_name: String,
+    pub winner: Option<&'static str>,
+    /// 勝方座位（0 先手 / 1 後手），None = 和局
+    pub winner_seat: Option<i16>,
+    /// 兩座位綁定的會員，見 `Game::members`
+    pub members: [Option<i64>; 2],
+    /// 是否計分，見 `Game::is_rated`
+    pub rated: bool,
+    pub reason: String,
+    pub moves: Vec<Value>,
+    pub initial_clock_ms: i64,
+    pub time_control: Value,
+    pub options: Value,
+    pub final_clock: Value,
+    pub started_at: DateTime<Utc>,
+}
+
+impl NewGameRecord {
+    /// 計分局的雙方會員
+    pub fn rated_pair(&self) -> Option<[i64; 2]> {
+        match self.members {
+            [Some(a), Some(b)] if self.rated => Some([a, b]),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,101 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::FromRow;
+
+/// 錦標賽（DB 列 + 報名人數）
+#[derive(Serialize, FromRow)]
+pub struct GameTournament {
+    pub id: i64,
+    pub game: String,
+    pub name: String,
+    /// round_robin / swiss
+    pub format: String,
+    /// 總輪數；循環賽開賽前為 0，瑞士制開賽時依人數下修（見 `tournament::total_rounds`）
+    pub rounds: i32,
+    pub time_control: Value,
+    pub options: Value,
+    /// registering / running / finished
+    pub status: String,
+    /// 目前進行到第幾輪（開賽前 0）
+    pub current_round: i32,
+    pub entrants: i64,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// 報名者（name / avatar 取自 members，公開顯示）
+#[derive(FromRow)]
+pub struct TournamentEntrant {
+    pub member_id: i64,
+    pub name: String,
+    pub avatar_url: Option<String>,
+}
+
+/// 一組對陣（DB 列 + 雙方名字）；`second_*` 為 None = 輪空
+#[derive(Serialize, FromRow)]
+pub struct TournamentPairing {
+    pub id: i64,
+    pub round: i32,
+    #[serde(skip_serializing)]
+    pub first_member_id: i64,
+    pub first_name: String,
+    #[serde(skip_serializing)]
+    pub second_member_id: Option<i64>,
+    pub second_name: Option<String>,
+    /// 1 / 0.5 / 0；None = 尚未完成
+    pub first_score: Option<f64>,
+    pub second_score: Option<f64>,
+    pub forfeit: bool,
+}
+
+/// 名次表一列（完賽後即最終名次，進行中為目前排名）
+#[derive(Serialize)]
+pub struct TournamentStandingRow {
+    pub rank: u32,
+    pub name: String,
+    pub avatar_url: Option<String>,
+    pub points: f64,
+    pub buchholz: f64,
+    pub sonneborn_berger: f64,
+    pub games: u32,
+}
+
+/// 單一錦標賽：基本資料 + 名次表 + 全部對陣（依輪次）
+#[derive(Serialize)]
+pub struct TournamentDetail {
+    pub tournament: GameTournament,
+    pub standings: Vec<TournamentStandingRow>,
+    pub pairings: Vec<TournamentPairing>,
+}
+
+/// POST /admin/games/tournaments
+#[derive(Deserialize)]
+pub struct NewGameTournament {
+    pub game: String,
+    pub name: String,
+    /// round_robin / swiss
+    pub format: String,
+    /// 瑞士制必填（1–20）；開賽時超過人數可排的不重賽輪數會下修。循環賽忽略
+    pub rounds: Option<i32>,
+    /// 省略 = 遊戲預設計時制
+    pub time_control: Option<Value>,
+    /// 省略 = 遊戲預設規則
+    pub options: Option<Value>,
+}
+
+/// PUT /admin/games/tournaments/{id}/pairings/{pairing_id}：手動登錄結果
+#[derive(Deserialize)]
+pub struct SetPairingResult {
+    /// first / second / draw / double_forfeit
+    pub result: String,
+}
+
+/// GET /games/tournaments 的篩選 + 分頁
+#[derive(Deserialize)]
+pub struct TournamentQuery {
+    pub game: Option<String>,
+    pub page: Option<i64>,
+    pub per_page: Option<i64>,
+}
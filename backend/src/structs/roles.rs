@@ -31,6 +31,7 @@ pub enum Perm {
     TorrentCreate,
     TorrentDelete,
     GameRead,
+    GameManage,
     InvoiceLotteryWrite,
     StatRead,
     GovTenderRead,
@@ -74,6 +75,7 @@ impl Perm {
             Self::TorrentCreate  => "torrent:create",
             Self::TorrentDelete  => "torrent:delete",
             Self::GameRead       => "game:read",
+            Self::GameManage     => "game:manage",
             Self::InvoiceLotteryWrite => "invoice_lottery:write",
             Self::StatRead       => "stat:read",
             Self::GovTenderRead  => "gov_tender:read",
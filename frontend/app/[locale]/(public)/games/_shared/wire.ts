@@ -2,14 +2,26 @@
 // 大廳/桌位/配對/計時/斷線三遊戲共用；各遊戲的 move/move_made 內容在各自 logic 檔。
 // 信封 { game, type, data }，game 必填（'chess'/'gomoku'/'banqi'）。
 
-export type GameId = 'chess' | 'gomoku' | 'banqi' | 'western_chess' | 'go';
+export type GameId = 'chess' | 'gomoku' | 'banqi' | 'western_chess' | 'go' | 'reversi' | 'shogi';
 
 export type TableStatus = 'waiting' | 'playing';
 export interface WireTable { id: number; name: string; status: TableStatus; }
 
 export interface TableListData { tables: WireTable[]; }
-export interface TableCreatedData { table_id: number; }
+// 私人桌（create_table 帶 private: true）才有 invite_code；對方以 join_table { invite_code } 入座
+export interface TableCreatedData { table_id: number; invite_code: string | null; }
+// 直接挑戰：from/to/by 為連線 addr（/ws/connections）
+export interface ChallengeReceivedData { from: string; time_control: unknown; options: unknown; }
+export interface ChallengeSentData { to: string; }
+export interface ChallengeCancelledData { from: string; }
+export interface ChallengeDeclinedData { by: string; }
 export interface QueuedData { position: number; }
+// 錦標賽：每輪開桌時推（match_found 照常推）；table_id 為 null = 輪空 / 不戰
+export interface TournamentPairingData { tournament_id: number; round: number; table_id: number; color: string; opponent: string; }
+export interface TournamentRoundPairing { first: string; second: string | null; table_id: number | null; status: 'playing' | 'bye' | 'forfeit'; }
+export interface TournamentRoundData { tournament_id: number; name: string; round: number; pairings: TournamentRoundPairing[]; }
+export interface TournamentStanding { rank: number; name: string; avatar_url: string | null; points: number; buchholz: number; sonneborn_berger: number; games: number; }
+export interface TournamentFinishedData { tournament_id: number; name: string; standings: TournamentStanding[]; }
 // match_found.color = 我方 color/seat 標籤（chess red/black、gomoku black/white、banqi first/second）
 export interface MatchFoundData { color: string; clock_ms: number; table_id: number; }
 // move_made 外殼三遊戲共用：turn=下一手輪到誰，clock 鍵=兩方標籤、值=剩餘 ms；其餘欄位各遊戲自有
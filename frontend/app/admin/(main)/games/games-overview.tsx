@@ -17,6 +17,8 @@ const GAME_LABELS: Record<string, { name: string; type: string }> = {
     banqi: { name: "暗棋翻棋", type: "2 人回合制" },
     western_chess: { name: "西洋棋", type: "2 人回合制" },
     go: { name: "圍棋", type: "2 人回合制" },
+    reversi: { name: "黑白棋", type: "2 人回合制" },
+    shogi: { name: "將棋", type: "2 人回合制" },
     avalon: { name: "阿瓦隆", type: "N 人社交推理" },
     farm: { name: "農場經營", type: "N 人 worker-placement" },
 };